@@ -6,6 +6,7 @@
  */
 
 import { Connection, PublicKey, Keypair } from '@solana/web3.js';
+import { keccak_256 } from '@noble/hashes/sha3.js';
 
 // Arcium Program IDs (mainnet)
 const ARCIUM_PROGRAM_ID = new PublicKey('ArcProgramXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXX');
@@ -209,6 +210,25 @@ export function deriveOrderAddress(
   return orderPda;
 }
 
+/**
+ * Derive a computation offset the same way as the program's
+ * `derive_computation_offset`: first 8 bytes (LE) of
+ * keccak256("computation_offset" || subject || sequence || slot)
+ */
+export function deriveComputationOffset(
+  subject: PublicKey,
+  sequence: bigint,
+  slot: bigint
+): bigint {
+  const data = Buffer.alloc(18 + 32 + 8 + 8);
+  data.write('computation_offset', 0);
+  subject.toBuffer().copy(data, 18);
+  data.writeBigUInt64LE(sequence, 50);
+  data.writeBigUInt64LE(slot, 58);
+
+  return Buffer.from(keccak_256(data)).readBigUInt64LE(0);
+}
+
 /**
  * Get cluster account addresses
  */
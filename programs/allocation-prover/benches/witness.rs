@@ -0,0 +1,47 @@
+//! Witness generation for a full batch
+//!
+//! Compare `cargo bench -p allocation-prover` against
+//! `cargo bench -p allocation-prover --features parallel`.
+
+use allocation_prover::{BatchSnapshot, OrderSnapshot, Witness, MAX_BATCH_SIZE};
+use circuit_sim::{run_batch, Order};
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+fn full_batch() -> BatchSnapshot {
+    let orders: Vec<([u8; 32], u64)> = (0..MAX_BATCH_SIZE)
+        .map(|i| ([i as u8 + 1; 32], 1_000_000 * (i as u64 + 1)))
+        .collect();
+    let sim_orders: Vec<Order> = orders
+        .iter()
+        .map(|(wallet, amount)| Order::new(*amount, wallet))
+        .collect();
+    let (state, output) = run_batch(&sim_orders, 1_000_000, 5_000);
+
+    BatchSnapshot {
+        merkle_root: hex::encode(output.merkle_root),
+        total_usdc: state.total_amount,
+        total_shares: 1_000_000,
+        execution_price: 5_000,
+        orders: orders
+            .iter()
+            .map(|(wallet, amount)| OrderSnapshot {
+                user: bs58::encode(wallet).into_string(),
+                amount: *amount,
+            })
+            .collect(),
+    }
+}
+
+fn witness(c: &mut Criterion) {
+    let snapshot = full_batch();
+
+    c.bench_function("witness_from_snapshot", |b| {
+        b.iter(|| Witness::from_snapshot(black_box(&snapshot)).unwrap())
+    });
+
+    let witness = Witness::from_snapshot(&snapshot).unwrap();
+    c.bench_function("witness_to_prover_toml", |b| b.iter(|| black_box(&witness).to_prover_toml()));
+}
+
+criterion_group!(benches, witness);
+criterion_main!(benches);
@@ -0,0 +1,388 @@
+//! Allocation Prover
+//!
+//! Builds witnesses for the `circuits/allocation` Noir package from indexed
+//! batch data and drives `nargo` / `bb` to produce an UltraHonk proof in the
+//! layout `verify_allocation` expects. The order fold and execution hash
+//! come from `circuit_sim`, so a snapshot that does not replay to the
+//! on-chain merkle root is rejected before proving.
+//!
+//! With the `parallel` feature, witness generation runs on rayon and
+//! `prove_many` proves several batches at once.
+
+use circuit_sim::{compute_order_hash, execute_batch, split_wallet, BatchState, Order};
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "parallel")]
+use std::path::PathBuf;
+use std::{fmt, fs, io, path::Path, process::Command};
+
+/// Max orders per batch (`ErrorCode::BatchFull`), the circuit's array size
+pub const MAX_BATCH_SIZE: usize = 32;
+
+/// Number of public inputs: merkle_root_lo, merkle_root_hi, total_usdc,
+/// total_shares, execution_price, allocation_root
+pub const PUBLIC_INPUTS: usize = 6;
+
+/// Noir package name, used for the `target/<name>.json` / `.gz` artifacts
+const CIRCUIT_NAME: &str = "allocation";
+
+// ============================================
+// Indexer Input
+// ============================================
+
+/// An executed batch as indexed off-chain
+///
+/// `merkle_root` and `total_usdc` come from the `TradingBatch` account,
+/// `total_shares` / `execution_price` from the `execute_batch` call, and
+/// the order amounts from the MPC operator.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchSnapshot {
+    /// Hex-encoded `TradingBatch::merkle_root`
+    pub merkle_root: String,
+    pub total_usdc: u64,
+    pub total_shares: u64,
+    pub execution_price: u64,
+    /// Orders in `order_index` order
+    pub orders: Vec<OrderSnapshot>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct OrderSnapshot {
+    /// Base58 wallet the order was folded under (`OrderCommitment::user`)
+    pub user: String,
+    pub amount: u64,
+}
+
+// ============================================
+// Errors
+// ============================================
+
+#[derive(Debug)]
+pub enum HarnessError {
+    InvalidWallet(String),
+    InvalidMerkleRoot,
+    EmptyBatch,
+    TooManyOrders(usize),
+    /// Orders do not replay to the batch's merkle root
+    MerkleRootMismatch,
+    /// Order amounts do not add up to `total_usdc`
+    TotalMismatch,
+    Io(io::Error),
+    /// `nargo` or `bb` exited unsuccessfully
+    Prover(String),
+    InvalidPublicInputs,
+}
+
+impl fmt::Display for HarnessError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::InvalidWallet(wallet) => write!(f, "invalid wallet {wallet}"),
+            Self::InvalidMerkleRoot => write!(f, "merkle root is not 32 hex-encoded bytes"),
+            Self::EmptyBatch => write!(f, "batch has no notional"),
+            Self::TooManyOrders(count) => write!(f, "{count} orders exceed the batch size"),
+            Self::MerkleRootMismatch => write!(f, "orders do not replay to the merkle root"),
+            Self::TotalMismatch => write!(f, "order amounts do not sum to total_usdc"),
+            Self::Io(err) => write!(f, "{err}"),
+            Self::Prover(msg) => write!(f, "prover failed: {msg}"),
+            Self::InvalidPublicInputs => write!(f, "prover wrote malformed public inputs"),
+        }
+    }
+}
+
+impl std::error::Error for HarnessError {}
+
+impl From<io::Error> for HarnessError {
+    fn from(err: io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+pub type Result<T> = std::result::Result<T, HarnessError>;
+
+// ============================================
+// Witness
+// ============================================
+
+/// Inputs to the allocation circuit's `main`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Witness {
+    pub merkle_root_lo: u128,
+    pub merkle_root_hi: u128,
+    pub total_usdc: u64,
+    pub total_shares: u64,
+    pub execution_price: u64,
+    pub orders: Vec<Order>,
+    pub shares: Vec<u64>,
+}
+
+impl Witness {
+    /// Build and check a witness from an indexed batch
+    pub fn from_snapshot(snapshot: &BatchSnapshot) -> Result<Self> {
+        if snapshot.orders.len() > MAX_BATCH_SIZE {
+            return Err(HarnessError::TooManyOrders(snapshot.orders.len()));
+        }
+        if snapshot.total_usdc == 0 {
+            return Err(HarnessError::EmptyBatch);
+        }
+
+        let root = decode_root(&snapshot.merkle_root)?;
+        let orders = decode_orders(&snapshot.orders)?;
+
+        let state = replay(&orders).ok_or(HarnessError::TotalMismatch)?;
+        if state.total_amount != snapshot.total_usdc {
+            return Err(HarnessError::TotalMismatch);
+        }
+        let output = execute_batch(&state, snapshot.total_shares, snapshot.execution_price);
+        if output.merkle_root != root {
+            return Err(HarnessError::MerkleRootMismatch);
+        }
+
+        #[cfg(feature = "parallel")]
+        let amounts = orders.par_iter();
+        #[cfg(not(feature = "parallel"))]
+        let amounts = orders.iter();
+        let shares = amounts
+            .map(|order| pro_rata_shares(order.amount, snapshot.total_shares, snapshot.total_usdc))
+            .collect();
+        let (merkle_root_lo, merkle_root_hi) = split_wallet(&root);
+
+        Ok(Self {
+            merkle_root_lo,
+            merkle_root_hi,
+            total_usdc: snapshot.total_usdc,
+            total_shares: snapshot.total_shares,
+            execution_price: snapshot.execution_price,
+            orders,
+            shares,
+        })
+    }
+
+    /// Render as a nargo `Prover.toml`, padding to `MAX_BATCH_SIZE`
+    pub fn to_prover_toml(&self) -> String {
+        let mut shares = self.shares.clone();
+        shares.resize(MAX_BATCH_SIZE, 0);
+        let shares: Vec<String> = shares.iter().map(|s| format!("\"{s}\"")).collect();
+
+        // Integers are quoted: u128 values do not fit a TOML integer
+        let mut toml = format!(
+            "merkle_root_lo = \"{}\"\n\
+             merkle_root_hi = \"{}\"\n\
+             total_usdc = \"{}\"\n\
+             total_shares = \"{}\"\n\
+             execution_price = \"{}\"\n\
+             num_orders = \"{}\"\n\
+             shares = [{}]\n",
+            self.merkle_root_lo,
+            self.merkle_root_hi,
+            self.total_usdc,
+            self.total_shares,
+            self.execution_price,
+            self.orders.len(),
+            shares.join(", "),
+        );
+
+        let padding = Order {
+            amount: 0,
+            wallet_lo: 0,
+            wallet_hi: 0,
+        };
+        for i in 0..MAX_BATCH_SIZE {
+            let order = self.orders.get(i).unwrap_or(&padding);
+            toml.push_str(&format!(
+                "\n[[orders]]\nwallet_lo = \"{}\"\nwallet_hi = \"{}\"\namount = \"{}\"\n",
+                order.wallet_lo, order.wallet_hi, order.amount,
+            ));
+        }
+
+        toml
+    }
+}
+
+fn decode_orders(orders: &[OrderSnapshot]) -> Result<Vec<Order>> {
+    #[cfg(feature = "parallel")]
+    let orders = orders.par_iter();
+    #[cfg(not(feature = "parallel"))]
+    let orders = orders.iter();
+
+    orders
+        .map(|order| Ok(Order::new(order.amount, &decode_wallet(&order.user)?)))
+        .collect()
+}
+
+/// Replay orders into the state `add_order` leaves behind, for `execute_batch`
+///
+/// The running root is XOR / wrapping-add of leaves, so the fold is a
+/// reduce that can run in any order. Returns `None` when the amounts
+/// overflow a u64: the circuit sums them unchecked, so such a batch has
+/// no valid witness. The stored hash slots are left empty.
+fn replay(orders: &[Order]) -> Option<BatchState> {
+    type Acc = (u128, u128, u128);
+    let combine = |a: Acc, b: Acc| (a.0 + b.0, a.1 ^ b.1, a.2.wrapping_add(b.2));
+
+    #[cfg(feature = "parallel")]
+    let leaves = orders.par_iter();
+    #[cfg(not(feature = "parallel"))]
+    let leaves = orders.iter();
+    let leaves = leaves.map(|order| {
+        let leaf = compute_order_hash(order.amount, order.wallet_lo, order.wallet_hi);
+        (order.amount as u128, leaf, leaf)
+    });
+
+    #[cfg(feature = "parallel")]
+    let (total, root_lo, root_hi) = leaves.reduce(|| (0, 0, 0), combine);
+    #[cfg(not(feature = "parallel"))]
+    let (total, root_lo, root_hi) = leaves.fold((0, 0, 0), combine);
+
+    Some(BatchState {
+        total_amount: u64::try_from(total).ok()?,
+        order_count: orders.len() as u8,
+        commitment_root: root_lo,
+        commitment_root_hi: root_hi,
+        ..BatchState::default()
+    })
+}
+
+/// Shares for an order, rounded down, as the circuit constrains them
+pub fn pro_rata_shares(amount: u64, total_shares: u64, total_usdc: u64) -> u64 {
+    ((amount as u128) * (total_shares as u128) / (total_usdc as u128)) as u64
+}
+
+fn decode_root(root: &str) -> Result<[u8; 32]> {
+    hex::decode(root)
+        .ok()
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or(HarnessError::InvalidMerkleRoot)
+}
+
+fn decode_wallet(wallet: &str) -> Result<[u8; 32]> {
+    bs58::decode(wallet)
+        .into_vec()
+        .ok()
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or_else(|| HarnessError::InvalidWallet(wallet.to_string()))
+}
+
+// ============================================
+// Proving
+// ============================================
+
+/// Proof and public inputs, passed as-is to `verify_allocation`
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct AllocationProof {
+    #[serde(with = "hex::serde")]
+    pub proof_data: Vec<u8>,
+    #[serde(serialize_with = "serialize_inputs", deserialize_with = "deserialize_inputs")]
+    pub public_inputs: Vec<[u8; 32]>,
+}
+
+fn serialize_inputs<S: serde::Serializer>(inputs: &[[u8; 32]], s: S) -> std::result::Result<S::Ok, S::Error> {
+    s.collect_seq(inputs.iter().map(hex::encode))
+}
+
+fn deserialize_inputs<'de, D: serde::Deserializer<'de>>(d: D) -> std::result::Result<Vec<[u8; 32]>, D::Error> {
+    Vec::<String>::deserialize(d)?
+        .iter()
+        .map(|input| {
+            hex::decode(input)
+                .ok()
+                .and_then(|bytes| bytes.try_into().ok())
+                .ok_or_else(|| serde::de::Error::custom("public input is not 32 hex-encoded bytes"))
+        })
+        .collect()
+}
+
+/// Compile the circuit and write its verification key, once per package
+///
+/// Later `prove` calls in the same `circuit_dir` reuse the compiled circuit
+/// and key. Skipped when both artifacts already exist.
+pub fn prepare(circuit_dir: &Path) -> Result<()> {
+    let target = circuit_dir.join("target");
+    let circuit = target.join(format!("{CIRCUIT_NAME}.json"));
+    if circuit.exists() && target.join("vk").exists() {
+        return Ok(());
+    }
+
+    run(Command::new("nargo").arg("compile").current_dir(circuit_dir))?;
+    run(Command::new("bb")
+        .args(["write_vk", "--scheme", "ultra_honk", "-b"])
+        .arg(&circuit)
+        .arg("-o")
+        .arg(&target))
+}
+
+/// Prove a witness with `nargo execute` and `bb prove`
+///
+/// `circuit_dir` is the `circuits/allocation` package. Both tools must be
+/// on `PATH`. Runs that share a `circuit_dir` must not overlap.
+pub fn prove(circuit_dir: &Path, witness: &Witness) -> Result<AllocationProof> {
+    prepare(circuit_dir)?;
+    fs::write(circuit_dir.join("Prover.toml"), witness.to_prover_toml())?;
+    run(Command::new("nargo").arg("execute").current_dir(circuit_dir))?;
+
+    let target = circuit_dir.join("target");
+    run(Command::new("bb")
+        .args(["prove", "--scheme", "ultra_honk", "-b"])
+        .arg(target.join(format!("{CIRCUIT_NAME}.json")))
+        .arg("-w")
+        .arg(target.join(format!("{CIRCUIT_NAME}.gz")))
+        .arg("-k")
+        .arg(target.join("vk"))
+        .arg("-o")
+        .arg(&target))?;
+
+    Ok(AllocationProof {
+        proof_data: fs::read(target.join("proof"))?,
+        public_inputs: split_public_inputs(&fs::read(target.join("public_inputs"))?)?,
+    })
+}
+
+/// Prove several witnesses at once, one thread per package copy
+///
+/// Witnesses are dealt round-robin over `circuit_dirs` (copies of
+/// `circuits/allocation`); each directory proves its share in sequence,
+/// since runs in one package must not overlap. Results come back in
+/// witness order.
+#[cfg(feature = "parallel")]
+pub fn prove_many(circuit_dirs: &[PathBuf], witnesses: &[Witness]) -> Vec<Result<AllocationProof>> {
+    assert!(!circuit_dirs.is_empty(), "prove_many needs a circuit directory");
+
+    let mut proofs: Vec<(usize, Result<AllocationProof>)> = circuit_dirs
+        .par_iter()
+        .enumerate()
+        .flat_map_iter(|(lane, dir)| {
+            witnesses
+                .iter()
+                .enumerate()
+                .skip(lane)
+                .step_by(circuit_dirs.len())
+                .map(move |(i, witness)| (i, prove(dir, witness)))
+        })
+        .collect();
+
+    proofs.sort_by_key(|(i, _)| *i);
+    proofs.into_iter().map(|(_, proof)| proof).collect()
+}
+
+/// Split bb's concatenated 32-byte field elements
+pub fn split_public_inputs(bytes: &[u8]) -> Result<Vec<[u8; 32]>> {
+    if bytes.len() != PUBLIC_INPUTS * 32 {
+        return Err(HarnessError::InvalidPublicInputs);
+    }
+    Ok(bytes
+        .chunks_exact(32)
+        .map(|chunk| chunk.try_into().expect("chunks are 32 bytes"))
+        .collect())
+}
+
+fn run(command: &mut Command) -> Result<()> {
+    let output = command.output()?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(HarnessError::Prover(String::from_utf8_lossy(&output.stderr).into_owned()))
+    }
+}
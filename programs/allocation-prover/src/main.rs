@@ -0,0 +1,32 @@
+//! `allocation-prover <snapshot.json> [circuit_dir]`
+//!
+//! Proves an indexed batch snapshot and prints the proof JSON for
+//! `verify_allocation`.
+
+use allocation_prover::{prove, BatchSnapshot, Witness};
+use std::{fs, path::PathBuf, process};
+
+const DEFAULT_CIRCUIT_DIR: &str = "../circuits/allocation";
+
+fn main() {
+    let mut args = std::env::args().skip(1);
+    let Some(snapshot_path) = args.next() else {
+        eprintln!("usage: allocation-prover <snapshot.json> [circuit_dir]");
+        process::exit(2);
+    };
+    let circuit_dir = PathBuf::from(args.next().unwrap_or_else(|| DEFAULT_CIRCUIT_DIR.to_string()));
+
+    let result = fs::read_to_string(&snapshot_path)
+        .map_err(|err| err.to_string())
+        .and_then(|json| serde_json::from_str::<BatchSnapshot>(&json).map_err(|err| err.to_string()))
+        .and_then(|snapshot| Witness::from_snapshot(&snapshot).map_err(|err| err.to_string()))
+        .and_then(|witness| prove(&circuit_dir, &witness).map_err(|err| err.to_string()));
+
+    match result {
+        Ok(proof) => println!("{}", serde_json::to_string_pretty(&proof).expect("proof serializes")),
+        Err(err) => {
+            eprintln!("allocation-prover: {err}");
+            process::exit(1);
+        }
+    }
+}
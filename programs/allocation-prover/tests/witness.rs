@@ -0,0 +1,86 @@
+use allocation_prover::*;
+use circuit_sim::{run_batch, Order};
+
+fn snapshot(orders: &[(u8, u64)], total_shares: u64, execution_price: u64) -> BatchSnapshot {
+    let wallets: Vec<[u8; 32]> = orders.iter().map(|&(seed, _)| [seed; 32]).collect();
+    let sim_orders: Vec<Order> = orders
+        .iter()
+        .zip(&wallets)
+        .map(|(&(_, amount), wallet)| Order::new(amount, wallet))
+        .collect();
+    let (state, output) = run_batch(&sim_orders, total_shares, execution_price);
+
+    BatchSnapshot {
+        merkle_root: hex::encode(output.merkle_root),
+        total_usdc: state.total_amount,
+        total_shares,
+        execution_price,
+        orders: orders
+            .iter()
+            .zip(&wallets)
+            .map(|(&(_, amount), wallet)| OrderSnapshot {
+                user: bs58::encode(wallet).into_string(),
+                amount,
+            })
+            .collect(),
+    }
+}
+
+#[test]
+fn witness_allocates_pro_rata() {
+    let witness = Witness::from_snapshot(&snapshot(&[(1, 50_000_000), (2, 150_000_000)], 1000, 5_000)).unwrap();
+
+    assert_eq!(witness.shares, vec![250, 750]);
+    assert_eq!(witness.total_usdc, 200_000_000);
+}
+
+#[test]
+fn witness_rejects_tampered_amounts() {
+    let mut tampered = snapshot(&[(1, 50_000_000), (2, 150_000_000)], 1000, 5_000);
+    tampered.orders[0].amount += 1;
+    tampered.orders[1].amount -= 1;
+
+    assert!(matches!(
+        Witness::from_snapshot(&tampered),
+        Err(HarnessError::MerkleRootMismatch)
+    ));
+}
+
+#[test]
+fn witness_rejects_wrong_total() {
+    let mut snapshot = snapshot(&[(1, 10)], 1, 1);
+    snapshot.total_usdc = 11;
+
+    assert!(matches!(Witness::from_snapshot(&snapshot), Err(HarnessError::TotalMismatch)));
+}
+
+#[test]
+fn prover_toml_pads_to_batch_size() {
+    let witness = Witness::from_snapshot(&snapshot(&[(1, 10), (2, 30)], 4, 1)).unwrap();
+    let toml = witness.to_prover_toml();
+
+    assert_eq!(toml.matches("[[orders]]").count(), MAX_BATCH_SIZE);
+    assert!(toml.contains("num_orders = \"2\""));
+    assert!(toml.starts_with(&format!("merkle_root_lo = \"{}\"", witness.merkle_root_lo)));
+}
+
+#[test]
+fn public_inputs_split_into_fields() {
+    let bytes: Vec<u8> = (0..PUBLIC_INPUTS * 32).map(|i| i as u8).collect();
+    let inputs = split_public_inputs(&bytes).unwrap();
+
+    assert_eq!(inputs.len(), PUBLIC_INPUTS);
+    assert_eq!(inputs[1][0], 32);
+    assert!(split_public_inputs(&bytes[1..]).is_err());
+}
+
+#[test]
+fn full_batch_replays_to_root() {
+    let orders: Vec<(u8, u64)> = (0..MAX_BATCH_SIZE as u8).map(|i| (i + 1, 1_000 * (i as u64 + 1))).collect();
+    let witness = Witness::from_snapshot(&snapshot(&orders, 1_000_000, 5_000)).unwrap();
+
+    assert_eq!(witness.orders.len(), MAX_BATCH_SIZE);
+    // Rounding down loses less than one share per order
+    let allocated: u64 = witness.shares.iter().sum();
+    assert!(allocated <= 1_000_000 && allocated > 1_000_000 - MAX_BATCH_SIZE as u64);
+}
@@ -0,0 +1,53 @@
+//! `export-audit-bundle <rpc_url> <program_id> <batch> <snapshot.json> <bundle.json> [proof.json] [vk]`
+//!
+//! Reads the batch's accounts and transactions and writes a sealed bundle
+//! with the indexed snapshot and, when given, the allocation proof and
+//! its verification key.
+
+use allocation_prover::{AllocationProof, BatchSnapshot};
+use audit_bundle::export::{export_bundle, RpcClient};
+use std::{fs, process};
+
+const USAGE: &str =
+    "usage: export-audit-bundle <rpc_url> <program_id> <batch> <snapshot.json> <bundle.json> [proof.json] [vk]";
+
+fn read_json<T: serde::de::DeserializeOwned>(path: &str) -> Result<T, String> {
+    let json = fs::read_to_string(path).map_err(|e| format!("{path}: {e}"))?;
+    serde_json::from_str(&json).map_err(|e| format!("{path}: {e}"))
+}
+
+async fn run(args: &[String]) -> Result<(), String> {
+    let [rpc_url, program_id, batch, snapshot_path, bundle_path, rest @ ..] = args else {
+        return Err(USAGE.to_string());
+    };
+    let rpc_url = rpc_url.parse().map_err(|e| format!("invalid rpc url {rpc_url}: {e}"))?;
+    let snapshot: BatchSnapshot = read_json(snapshot_path)?;
+    let proof: Option<AllocationProof> = rest.first().map(|path| read_json(path)).transpose()?;
+    let verification_key = rest
+        .get(1)
+        .map(|path| fs::read(path).map_err(|e| format!("{path}: {e}")))
+        .transpose()?;
+
+    let bundle = export_bundle(&RpcClient::new(rpc_url), program_id, batch, snapshot, proof, verification_key)
+        .await
+        .map_err(|e| e.to_string())?;
+    let json = serde_json::to_string_pretty(&bundle).expect("bundle serializes");
+    fs::write(bundle_path, json).map_err(|e| format!("{bundle_path}: {e}"))?;
+
+    println!(
+        "{bundle_path}: {} accounts, {} transactions, digest {}",
+        bundle.accounts.len(),
+        bundle.transactions.len(),
+        bundle.digest
+    );
+    Ok(())
+}
+
+#[tokio::main]
+async fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if let Err(e) = run(&args).await {
+        eprintln!("export-audit-bundle: {e}");
+        process::exit(if args.len() < 5 { 2 } else { 1 });
+    }
+}
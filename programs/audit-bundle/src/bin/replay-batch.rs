@@ -0,0 +1,39 @@
+//! `replay-batch <bundle.json> <orders.json>`
+//!
+//! Replays the auditor's decrypted order inputs (a `DecryptedOrder` array)
+//! through the circuit simulator and checks them against the bundled
+//! batch. Exits non-zero on any mismatch.
+
+use audit_bundle::{replay::replay_batch, replay::DecryptedOrder, AuditBundle};
+use std::{fs, path::Path, process};
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let [bundle_path, orders_path] = args.as_slice() else {
+        eprintln!("usage: replay-batch <bundle.json> <orders.json>");
+        process::exit(2);
+    };
+
+    let inputs = AuditBundle::read(Path::new(bundle_path))
+        .map_err(|e| format!("invalid bundle {bundle_path}: {e}"))
+        .and_then(|bundle| {
+            let orders: Vec<DecryptedOrder> = fs::read_to_string(orders_path)
+                .map_err(|e| e.to_string())
+                .and_then(|json| serde_json::from_str(&json).map_err(|e| e.to_string()))
+                .map_err(|e| format!("invalid orders {orders_path}: {e}"))?;
+            Ok((bundle, orders))
+        });
+    let (bundle, orders) = match inputs {
+        Ok(inputs) => inputs,
+        Err(e) => {
+            eprintln!("replay-batch: {e}");
+            process::exit(1);
+        }
+    };
+
+    let report = replay_batch(&bundle, &orders);
+    print!("{report}");
+    if !report.passed() {
+        process::exit(1);
+    }
+}
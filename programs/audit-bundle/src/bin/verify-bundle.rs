@@ -0,0 +1,28 @@
+//! `verify-bundle <bundle.json>`
+//!
+//! Re-checks a bundle offline and prints one line per check. Exits
+//! non-zero when any check fails; skipped checks don't count as failures.
+
+use audit_bundle::{verify_bundle, AuditBundle};
+use std::{path::Path, process};
+
+fn main() {
+    let Some(path) = std::env::args().nth(1) else {
+        eprintln!("usage: verify-bundle <bundle.json>");
+        process::exit(2);
+    };
+
+    let bundle = match AuditBundle::read(Path::new(&path)) {
+        Ok(bundle) => bundle,
+        Err(e) => {
+            eprintln!("verify-bundle: invalid bundle {path}: {e}");
+            process::exit(1);
+        }
+    };
+
+    let report = verify_bundle(&bundle);
+    print!("{report}");
+    if !report.passed() {
+        process::exit(1);
+    }
+}
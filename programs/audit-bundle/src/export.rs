@@ -0,0 +1,190 @@
+//! Bundle export over Solana JSON-RPC
+
+use crate::{
+    account_discriminator, AccountSnapshot, AuditBundle, TransactionRecord, BUNDLE_VERSION,
+    ORDER_ACCOUNT_SIZE, ORDER_BATCH_OFFSET,
+};
+use allocation_prover::{AllocationProof, BatchSnapshot};
+use hyper::{client::HttpConnector, Body, Client, Method, Request, Uri};
+use serde_json::{json, Value};
+use std::fmt;
+
+/// Most transactions `getSignaturesForAddress` returns in one call
+const SIGNATURE_PAGE: usize = 1_000;
+
+#[derive(Debug)]
+pub enum ExportError {
+    Http(hyper::Error),
+    /// Error object or unexpected result from the node
+    Rpc(String),
+    BatchNotFound,
+}
+
+impl fmt::Display for ExportError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Http(err) => write!(f, "rpc request failed: {err}"),
+            Self::Rpc(err) => write!(f, "rpc error: {err}"),
+            Self::BatchNotFound => write!(f, "batch account not found"),
+        }
+    }
+}
+
+impl std::error::Error for ExportError {}
+
+impl From<hyper::Error> for ExportError {
+    fn from(err: hyper::Error) -> Self {
+        Self::Http(err)
+    }
+}
+
+pub struct RpcClient {
+    url: Uri,
+    client: Client<HttpConnector>,
+}
+
+impl RpcClient {
+    pub fn new(url: Uri) -> Self {
+        Self { url, client: Client::new() }
+    }
+
+    async fn call(&self, method: &str, params: Value) -> Result<Value, ExportError> {
+        let body = json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params });
+        let request = Request::builder()
+            .method(Method::POST)
+            .uri(self.url.clone())
+            .header("content-type", "application/json")
+            .body(Body::from(body.to_string()))
+            .expect("static request parts");
+
+        let response = self.client.request(request).await?;
+        let body = hyper::body::to_bytes(response.into_body()).await?;
+        let mut reply: Value =
+            serde_json::from_slice(&body).map_err(|e| ExportError::Rpc(e.to_string()))?;
+
+        if let Some(error) = reply.get("error") {
+            return Err(ExportError::Rpc(error.to_string()));
+        }
+        Ok(reply["result"].take())
+    }
+}
+
+fn field<'a>(value: &'a Value, pointer: &str) -> Result<&'a Value, ExportError> {
+    value
+        .pointer(pointer)
+        .ok_or_else(|| ExportError::Rpc(format!("missing {pointer} in {value}")))
+}
+
+fn string(value: &Value, pointer: &str) -> Result<String, ExportError> {
+    field(value, pointer)?
+        .as_str()
+        .map(str::to_string)
+        .ok_or_else(|| ExportError::Rpc(format!("{pointer} is not a string")))
+}
+
+fn snapshot_of(address: String, account: &Value) -> Result<AccountSnapshot, ExportError> {
+    Ok(AccountSnapshot {
+        address,
+        owner: string(account, "/owner")?,
+        data: string(account, "/data/0")?,
+    })
+}
+
+/// Read a batch's accounts and transaction logs and seal them into a
+/// bundle with the indexed snapshot and allocation proof
+pub async fn export_bundle(
+    rpc: &RpcClient,
+    program_id: &str,
+    batch: &str,
+    snapshot: BatchSnapshot,
+    proof: Option<AllocationProof>,
+    verification_key: Option<Vec<u8>>,
+) -> Result<AuditBundle, ExportError> {
+    let config = json!({ "encoding": "base64", "commitment": "finalized" });
+
+    let result = rpc.call("getAccountInfo", json!([batch, config])).await?;
+    let slot = field(&result, "/context/slot")?.as_u64().unwrap_or_default();
+    let batch_account = field(&result, "/value")?;
+    if batch_account.is_null() {
+        return Err(ExportError::BatchNotFound);
+    }
+    let mut accounts = vec![snapshot_of(batch.to_string(), batch_account)?];
+
+    let discriminator = bs58::encode(account_discriminator("OrderCommitment")).into_string();
+    let filters = json!([
+        { "dataSize": ORDER_ACCOUNT_SIZE },
+        { "memcmp": { "offset": 0, "bytes": discriminator } },
+        { "memcmp": { "offset": ORDER_BATCH_OFFSET, "bytes": batch } },
+    ]);
+    let orders = rpc
+        .call(
+            "getProgramAccounts",
+            json!([program_id, { "encoding": "base64", "commitment": "finalized", "filters": filters }]),
+        )
+        .await?;
+    for order in orders.as_array().into_iter().flatten() {
+        accounts.push(snapshot_of(string(order, "/pubkey")?, field(order, "/account")?)?);
+    }
+
+    let transactions = batch_transactions(rpc, batch).await?;
+
+    let mut bundle = AuditBundle {
+        version: BUNDLE_VERSION,
+        program_id: program_id.to_string(),
+        batch: batch.to_string(),
+        slot,
+        accounts,
+        transactions,
+        snapshot,
+        proof,
+        verification_key: verification_key.map(hex::encode),
+        digest: String::new(),
+    };
+    bundle.seal();
+    Ok(bundle)
+}
+
+/// Every finalized transaction mentioning the batch, oldest first
+async fn batch_transactions(rpc: &RpcClient, batch: &str) -> Result<Vec<TransactionRecord>, ExportError> {
+    let mut signatures = Vec::new();
+    let mut before: Option<String> = None;
+    loop {
+        let mut options = json!({ "limit": SIGNATURE_PAGE, "commitment": "finalized" });
+        if let Some(before) = &before {
+            options["before"] = json!(before);
+        }
+        let page = rpc.call("getSignaturesForAddress", json!([batch, options])).await?;
+        let page = page.as_array().cloned().unwrap_or_default();
+        for entry in &page {
+            // Failed transactions changed nothing
+            if entry["err"].is_null() {
+                signatures.push(string(entry, "/signature")?);
+            }
+        }
+        match page.last() {
+            Some(last) if page.len() == SIGNATURE_PAGE => before = Some(string(last, "/signature")?),
+            _ => break,
+        }
+    }
+    signatures.reverse();
+
+    let mut transactions = Vec::with_capacity(signatures.len());
+    for signature in signatures {
+        let options = json!({
+            "encoding": "json",
+            "commitment": "finalized",
+            "maxSupportedTransactionVersion": 0,
+        });
+        let tx = rpc.call("getTransaction", json!([signature, options])).await?;
+        let logs = field(&tx, "/meta/logMessages")?
+            .as_array()
+            .map(|logs| logs.iter().filter_map(|line| line.as_str().map(str::to_string)).collect())
+            .unwrap_or_default();
+        transactions.push(TransactionRecord {
+            signature,
+            slot: field(&tx, "/slot")?.as_u64().unwrap_or_default(),
+            logs,
+        });
+    }
+    Ok(transactions)
+}
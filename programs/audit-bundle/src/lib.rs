@@ -0,0 +1,552 @@
+//! Audit Bundles
+//!
+//! One JSON archive per executed batch holding everything an auditor
+//! needs to re-check it offline: the raw `TradingBatch` and
+//! `OrderCommitment` accounts, the logs of every transaction that touched
+//! the batch (order, execute and verify events), the indexed
+//! `BatchSnapshot`, and the allocation proof with its verification key.
+//!
+//! The batch root is an order-independent fold of per-order leaves, so the
+//! snapshot's order list is itself the inclusion proof for every order:
+//! `verify_bundle` replays it through `circuit_sim` and checks the result
+//! against the account and the `BatchExecuted` event. The MPC cluster's
+//! output signature travels in the recorded callback transaction and was
+//! checked on-chain by `verify_output`; re-checking it needs the cluster
+//! keys and is out of scope here.
+//!
+//! `replay` goes further for an auditor holding the orders' viewing key,
+//! re-running each decrypted order through the simulator.
+//!
+//! The bundle carries a sha256 digest over its own contents, so a bundle
+//! that was edited after export fails verification.
+
+use allocation_prover::{AllocationProof, BatchSnapshot, Witness, PUBLIC_INPUTS};
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::{fmt, fs, path::Path, process::Command};
+
+pub mod export;
+pub mod replay;
+
+pub const BUNDLE_VERSION: u8 = 1;
+
+/// `BatchStatus::Executed`; later statuses are executed too
+const STATUS_EXECUTED: u8 = 2;
+const STATUS_VERIFIED: u8 = 3;
+
+// ============================================
+// Bundle
+// ============================================
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditBundle {
+    pub version: u8,
+    /// Base58 privacy-trading program id
+    pub program_id: String,
+    /// Base58 `TradingBatch` address
+    pub batch: String,
+    /// Slot the accounts were read at
+    pub slot: u64,
+    /// The batch account, then its order commitments
+    pub accounts: Vec<AccountSnapshot>,
+    /// Transactions touching the batch, oldest first
+    pub transactions: Vec<TransactionRecord>,
+    pub snapshot: BatchSnapshot,
+    pub proof: Option<AllocationProof>,
+    /// Hex `bb` verification key of the allocation circuit
+    pub verification_key: Option<String>,
+    /// Hex sha256 of the bundle with this field empty
+    pub digest: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountSnapshot {
+    pub address: String,
+    pub owner: String,
+    /// Base64 account data
+    pub data: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionRecord {
+    pub signature: String,
+    pub slot: u64,
+    pub logs: Vec<String>,
+}
+
+impl AuditBundle {
+    pub fn compute_digest(&self) -> String {
+        let mut unsealed = self.clone();
+        unsealed.digest = String::new();
+        let json = serde_json::to_vec(&unsealed).expect("bundle serializes");
+        hex::encode(Sha256::digest(json))
+    }
+
+    /// Set `digest` over the current contents
+    pub fn seal(&mut self) {
+        self.digest = self.compute_digest();
+    }
+
+    pub fn read(path: &Path) -> Result<Self, String> {
+        let json = fs::read_to_string(path).map_err(|e| e.to_string())?;
+        serde_json::from_str(&json).map_err(|e| e.to_string())
+    }
+}
+
+// ============================================
+// Account Layouts
+// ============================================
+
+/// Anchor account discriminator for `name`
+pub fn account_discriminator(name: &str) -> [u8; 8] {
+    Sha256::digest(format!("account:{name}"))[..8].try_into().unwrap()
+}
+
+/// Anchor event discriminator for `name`
+pub fn event_discriminator(name: &str) -> [u8; 8] {
+    Sha256::digest(format!("event:{name}"))[..8].try_into().unwrap()
+}
+
+/// Leading `TradingBatch` fields, up to the merkle root
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BatchAccount {
+    pub authority: [u8; 32],
+    pub market_id: String,
+    pub status: u8,
+    pub order_count: u8,
+    pub total_usdc: u64,
+    pub merkle_root: [u8; 32],
+}
+
+/// Leading `OrderCommitment` fields, up to `index`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OrderAccount {
+    pub batch: [u8; 32],
+    pub user: [u8; 32],
+    pub commitment_hash: [u8; 32],
+    pub index: u8,
+}
+
+/// Size of an `OrderCommitment` account, for `getProgramAccounts` filters
+pub const ORDER_ACCOUNT_SIZE: usize = 8 + 1 + 32 + 32 + 32 + 1 + 1 + 33 + 1;
+
+/// Byte offset of `OrderCommitment::batch`
+pub const ORDER_BATCH_OFFSET: usize = 8 + 1;
+
+struct Reader<'a> {
+    data: &'a [u8],
+    at: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Option<&'a [u8]> {
+        let bytes = self.data.get(self.at..self.at + len)?;
+        self.at += len;
+        Some(bytes)
+    }
+
+    fn u8(&mut self) -> Option<u8> {
+        Some(self.take(1)?[0])
+    }
+
+    fn u64(&mut self) -> Option<u64> {
+        Some(u64::from_le_bytes(self.take(8)?.try_into().ok()?))
+    }
+
+    fn key(&mut self) -> Option<[u8; 32]> {
+        self.take(32)?.try_into().ok()
+    }
+
+    fn string(&mut self) -> Option<String> {
+        let len = u32::from_le_bytes(self.take(4)?.try_into().ok()?) as usize;
+        String::from_utf8(self.take(len)?.to_vec()).ok()
+    }
+}
+
+fn account_reader<'a>(data: &'a [u8], name: &str) -> Option<Reader<'a>> {
+    let mut reader = Reader { data, at: 0 };
+    (reader.take(8)? == account_discriminator(name)).then_some(reader)
+}
+
+impl BatchAccount {
+    pub fn decode(data: &[u8]) -> Option<Self> {
+        let mut r = account_reader(data, "TradingBatch")?;
+        let _bump = r.u8()?;
+        let authority = r.key()?;
+        // state_nonce, encrypted_state
+        r.take(16 + 32 * 8)?;
+        let market_id = r.string()?;
+        let _side = r.u8()?;
+        let status = r.u8()?;
+        let order_count = r.u8()?;
+        let total_usdc = r.u64()?;
+        let merkle_root = r.key()?;
+
+        Some(Self { authority, market_id, status, order_count, total_usdc, merkle_root })
+    }
+}
+
+impl OrderAccount {
+    pub fn decode(data: &[u8]) -> Option<Self> {
+        let mut r = account_reader(data, "OrderCommitment")?;
+        let _bump = r.u8()?;
+        Some(Self {
+            batch: r.key()?,
+            user: r.key()?,
+            commitment_hash: r.key()?,
+            index: r.u8()?,
+        })
+    }
+}
+
+// ============================================
+// Events
+// ============================================
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BatchEvent {
+    OrderAdded { batch: [u8; 32], order_index: u8, commitment_hash: [u8; 32] },
+    /// `reason` is the `OrderRejection` variant index
+    OrderRejected { batch: [u8; 32], order_index: u8, reason: u8 },
+    BatchExecuted { batch: [u8; 32], merkle_root: [u8; 32], total_usdc: u64, order_count: u8 },
+    AllocationVerified { batch: [u8; 32], merkle_root: [u8; 32], allocation_root: [u8; 32] },
+}
+
+/// Events of interest in a transaction's `Program data:` log lines
+pub fn decode_events(logs: &[String]) -> Vec<BatchEvent> {
+    logs.iter()
+        .filter_map(|line| line.strip_prefix("Program data: "))
+        .filter_map(|data| base64::engine::general_purpose::STANDARD.decode(data).ok())
+        .filter_map(|data| decode_event(&data))
+        .collect()
+}
+
+fn decode_event(data: &[u8]) -> Option<BatchEvent> {
+    let mut r = Reader { data, at: 8 };
+    let discriminator = data.get(..8)?;
+
+    if discriminator == event_discriminator("OrderAdded") {
+        let batch = r.key()?;
+        let _order = r.key()?;
+        Some(BatchEvent::OrderAdded { batch, order_index: r.u8()?, commitment_hash: r.key()? })
+    } else if discriminator == event_discriminator("OrderRejected") {
+        let batch = r.key()?;
+        let _order = r.key()?;
+        Some(BatchEvent::OrderRejected { batch, order_index: r.u8()?, reason: r.u8()? })
+    } else if discriminator == event_discriminator("BatchExecuted") {
+        Some(BatchEvent::BatchExecuted {
+            batch: r.key()?,
+            merkle_root: r.key()?,
+            total_usdc: r.u64()?,
+            order_count: r.u8()?,
+        })
+    } else if discriminator == event_discriminator("AllocationVerified") {
+        Some(BatchEvent::AllocationVerified {
+            batch: r.key()?,
+            merkle_root: r.key()?,
+            allocation_root: r.key()?,
+        })
+    } else {
+        None
+    }
+}
+
+// ============================================
+// Verification
+// ============================================
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome {
+    Passed,
+    Failed(String),
+    /// Not checkable from this bundle or machine
+    Skipped(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Check {
+    pub name: &'static str,
+    pub outcome: Outcome,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Report {
+    pub checks: Vec<Check>,
+}
+
+impl Report {
+    fn record(&mut self, name: &'static str, result: Result<(), String>) {
+        let outcome = match result {
+            Ok(()) => Outcome::Passed,
+            Err(reason) => Outcome::Failed(reason),
+        };
+        self.checks.push(Check { name, outcome });
+    }
+
+    fn skip(&mut self, name: &'static str, reason: &str) {
+        self.checks.push(Check { name, outcome: Outcome::Skipped(reason.to_string()) });
+    }
+
+    pub fn passed(&self) -> bool {
+        !self.checks.iter().any(|check| matches!(check.outcome, Outcome::Failed(_)))
+    }
+
+    pub fn outcome(&self, name: &str) -> Option<&Outcome> {
+        self.checks.iter().find(|check| check.name == name).map(|check| &check.outcome)
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for check in &self.checks {
+            match &check.outcome {
+                Outcome::Passed => writeln!(f, "ok      {}", check.name)?,
+                Outcome::Failed(reason) => writeln!(f, "FAILED  {}: {reason}", check.name)?,
+                Outcome::Skipped(reason) => writeln!(f, "skipped {}: {reason}", check.name)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+fn ensure(condition: bool, reason: impl Into<String>) -> Result<(), String> {
+    if condition {
+        Ok(())
+    } else {
+        Err(reason.into())
+    }
+}
+
+fn decode_key(address: &str) -> Result<[u8; 32], String> {
+    bs58::decode(address)
+        .into_vec()
+        .ok()
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or_else(|| format!("invalid address {address}"))
+}
+
+/// Re-check a bundle offline
+///
+/// Later checks build on the batch account, so a bundle without a readable
+/// one stops there. The proof itself is checked with `bb verify` when the
+/// bundle has a verification key and `bb` is on `PATH`.
+pub fn verify_bundle(bundle: &AuditBundle) -> Report {
+    let mut report = Report::default();
+
+    report.record("digest", ensure(bundle.compute_digest() == bundle.digest, "bundle was modified"));
+    report.record(
+        "version",
+        ensure(bundle.version == BUNDLE_VERSION, format!("unknown version {}", bundle.version)),
+    );
+
+    let batch = match read_batch(bundle) {
+        Ok(batch) => {
+            report.record("batch_account", Ok(()));
+            batch
+        }
+        Err(reason) => {
+            report.record("batch_account", Err(reason));
+            return report;
+        }
+    };
+
+    let witness = Witness::from_snapshot(&bundle.snapshot).map_err(|e| e.to_string());
+    report.record(
+        "replay",
+        witness.as_ref().map_err(Clone::clone).and_then(|_| {
+            ensure(
+                hex::encode(batch.merkle_root) == bundle.snapshot.merkle_root
+                    && batch.total_usdc == bundle.snapshot.total_usdc,
+                "snapshot root or total differs from the batch account",
+            )
+        }),
+    );
+    let orders = read_orders(bundle, &batch);
+    report.record("order_accounts", orders.as_ref().map(|_| ()).map_err(Clone::clone));
+
+    let events: Vec<BatchEvent> = bundle
+        .transactions
+        .iter()
+        .flat_map(|tx| decode_events(&tx.logs))
+        .collect();
+    report.record("events", check_events(bundle, &batch, orders.as_deref().unwrap_or(&[]), &events));
+
+    match (&bundle.proof, witness) {
+        (None, _) => {
+            report.skip("public_inputs", "bundle has no allocation proof");
+            report.skip("proof", "bundle has no allocation proof");
+        }
+        (Some(_), Err(_)) => report.skip("public_inputs", "snapshot does not replay"),
+        (Some(proof), Ok(witness)) => {
+            report.record("public_inputs", check_public_inputs(proof, &witness, &batch, &events));
+            match &bundle.verification_key {
+                Some(vk) => match verify_proof(proof, vk, &bundle.digest) {
+                    Some(result) => report.record("proof", result),
+                    None => report.skip("proof", "bb is not installed"),
+                },
+                None => report.skip("proof", "bundle has no verification key"),
+            }
+        }
+    }
+
+    report
+}
+
+fn read_batch(bundle: &AuditBundle) -> Result<BatchAccount, String> {
+    let account = bundle
+        .accounts
+        .iter()
+        .find(|account| account.address == bundle.batch)
+        .ok_or("batch account missing")?;
+    ensure(account.owner == bundle.program_id, "batch account not owned by the program")?;
+    let data = base64::engine::general_purpose::STANDARD
+        .decode(&account.data)
+        .map_err(|e| e.to_string())?;
+    let batch = BatchAccount::decode(&data).ok_or("batch account does not decode")?;
+    ensure(batch.status >= STATUS_EXECUTED, "batch is not executed")?;
+    Ok(batch)
+}
+
+/// Order accounts in index order, one per snapshot order
+fn read_orders(bundle: &AuditBundle, batch: &BatchAccount) -> Result<Vec<OrderAccount>, String> {
+    let batch_key = decode_key(&bundle.batch)?;
+    let mut orders = Vec::new();
+    for account in &bundle.accounts {
+        let Some(order) = base64::engine::general_purpose::STANDARD
+            .decode(&account.data)
+            .ok()
+            .and_then(|data| OrderAccount::decode(&data))
+        else {
+            continue;
+        };
+        ensure(account.owner == bundle.program_id, format!("{} not owned by the program", account.address))?;
+        ensure(order.batch == batch_key, format!("{} belongs to another batch", account.address))?;
+        orders.push(order);
+    }
+    orders.sort_by_key(|order| order.index);
+
+    ensure(
+        orders.len() == batch.order_count as usize && orders.len() == bundle.snapshot.orders.len(),
+        format!(
+            "{} order accounts, {} in the batch, {} in the snapshot",
+            orders.len(),
+            batch.order_count,
+            bundle.snapshot.orders.len()
+        ),
+    )?;
+    for (i, (order, snapshot)) in orders.iter().zip(&bundle.snapshot.orders).enumerate() {
+        ensure(order.index as usize == i, format!("order index {i} missing"))?;
+        ensure(
+            decode_key(&snapshot.user)? == order.user,
+            format!("order {i} user differs from the snapshot"),
+        )?;
+    }
+    Ok(orders)
+}
+
+fn check_events(
+    bundle: &AuditBundle,
+    batch: &BatchAccount,
+    orders: &[OrderAccount],
+    events: &[BatchEvent],
+) -> Result<(), String> {
+    let batch_key = decode_key(&bundle.batch)?;
+
+    let executed = events.iter().any(|event| {
+        *event
+            == BatchEvent::BatchExecuted {
+                batch: batch_key,
+                merkle_root: batch.merkle_root,
+                total_usdc: batch.total_usdc,
+                order_count: batch.order_count,
+            }
+    });
+    ensure(executed, "no BatchExecuted event matching the batch account")?;
+
+    // Orders the circuit rejected were folded with a zero amount and
+    // emitted OrderRejected instead
+    for order in orders {
+        let folded = events.iter().any(|event| match *event {
+            BatchEvent::OrderAdded { batch, order_index, commitment_hash } => {
+                batch == batch_key && order_index == order.index && commitment_hash == order.commitment_hash
+            }
+            BatchEvent::OrderRejected { batch, order_index, .. } => {
+                batch == batch_key && order_index == order.index
+            }
+            _ => false,
+        });
+        ensure(folded, format!("no OrderAdded or OrderRejected event for order {}", order.index))?;
+    }
+    Ok(())
+}
+
+/// Big-endian field element holding a u128, as `bb` writes them
+fn field(value: u128) -> [u8; 32] {
+    let mut bytes = [0u8; 32];
+    bytes[16..].copy_from_slice(&value.to_be_bytes());
+    bytes
+}
+
+fn check_public_inputs(
+    proof: &AllocationProof,
+    witness: &Witness,
+    batch: &BatchAccount,
+    events: &[BatchEvent],
+) -> Result<(), String> {
+    ensure(proof.public_inputs.len() == PUBLIC_INPUTS, "wrong number of public inputs")?;
+    let expected = [
+        witness.merkle_root_lo,
+        witness.merkle_root_hi,
+        witness.total_usdc as u128,
+        witness.total_shares as u128,
+        witness.execution_price as u128,
+    ];
+    for (i, value) in expected.iter().enumerate() {
+        ensure(proof.public_inputs[i] == field(*value), format!("public input {i} differs from the snapshot"))?;
+    }
+
+    if batch.status >= STATUS_VERIFIED {
+        let verified = events.iter().any(|event| {
+            matches!(event, BatchEvent::AllocationVerified { merkle_root, allocation_root, .. }
+                if *merkle_root == batch.merkle_root && *allocation_root == proof.public_inputs[PUBLIC_INPUTS - 1])
+        });
+        ensure(verified, "no AllocationVerified event for this proof")?;
+    }
+    Ok(())
+}
+
+/// `bb verify` in a scratch directory; `None` when `bb` can't be run
+fn verify_proof(proof: &AllocationProof, verification_key: &str, digest: &str) -> Option<Result<(), String>> {
+    let vk = match hex::decode(verification_key) {
+        Ok(vk) => vk,
+        Err(e) => return Some(Err(format!("verification key: {e}"))),
+    };
+    let dir = std::env::temp_dir().join(format!("audit-bundle-{digest}"));
+    let written = fs::create_dir_all(&dir)
+        .and_then(|_| fs::write(dir.join("vk"), vk))
+        .and_then(|_| fs::write(dir.join("proof"), &proof.proof_data))
+        .and_then(|_| fs::write(dir.join("public_inputs"), proof.public_inputs.concat()));
+    if let Err(e) = written {
+        return Some(Err(e.to_string()));
+    }
+
+    let output = Command::new("bb")
+        .args(["verify", "--scheme", "ultra_honk", "-k"])
+        .arg(dir.join("vk"))
+        .arg("-p")
+        .arg(dir.join("proof"))
+        .arg("-i")
+        .arg(dir.join("public_inputs"))
+        .output();
+    let _ = fs::remove_dir_all(&dir);
+
+    let output = output.ok()?;
+    Some(ensure(
+        output.status.success(),
+        String::from_utf8_lossy(&output.stderr).into_owned(),
+    ))
+}
@@ -0,0 +1,161 @@
+//! Deterministic replay of a batch's orders
+//!
+//! Feeds every `add_order` input of a bundled batch through `circuit_sim`
+//! in order, with the same signer binding, expiry and overflow rules the
+//! circuit applies, and checks the outcome against what the MPC posted:
+//! each order's `OrderAdded` / `OrderRejected` event, the batch total and
+//! the merkle root. A mismatch means the cluster misbehaved or the circuit
+//! and the simulator drifted apart.
+//!
+//! The inputs are the auditor's decryption of each order's `Enc<Shared>`
+//! ciphertexts with their viewing key, done by the Arcium client; this
+//! module only sees plaintexts. Signers come from the order accounts and
+//! queue slots from the bundled `add_order` transactions.
+
+use crate::{
+    decode_events, decode_key, ensure, read_batch, read_orders, AuditBundle, BatchEvent, Report,
+};
+use circuit_sim::{add_order, bind_order, check_total, execute_batch, expire_order, init_batch, Order};
+use serde::{Deserialize, Serialize};
+
+/// Log line anchor writes for each `add_order` instruction
+const ADD_ORDER_LOG: &str = "Program log: Instruction: AddOrder";
+
+/// `OrderRejection` variant indices
+const REJECT_WALLET_MISMATCH: u8 = 0;
+const REJECT_AMOUNT_OVERFLOW: u8 = 1;
+const REJECT_EXPIRED: u8 = 2;
+
+/// One order's decrypted `add_order` inputs
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct DecryptedOrder {
+    pub index: u8,
+    pub amount: u64,
+    /// Base58 wallet from `encrypted_wallet_lo/hi`
+    pub wallet: String,
+    pub expiry_slot: u64,
+}
+
+/// What the circuit should have revealed for an order
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Fold {
+    Added,
+    /// `OrderRejection` variant index
+    Rejected(u8),
+}
+
+/// Replay `orders` against the bundled batch
+///
+/// Run `verify_bundle` first: this trusts the bundle's accounts and
+/// events once its digest checks out.
+pub fn replay_batch(bundle: &AuditBundle, orders: &[DecryptedOrder]) -> Report {
+    let mut report = Report::default();
+
+    report.record("digest", ensure(bundle.compute_digest() == bundle.digest, "bundle was modified"));
+    let accounts = read_batch(bundle).and_then(|batch| {
+        let order_accounts = read_orders(bundle, &batch)?;
+        Ok((batch, order_accounts))
+    });
+    let (batch, order_accounts) = match accounts {
+        Ok(accounts) => accounts,
+        Err(reason) => {
+            report.record("accounts", Err(reason));
+            return report;
+        }
+    };
+
+    let mut orders = orders.to_vec();
+    orders.sort_by_key(|order| order.index);
+    let queue_slots: Vec<u64> = bundle
+        .transactions
+        .iter()
+        .filter(|tx| tx.logs.iter().any(|line| line == ADD_ORDER_LOG))
+        .map(|tx| tx.slot)
+        .collect();
+    let inputs = ensure(
+        orders.iter().enumerate().all(|(i, order)| order.index as usize == i)
+            && orders.len() == order_accounts.len()
+            && queue_slots.len() == order_accounts.len(),
+        format!(
+            "{} decrypted orders and {} add_order transactions for {} orders",
+            orders.len(),
+            queue_slots.len(),
+            order_accounts.len()
+        ),
+    )
+    .and_then(|_| orders.iter().map(|order| decode_key(&order.wallet)).collect());
+    let wallets: Vec<[u8; 32]> = match inputs {
+        Ok(wallets) => {
+            report.record("inputs", Ok(()));
+            wallets
+        }
+        Err(reason) => {
+            report.record("inputs", Err(reason));
+            return report;
+        }
+    };
+
+    let mut state = init_batch();
+    let mut folds = Vec::with_capacity(orders.len());
+    for (i, order) in orders.iter().enumerate() {
+        let decrypted = Order::new(order.amount, &wallets[i]);
+        let (bound, wallet_bound) = bind_order(&decrypted, &order_accounts[i].user);
+        let (folded, expired) = expire_order(&bound, order.expiry_slot, queue_slots[i]);
+        let (_, amount_overflow) = check_total(state.total_amount, folded.amount);
+
+        folds.push(if !wallet_bound {
+            Fold::Rejected(REJECT_WALLET_MISMATCH)
+        } else if expired {
+            Fold::Rejected(REJECT_EXPIRED)
+        } else if amount_overflow {
+            Fold::Rejected(REJECT_AMOUNT_OVERFLOW)
+        } else {
+            Fold::Added
+        });
+        state = add_order(&state, &folded);
+    }
+
+    let batch_key = decode_key(&bundle.batch).unwrap_or_default();
+    let events: Vec<BatchEvent> = bundle
+        .transactions
+        .iter()
+        .flat_map(|tx| decode_events(&tx.logs))
+        .collect();
+    let mismatched: Vec<String> = folds
+        .iter()
+        .enumerate()
+        .filter(|(i, fold)| {
+            let index = *i as u8;
+            !events.iter().any(|event| match (*fold, event) {
+                (Fold::Added, BatchEvent::OrderAdded { batch, order_index, .. }) => {
+                    *batch == batch_key && *order_index == index
+                }
+                (Fold::Rejected(expected), BatchEvent::OrderRejected { batch, order_index, reason }) => {
+                    *batch == batch_key && *order_index == index && *reason == *expected
+                }
+                _ => false,
+            })
+        })
+        .map(|(i, fold)| format!("order {i} should be {fold:?}"))
+        .collect();
+    report.record("order_outcomes", ensure(mismatched.is_empty(), mismatched.join(", ")));
+
+    report.record(
+        "total",
+        ensure(
+            state.total_amount == batch.total_usdc,
+            format!("replayed {}, posted {}", state.total_amount, batch.total_usdc),
+        ),
+    );
+    let output = execute_batch(&state, bundle.snapshot.total_shares, bundle.snapshot.execution_price);
+    report.record(
+        "merkle_root",
+        ensure(
+            output.merkle_root == batch.merkle_root,
+            format!("replayed {}, posted {}", hex::encode(output.merkle_root), hex::encode(batch.merkle_root)),
+        ),
+    );
+
+    report
+}
@@ -0,0 +1,76 @@
+mod common;
+
+use allocation_prover::{AllocationProof, Witness};
+use audit_bundle::*;
+use common::*;
+
+#[test]
+fn exported_bundle_verifies() {
+    let report = verify_bundle(&bundle(&[10, 30, 60]));
+
+    assert!(report.passed(), "{report}");
+    assert_eq!(report.outcome("replay"), Some(&Outcome::Passed));
+    assert_eq!(report.outcome("events"), Some(&Outcome::Passed));
+    assert!(matches!(report.outcome("proof"), Some(Outcome::Skipped(_))));
+}
+
+#[test]
+fn edited_bundle_fails_its_digest() {
+    let mut bundle = bundle(&[10, 30]);
+    bundle.slot += 1;
+
+    assert!(matches!(verify_bundle(&bundle).outcome("digest"), Some(Outcome::Failed(_))));
+}
+
+#[test]
+fn tampered_snapshot_fails_replay() {
+    let mut bundle = bundle(&[10, 30]);
+    bundle.snapshot.orders[0].amount += 1;
+    bundle.snapshot.orders[1].amount -= 1;
+    bundle.seal();
+
+    let report = verify_bundle(&bundle);
+    assert!(!report.passed());
+    assert!(matches!(report.outcome("replay"), Some(Outcome::Failed(_))));
+}
+
+#[test]
+fn missing_order_event_fails() {
+    let mut bundle = bundle(&[10, 30]);
+    bundle.transactions.last_mut().unwrap().logs.remove(1);
+    bundle.seal();
+
+    assert!(matches!(verify_bundle(&bundle).outcome("events"), Some(Outcome::Failed(_))));
+}
+
+#[test]
+fn rejected_order_counts_as_folded() {
+    let mut bundle = bundle(&[10, 30]);
+    let logs = &mut bundle.transactions.last_mut().unwrap().logs;
+    logs[1] = event("OrderRejected", &[&BATCH, &[0u8; 32], &[1], &[2]]);
+    bundle.seal();
+
+    assert_eq!(verify_bundle(&bundle).outcome("events"), Some(&Outcome::Passed));
+}
+
+#[test]
+fn proof_public_inputs_must_match_the_snapshot() {
+    let mut bundle = bundle(&[10, 30]);
+    let witness = Witness::from_snapshot(&bundle.snapshot).unwrap();
+    let mut public_inputs = vec![
+        field(witness.merkle_root_lo),
+        field(witness.merkle_root_hi),
+        field(witness.total_usdc as u128),
+        field(1_000),
+        field(5_000),
+        [9u8; 32],
+    ];
+    bundle.proof = Some(AllocationProof { proof_data: vec![0u8; 64], public_inputs: public_inputs.clone() });
+    bundle.seal();
+    assert_eq!(verify_bundle(&bundle).outcome("public_inputs"), Some(&Outcome::Passed));
+
+    public_inputs[3] = field(1_001);
+    bundle.proof = Some(AllocationProof { proof_data: vec![0u8; 64], public_inputs });
+    bundle.seal();
+    assert!(matches!(verify_bundle(&bundle).outcome("public_inputs"), Some(Outcome::Failed(_))));
+}
@@ -0,0 +1,119 @@
+#![allow(dead_code)]
+
+use allocation_prover::{BatchSnapshot, OrderSnapshot};
+use audit_bundle::*;
+use base64::Engine;
+use circuit_sim::{run_batch, Order};
+
+pub const PROGRAM: [u8; 32] = [50u8; 32];
+pub const BATCH: [u8; 32] = [60u8; 32];
+
+pub fn b58(key: &[u8; 32]) -> String {
+    bs58::encode(key).into_string()
+}
+
+pub fn b64(data: &[u8]) -> String {
+    base64::engine::general_purpose::STANDARD.encode(data)
+}
+
+pub fn batch_data(status: u8, order_count: u8, total_usdc: u64, merkle_root: [u8; 32]) -> Vec<u8> {
+    let mut data = account_discriminator("TradingBatch").to_vec();
+    data.push(255);
+    data.extend_from_slice(&[1u8; 32]);
+    data.extend_from_slice(&[0u8; 16 + 32 * 8]);
+    data.extend_from_slice(&4u32.to_le_bytes());
+    data.extend_from_slice(b"mkt1");
+    data.extend_from_slice(&[0, status, order_count]);
+    data.extend_from_slice(&total_usdc.to_le_bytes());
+    data.extend_from_slice(&merkle_root);
+    data.extend_from_slice(&[0u8; 64]);
+    data
+}
+
+pub fn order_data(user: &[u8; 32], index: u8) -> Vec<u8> {
+    let mut data = account_discriminator("OrderCommitment").to_vec();
+    data.push(255);
+    data.extend_from_slice(&BATCH);
+    data.extend_from_slice(user);
+    data.extend_from_slice(&[index + 100; 32]);
+    data.extend_from_slice(&[index, 0, 0, 0]);
+    data
+}
+
+pub fn event(name: &str, fields: &[&[u8]]) -> String {
+    let mut data = event_discriminator(name).to_vec();
+    fields.iter().for_each(|field| data.extend_from_slice(field));
+    format!("Program data: {}", b64(&data))
+}
+
+pub fn bundle(amounts: &[u64]) -> AuditBundle {
+    let wallets: Vec<[u8; 32]> = (0..amounts.len()).map(|i| [i as u8 + 1; 32]).collect();
+    let orders: Vec<Order> = amounts.iter().zip(&wallets).map(|(&a, w)| Order::new(a, w)).collect();
+    let (state, output) = run_batch(&orders, 1_000, 5_000);
+    let count = amounts.len() as u8;
+
+    let mut accounts = vec![AccountSnapshot {
+        address: b58(&BATCH),
+        owner: b58(&PROGRAM),
+        data: b64(&batch_data(2, count, state.total_amount, output.merkle_root)),
+    }];
+    let mut logs = Vec::new();
+    for (i, wallet) in wallets.iter().enumerate() {
+        accounts.push(AccountSnapshot {
+            address: b58(&[i as u8 + 200; 32]),
+            owner: b58(&PROGRAM),
+            data: b64(&order_data(wallet, i as u8)),
+        });
+        let index = [i as u8];
+        logs.push(event("OrderAdded", &[&BATCH, &[0u8; 32], &index, &[i as u8 + 100; 32]]));
+    }
+    logs.push(event(
+        "BatchExecuted",
+        &[&BATCH, &output.merkle_root, &state.total_amount.to_le_bytes(), &[count]],
+    ));
+
+    let mut bundle = AuditBundle {
+        version: BUNDLE_VERSION,
+        program_id: b58(&PROGRAM),
+        batch: b58(&BATCH),
+        slot: 42,
+        accounts,
+        transactions: queue_transactions(count)
+            .into_iter()
+            .chain([TransactionRecord { signature: "sig".into(), slot: 41, logs }])
+            .collect(),
+        snapshot: BatchSnapshot {
+            merkle_root: hex::encode(output.merkle_root),
+            total_usdc: state.total_amount,
+            total_shares: 1_000,
+            execution_price: 5_000,
+            orders: amounts
+                .iter()
+                .zip(&wallets)
+                .map(|(&amount, wallet)| OrderSnapshot { user: b58(wallet), amount })
+                .collect(),
+        },
+        proof: None,
+        verification_key: None,
+        digest: String::new(),
+    };
+    bundle.seal();
+    bundle
+}
+
+pub fn field(value: u128) -> [u8; 32] {
+    let mut bytes = [0u8; 32];
+    bytes[16..].copy_from_slice(&value.to_be_bytes());
+    bytes
+}
+
+/// One `add_order` transaction per order, queued at slot `10 + index`
+pub fn queue_transactions(count: u8) -> Vec<TransactionRecord> {
+    (0..count)
+        .map(|i| TransactionRecord {
+            signature: format!("queue{i}"),
+            slot: 10 + i as u64,
+            logs: vec!["Program log: Instruction: AddOrder".into()],
+        })
+        .collect()
+}
@@ -0,0 +1,68 @@
+mod common;
+
+use audit_bundle::replay::*;
+use audit_bundle::*;
+use common::*;
+
+fn decrypted(amounts: &[u64], expiry_slot: u64) -> Vec<DecryptedOrder> {
+    amounts
+        .iter()
+        .enumerate()
+        .map(|(i, &amount)| DecryptedOrder {
+            index: i as u8,
+            amount,
+            wallet: b58(&[i as u8 + 1; 32]),
+            expiry_slot,
+        })
+        .collect()
+}
+
+#[test]
+fn honest_batch_replays() {
+    let report = replay_batch(&bundle(&[10, 30, 60]), &decrypted(&[10, 30, 60], 1_000));
+
+    assert!(report.passed(), "{report}");
+    assert_eq!(report.outcome("merkle_root"), Some(&Outcome::Passed));
+}
+
+#[test]
+fn expired_order_replays_as_rejected() {
+    // Order 1 (queued at slot 11) expired at slot 5 and was folded as zero
+    let mut bundle = bundle(&[10, 0]);
+    let logs = &mut bundle.transactions.last_mut().unwrap().logs;
+    logs[1] = event("OrderRejected", &[&BATCH, &[0u8; 32], &[1], &[2]]);
+    bundle.seal();
+
+    let mut orders = decrypted(&[10, 30], 1_000);
+    orders[1].expiry_slot = 5;
+    let report = replay_batch(&bundle, &orders);
+
+    assert!(report.passed(), "{report}");
+}
+
+#[test]
+fn order_the_mpc_should_have_rejected_is_caught() {
+    let mut orders = decrypted(&[10, 30], 1_000);
+    orders[1].expiry_slot = 5;
+    let report = replay_batch(&bundle(&[10, 30]), &orders);
+
+    assert!(matches!(report.outcome("order_outcomes"), Some(Outcome::Failed(_))));
+    assert!(matches!(report.outcome("total"), Some(Outcome::Failed(_))));
+    assert!(matches!(report.outcome("merkle_root"), Some(Outcome::Failed(_))));
+}
+
+#[test]
+fn misreported_amount_fails_the_root() {
+    let report = replay_batch(&bundle(&[10, 30]), &decrypted(&[11, 29], 1_000));
+
+    assert_eq!(report.outcome("total"), Some(&Outcome::Passed));
+    assert!(matches!(report.outcome("merkle_root"), Some(Outcome::Failed(_))));
+}
+
+#[test]
+fn missing_inputs_stop_the_replay() {
+    let report = replay_batch(&bundle(&[10, 30]), &decrypted(&[10], 1_000));
+
+    assert!(matches!(report.outcome("inputs"), Some(Outcome::Failed(_))));
+    assert_eq!(report.outcome("merkle_root"), None);
+}
@@ -0,0 +1,155 @@
+//! Privacy-Trading Backtests
+//!
+//! Replays a [`Tape`] of recorded batch history through a [`Strategy`].
+//! The strategy sees each batch as it opens, with the public history up to
+//! that slot, and may join it with one order. An order fills when its
+//! batch executed in the recording, at that batch's clearing price from
+//! the fill bulletin, and pays out when the market resolves.
+//!
+//! The strategy's orders do not change the recording: a batch that filled
+//! up or closed early without them does so with them too, and the price
+//! is the one the batch cleared at without them.
+
+use privacy_sdk::simulate::{BatchStatus, BatchView, MarketHistory, Side};
+use std::collections::BTreeMap;
+
+pub mod tape;
+
+pub use tape::{Event, Resolution, Tape};
+
+/// Collateral base units a winning share pays; execution prices are
+/// quoted in these units per share
+pub const SHARE_PAYOUT: u64 = 1_000_000;
+
+pub trait Strategy {
+    /// A batch opened at `slot`; return an amount to join it with one order
+    fn on_batch_open(
+        &mut self,
+        slot: u64,
+        batch: &BatchView,
+        history: &MarketHistory,
+    ) -> Option<u64>;
+
+    /// A market resolved, settling the strategy's positions in it
+    fn on_resolution(&mut self, _resolution: &Resolution, _settled: &[Settlement]) {}
+}
+
+/// Shares held on one side of a market
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Position {
+    pub market_id: String,
+    pub side: Side,
+    /// Collateral paid for the shares
+    pub stake: u64,
+    /// In millionths of a share, so a winning position pays `shares`
+    /// base units
+    pub shares: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Settlement {
+    pub position: Position,
+    pub payout: u64,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Report {
+    pub orders: u64,
+    pub filled: u64,
+    /// Orders whose batch never executed in the recording
+    pub unfilled: u64,
+    /// Orders whose batch executed with no bulletin fill to price it
+    pub unpriced: u64,
+    pub settled: Vec<Settlement>,
+    /// Filled positions still open when the tape ends
+    pub open: Vec<Position>,
+}
+
+impl Report {
+    /// Profit over settled positions, in collateral base units
+    pub fn realized_pnl(&self) -> i128 {
+        self.settled
+            .iter()
+            .map(|settlement| settlement.payout as i128 - settlement.position.stake as i128)
+            .sum()
+    }
+}
+
+/// Replay `tape` through `strategy`
+pub fn run(tape: &Tape, strategy: &mut impl Strategy) -> Report {
+    let mut report = Report::default();
+    let mut history = MarketHistory::new();
+    // Batch pubkeys seen open, then the strategy's order amount in each
+    let mut opened: BTreeMap<&str, Option<u64>> = BTreeMap::new();
+    let mut positions: BTreeMap<(String, u8), Position> = BTreeMap::new();
+
+    for event in &tape.events {
+        match event {
+            Event::Batch(update) => {
+                history.record_batch(update.pubkey.clone(), update.slot, update.view.clone());
+                let view = &update.view;
+                match view.status {
+                    BatchStatus::Open if !opened.contains_key(update.pubkey.as_str()) => {
+                        let amount = strategy
+                            .on_batch_open(update.slot, view, &history)
+                            .filter(|amount| *amount > 0);
+                        report.orders += amount.is_some() as u64;
+                        opened.insert(&update.pubkey, amount);
+                    }
+                    BatchStatus::Executed | BatchStatus::Verified => {
+                        let Some(amount) = opened
+                            .get_mut(update.pubkey.as_str())
+                            .and_then(Option::take)
+                        else {
+                            continue;
+                        };
+                        let Some(&price) =
+                            tape.prices.get(&update.pubkey).filter(|price| **price > 0)
+                        else {
+                            report.unpriced += 1;
+                            continue;
+                        };
+                        report.filled += 1;
+                        let shares = (amount as u128 * SHARE_PAYOUT as u128 / price as u128) as u64;
+                        let position = positions
+                            .entry((view.market_id.clone(), view.side as u8))
+                            .or_insert_with(|| Position {
+                                market_id: view.market_id.clone(),
+                                side: view.side,
+                                stake: 0,
+                                shares: 0,
+                            });
+                        position.stake += amount;
+                        position.shares += shares;
+                    }
+                    _ => {}
+                }
+            }
+            Event::FillPublished {
+                market_id, fill, ..
+            } => history.record_fill(market_id, *fill),
+            Event::Resolved(resolution) => {
+                let settled: Vec<Settlement> = [Side::Yes, Side::No]
+                    .into_iter()
+                    .filter_map(|side| {
+                        positions.remove(&(resolution.market_id.clone(), side as u8))
+                    })
+                    .map(|position| Settlement {
+                        payout: if position.side == resolution.outcome {
+                            position.shares
+                        } else {
+                            0
+                        },
+                        position,
+                    })
+                    .collect();
+                strategy.on_resolution(resolution, &settled);
+                report.settled.extend(settled);
+            }
+        }
+    }
+
+    report.unfilled = opened.values().filter(|amount| amount.is_some()).count() as u64;
+    report.open = positions.into_values().collect();
+    report
+}
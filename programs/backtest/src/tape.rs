@@ -0,0 +1,130 @@
+//! Replay tapes from recorded history
+//!
+//! A tape merges three public sources into one slot-ordered stream: the
+//! indexer's `TradingBatch` updates, the markets' fill bulletins and the
+//! oracles' resolutions. Batches carry no clearing price in the indexer's
+//! view, so each executed batch is priced from the bulletin fill with its
+//! market, side and execution epoch; fills are matched in order, so two
+//! same-side batches executing in one epoch take that epoch's fills in
+//! execution order.
+
+use privacy_sdk::simulate::{
+    read_batch_update, BatchStatus, BatchUpdate, FillTape, PublicFill, Side,
+};
+use serde::Deserialize;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// `FILL_BULLETIN_DELAY_SLOTS` in the program: the length of a fill's
+/// epoch and the earliest a fill reaches the bulletin after execution
+pub const FILL_BULLETIN_DELAY_SLOTS: u64 = 1_500;
+
+/// A market outcome, as an oracle's `OracleResolved` event reports it
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct Resolution {
+    pub market_id: String,
+    pub outcome: Side,
+    /// Slot of the `resolve_oracle` callback
+    pub slot: u64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Event {
+    Batch(BatchUpdate),
+    /// A fill becoming visible on its market's bulletin
+    FillPublished {
+        slot: u64,
+        market_id: String,
+        fill: PublicFill,
+    },
+    Resolved(Resolution),
+}
+
+impl Event {
+    pub fn slot(&self) -> u64 {
+        match self {
+            Self::Batch(update) => update.slot,
+            Self::FillPublished { slot, .. } => *slot,
+            Self::Resolved(resolution) => resolution.slot,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Tape {
+    /// In slot order; same-slot events keep their source order
+    pub events: Vec<Event>,
+    /// Clearing price per executed batch pubkey, where a fill matched
+    pub prices: BTreeMap<String, u64>,
+}
+
+impl Tape {
+    pub fn new(
+        mut updates: Vec<BatchUpdate>,
+        fill_tapes: &[FillTape],
+        resolutions: Vec<Resolution>,
+    ) -> Self {
+        updates.sort_by_key(|update| update.slot);
+
+        let mut unmatched: BTreeMap<&str, Vec<&PublicFill>> = BTreeMap::new();
+        for tape in fill_tapes {
+            unmatched
+                .entry(&tape.market_id)
+                .or_default()
+                .extend(&tape.fills);
+        }
+
+        let mut events = Vec::new();
+        let mut prices = BTreeMap::new();
+        let mut executed = BTreeSet::new();
+        for update in updates {
+            let view = &update.view;
+            let executes = matches!(view.status, BatchStatus::Executed | BatchStatus::Verified);
+            if executes && executed.insert(update.pubkey.clone()) {
+                let epoch_slot = update.slot - update.slot % FILL_BULLETIN_DELAY_SLOTS;
+                let fills = unmatched.get_mut(view.market_id.as_str());
+                let matched = fills.and_then(|fills| {
+                    let at = fills
+                        .iter()
+                        .position(|fill| fill.side == view.side && fill.epoch_slot == epoch_slot)?;
+                    Some(*fills.remove(at))
+                });
+                if let Some(fill) = matched {
+                    prices.insert(update.pubkey.clone(), fill.execution_price);
+                    events.push(Event::FillPublished {
+                        slot: update.slot + FILL_BULLETIN_DELAY_SLOTS,
+                        market_id: view.market_id.clone(),
+                        fill,
+                    });
+                }
+            }
+            events.push(Event::Batch(update));
+        }
+        events.extend(resolutions.into_iter().map(Event::Resolved));
+        events.sort_by_key(Event::slot);
+
+        Self { events, prices }
+    }
+
+    /// Build a tape from the indexer's JSON lines; blank lines and other
+    /// account types are skipped
+    pub fn from_indexer(
+        lines: &str,
+        fill_tapes: &[FillTape],
+        resolutions: Vec<Resolution>,
+    ) -> Result<Self, String> {
+        let mut updates = Vec::new();
+        for (i, line) in lines
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+        {
+            if let Some(update) =
+                read_batch_update(line).map_err(|e| format!("line {}: {e}", i + 1))?
+            {
+                updates.push(update);
+            }
+        }
+        Ok(Self::new(updates, fill_tapes, resolutions))
+    }
+}
@@ -0,0 +1,197 @@
+use privacy_backtest::*;
+use privacy_sdk::simulate::{
+    estimate, BatchStatus, BatchUpdate, BatchView, FillTape, MarketHistory, PublicFill, Side,
+};
+
+const MARKET: &str = "btc-100k";
+
+fn update(pubkey: &str, slot: u64, side: Side, status: BatchStatus) -> BatchUpdate {
+    let (order_count, total_usdc) = match status {
+        BatchStatus::Open => (0, 0),
+        _ => (4, 40_000_000),
+    };
+    BatchUpdate {
+        pubkey: pubkey.to_string(),
+        slot,
+        view: BatchView {
+            market_id: MARKET.to_string(),
+            side,
+            status,
+            order_count,
+            total_usdc,
+        },
+    }
+}
+
+fn fill(side: Side, execution_price: u64, executed_at: u64) -> PublicFill {
+    PublicFill {
+        side,
+        execution_price,
+        size_bucket: 2,
+        epoch_slot: executed_at - executed_at % tape::FILL_BULLETIN_DELAY_SLOTS,
+    }
+}
+
+fn resolution(outcome: Side, slot: u64) -> Resolution {
+    Resolution {
+        market_id: MARKET.to_string(),
+        outcome,
+        slot,
+    }
+}
+
+/// Joins every batch with 1 USDC, recording the resolutions it saw
+#[derive(Default)]
+struct Always {
+    resolutions: Vec<(Side, usize)>,
+}
+
+impl Strategy for Always {
+    fn on_batch_open(
+        &mut self,
+        _slot: u64,
+        _batch: &BatchView,
+        _history: &MarketHistory,
+    ) -> Option<u64> {
+        Some(1_000_000)
+    }
+
+    fn on_resolution(&mut self, resolution: &Resolution, settled: &[Settlement]) {
+        self.resolutions.push((resolution.outcome, settled.len()));
+    }
+}
+
+#[test]
+fn orders_fill_at_the_matched_clearing_price_and_settle() {
+    let updates = vec![
+        update("yes1", 100, Side::Yes, BatchStatus::Open),
+        update("no1", 120, Side::No, BatchStatus::Open),
+        update("yes1", 3_100, Side::Yes, BatchStatus::Executed),
+        update("no1", 3_200, Side::No, BatchStatus::Executed),
+        update("yes1", 3_300, Side::Yes, BatchStatus::Verified),
+        // Opened but never executed, and executed with no fill to price it
+        update("yes2", 4_000, Side::Yes, BatchStatus::Open),
+        update("yes2", 5_000, Side::Yes, BatchStatus::Closed),
+        update("no2", 4_100, Side::No, BatchStatus::Open),
+        update("no2", 9_000, Side::No, BatchStatus::Executed),
+    ];
+    let fills = FillTape {
+        market_id: MARKET.to_string(),
+        last_published_at: 6_000,
+        fills: vec![
+            fill(Side::Yes, 250_000, 3_100),
+            fill(Side::No, 800_000, 3_200),
+        ],
+    };
+    let tape = Tape::new(updates, &[fills], vec![resolution(Side::Yes, 20_000)]);
+
+    let mut strategy = Always::default();
+    let report = run(&tape, &mut strategy);
+
+    assert_eq!(tape.prices.len(), 2);
+    assert_eq!(report.orders, 4);
+    assert_eq!(report.filled, 2);
+    assert_eq!(report.unfilled, 1);
+    assert_eq!(report.unpriced, 1);
+    assert!(report.open.is_empty());
+    assert_eq!(strategy.resolutions, vec![(Side::Yes, 2)]);
+
+    // 1 USDC at 0.25 buys 4 YES shares, paying 4 USDC; the NO stake is lost
+    let yes = report
+        .settled
+        .iter()
+        .find(|s| s.position.side == Side::Yes)
+        .unwrap();
+    assert_eq!((yes.position.shares, yes.payout), (4_000_000, 4_000_000));
+    let no = report
+        .settled
+        .iter()
+        .find(|s| s.position.side == Side::No)
+        .unwrap();
+    assert_eq!((no.position.stake, no.payout), (1_000_000, 0));
+    assert_eq!(report.realized_pnl(), 2_000_000);
+}
+
+#[test]
+fn positions_stay_open_without_a_resolution() {
+    let updates = vec![
+        update("yes1", 100, Side::Yes, BatchStatus::Open),
+        update("yes1", 200, Side::Yes, BatchStatus::Executed),
+    ];
+    let fills = FillTape {
+        market_id: MARKET.to_string(),
+        last_published_at: 0,
+        fills: vec![fill(Side::Yes, 500_000, 200)],
+    };
+    let report = run(
+        &Tape::new(updates, &[fills], vec![]),
+        &mut Always::default(),
+    );
+
+    assert_eq!(report.open.len(), 1);
+    assert_eq!(report.open[0].shares, 2_000_000);
+    assert_eq!(report.realized_pnl(), 0);
+}
+
+/// Joins only once the bulletin shows a cheap enough fill
+struct Cheap;
+
+impl Strategy for Cheap {
+    fn on_batch_open(
+        &mut self,
+        slot: u64,
+        batch: &BatchView,
+        history: &MarketHistory,
+    ) -> Option<u64> {
+        let estimate = estimate(history, batch, 1_000_000, slot);
+        estimate
+            .expected_price
+            .filter(|price| *price <= 300_000)
+            .map(|_| 1_000_000)
+    }
+}
+
+#[test]
+fn strategies_see_fills_only_once_published() {
+    let updates = vec![
+        update("a", 100, Side::Yes, BatchStatus::Open),
+        update("a", 200, Side::Yes, BatchStatus::Executed),
+        // Before a's fill can reach the bulletin
+        update("b", 1_000, Side::Yes, BatchStatus::Open),
+        update("b", 1_100, Side::Yes, BatchStatus::Executed),
+        update("c", 2_000, Side::Yes, BatchStatus::Open),
+        update("c", 2_100, Side::Yes, BatchStatus::Executed),
+    ];
+    let fills = FillTape {
+        market_id: MARKET.to_string(),
+        last_published_at: 0,
+        fills: vec![
+            fill(Side::Yes, 200_000, 200),
+            fill(Side::Yes, 200_000, 1_100),
+            fill(Side::Yes, 300_000, 2_100),
+        ],
+    };
+    let report = run(&Tape::new(updates, &[fills], vec![]), &mut Cheap);
+
+    assert_eq!(report.orders, 1);
+    assert_eq!(report.filled, 1);
+    // c cleared at 0.30
+    assert_eq!(report.open[0].shares, 3_333_333);
+}
+
+#[test]
+fn tape_reads_indexer_lines() {
+    let line = |slot: u64, status: &str| {
+        format!(
+            r#"{{"slot":{slot},"pubkey":"Batch1","isStartup":false,"account":"TradingBatch","data":{{"bump":255,"authority":"Auth","marketId":"{MARKET}","side":"no","status":"{status}","orderCount":1,"totalUsdc":0,"stateNonce":"0","merkleRoot":"00"}}}}"#
+        )
+    };
+    let lines = [line(50, "executed"), String::new(), line(10, "open")].join("\n");
+    let tape = Tape::from_indexer(&lines, &[], vec![resolution(Side::No, 30)]).unwrap();
+
+    let slots: Vec<u64> = tape.events.iter().map(Event::slot).collect();
+    assert_eq!(slots, vec![10, 30, 50]);
+    assert!(Tape::from_indexer("{", &[], vec![])
+        .unwrap_err()
+        .starts_with("line 1:"));
+}
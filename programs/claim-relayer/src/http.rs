@@ -0,0 +1,103 @@
+//! HTTP API
+//!
+//! - `POST /vouchers` with a `VoucherRequest` body queues a claim
+//! - `GET /vouchers/<id>` returns its status, with the transaction
+//!   signature once sent
+//! - `GET /fee_accounts` returns the fee account vouchers must name, by
+//!   mint, both in base58
+
+use crate::{ClaimStatus, RelayerService, SubmitError, VoucherRequest};
+use hyper::{
+    service::{make_service_fn, service_fn},
+    Body, Method, Request, Response, Server, StatusCode,
+};
+use serde::Serialize;
+use std::{collections::HashMap, convert::Infallible, net::SocketAddr, sync::Arc};
+
+#[derive(Serialize)]
+struct ClaimView<'a> {
+    id: &'a str,
+    #[serde(flatten)]
+    status: &'a ClaimStatus,
+}
+
+#[derive(Serialize)]
+struct ErrorView {
+    error: String,
+}
+
+/// Serve the voucher API until the server stops
+pub async fn serve(address: SocketAddr, service: Arc<RelayerService>) {
+    let make_service = make_service_fn(move |_| {
+        let service = service.clone();
+        async move {
+            Ok::<_, Infallible>(service_fn(move |request| {
+                let service = service.clone();
+                async move { Ok::<_, Infallible>(route(&service, request).await) }
+            }))
+        }
+    });
+
+    match Server::try_bind(&address) {
+        Ok(server) => {
+            if let Err(e) = server.serve(make_service).await {
+                log::error!("Relayer server stopped: {e}");
+            }
+        }
+        Err(e) => log::error!("Failed to bind relayer server on {address}: {e}"),
+    }
+}
+
+async fn route(service: &RelayerService, request: Request<Body>) -> Response<Body> {
+    let path = request.uri().path().to_string();
+
+    if request.method() == Method::GET && path == "/fee_accounts" {
+        let address = |key: &[u8; 32]| bs58::encode(key).into_string();
+        let accounts: HashMap<_, _> = service
+            .fee_accounts()
+            .iter()
+            .map(|(mint, account)| (address(mint), address(account)))
+            .collect();
+        return json(StatusCode::OK, &accounts);
+    }
+
+    match (request.method(), path.strip_prefix("/vouchers")) {
+        (&Method::POST, Some("")) => {
+            let body = match hyper::body::to_bytes(request.into_body()).await {
+                Ok(body) => body,
+                Err(e) => return error(StatusCode::BAD_REQUEST, e.to_string()),
+            };
+            let voucher: VoucherRequest = match serde_json::from_slice(&body) {
+                Ok(voucher) => voucher,
+                Err(e) => return error(StatusCode::BAD_REQUEST, e.to_string()),
+            };
+
+            match service.submit(&voucher) {
+                Ok((id, status)) => json(StatusCode::ACCEPTED, &ClaimView { id: &id, status: &status }),
+                Err(e @ SubmitError::QueueFull) => error(StatusCode::SERVICE_UNAVAILABLE, e.to_string()),
+                Err(e) => error(StatusCode::BAD_REQUEST, e.to_string()),
+            }
+        }
+        (&Method::GET, Some(id)) if id.starts_with('/') => {
+            let id = &id[1..];
+            match service.status(id) {
+                Some(status) => json(StatusCode::OK, &ClaimView { id, status: &status }),
+                None => error(StatusCode::NOT_FOUND, format!("unknown voucher {id}")),
+            }
+        }
+        _ => error(StatusCode::NOT_FOUND, format!("no route for {path}")),
+    }
+}
+
+fn json<T: Serialize>(status: StatusCode, body: &T) -> Response<Body> {
+    let body = serde_json::to_vec(body).expect("views serialize");
+    Response::builder()
+        .status(status)
+        .header("content-type", "application/json")
+        .body(Body::from(body))
+        .expect("static response parts")
+}
+
+fn error(status: StatusCode, error: String) -> Response<Body> {
+    json(status, &ErrorView { error })
+}
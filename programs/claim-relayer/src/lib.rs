@@ -0,0 +1,362 @@
+//! Claim Relayer
+//!
+//! Fee-payer service for distribution claims. A winner without SOL signs a
+//! claim voucher (the leaf, their proof, a fee and the relayer token
+//! account it is paid to, from `GET /fee_accounts`) with their wallet key
+//! and posts it here; the relayer checks the signature, fee and fee
+//! account, then sends `claim_distribution_voucher` behind an ed25519
+//! program instruction carrying that signature, paying the transaction
+//! fees itself. The program pays the voucher's fee to that token account
+//! out of the claim, which is how the relayer recoups its gas.
+//!
+//! Vouchers are keyed by their digest, so posting one twice returns the
+//! existing job. A voucher for an already claimed leaf fails on-chain and
+//! only costs the relayer the transaction fee.
+
+use ed25519_dalek::{Signature, SigningKey, VerifyingKey};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    fmt,
+    net::SocketAddr,
+    path::PathBuf,
+    sync::{Arc, Mutex},
+};
+use tokio::sync::mpsc;
+
+pub mod http;
+pub mod rpc;
+pub mod tx;
+
+use rpc::RpcClient;
+
+const DEFAULT_QUEUE_CAPACITY: usize = 256;
+
+/// Raw 32-byte Solana address
+pub type Pubkey = [u8; 32];
+
+// ============================================
+// Configuration
+// ============================================
+
+/// Service config file, e.g.
+/// `{ "bind_address": "0.0.0.0:8920", "rpc_url": "http://127.0.0.1:8899", "keypair": "relayer.json",
+///    "program_id": "...", "fee_accounts": { "<mint>": "<relayer token account>" }, "fees": { "min_fee": 10000 } }`
+#[derive(Deserialize, Debug, Clone)]
+pub struct ServiceConfig {
+    pub bind_address: SocketAddr,
+    /// JSON-RPC endpoint (plain HTTP, e.g. a local node or proxy)
+    pub rpc_url: String,
+    /// Fee payer keypair file (Solana CLI JSON format)
+    pub keypair: PathBuf,
+    /// privacy-trading program id
+    pub program_id: String,
+    /// Relayer token account receiving fees, per distribution mint
+    pub fee_accounts: HashMap<String, String>,
+    #[serde(default)]
+    pub fees: FeePolicy,
+    #[serde(default = "default_queue_capacity")]
+    pub queue_capacity: usize,
+}
+
+fn default_queue_capacity() -> usize {
+    DEFAULT_QUEUE_CAPACITY
+}
+
+/// Smallest fee the relayer accepts: the larger of a flat minimum and a
+/// share of the claim
+#[derive(Deserialize, Debug, Clone, Copy, Default)]
+pub struct FeePolicy {
+    #[serde(default)]
+    pub min_fee: u64,
+    #[serde(default)]
+    pub fee_bps: u16,
+}
+
+impl FeePolicy {
+    pub fn required_fee(&self, amount: u64) -> u64 {
+        let share = amount as u128 * self.fee_bps as u128 / 10_000;
+        self.min_fee.max(share as u64)
+    }
+}
+
+/// Decode a base58 address
+pub fn parse_pubkey(address: &str) -> Option<Pubkey> {
+    bs58::decode(address).into_vec().ok()?.try_into().ok()
+}
+
+// ============================================
+// Vouchers
+// ============================================
+
+/// Voucher as posted by a client: addresses in base58, proof and
+/// signature in hex
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct VoucherRequest {
+    pub distributor: String,
+    pub mint: String,
+    pub vault: String,
+    pub token_program: String,
+    pub claimant: String,
+    pub claimant_token_account: String,
+    pub index: u32,
+    pub amount: u64,
+    pub fee: u64,
+    /// Relayer token account the fee is paid to
+    pub fee_account: String,
+    pub proof: Vec<String>,
+    pub signature: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Voucher {
+    pub distributor: Pubkey,
+    pub mint: Pubkey,
+    pub vault: Pubkey,
+    pub token_program: Pubkey,
+    pub claimant: Pubkey,
+    pub claimant_token_account: Pubkey,
+    pub index: u32,
+    pub amount: u64,
+    pub fee: u64,
+    pub fee_account: Pubkey,
+    pub proof: Vec<[u8; 32]>,
+    pub signature: [u8; 64],
+}
+
+impl Voucher {
+    pub fn parse(request: &VoucherRequest) -> Result<Self, SubmitError> {
+        let address = |field: &str, value: &str| {
+            parse_pubkey(value).ok_or_else(|| SubmitError::InvalidVoucher(format!("bad {field} address")))
+        };
+        let proof = request
+            .proof
+            .iter()
+            .map(|node| hex::decode(node).ok().and_then(|bytes| bytes.try_into().ok()))
+            .collect::<Option<Vec<[u8; 32]>>>()
+            .ok_or_else(|| SubmitError::InvalidVoucher("bad proof node".into()))?;
+        let signature = hex::decode(&request.signature)
+            .ok()
+            .and_then(|bytes| bytes.try_into().ok())
+            .ok_or_else(|| SubmitError::InvalidVoucher("bad signature encoding".into()))?;
+
+        Ok(Self {
+            distributor: address("distributor", &request.distributor)?,
+            mint: address("mint", &request.mint)?,
+            vault: address("vault", &request.vault)?,
+            token_program: address("token_program", &request.token_program)?,
+            claimant: address("claimant", &request.claimant)?,
+            claimant_token_account: address("claimant_token_account", &request.claimant_token_account)?,
+            index: request.index,
+            amount: request.amount,
+            fee: request.fee,
+            fee_account: address("fee_account", &request.fee_account)?,
+            proof,
+            signature,
+        })
+    }
+
+    /// The program's `claim_voucher_digest` of this voucher
+    pub fn digest(&self) -> [u8; 32] {
+        trading_rules::claim_voucher_digest(
+            &self.distributor,
+            self.index,
+            self.amount,
+            self.fee,
+            &self.fee_account,
+        )
+    }
+
+    /// Whether `signature` is the claimant's over the voucher digest
+    pub fn signature_valid(&self) -> bool {
+        let Ok(key) = VerifyingKey::from_bytes(&self.claimant) else {
+            return false;
+        };
+        key.verify_strict(&self.digest(), &Signature::from_bytes(&self.signature))
+            .is_ok()
+    }
+}
+
+/// Sign a voucher as its claimant (wallets and tests)
+pub fn sign_voucher(claimant: &SigningKey, voucher: &mut Voucher) {
+    use ed25519_dalek::Signer;
+    voucher.claimant = claimant.verifying_key().to_bytes();
+    voucher.signature = claimant.sign(&voucher.digest()).to_bytes();
+}
+
+// ============================================
+// Jobs
+// ============================================
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(tag = "status", rename_all = "camelCase")]
+pub enum ClaimStatus {
+    Queued,
+    Sending,
+    Sent { signature: String },
+    Failed { error: String },
+}
+
+#[derive(Debug)]
+pub struct Claim {
+    pub id: String,
+    pub voucher: Voucher,
+}
+
+#[derive(Debug)]
+pub enum SubmitError {
+    InvalidVoucher(String),
+    BadSignature,
+    FeeTooLow { required: u64 },
+    UnsupportedMint,
+    WrongFeeAccount,
+    QueueFull,
+}
+
+impl fmt::Display for SubmitError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::InvalidVoucher(reason) => write!(f, "invalid voucher: {reason}"),
+            Self::BadSignature => write!(f, "voucher is not signed by its claimant"),
+            Self::FeeTooLow { required } => write!(f, "voucher fee below the required {required}"),
+            Self::UnsupportedMint => write!(f, "no fee account for this mint"),
+            Self::WrongFeeAccount => write!(f, "voucher fee account is not this relayer's"),
+            Self::QueueFull => write!(f, "claim queue is full"),
+        }
+    }
+}
+
+impl std::error::Error for SubmitError {}
+
+// ============================================
+// Service
+// ============================================
+
+#[derive(Debug)]
+pub struct RelayerService {
+    fees: FeePolicy,
+    /// Relayer token account per mint
+    fee_accounts: HashMap<Pubkey, Pubkey>,
+    claims: Mutex<HashMap<String, ClaimStatus>>,
+    queue: mpsc::Sender<Claim>,
+}
+
+impl RelayerService {
+    /// Create the service and the receiving end of its claim queue
+    pub fn new(
+        fees: FeePolicy,
+        fee_accounts: HashMap<Pubkey, Pubkey>,
+        queue_capacity: usize,
+    ) -> (Arc<Self>, mpsc::Receiver<Claim>) {
+        let (queue, receiver) = mpsc::channel(queue_capacity);
+        let service = Self {
+            fees,
+            fee_accounts,
+            claims: Mutex::new(HashMap::new()),
+            queue,
+        };
+        (Arc::new(service), receiver)
+    }
+
+    /// Queue a voucher for redemption
+    ///
+    /// A voucher already queued, sending or sent returns its existing job.
+    /// Failed vouchers are queued again.
+    pub fn submit(&self, request: &VoucherRequest) -> Result<(String, ClaimStatus), SubmitError> {
+        let voucher = Voucher::parse(request)?;
+        if voucher.fee >= voucher.amount {
+            return Err(SubmitError::InvalidVoucher("fee must be below the amount".into()));
+        }
+        let required = self.fees.required_fee(voucher.amount);
+        if voucher.fee < required {
+            return Err(SubmitError::FeeTooLow { required });
+        }
+        let fee_account = self
+            .fee_accounts
+            .get(&voucher.mint)
+            .ok_or(SubmitError::UnsupportedMint)?;
+        if voucher.fee_account != *fee_account {
+            return Err(SubmitError::WrongFeeAccount);
+        }
+        if !voucher.signature_valid() {
+            return Err(SubmitError::BadSignature);
+        }
+        let id = hex::encode(voucher.digest());
+
+        let mut claims = self.claims.lock().unwrap();
+        if let Some(status) = claims.get(&id) {
+            if !matches!(status, ClaimStatus::Failed { .. }) {
+                return Ok((id, status.clone()));
+            }
+        }
+
+        self.queue
+            .try_send(Claim {
+                id: id.clone(),
+                voucher,
+            })
+            .map_err(|_| SubmitError::QueueFull)?;
+        claims.insert(id.clone(), ClaimStatus::Queued);
+
+        Ok((id, ClaimStatus::Queued))
+    }
+
+    /// Relayer token account per mint, which vouchers must name
+    pub fn fee_accounts(&self) -> &HashMap<Pubkey, Pubkey> {
+        &self.fee_accounts
+    }
+
+    pub fn status(&self, id: &str) -> Option<ClaimStatus> {
+        self.claims.lock().unwrap().get(id).cloned()
+    }
+
+    fn set_status(&self, id: &str, status: ClaimStatus) {
+        self.claims.lock().unwrap().insert(id.to_string(), status);
+    }
+}
+
+// ============================================
+// Sender
+// ============================================
+
+/// Send queued claims, paid by `payer`, until the queue closes
+///
+/// Claims go out one at a time so the payer's transactions don't race
+/// for the same blockhash and balance.
+pub async fn run_sender(
+    service: Arc<RelayerService>,
+    mut queue: mpsc::Receiver<Claim>,
+    rpc: RpcClient,
+    payer: SigningKey,
+    program_id: Pubkey,
+) {
+    while let Some(claim) = queue.recv().await {
+        service.set_status(&claim.id, ClaimStatus::Sending);
+
+        let status = match send_claim(&rpc, &payer, &program_id, &claim).await {
+            Ok(signature) => ClaimStatus::Sent { signature },
+            Err(e) => {
+                log::warn!("Claim {} failed: {e}", claim.id);
+                ClaimStatus::Failed { error: e.to_string() }
+            }
+        };
+        service.set_status(&claim.id, status);
+    }
+}
+
+async fn send_claim(
+    rpc: &RpcClient,
+    payer: &SigningKey,
+    program_id: &Pubkey,
+    claim: &Claim,
+) -> Result<String, rpc::RpcError> {
+    let voucher = &claim.voucher;
+    let instructions = [
+        tx::ed25519_instruction(&voucher.claimant, &voucher.signature, &voucher.digest()),
+        tx::claim_instruction(program_id, &payer.verifying_key().to_bytes(), voucher),
+    ];
+
+    let blockhash = rpc.latest_blockhash().await?;
+    rpc.send_transaction(&tx::signed_transaction(payer, &instructions, &blockhash))
+        .await
+}
@@ -0,0 +1,52 @@
+//! `claim-relayer <config.json>`
+
+use claim_relayer::{http, parse_pubkey, rpc::RpcClient, run_sender, RelayerService, ServiceConfig};
+use ed25519_dalek::SigningKey;
+use std::{collections::HashMap, fs, process};
+
+fn load_keypair(path: &std::path::Path) -> Result<SigningKey, String> {
+    let json = fs::read_to_string(path).map_err(|e| e.to_string())?;
+    let bytes: Vec<u8> = serde_json::from_str(&json).map_err(|e| e.to_string())?;
+    let bytes: [u8; 64] = bytes.try_into().map_err(|_| "keypair must be 64 bytes".to_string())?;
+    SigningKey::from_keypair_bytes(&bytes).map_err(|e| e.to_string())
+}
+
+fn fail(message: String) -> ! {
+    eprintln!("claim-relayer: {message}");
+    process::exit(1);
+}
+
+#[tokio::main]
+async fn main() {
+    let Some(config_path) = std::env::args().nth(1) else {
+        eprintln!("usage: claim-relayer <config.json>");
+        process::exit(2);
+    };
+
+    let config: ServiceConfig = fs::read_to_string(&config_path)
+        .map_err(|e| e.to_string())
+        .and_then(|json| serde_json::from_str(&json).map_err(|e| e.to_string()))
+        .unwrap_or_else(|e| fail(format!("invalid config {config_path}: {e}")));
+
+    let payer = load_keypair(&config.keypair)
+        .unwrap_or_else(|e| fail(format!("invalid keypair {}: {e}", config.keypair.display())));
+    let program_id = parse_pubkey(&config.program_id)
+        .unwrap_or_else(|| fail(format!("invalid program id {}", config.program_id)));
+    let rpc_url = config
+        .rpc_url
+        .parse()
+        .unwrap_or_else(|e| fail(format!("invalid rpc url {}: {e}", config.rpc_url)));
+    let fee_accounts: HashMap<_, _> = config
+        .fee_accounts
+        .iter()
+        .map(|(mint, account)| match (parse_pubkey(mint), parse_pubkey(account)) {
+            (Some(mint), Some(account)) => (mint, account),
+            _ => fail(format!("invalid fee account {mint}: {account}")),
+        })
+        .collect();
+
+    let (service, queue) = RelayerService::new(config.fees, fee_accounts, config.queue_capacity);
+    tokio::spawn(http::serve(config.bind_address, service.clone()));
+
+    run_sender(service, queue, RpcClient::new(rpc_url), payer, program_id).await;
+}
@@ -0,0 +1,83 @@
+//! Minimal Solana JSON-RPC client: a recent blockhash and transaction sends
+
+use base64::Engine;
+use hyper::{client::HttpConnector, Body, Client, Method, Request, Uri};
+use serde_json::{json, Value};
+use std::fmt;
+
+#[derive(Debug)]
+pub enum RpcError {
+    Http(hyper::Error),
+    /// Error object or unexpected result from the node
+    Response(String),
+}
+
+impl fmt::Display for RpcError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Http(err) => write!(f, "rpc request failed: {err}"),
+            Self::Response(err) => write!(f, "rpc error: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for RpcError {}
+
+impl From<hyper::Error> for RpcError {
+    fn from(err: hyper::Error) -> Self {
+        Self::Http(err)
+    }
+}
+
+pub struct RpcClient {
+    url: Uri,
+    client: Client<HttpConnector>,
+}
+
+impl RpcClient {
+    pub fn new(url: Uri) -> Self {
+        Self { url, client: Client::new() }
+    }
+
+    async fn call(&self, method: &str, params: Value) -> Result<Value, RpcError> {
+        let body = json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params });
+        let request = Request::builder()
+            .method(Method::POST)
+            .uri(self.url.clone())
+            .header("content-type", "application/json")
+            .body(Body::from(body.to_string()))
+            .expect("static request parts");
+
+        let response = self.client.request(request).await?;
+        let body = hyper::body::to_bytes(response.into_body()).await?;
+        let mut reply: Value =
+            serde_json::from_slice(&body).map_err(|e| RpcError::Response(e.to_string()))?;
+
+        if let Some(error) = reply.get("error") {
+            return Err(RpcError::Response(error.to_string()));
+        }
+        Ok(reply["result"].take())
+    }
+
+    pub async fn latest_blockhash(&self) -> Result<[u8; 32], RpcError> {
+        let result = self
+            .call("getLatestBlockhash", json!([{ "commitment": "confirmed" }]))
+            .await?;
+        result["value"]["blockhash"]
+            .as_str()
+            .and_then(crate::parse_pubkey)
+            .ok_or_else(|| RpcError::Response(format!("bad blockhash in {result}")))
+    }
+
+    /// Send a wire transaction, returning its signature
+    pub async fn send_transaction(&self, transaction: &[u8]) -> Result<String, RpcError> {
+        let encoded = base64::engine::general_purpose::STANDARD.encode(transaction);
+        let result = self
+            .call("sendTransaction", json!([encoded, { "encoding": "base64" }]))
+            .await?;
+        result
+            .as_str()
+            .map(str::to_string)
+            .ok_or_else(|| RpcError::Response(format!("bad signature in {result}")))
+    }
+}
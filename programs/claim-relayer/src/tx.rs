@@ -0,0 +1,164 @@
+//! Instruction and legacy transaction encoding
+//!
+//! Just enough of the Solana wire format to send one claim: the ed25519
+//! program instruction, the anchor `claim_distribution_voucher`
+//! instruction, and a message signed by the fee payer alone.
+
+use crate::{parse_pubkey, Pubkey, Voucher};
+use ed25519_dalek::{Signer, SigningKey};
+use sha2::{Digest, Sha256};
+
+pub fn ed25519_program_id() -> Pubkey {
+    parse_pubkey("Ed25519SigVerify111111111111111111111111111").expect("valid address")
+}
+
+pub fn instructions_sysvar_id() -> Pubkey {
+    parse_pubkey("Sysvar1nstructions1111111111111111111111111").expect("valid address")
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AccountMeta {
+    pub pubkey: Pubkey,
+    pub is_signer: bool,
+    pub is_writable: bool,
+}
+
+impl AccountMeta {
+    pub fn writable(pubkey: Pubkey) -> Self {
+        Self { pubkey, is_signer: false, is_writable: true }
+    }
+
+    pub fn readonly(pubkey: Pubkey) -> Self {
+        Self { pubkey, is_signer: false, is_writable: false }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Instruction {
+    pub program_id: Pubkey,
+    pub accounts: Vec<AccountMeta>,
+    pub data: Vec<u8>,
+}
+
+/// ed25519 program instruction checking one signature, with the key,
+/// signature and message all in its own data
+pub fn ed25519_instruction(signer: &Pubkey, signature: &[u8; 64], message: &[u8]) -> Instruction {
+    Instruction {
+        program_id: ed25519_program_id(),
+        accounts: Vec::new(),
+        data: ed25519_instruction::encode(signer, signature, message),
+    }
+}
+
+/// Anchor instruction discriminator for `name`
+pub fn discriminator(name: &str) -> [u8; 8] {
+    let hash = Sha256::digest(format!("global:{name}"));
+    hash[..8].try_into().unwrap()
+}
+
+/// `claim_distribution_voucher` paying the voucher's `fee_account` the fee
+///
+/// Account order follows `ClaimDistributionVoucher`; the memo program is
+/// left out (anchor reads the program id as `None`) and transfer-hook
+/// mints are not relayed.
+pub fn claim_instruction(
+    program_id: &Pubkey,
+    relayer: &Pubkey,
+    voucher: &Voucher,
+) -> Instruction {
+    let mut data = discriminator("claim_distribution_voucher").to_vec();
+    data.extend_from_slice(&voucher.claimant);
+    data.extend_from_slice(&voucher.index.to_le_bytes());
+    data.extend_from_slice(&voucher.amount.to_le_bytes());
+    data.extend_from_slice(&(voucher.proof.len() as u32).to_le_bytes());
+    for node in &voucher.proof {
+        data.extend_from_slice(node);
+    }
+    data.extend_from_slice(&voucher.fee.to_le_bytes());
+    data.extend_from_slice(&voucher.fee_account);
+    // memo: None
+    data.push(0);
+
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta { pubkey: *relayer, is_signer: true, is_writable: false },
+            AccountMeta::writable(voucher.distributor),
+            AccountMeta::readonly(voucher.mint),
+            AccountMeta::writable(voucher.vault),
+            AccountMeta::writable(voucher.claimant_token_account),
+            AccountMeta::writable(voucher.fee_account),
+            AccountMeta::readonly(instructions_sysvar_id()),
+            AccountMeta::readonly(voucher.token_program),
+            AccountMeta::readonly(*program_id),
+        ],
+        data,
+    }
+}
+
+/// Compact-u16 length prefix
+fn push_length(out: &mut Vec<u8>, mut length: usize) {
+    loop {
+        let byte = (length & 0x7f) as u8;
+        length >>= 7;
+        if length == 0 {
+            out.push(byte);
+            return;
+        }
+        out.push(byte | 0x80);
+    }
+}
+
+/// Legacy message with `payer` as its only signer
+///
+/// Keys are ordered writable signers, readonly signers, writable, then
+/// readonly, with the payer first; a key used several ways keeps the
+/// strongest flags.
+pub fn compile_message(payer: &Pubkey, instructions: &[Instruction], blockhash: &[u8; 32]) -> Vec<u8> {
+    let mut keys: Vec<AccountMeta> = vec![AccountMeta { pubkey: *payer, is_signer: true, is_writable: true }];
+    let mut add = |meta: &AccountMeta| match keys.iter_mut().find(|key| key.pubkey == meta.pubkey) {
+        Some(key) => {
+            key.is_signer |= meta.is_signer;
+            key.is_writable |= meta.is_writable;
+        }
+        None => keys.push(meta.clone()),
+    };
+    for ix in instructions {
+        ix.accounts.iter().for_each(&mut add);
+        add(&AccountMeta::readonly(ix.program_id));
+    }
+    keys.sort_by_key(|key| (!key.is_signer, !key.is_writable));
+
+    let signers = keys.iter().filter(|key| key.is_signer).count();
+    let readonly_signers = keys.iter().filter(|key| key.is_signer && !key.is_writable).count();
+    let readonly = keys.iter().filter(|key| !key.is_signer && !key.is_writable).count();
+    let index = |pubkey: &Pubkey| keys.iter().position(|key| key.pubkey == *pubkey).unwrap() as u8;
+
+    let mut message = vec![signers as u8, readonly_signers as u8, readonly as u8];
+    push_length(&mut message, keys.len());
+    for key in &keys {
+        message.extend_from_slice(&key.pubkey);
+    }
+    message.extend_from_slice(blockhash);
+
+    push_length(&mut message, instructions.len());
+    for ix in instructions {
+        message.push(index(&ix.program_id));
+        push_length(&mut message, ix.accounts.len());
+        message.extend(ix.accounts.iter().map(|meta| index(&meta.pubkey)));
+        push_length(&mut message, ix.data.len());
+        message.extend_from_slice(&ix.data);
+    }
+    message
+}
+
+/// Wire transaction: the payer's signature followed by the message
+pub fn signed_transaction(payer: &SigningKey, instructions: &[Instruction], blockhash: &[u8; 32]) -> Vec<u8> {
+    let message = compile_message(&payer.verifying_key().to_bytes(), instructions, blockhash);
+
+    let mut transaction = Vec::with_capacity(1 + 64 + message.len());
+    push_length(&mut transaction, 1);
+    transaction.extend_from_slice(&payer.sign(&message).to_bytes());
+    transaction.extend_from_slice(&message);
+    transaction
+}
@@ -0,0 +1,138 @@
+use claim_relayer::tx::*;
+use claim_relayer::*;
+use ed25519_dalek::SigningKey;
+use std::collections::HashMap;
+
+const MINT: Pubkey = [3u8; 32];
+const FEE_ACCOUNT: Pubkey = [9u8; 32];
+
+fn claimant() -> SigningKey {
+    SigningKey::from_bytes(&[7u8; 32])
+}
+
+fn request(amount: u64, fee: u64) -> VoucherRequest {
+    let mut voucher = Voucher {
+        distributor: [1u8; 32],
+        mint: MINT,
+        vault: [2u8; 32],
+        token_program: [4u8; 32],
+        claimant: [0u8; 32],
+        claimant_token_account: [5u8; 32],
+        index: 6,
+        amount,
+        fee,
+        fee_account: FEE_ACCOUNT,
+        proof: vec![[8u8; 32]],
+        signature: [0u8; 64],
+    };
+    sign_voucher(&claimant(), &mut voucher);
+
+    let address = |key: &Pubkey| bs58::encode(key).into_string();
+    VoucherRequest {
+        distributor: address(&voucher.distributor),
+        mint: address(&voucher.mint),
+        vault: address(&voucher.vault),
+        token_program: address(&voucher.token_program),
+        claimant: address(&voucher.claimant),
+        claimant_token_account: address(&voucher.claimant_token_account),
+        index: voucher.index,
+        amount,
+        fee,
+        fee_account: address(&voucher.fee_account),
+        proof: voucher.proof.iter().map(hex::encode).collect(),
+        signature: hex::encode(voucher.signature),
+    }
+}
+
+fn service(queue_capacity: usize) -> (std::sync::Arc<RelayerService>, tokio::sync::mpsc::Receiver<Claim>) {
+    let fees = FeePolicy { min_fee: 10, fee_bps: 50 };
+    RelayerService::new(fees, HashMap::from([(MINT, FEE_ACCOUNT)]), queue_capacity)
+}
+
+#[test]
+fn signed_voucher_is_queued_once() {
+    let (service, mut queue) = service(4);
+
+    let (first, status) = service.submit(&request(10_000, 50)).unwrap();
+    let (second, _) = service.submit(&request(10_000, 50)).unwrap();
+
+    assert_eq!(first, second);
+    assert_eq!(status, ClaimStatus::Queued);
+    let claim = queue.try_recv().unwrap();
+    assert_eq!(claim.voucher.fee_account, FEE_ACCOUNT);
+    assert!(queue.try_recv().is_err());
+}
+
+#[test]
+fn tampered_voucher_is_rejected() {
+    let (service, _queue) = service(4);
+    let mut tampered = request(10_000, 50);
+    tampered.fee = 60;
+
+    assert!(matches!(service.submit(&tampered), Err(SubmitError::BadSignature)));
+}
+
+#[test]
+fn fee_account_is_signed_and_must_be_the_relayers() {
+    let (service, _queue) = service(4);
+    let mut redirected = request(10_000, 50);
+    redirected.fee_account = bs58::encode([10u8; 32]).into_string();
+
+    assert!(matches!(service.submit(&redirected), Err(SubmitError::WrongFeeAccount)));
+
+    let (service, _queue) = {
+        let fees = FeePolicy { min_fee: 10, fee_bps: 50 };
+        RelayerService::new(fees, HashMap::from([(MINT, [10u8; 32])]), 4)
+    };
+    assert!(matches!(service.submit(&redirected), Err(SubmitError::BadSignature)));
+}
+
+#[test]
+fn fee_below_policy_is_rejected() {
+    let (service, _queue) = service(4);
+
+    assert!(matches!(
+        service.submit(&request(10_000, 49)),
+        Err(SubmitError::FeeTooLow { required: 50 })
+    ));
+    assert!(matches!(
+        service.submit(&request(100, 9)),
+        Err(SubmitError::FeeTooLow { required: 10 })
+    ));
+}
+
+#[test]
+fn ed25519_instruction_points_at_its_own_data() {
+    let voucher = Voucher::parse(&request(10_000, 50)).unwrap();
+    let ix = ed25519_instruction(&voucher.claimant, &voucher.signature, &voucher.digest());
+    let data = &ix.data;
+    let read = |at: usize| u16::from_le_bytes([data[at], data[at + 1]]) as usize;
+
+    assert_eq!(data[0], 1);
+    assert_eq!([read(4), read(8), read(14)], [u16::MAX as usize; 3]);
+    assert_eq!(&data[read(2)..read(2) + 64], &voucher.signature[..]);
+    assert_eq!(&data[read(6)..read(6) + 32], &voucher.claimant[..]);
+    assert_eq!(&data[read(10)..read(10) + read(12)], &voucher.digest()[..]);
+}
+
+#[test]
+fn claim_transaction_has_the_payer_as_only_signer() {
+    let voucher = Voucher::parse(&request(10_000, 50)).unwrap();
+    let payer = SigningKey::from_bytes(&[11u8; 32]);
+    let payer_key = payer.verifying_key().to_bytes();
+    let program_id = [12u8; 32];
+    let instructions = [
+        ed25519_instruction(&voucher.claimant, &voucher.signature, &voucher.digest()),
+        claim_instruction(&program_id, &payer_key, &voucher),
+    ];
+
+    let message = compile_message(&payer_key, &instructions, &[0u8; 32]);
+    // payer, distributor, vault, both token accounts, then the readonly
+    // mint, sysvar, token program, program and ed25519 program
+    assert_eq!(&message[..4], &[1, 0, 5, 10]);
+    assert_eq!(&message[4..36], &payer_key[..]);
+
+    let transaction = signed_transaction(&payer, &instructions, &[0u8; 32]);
+    assert_eq!(transaction[0], 1);
+    assert_eq!(&transaction[65..], &message[..]);
+}
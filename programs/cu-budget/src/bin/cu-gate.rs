@@ -0,0 +1,60 @@
+//! `cu-gate <budgets.json> <rpc-url> [--update]`
+//!
+//! Run against the local validator a test run (`anchor test
+//! --detach`) left behind. Measures every budgeted program's instructions
+//! from that validator's transactions, prints one line per instruction
+//! and exits non-zero on a regression or an unbudgeted instruction.
+//! `--update` writes the measurements into the budgets file instead, for
+//! a change that is meant to cost more.
+
+use cu_budget::{rpc::RpcClient, Budgets};
+use std::{path::Path, process};
+
+fn fail(message: String) -> ! {
+    eprintln!("cu-gate: {message}");
+    process::exit(1);
+}
+
+#[tokio::main]
+async fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let (budgets_path, rpc_url, update) = match args.as_slice() {
+        [budgets, rpc] => (budgets, rpc, false),
+        [budgets, rpc, flag] if flag == "--update" => (budgets, rpc, true),
+        _ => {
+            eprintln!("usage: cu-gate <budgets.json> <rpc-url> [--update]");
+            process::exit(2);
+        }
+    };
+
+    let budgets_path = Path::new(budgets_path);
+    let mut budgets =
+        Budgets::read(budgets_path).unwrap_or_else(|e| fail(format!("invalid budgets {}: {e}", budgets_path.display())));
+    let rpc = RpcClient::new(rpc_url.parse().unwrap_or_else(|e| fail(format!("invalid rpc url {rpc_url}: {e}"))));
+
+    let mut transactions = Vec::new();
+    for program in budgets.programs.values() {
+        let logs = rpc
+            .transaction_logs(&program.program_id)
+            .await
+            .unwrap_or_else(|e| fail(format!("reading {} transactions: {e}", program.program_id)));
+        transactions.extend(logs);
+    }
+    // A transaction touching both programs is listed once per program;
+    // measuring it twice changes nothing since only maxima are kept
+    let measurements = budgets.measure(transactions.iter().map(Vec::as_slice));
+
+    if update {
+        budgets.record(&measurements);
+        budgets
+            .write(budgets_path)
+            .unwrap_or_else(|e| fail(format!("writing {}: {e}", budgets_path.display())));
+        return;
+    }
+
+    let report = budgets.check(&measurements);
+    print!("{report}");
+    if !report.passed() {
+        process::exit(1);
+    }
+}
@@ -0,0 +1,199 @@
+//! Compute-Unit Budgets
+//!
+//! `budgets.json` commits how many compute units each instruction of each
+//! program may use. The gate measures them from the transactions a test
+//! run left on a local validator and fails when one runs more than
+//! `threshold_bps` over its budget, or has no budget at all.
+//!
+//! Measurements come from program logs: every invocation of a program
+//! ends with `Program <id> consumed <n> of <m> compute units`, and Anchor
+//! names the instruction first with `Program log: Instruction: <Name>`.
+//! Arcium callbacks are invoked by the Arcium program, so they show up one
+//! level deeper and are measured the same way, as are CPIs between the
+//! two programs. Only the invoked program's own line counts; the caller's
+//! total includes its callees.
+
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, fmt, fs, path::Path};
+
+pub mod rpc;
+
+/// Log prefix Anchor writes with an instruction's name
+const INSTRUCTION_LOG: &str = "Program log: Instruction: ";
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct Budgets {
+    /// How far past its budget an instruction may run, in bps of the budget
+    pub threshold_bps: u64,
+    /// Keyed by program name
+    pub programs: BTreeMap<String, ProgramBudgets>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ProgramBudgets {
+    pub program_id: String,
+    /// Compute units per instruction, named as Anchor logs it
+    pub instructions: BTreeMap<String, u64>,
+}
+
+/// Most compute units seen per program name and instruction
+pub type Measurements = BTreeMap<String, BTreeMap<String, u64>>;
+
+impl Budgets {
+    pub fn read(path: &Path) -> Result<Self, String> {
+        let json = fs::read_to_string(path).map_err(|e| e.to_string())?;
+        serde_json::from_str(&json).map_err(|e| e.to_string())
+    }
+
+    pub fn write(&self, path: &Path) -> Result<(), String> {
+        let json = serde_json::to_string_pretty(self).map_err(|e| e.to_string())?;
+        fs::write(path, json + "\n").map_err(|e| e.to_string())
+    }
+
+    /// Measure every budgeted program across `transactions`' logs
+    pub fn measure<'a>(&self, transactions: impl IntoIterator<Item = &'a [String]>) -> Measurements {
+        let mut measurements = Measurements::new();
+        for logs in transactions {
+            for (name, program) in &self.programs {
+                for (instruction, units) in invocations(logs, &program.program_id) {
+                    let seen = measurements
+                        .entry(name.clone())
+                        .or_default()
+                        .entry(instruction)
+                        .or_default();
+                    *seen = (*seen).max(units);
+                }
+            }
+        }
+        measurements
+    }
+
+    /// Take `measurements` as the new budgets, for an intended change
+    ///
+    /// Budgets of instructions that were not measured are kept.
+    pub fn record(&mut self, measurements: &Measurements) {
+        for (name, measured) in measurements {
+            if let Some(program) = self.programs.get_mut(name) {
+                program.instructions.extend(measured.iter().map(|(ix, units)| (ix.clone(), *units)));
+            }
+        }
+    }
+
+    /// Check `measurements` against the budgets
+    pub fn check(&self, measurements: &Measurements) -> Report {
+        let mut findings = Vec::new();
+        for (name, program) in &self.programs {
+            let measured = measurements.get(name);
+            for (instruction, &units) in measured.into_iter().flatten() {
+                let finding = match program.instructions.get(instruction) {
+                    None => Finding::Unbudgeted { units },
+                    Some(&budget) if units > self.limit(budget) => Finding::Regressed { budget, units },
+                    Some(&budget) => Finding::Within { budget, units },
+                };
+                findings.push((name.clone(), instruction.clone(), finding));
+            }
+            for instruction in program.instructions.keys() {
+                if !measured.is_some_and(|measured| measured.contains_key(instruction)) {
+                    findings.push((name.clone(), instruction.clone(), Finding::Unmeasured));
+                }
+            }
+        }
+        Report { findings }
+    }
+
+    /// Most units an instruction budgeted at `budget` may use
+    pub fn limit(&self, budget: u64) -> u64 {
+        budget.saturating_add(budget.saturating_mul(self.threshold_bps) / 10_000)
+    }
+}
+
+/// Units used by each invocation of `program_id` in one transaction's
+/// logs, with the instruction name Anchor logged for it
+///
+/// Invocations that logged no name (a program built with
+/// `no-log-ix-name`) are skipped.
+pub fn invocations(logs: &[String], program_id: &str) -> Vec<(String, u64)> {
+    // (program, instruction name) per open invocation
+    let mut stack: Vec<(&str, Option<&str>)> = Vec::new();
+    let mut found = Vec::new();
+
+    for line in logs {
+        if let Some(name) = line.strip_prefix(INSTRUCTION_LOG) {
+            if let Some((_, instruction @ None)) = stack.last_mut() {
+                *instruction = Some(name);
+            }
+            continue;
+        }
+        let Some((program, event)) = line.strip_prefix("Program ").and_then(|rest| rest.split_once(' ')) else {
+            continue;
+        };
+        if event.starts_with("invoke [") {
+            stack.push((program, None));
+        } else if let Some(consumed) = event.strip_prefix("consumed ") {
+            let units = consumed.split(' ').next().and_then(|n| n.parse().ok());
+            if let (Some(units), Some(&(invoked, Some(instruction)))) = (units, stack.last()) {
+                if invoked == program && program == program_id {
+                    found.push((instruction.to_string(), units));
+                }
+            }
+        } else if event == "success" || event.starts_with("failed") {
+            stack.pop();
+        }
+    }
+    found
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Finding {
+    Within { budget: u64, units: u64 },
+    Regressed { budget: u64, units: u64 },
+    /// Measured but missing from the budgets
+    Unbudgeted { units: u64 },
+    /// Budgeted but not exercised by the test run
+    Unmeasured,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Report {
+    /// (program name, instruction, finding)
+    pub findings: Vec<(String, String, Finding)>,
+}
+
+impl Report {
+    /// Whether no instruction regressed or went unbudgeted
+    pub fn passed(&self) -> bool {
+        !self
+            .findings
+            .iter()
+            .any(|(_, _, finding)| matches!(finding, Finding::Regressed { .. } | Finding::Unbudgeted { .. }))
+    }
+
+    pub fn finding(&self, program: &str, instruction: &str) -> Option<Finding> {
+        self.findings
+            .iter()
+            .find(|(name, ix, _)| name == program && ix == instruction)
+            .map(|(_, _, finding)| *finding)
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (program, instruction, finding) in &self.findings {
+            match finding {
+                Finding::Within { budget, units } => {
+                    writeln!(f, "ok        {program}::{instruction}: {units} of {budget}")?
+                }
+                Finding::Regressed { budget, units } => {
+                    writeln!(f, "REGRESSED {program}::{instruction}: {units} over budget {budget}")?
+                }
+                Finding::Unbudgeted { units } => {
+                    writeln!(f, "NO BUDGET {program}::{instruction}: {units}")?
+                }
+                Finding::Unmeasured => writeln!(f, "unused    {program}::{instruction}: not exercised")?,
+            }
+        }
+        Ok(())
+    }
+}
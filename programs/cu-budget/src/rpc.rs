@@ -0,0 +1,112 @@
+//! Transaction logs over Solana JSON-RPC
+
+use hyper::{client::HttpConnector, Body, Client, Method, Request, Uri};
+use serde_json::{json, Value};
+use std::fmt;
+
+/// Most transactions `getSignaturesForAddress` returns in one call
+const SIGNATURE_PAGE: usize = 1_000;
+
+#[derive(Debug)]
+pub enum RpcError {
+    Http(hyper::Error),
+    /// Error object or unexpected result from the node
+    Rpc(String),
+}
+
+impl fmt::Display for RpcError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Http(err) => write!(f, "rpc request failed: {err}"),
+            Self::Rpc(err) => write!(f, "rpc error: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for RpcError {}
+
+impl From<hyper::Error> for RpcError {
+    fn from(err: hyper::Error) -> Self {
+        Self::Http(err)
+    }
+}
+
+pub struct RpcClient {
+    url: Uri,
+    client: Client<HttpConnector>,
+}
+
+impl RpcClient {
+    pub fn new(url: Uri) -> Self {
+        Self { url, client: Client::new() }
+    }
+
+    async fn call(&self, method: &str, params: Value) -> Result<Value, RpcError> {
+        let body = json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params });
+        let request = Request::builder()
+            .method(Method::POST)
+            .uri(self.url.clone())
+            .header("content-type", "application/json")
+            .body(Body::from(body.to_string()))
+            .expect("static request parts");
+
+        let response = self.client.request(request).await?;
+        let body = hyper::body::to_bytes(response.into_body()).await?;
+        let mut reply: Value = serde_json::from_slice(&body).map_err(|e| RpcError::Rpc(e.to_string()))?;
+
+        if let Some(error) = reply.get("error") {
+            return Err(RpcError::Rpc(error.to_string()));
+        }
+        Ok(reply["result"].take())
+    }
+
+    /// Logs of every successful transaction that mentions `address`
+    ///
+    /// Failed transactions are left out: they stop wherever they failed,
+    /// so their compute units say nothing about the instruction's cost.
+    pub async fn transaction_logs(&self, address: &str) -> Result<Vec<Vec<String>>, RpcError> {
+        let mut signatures = Vec::new();
+        let mut before: Option<String> = None;
+        loop {
+            let mut options = json!({ "limit": SIGNATURE_PAGE, "commitment": "confirmed" });
+            if let Some(before) = &before {
+                options["before"] = json!(before);
+            }
+            let page = self.call("getSignaturesForAddress", json!([address, options])).await?;
+            let page = page.as_array().cloned().unwrap_or_default();
+            for entry in &page {
+                if entry["err"].is_null() {
+                    signatures.push(signature(entry)?);
+                }
+            }
+            match page.last() {
+                Some(last) if page.len() == SIGNATURE_PAGE => before = Some(signature(last)?),
+                _ => break,
+            }
+        }
+
+        let mut transactions = Vec::with_capacity(signatures.len());
+        for signature in signatures {
+            let options = json!({
+                "encoding": "json",
+                "commitment": "confirmed",
+                "maxSupportedTransactionVersion": 0,
+            });
+            let tx = self.call("getTransaction", json!([signature, options])).await?;
+            let logs = tx
+                .pointer("/meta/logMessages")
+                .and_then(Value::as_array)
+                .map(|logs| logs.iter().filter_map(|line| line.as_str().map(str::to_string)).collect())
+                .unwrap_or_default();
+            transactions.push(logs);
+        }
+        Ok(transactions)
+    }
+}
+
+fn signature(entry: &Value) -> Result<String, RpcError> {
+    entry["signature"]
+        .as_str()
+        .map(str::to_string)
+        .ok_or_else(|| RpcError::Rpc(format!("missing signature in {entry}")))
+}
@@ -0,0 +1,121 @@
+use cu_budget::*;
+use std::collections::BTreeMap;
+
+const TRADING: &str = "3vfatmfrqUfPFRFKP9xTUWKYNYRL7X1wqg2Dz2z4zMQL";
+const ARCIUM: &str = "Arcj82pX7HxYKLR92qvgZUAd7vGS1k4hQvAFcPATFdEQ";
+
+fn budgets(instructions: &[(&str, u64)]) -> Budgets {
+    Budgets {
+        threshold_bps: 500,
+        programs: BTreeMap::from([(
+            "privacy_trading".to_string(),
+            ProgramBudgets {
+                program_id: TRADING.to_string(),
+                instructions: instructions.iter().map(|(ix, units)| (ix.to_string(), *units)).collect(),
+            },
+        )]),
+    }
+}
+
+fn top_level(instruction: &str, units: u64) -> Vec<String> {
+    vec![
+        format!("Program {TRADING} invoke [1]"),
+        format!("Program log: Instruction: {instruction}"),
+        format!("Program {TRADING} consumed {units} of 200000 compute units"),
+        format!("Program {TRADING} success"),
+    ]
+}
+
+/// A callback as Arcium delivers it: our program one level down
+fn callback(instruction: &str, units: u64) -> Vec<String> {
+    vec![
+        format!("Program {ARCIUM} invoke [1]"),
+        "Program log: Instruction: CallbackComputation".to_string(),
+        format!("Program {TRADING} invoke [2]"),
+        format!("Program log: Instruction: {instruction}"),
+        "Program data: ZXZlbnQ=".to_string(),
+        format!("Program {TRADING} consumed {units} of 180000 compute units"),
+        format!("Program {TRADING} success"),
+        format!("Program {ARCIUM} consumed {} of 200000 compute units", units + 20_000),
+        format!("Program {ARCIUM} success"),
+    ]
+}
+
+fn measure(budgets: &Budgets, transactions: &[Vec<String>]) -> Measurements {
+    budgets.measure(transactions.iter().map(Vec::as_slice))
+}
+
+#[test]
+fn callbacks_are_measured_under_their_own_name() {
+    let logs = callback("AddOrderCallback", 41_000);
+
+    assert_eq!(invocations(&logs, TRADING), vec![("AddOrderCallback".to_string(), 41_000)]);
+    assert_eq!(invocations(&logs, ARCIUM), vec![("CallbackComputation".to_string(), 61_000)]);
+}
+
+#[test]
+fn highest_measurement_is_kept() {
+    let budgets = budgets(&[]);
+    let measurements = measure(&budgets, &[top_level("AddOrder", 30_000), top_level("AddOrder", 32_000)]);
+
+    assert_eq!(measurements["privacy_trading"]["AddOrder"], 32_000);
+}
+
+#[test]
+fn regression_past_threshold_fails() {
+    let budgets = budgets(&[("AddOrder", 30_000), ("ExecuteBatch", 50_000)]);
+    let measurements = measure(
+        &budgets,
+        &[top_level("AddOrder", 31_500), top_level("ExecuteBatch", 52_501)],
+    );
+    let report = budgets.check(&measurements);
+
+    assert_eq!(
+        report.finding("privacy_trading", "AddOrder"),
+        Some(Finding::Within { budget: 30_000, units: 31_500 })
+    );
+    assert_eq!(
+        report.finding("privacy_trading", "ExecuteBatch"),
+        Some(Finding::Regressed { budget: 50_000, units: 52_501 })
+    );
+    assert!(!report.passed());
+}
+
+#[test]
+fn unbudgeted_instruction_fails_and_unmeasured_does_not() {
+    let budgets = budgets(&[("AddOrder", 30_000), ("ExecuteBatch", 50_000)]);
+    let measurements = measure(&budgets, &[top_level("AddOrder", 30_000)]);
+
+    let report = budgets.check(&measurements);
+    assert_eq!(report.finding("privacy_trading", "ExecuteBatch"), Some(Finding::Unmeasured));
+    assert!(report.passed());
+
+    let measurements = measure(&budgets, &[callback("AddOrderCallback", 41_000)]);
+    let report = budgets.check(&measurements);
+    assert_eq!(
+        report.finding("privacy_trading", "AddOrderCallback"),
+        Some(Finding::Unbudgeted { units: 41_000 })
+    );
+    assert!(!report.passed());
+}
+
+#[test]
+fn update_records_measurements_and_keeps_the_rest() {
+    let mut budgets = budgets(&[("AddOrder", 30_000), ("ExecuteBatch", 50_000)]);
+    let measurements = measure(&budgets, &[top_level("AddOrder", 34_000), callback("AddOrderCallback", 41_000)]);
+
+    budgets.record(&measurements);
+    let instructions = &budgets.programs["privacy_trading"].instructions;
+    assert_eq!(instructions["AddOrder"], 34_000);
+    assert_eq!(instructions["AddOrderCallback"], 41_000);
+    assert_eq!(instructions["ExecuteBatch"], 50_000);
+    assert!(budgets.check(&measurements).passed());
+}
+
+#[test]
+fn committed_budgets_cover_both_programs() {
+    let budgets = Budgets::read(std::path::Path::new(concat!(env!("CARGO_MANIFEST_DIR"), "/budgets.json"))).unwrap();
+
+    assert_eq!(budgets.programs["privacy_trading"].program_id, TRADING);
+    assert!(budgets.programs.contains_key("zk_verifier"));
+}
@@ -0,0 +1,76 @@
+//! ed25519 Program Instructions
+//!
+//! The ed25519 precompile verifies signatures before the transaction's
+//! other instructions run; a program that relies on one reads the
+//! instruction back from the instructions sysvar to learn which key and
+//! message it covered. This crate holds that instruction layout for the
+//! single-signature case, with no Solana dependency so programs on
+//! different anchor versions and the off-chain relayer can share it.
+
+/// Start of the single signature offsets entry, after count and padding
+const OFFSETS_START: usize = 2;
+/// Signature, public key and message offsets (offset + instruction index
+/// each, message also has a size)
+const OFFSETS_SIZE: usize = 14;
+/// Instruction index meaning "this instruction's own data"
+const CURRENT_INSTRUCTION: u16 = u16::MAX;
+
+/// Key and message covered by a verified signature
+#[derive(Debug, PartialEq, Eq)]
+pub struct SignedMessage<'a> {
+    pub pubkey: &'a [u8; 32],
+    pub message: &'a [u8],
+}
+
+/// Instruction data checking one signature, with the key, signature and
+/// message all in the data itself
+pub fn encode(pubkey: &[u8; 32], signature: &[u8; 64], message: &[u8]) -> Vec<u8> {
+    let key_offset = OFFSETS_START + OFFSETS_SIZE;
+    let signature_offset = key_offset + 32;
+    let message_offset = signature_offset + 64;
+
+    let mut data = vec![1, 0];
+    for field in [
+        signature_offset as u16,
+        CURRENT_INSTRUCTION,
+        key_offset as u16,
+        CURRENT_INSTRUCTION,
+        message_offset as u16,
+        message.len() as u16,
+        CURRENT_INSTRUCTION,
+    ] {
+        data.extend_from_slice(&field.to_le_bytes());
+    }
+    data.extend_from_slice(pubkey);
+    data.extend_from_slice(signature);
+    data.extend_from_slice(message);
+    data
+}
+
+/// Key and message of a single-signature instruction, or `None` unless
+/// the signature, key and message all live in its own data
+///
+/// The precompile only checks that offsets resolve; pointing them at
+/// another instruction would let a program read a key or message the
+/// signature never covered.
+pub fn parse(data: &[u8]) -> Option<SignedMessage<'_>> {
+    if data.first() != Some(&1) || data.len() < OFFSETS_START + OFFSETS_SIZE {
+        return None;
+    }
+    let read_u16 = |at: usize| u16::from_le_bytes([data[at], data[at + 1]]);
+
+    let o = OFFSETS_START;
+    if [o + 2, o + 6, o + 12]
+        .into_iter()
+        .any(|at| read_u16(at) != CURRENT_INSTRUCTION)
+    {
+        return None;
+    }
+
+    let key_offset = read_u16(o + 4) as usize;
+    let (message_offset, message_size) = (read_u16(o + 8) as usize, read_u16(o + 10) as usize);
+    Some(SignedMessage {
+        pubkey: data.get(key_offset..key_offset + 32)?.try_into().ok()?,
+        message: data.get(message_offset..message_offset + message_size)?,
+    })
+}
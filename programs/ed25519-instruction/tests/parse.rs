@@ -0,0 +1,43 @@
+use ed25519_instruction::{encode, parse, SignedMessage};
+
+const PUBKEY: [u8; 32] = [7; 32];
+const SIGNATURE: [u8; 64] = [8; 64];
+
+#[test]
+fn parses_what_it_encodes() {
+    let data = encode(&PUBKEY, &SIGNATURE, b"claim voucher digest");
+    assert_eq!(
+        parse(&data),
+        Some(SignedMessage {
+            pubkey: &PUBKEY,
+            message: b"claim voucher digest"
+        })
+    );
+}
+
+#[test]
+fn rejects_offsets_into_other_instructions() {
+    // Signature, public key and message instruction indices
+    for at in [4, 8, 14] {
+        let mut data = encode(&PUBKEY, &SIGNATURE, &[1; 32]);
+        data[at..at + 2].copy_from_slice(&0u16.to_le_bytes());
+        assert_eq!(parse(&data), None, "index at {at}");
+    }
+}
+
+#[test]
+fn rejects_other_signature_counts() {
+    let mut data = encode(&PUBKEY, &SIGNATURE, &[1; 32]);
+    data[0] = 2;
+    assert_eq!(parse(&data), None);
+    data[0] = 0;
+    assert_eq!(parse(&data), None);
+}
+
+#[test]
+fn rejects_out_of_bounds_data() {
+    let data = encode(&PUBKEY, &SIGNATURE, &[1; 32]);
+    assert_eq!(parse(&data[..data.len() - 1]), None);
+    assert_eq!(parse(&data[..15]), None);
+    assert_eq!(parse(&[]), None);
+}
@@ -0,0 +1,36 @@
+//! `privacy-indexer <config.json>`
+//!
+//! Serves the plugin's WebSocket stream without a validator, fed from a
+//! hosted RPC provider by the configured `ingest` source.
+
+use privacy_geyser_plugin::{ingest::IndexerConfig, Publisher};
+use std::{fs, process, sync::Arc};
+
+fn fail(message: String) -> ! {
+    eprintln!("privacy-indexer: {message}");
+    process::exit(1);
+}
+
+fn main() {
+    let Some(config_path) = std::env::args().nth(1) else {
+        eprintln!("usage: privacy-indexer <config.json>");
+        process::exit(2);
+    };
+
+    let config: IndexerConfig = fs::read_to_string(&config_path)
+        .map_err(|e| e.to_string())
+        .and_then(|json| serde_json::from_str(&json).map_err(|e| e.to_string()))
+        .unwrap_or_else(|e| fail(format!("invalid config {config_path}: {e}")));
+
+    // Built by hand like the plugin's: `Publisher::start` blocks on binding
+    let runtime = tokio::runtime::Builder::new_multi_thread()
+        .thread_name("privacy-indexer")
+        .enable_all()
+        .build()
+        .unwrap_or_else(|e| fail(format!("starting runtime: {e}")));
+    let publisher = Publisher::start(&config.stream, &runtime).unwrap_or_else(|e| fail(e.to_string()));
+
+    if let Err(e) = runtime.block_on(config.run(Arc::new(publisher))) {
+        fail(e.to_string());
+    }
+}
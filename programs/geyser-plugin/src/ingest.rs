@@ -0,0 +1,235 @@
+//! Account ingestion from hosted infrastructure
+//!
+//! The plugin receives account updates from the validator it is loaded into.
+//! Without a validator of one's own, an [`Ingestor`] feeds the same
+//! [`Publisher`] from an RPC provider instead, so WebSocket clients see the
+//! same `AccountUpdate` stream either way:
+//!
+//! - [`webhook::Webhook`] takes Helius webhook deliveries and reads the
+//!   accounts each delivered transaction touched
+//! - [`subscribe::ProgramSubscribe`] holds `programSubscribe` subscriptions
+//!   on a pubsub endpoint, e.g. a Triton Whirligig stream
+
+use crate::{PluginConfig, Publisher};
+use base64::Engine;
+use hyper::{client::HttpConnector, Body, Client, Method, Request, Uri};
+use hyper_rustls::{HttpsConnector, HttpsConnectorBuilder};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::{fmt, future::Future, sync::Arc};
+use tokio_tungstenite::tungstenite;
+
+pub mod subscribe;
+pub mod webhook;
+
+/// Most keys `getMultipleAccounts` takes per call
+const MAX_MULTIPLE_ACCOUNTS: usize = 100;
+
+// ============================================
+// Configuration
+// ============================================
+
+/// `privacy-indexer` config: the plugin's settings plus a source, e.g.
+/// `{ "bind_address": "0.0.0.0:8900",
+///    "source": { "program_subscribe": { "ws_url": "wss://...", "rpc_url": "https://..." } } }`
+#[derive(Deserialize, Debug)]
+pub struct IndexerConfig {
+    #[serde(flatten)]
+    pub stream: PluginConfig,
+    pub source: SourceConfig,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum SourceConfig {
+    Webhook(webhook::WebhookConfig),
+    ProgramSubscribe(subscribe::SubscribeConfig),
+}
+
+impl IndexerConfig {
+    /// Run the configured source into `publisher` until it fails
+    pub async fn run(self, publisher: Arc<Publisher>) -> Result<(), IngestError> {
+        match self.source {
+            SourceConfig::Webhook(config) => webhook::Webhook::new(config)?.run(publisher).await,
+            SourceConfig::ProgramSubscribe(config) => {
+                let programs = vec![self.stream.privacy_trading_program, self.stream.zk_verifier_program];
+                subscribe::ProgramSubscribe::new(config, programs)?.run(publisher).await
+            }
+        }
+    }
+}
+
+// ============================================
+// Ingestor
+// ============================================
+
+/// A source of account updates outside the validator
+pub trait Ingestor {
+    /// Publish account updates until the source fails; transient failures
+    /// are retried inside
+    fn run(self, publisher: Arc<Publisher>) -> impl Future<Output = Result<(), IngestError>> + Send;
+}
+
+#[derive(Debug)]
+pub enum IngestError {
+    Http(hyper::Error),
+    WebSocket(Box<tungstenite::Error>),
+    /// Error object or unexpected result from the node
+    Rpc(String),
+    Config(String),
+}
+
+impl fmt::Display for IngestError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Http(err) => write!(f, "http request failed: {err}"),
+            Self::WebSocket(err) => write!(f, "websocket failed: {err}"),
+            Self::Rpc(err) => write!(f, "rpc error: {err}"),
+            Self::Config(err) => write!(f, "invalid source config: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for IngestError {}
+
+impl From<hyper::Error> for IngestError {
+    fn from(err: hyper::Error) -> Self {
+        Self::Http(err)
+    }
+}
+
+impl From<tungstenite::Error> for IngestError {
+    fn from(err: tungstenite::Error) -> Self {
+        Self::WebSocket(Box::new(err))
+    }
+}
+
+// ============================================
+// RPC Accounts
+// ============================================
+
+/// An account read from the RPC
+#[derive(Debug)]
+pub struct RawAccount {
+    pub slot: u64,
+    pub pubkey: [u8; 32],
+    pub owner: [u8; 32],
+    pub data: Vec<u8>,
+}
+
+impl RawAccount {
+    /// Decode a base64-encoded `UiAccount` as the RPC returns it
+    pub fn from_rpc(slot: u64, pubkey: &str, account: &Value) -> Result<Self, IngestError> {
+        let malformed = || IngestError::Rpc(format!("malformed account {pubkey}"));
+        let owner = account["owner"].as_str().and_then(decode_pubkey).ok_or_else(malformed)?;
+        let data = account["data"][0]
+            .as_str()
+            .and_then(|data| base64::engine::general_purpose::STANDARD.decode(data).ok())
+            .ok_or_else(malformed)?;
+        Ok(Self {
+            slot,
+            pubkey: decode_pubkey(pubkey).ok_or_else(malformed)?,
+            owner,
+            data,
+        })
+    }
+
+    pub fn publish(&self, publisher: &Publisher, is_startup: bool) {
+        if let Err(e) = publisher.publish(self.slot, &self.pubkey, &self.owner, &self.data, is_startup) {
+            log::warn!("Failed to publish {}: {e}", bs58::encode(self.pubkey).into_string());
+        }
+    }
+}
+
+fn decode_pubkey(encoded: &str) -> Option<[u8; 32]> {
+    bs58::decode(encoded).into_vec().ok()?.try_into().ok()
+}
+
+/// Solana JSON-RPC client for reading accounts, over HTTPS or HTTP
+pub struct RpcClient {
+    url: Uri,
+    client: Client<HttpsConnector<HttpConnector>>,
+}
+
+impl RpcClient {
+    pub fn new(url: Uri) -> Self {
+        let https = HttpsConnectorBuilder::new()
+            .with_native_roots()
+            .https_or_http()
+            .enable_http1()
+            .build();
+        Self { url, client: Client::builder().build(https) }
+    }
+
+    pub fn parse(url: &str) -> Result<Self, IngestError> {
+        url.parse()
+            .map(Self::new)
+            .map_err(|e| IngestError::Config(format!("invalid rpc url {url}: {e}")))
+    }
+
+    async fn call(&self, method: &str, params: Value) -> Result<Value, IngestError> {
+        let body = json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params });
+        let request = Request::builder()
+            .method(Method::POST)
+            .uri(self.url.clone())
+            .header("content-type", "application/json")
+            .body(Body::from(body.to_string()))
+            .expect("static request parts");
+
+        let response = self.client.request(request).await?;
+        let body = hyper::body::to_bytes(response.into_body()).await?;
+        let mut reply: Value = serde_json::from_slice(&body).map_err(|e| IngestError::Rpc(e.to_string()))?;
+
+        if let Some(error) = reply.get("error") {
+            return Err(IngestError::Rpc(error.to_string()));
+        }
+        Ok(reply["result"].take())
+    }
+
+    /// Current state of the given accounts; ones that do not exist are
+    /// skipped
+    pub async fn multiple_accounts(&self, pubkeys: &[String]) -> Result<Vec<RawAccount>, IngestError> {
+        let mut accounts = Vec::new();
+        for chunk in pubkeys.chunks(MAX_MULTIPLE_ACCOUNTS) {
+            let result = self
+                .call(
+                    "getMultipleAccounts",
+                    json!([chunk, { "encoding": "base64", "commitment": "confirmed" }]),
+                )
+                .await?;
+            let slot = context_slot(&result)?;
+            let values = result["value"].as_array().map(Vec::as_slice).unwrap_or_default();
+            for (pubkey, account) in chunk.iter().zip(values) {
+                if !account.is_null() {
+                    accounts.push(RawAccount::from_rpc(slot, pubkey, account)?);
+                }
+            }
+        }
+        Ok(accounts)
+    }
+
+    /// Every account `program` owns
+    pub async fn program_accounts(&self, program: &str) -> Result<Vec<RawAccount>, IngestError> {
+        let result = self
+            .call(
+                "getProgramAccounts",
+                json!([program, { "encoding": "base64", "commitment": "confirmed", "withContext": true }]),
+            )
+            .await?;
+        let slot = context_slot(&result)?;
+        let values = result["value"].as_array().map(Vec::as_slice).unwrap_or_default();
+        values
+            .iter()
+            .map(|keyed| {
+                let pubkey = keyed["pubkey"].as_str().unwrap_or_default();
+                RawAccount::from_rpc(slot, pubkey, &keyed["account"])
+            })
+            .collect()
+    }
+}
+
+fn context_slot(result: &Value) -> Result<u64, IngestError> {
+    result["context"]["slot"]
+        .as_u64()
+        .ok_or_else(|| IngestError::Rpc(format!("no context slot in {result}")))
+}
@@ -0,0 +1,107 @@
+//! `programSubscribe` ingestion
+//!
+//! Subscribes to every account change of the streamed programs on a
+//! pubsub endpoint: a Triton Whirligig stream, or any RPC provider's
+//! WebSocket URL. Notifications carry the account data, so each is
+//! published as it arrives. Updates sent while disconnected are lost, so
+//! every (re)connect subscribes first and then publishes a
+//! `getProgramAccounts` snapshot as startup updates.
+
+use super::{IngestError, Ingestor, RawAccount, RpcClient};
+use crate::Publisher;
+use futures_util::{SinkExt, StreamExt};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::{sync::Arc, time::Duration};
+use tokio_tungstenite::tungstenite::Message;
+
+/// Wait before reconnecting after the stream drops
+const RECONNECT_DELAY: Duration = Duration::from_secs(2);
+
+#[derive(Deserialize, Debug)]
+pub struct SubscribeConfig {
+    /// `ws://` or `wss://` pubsub endpoint
+    pub ws_url: String,
+    /// RPC the reconnect snapshots are read from
+    pub rpc_url: String,
+}
+
+pub struct ProgramSubscribe {
+    ws_url: String,
+    rpc: RpcClient,
+    programs: Vec<String>,
+}
+
+impl ProgramSubscribe {
+    pub fn new(config: SubscribeConfig, programs: Vec<String>) -> Result<Self, IngestError> {
+        Ok(Self {
+            ws_url: config.ws_url,
+            rpc: RpcClient::parse(&config.rpc_url)?,
+            programs,
+        })
+    }
+
+    /// One connection, until it closes or fails
+    async fn session(&self, publisher: &Publisher) -> Result<(), IngestError> {
+        let (stream, _) = tokio_tungstenite::connect_async(self.ws_url.as_str()).await?;
+        let (mut write, mut read) = stream.split();
+
+        for (id, program) in self.programs.iter().enumerate() {
+            let request = json!({
+                "jsonrpc": "2.0",
+                "id": id,
+                "method": "programSubscribe",
+                "params": [program, { "encoding": "base64", "commitment": "confirmed" }],
+            });
+            write.send(Message::Text(request.to_string())).await?;
+        }
+        log::info!("Subscribed to {} programs on {}", self.programs.len(), self.ws_url);
+
+        // Subscribed first, so a change made while the snapshot is read
+        // still arrives as a notification after it
+        for program in &self.programs {
+            for account in self.rpc.program_accounts(program).await? {
+                account.publish(publisher, true);
+            }
+        }
+
+        while let Some(message) = read.next().await {
+            let text = match message? {
+                Message::Text(text) => text,
+                Message::Close(_) => break,
+                _ => continue,
+            };
+            let Ok(message) = serde_json::from_str::<Value>(&text) else {
+                log::warn!("Ignoring malformed pubsub message: {text}");
+                continue;
+            };
+            if let Some(error) = message.get("error") {
+                return Err(IngestError::Rpc(error.to_string()));
+            }
+            if message["method"] != "programNotification" {
+                continue;
+            }
+
+            let result = &message["params"]["result"];
+            let slot = result["context"]["slot"].as_u64().unwrap_or_default();
+            let pubkey = result["value"]["pubkey"].as_str().unwrap_or_default();
+            match RawAccount::from_rpc(slot, pubkey, &result["value"]["account"]) {
+                Ok(account) => account.publish(publisher, false),
+                Err(e) => log::warn!("Ignoring notification: {e}"),
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Ingestor for ProgramSubscribe {
+    async fn run(self, publisher: Arc<Publisher>) -> Result<(), IngestError> {
+        loop {
+            match self.session(&publisher).await {
+                Ok(()) => log::warn!("Pubsub stream {} closed, reconnecting", self.ws_url),
+                Err(e) => log::warn!("Pubsub stream {} failed, reconnecting: {e}", self.ws_url),
+            }
+            tokio::time::sleep(RECONNECT_DELAY).await;
+        }
+    }
+}
@@ -0,0 +1,128 @@
+//! Helius webhook ingestion
+//!
+//! Point a Helius webhook, enhanced or raw, at the programs' addresses and
+//! at `listen_address`. Deliveries carry transactions rather than account
+//! data, so each one is answered by reading every account the delivered
+//! transactions touched and publishing the ones we stream. Reads are at the
+//! RPC's current slot, so a late or repeated delivery still publishes the
+//! latest state. A failed read answers 502, which Helius retries.
+
+use super::{IngestError, Ingestor, RpcClient};
+use crate::Publisher;
+use hyper::{
+    header::AUTHORIZATION,
+    service::{make_service_fn, service_fn},
+    Body, Method, Request, Response, Server, StatusCode,
+};
+use serde::Deserialize;
+use serde_json::Value;
+use std::{collections::BTreeSet, convert::Infallible, net::SocketAddr, sync::Arc};
+
+#[derive(Deserialize, Debug)]
+pub struct WebhookConfig {
+    /// Address the webhook posts to
+    pub listen_address: SocketAddr,
+    /// RPC the touched accounts are read from
+    pub rpc_url: String,
+    /// The webhook's auth header; deliveries without it are refused
+    #[serde(default)]
+    pub auth_header: Option<String>,
+}
+
+pub struct Webhook {
+    listen_address: SocketAddr,
+    rpc: RpcClient,
+    auth_header: Option<String>,
+}
+
+impl Webhook {
+    pub fn new(config: WebhookConfig) -> Result<Self, IngestError> {
+        Ok(Self {
+            listen_address: config.listen_address,
+            rpc: RpcClient::parse(&config.rpc_url)?,
+            auth_header: config.auth_header,
+        })
+    }
+
+    async fn handle(&self, publisher: &Publisher, request: Request<Body>) -> Response<Body> {
+        if request.method() != Method::POST {
+            return status(StatusCode::METHOD_NOT_ALLOWED);
+        }
+        if let Some(expected) = &self.auth_header {
+            let given = request.headers().get(AUTHORIZATION).map(|value| value.as_bytes());
+            if given != Some(expected.as_bytes()) {
+                return status(StatusCode::UNAUTHORIZED);
+            }
+        }
+
+        let body = match hyper::body::to_bytes(request.into_body()).await {
+            Ok(body) => body,
+            Err(_) => return status(StatusCode::BAD_REQUEST),
+        };
+        let Ok(delivery) = serde_json::from_slice::<Value>(&body) else {
+            return status(StatusCode::BAD_REQUEST);
+        };
+
+        let pubkeys = touched_accounts(&delivery);
+        match self.rpc.multiple_accounts(&pubkeys).await {
+            Ok(accounts) => {
+                for account in &accounts {
+                    account.publish(publisher, false);
+                }
+                status(StatusCode::OK)
+            }
+            Err(e) => {
+                log::warn!("Failed to read {} webhook accounts: {e}", pubkeys.len());
+                status(StatusCode::BAD_GATEWAY)
+            }
+        }
+    }
+}
+
+impl Ingestor for Webhook {
+    async fn run(self, publisher: Arc<Publisher>) -> Result<(), IngestError> {
+        let address = self.listen_address;
+        let webhook = Arc::new(self);
+        let make_service = make_service_fn(move |_| {
+            let (webhook, publisher) = (webhook.clone(), publisher.clone());
+            async move {
+                Ok::<_, Infallible>(service_fn(move |request| {
+                    let (webhook, publisher) = (webhook.clone(), publisher.clone());
+                    async move { Ok::<_, Infallible>(webhook.handle(&publisher, request).await) }
+                }))
+            }
+        });
+
+        log::info!("Receiving webhook deliveries on http://{address}");
+        Server::try_bind(&address)?.serve(make_service).await?;
+        Ok(())
+    }
+}
+
+/// Every account the delivered transactions touched, in either delivery
+/// format: enhanced transactions list them under `accountData`, raw ones
+/// as the message's account keys plus any loaded from lookup tables
+pub fn touched_accounts(delivery: &Value) -> Vec<String> {
+    let mut pubkeys = BTreeSet::new();
+    for transaction in delivery.as_array().map(Vec::as_slice).unwrap_or_default() {
+        let enhanced = transaction["accountData"].as_array().into_iter().flatten().map(|data| &data["account"]);
+        let keys = transaction["transaction"]["message"]["accountKeys"].as_array().into_iter().flatten();
+        let loaded = ["writable", "readonly"]
+            .into_iter()
+            .flat_map(|access| transaction["meta"]["loadedAddresses"][access].as_array().into_iter().flatten());
+
+        for key in enhanced.chain(keys).chain(loaded) {
+            // Parsed raw transactions list keys as `{ "pubkey": ... }`
+            if let Some(pubkey) = key.as_str().or_else(|| key["pubkey"].as_str()) {
+                pubkeys.insert(pubkey.to_string());
+            }
+        }
+    }
+    pubkeys.into_iter().collect()
+}
+
+fn status(status: StatusCode) -> Response<Body> {
+    let mut response = Response::new(Body::empty());
+    *response.status_mut() = status;
+    response
+}
@@ -0,0 +1,498 @@
+//! Geyser Plugin
+//!
+//! Filters validator account updates for privacy-trading `TradingBatch` /
+//! `OrderCommitment` and zk-verifier `ProofRecord` accounts, decodes them and
+//! broadcasts JSON to every connected WebSocket client.
+//!
+//! The same stream can run without a validator: the [`ingest`] sources feed
+//! a [`Publisher`] from a hosted RPC provider instead (see the
+//! `privacy-indexer` binary).
+//!
+//! Either way this is the project's indexer, and it is live only: it keeps
+//! no history and answers no queries. A market data gateway or historical
+//! analytics would first have to persist the stream.
+
+use borsh::BorshDeserialize;
+use futures_util::{SinkExt, StreamExt};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use solana_geyser_plugin_interface::geyser_plugin_interface::{
+    GeyserPlugin, GeyserPluginError, ReplicaAccountInfoVersions, Result,
+};
+use std::{fs, net::SocketAddr, sync::Arc};
+use tokio::{
+    net::{TcpListener, TcpStream},
+    runtime::Runtime,
+    sync::broadcast,
+};
+use tokio_tungstenite::tungstenite::Message;
+
+pub mod ingest;
+pub mod metrics;
+
+use metrics::Metrics;
+
+const DEFAULT_PRIVACY_TRADING_PROGRAM: &str = "3vfatmfrqUfPFRFKP9xTUWKYNYRL7X1wqg2Dz2z4zMQL";
+const DEFAULT_ZK_VERIFIER_PROGRAM: &str = "6n4EVsXYbKTz9aKcccCrsNVrnPrCNEHqMqan3G9AnDYN";
+const DEFAULT_CHANNEL_CAPACITY: usize = 1024;
+
+// ============================================
+// Configuration
+// ============================================
+
+/// Plugin config file, e.g.
+/// `{ "libpath": "libprivacy_geyser_plugin.so", "bind_address": "0.0.0.0:8900" }`
+#[derive(Deserialize, Debug)]
+pub struct PluginConfig {
+    pub bind_address: SocketAddr,
+    #[serde(default = "default_privacy_trading_program")]
+    pub privacy_trading_program: String,
+    #[serde(default = "default_zk_verifier_program")]
+    pub zk_verifier_program: String,
+    #[serde(default = "default_channel_capacity")]
+    pub channel_capacity: usize,
+    /// Prometheus exporter address, disabled when unset
+    #[serde(default)]
+    pub metrics_address: Option<SocketAddr>,
+}
+
+fn default_privacy_trading_program() -> String {
+    DEFAULT_PRIVACY_TRADING_PROGRAM.to_string()
+}
+
+fn default_zk_verifier_program() -> String {
+    DEFAULT_ZK_VERIFIER_PROGRAM.to_string()
+}
+
+fn default_channel_capacity() -> usize {
+    DEFAULT_CHANNEL_CAPACITY
+}
+
+// ============================================
+// On-chain Account Layouts
+// ============================================
+
+/// Borsh layout of `privacy_trading::TradingBatch` (after the discriminator)
+#[derive(BorshDeserialize)]
+struct TradingBatchLayout {
+    bump: u8,
+    authority: [u8; 32],
+    state_nonce: u128,
+    _encrypted_state: [[u8; 32]; 8],
+    market_id: String,
+    side: u8,
+    status: u8,
+    order_count: u8,
+    total_usdc: u64,
+    merkle_root: [u8; 32],
+    queued_at: u64,
+    completed_at: u64,
+}
+
+/// Borsh layout of `privacy_trading::OrderCommitment`
+#[derive(BorshDeserialize)]
+struct OrderCommitmentLayout {
+    bump: u8,
+    batch: [u8; 32],
+    user: [u8; 32],
+    commitment_hash: [u8; 32],
+    index: u8,
+    allocated: bool,
+}
+
+/// Borsh layout of `zk_verifier::ProofRecord`
+#[derive(BorshDeserialize)]
+struct ProofRecordLayout {
+    proof_id: String,
+    query_commitment: [u8; 32],
+    response_commitment: [u8; 32],
+    merkle_root: [u8; 32],
+    timestamp: u64,
+    verified: bool,
+    verified_at: i64,
+    verifier: [u8; 32],
+    bump: u8,
+}
+
+// ============================================
+// JSON Messages
+// ============================================
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountUpdate {
+    pub slot: u64,
+    pub pubkey: String,
+    pub is_startup: bool,
+    #[serde(flatten)]
+    pub account: DecodedAccount,
+}
+
+#[derive(Serialize)]
+#[serde(tag = "account", content = "data")]
+pub enum DecodedAccount {
+    TradingBatch(TradingBatchView),
+    OrderCommitment(OrderCommitmentView),
+    ProofRecord(ProofRecordView),
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TradingBatchView {
+    pub bump: u8,
+    pub authority: String,
+    pub market_id: String,
+    pub side: &'static str,
+    pub status: &'static str,
+    pub order_count: u8,
+    pub total_usdc: u64,
+    pub state_nonce: String,
+    pub merkle_root: String,
+    /// Slot the most recent MPC computation was queued at
+    pub queued_at: u64,
+    /// Slot the most recent MPC callback landed at
+    pub completed_at: u64,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OrderCommitmentView {
+    pub bump: u8,
+    pub batch: String,
+    pub user: String,
+    pub commitment_hash: String,
+    pub index: u8,
+    pub allocated: bool,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProofRecordView {
+    pub proof_id: String,
+    pub query_commitment: String,
+    pub response_commitment: String,
+    pub merkle_root: String,
+    pub timestamp: u64,
+    pub verified: bool,
+    pub verified_at: i64,
+    pub verifier: String,
+    pub bump: u8,
+}
+
+impl From<TradingBatchLayout> for TradingBatchView {
+    fn from(batch: TradingBatchLayout) -> Self {
+        Self {
+            bump: batch.bump,
+            authority: bs58::encode(batch.authority).into_string(),
+            market_id: batch.market_id,
+            side: match batch.side {
+                0 => "yes",
+                1 => "no",
+                _ => "unknown",
+            },
+            status: match batch.status {
+                0 => "open",
+                1 => "closed",
+                2 => "executed",
+                3 => "verified",
+                _ => "unknown",
+            },
+            order_count: batch.order_count,
+            total_usdc: batch.total_usdc,
+            // u128 does not fit in a JSON number
+            state_nonce: batch.state_nonce.to_string(),
+            merkle_root: hex::encode(batch.merkle_root),
+            queued_at: batch.queued_at,
+            completed_at: batch.completed_at,
+        }
+    }
+}
+
+impl From<OrderCommitmentLayout> for OrderCommitmentView {
+    fn from(order: OrderCommitmentLayout) -> Self {
+        Self {
+            bump: order.bump,
+            batch: bs58::encode(order.batch).into_string(),
+            user: bs58::encode(order.user).into_string(),
+            commitment_hash: hex::encode(order.commitment_hash),
+            index: order.index,
+            allocated: order.allocated,
+        }
+    }
+}
+
+impl From<ProofRecordLayout> for ProofRecordView {
+    fn from(record: ProofRecordLayout) -> Self {
+        Self {
+            proof_id: record.proof_id,
+            query_commitment: hex::encode(record.query_commitment),
+            response_commitment: hex::encode(record.response_commitment),
+            merkle_root: hex::encode(record.merkle_root),
+            timestamp: record.timestamp,
+            verified: record.verified,
+            verified_at: record.verified_at,
+            verifier: bs58::encode(record.verifier).into_string(),
+            bump: record.bump,
+        }
+    }
+}
+
+// ============================================
+// Account Filter
+// ============================================
+
+/// Recognises the accounts we stream by owner and Anchor discriminator
+#[derive(Debug)]
+pub struct AccountFilter {
+    privacy_trading: [u8; 32],
+    zk_verifier: [u8; 32],
+    trading_batch: [u8; 8],
+    order_commitment: [u8; 8],
+    proof_record: [u8; 8],
+}
+
+impl AccountFilter {
+    pub fn new(config: &PluginConfig) -> Result<Self> {
+        Ok(Self {
+            privacy_trading: parse_program_id(&config.privacy_trading_program)?,
+            zk_verifier: parse_program_id(&config.zk_verifier_program)?,
+            trading_batch: account_discriminator("TradingBatch"),
+            order_commitment: account_discriminator("OrderCommitment"),
+            proof_record: account_discriminator("ProofRecord"),
+        })
+    }
+
+    /// Decode an account if it is one we stream, `None` otherwise
+    pub fn decode(&self, owner: &[u8], data: &[u8]) -> Option<DecodedAccount> {
+        if data.len() < 8 {
+            return None;
+        }
+        let (discriminator, mut body) = data.split_at(8);
+
+        // Anchor accounts may carry fields appended after these layouts,
+        // so deserialize from the reader rather than requiring an exact length
+        if owner == self.privacy_trading {
+            if discriminator == self.trading_batch {
+                let batch = TradingBatchLayout::deserialize(&mut body).ok()?;
+                return Some(DecodedAccount::TradingBatch(batch.into()));
+            }
+            if discriminator == self.order_commitment {
+                let order = OrderCommitmentLayout::deserialize(&mut body).ok()?;
+                return Some(DecodedAccount::OrderCommitment(order.into()));
+            }
+        } else if owner == self.zk_verifier && discriminator == self.proof_record {
+            let record = ProofRecordLayout::deserialize(&mut body).ok()?;
+            return Some(DecodedAccount::ProofRecord(record.into()));
+        }
+
+        None
+    }
+}
+
+fn parse_program_id(program_id: &str) -> Result<[u8; 32]> {
+    let mut bytes = [0u8; 32];
+    let len = bs58::decode(program_id)
+        .onto(&mut bytes)
+        .map_err(|e| GeyserPluginError::ConfigFileReadError {
+            msg: format!("Invalid program id {program_id}: {e}"),
+        })?;
+    if len != 32 {
+        return Err(GeyserPluginError::ConfigFileReadError {
+            msg: format!("Invalid program id {program_id}: expected 32 bytes"),
+        });
+    }
+    Ok(bytes)
+}
+
+/// Anchor account discriminator: first 8 bytes of sha256("account:<Name>")
+fn account_discriminator(name: &str) -> [u8; 8] {
+    let hash = Sha256::digest(format!("account:{name}").as_bytes());
+    let mut discriminator = [0u8; 8];
+    discriminator.copy_from_slice(&hash[..8]);
+    discriminator
+}
+
+// ============================================
+// WebSocket Server
+// ============================================
+
+async fn serve(listener: TcpListener, sender: broadcast::Sender<String>) {
+    loop {
+        match listener.accept().await {
+            Ok((stream, peer)) => {
+                tokio::spawn(handle_client(stream, peer, sender.subscribe()));
+            }
+            Err(e) => log::warn!("Failed to accept WebSocket connection: {e}"),
+        }
+    }
+}
+
+async fn handle_client(stream: TcpStream, peer: SocketAddr, mut updates: broadcast::Receiver<String>) {
+    let ws = match tokio_tungstenite::accept_async(stream).await {
+        Ok(ws) => ws,
+        Err(e) => {
+            log::warn!("WebSocket handshake with {peer} failed: {e}");
+            return;
+        }
+    };
+    let (mut write, mut read) = ws.split();
+
+    loop {
+        tokio::select! {
+            update = updates.recv() => match update {
+                Ok(json) => {
+                    if write.send(Message::Text(json)).await.is_err() {
+                        break;
+                    }
+                }
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    log::warn!("WebSocket client {peer} lagged, dropped {skipped} updates");
+                }
+                Err(broadcast::error::RecvError::Closed) => break,
+            },
+            incoming = read.next() => match incoming {
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                Some(Ok(_)) => {}
+            },
+        }
+    }
+}
+
+// ============================================
+// Publisher
+// ============================================
+
+/// Decodes account updates, whatever their source, and broadcasts the ones
+/// we stream to every WebSocket client
+#[derive(Debug)]
+pub struct Publisher {
+    filter: AccountFilter,
+    sender: broadcast::Sender<String>,
+    metrics: Option<Arc<Metrics>>,
+}
+
+impl Publisher {
+    /// Start the WebSocket server, and the metrics exporter if configured, on
+    /// `runtime`
+    pub fn start(config: &PluginConfig, runtime: &Runtime) -> Result<Self> {
+        let filter = AccountFilter::new(config)?;
+        let (sender, _) = broadcast::channel(config.channel_capacity);
+
+        let listener = runtime
+            .block_on(TcpListener::bind(config.bind_address))
+            .map_err(|e| GeyserPluginError::Custom(Box::new(e)))?;
+        runtime.spawn(serve(listener, sender.clone()));
+
+        log::info!("Streaming batch/proof updates on ws://{}", config.bind_address);
+
+        let mut metrics = None;
+        if let Some(metrics_address) = config.metrics_address {
+            let exporter = Arc::new(Metrics::new().map_err(|e| GeyserPluginError::Custom(Box::new(e)))?);
+            runtime.spawn(metrics::serve(metrics_address, exporter.clone()));
+            log::info!("Serving Prometheus metrics on http://{metrics_address}");
+            metrics = Some(exporter);
+        }
+
+        Ok(Self { filter, sender, metrics })
+    }
+
+    /// Broadcast an account if it is one we stream
+    pub fn publish(&self, slot: u64, pubkey: &[u8], owner: &[u8], data: &[u8], is_startup: bool) -> Result<()> {
+        let Some(account) = self.filter.decode(owner, data) else {
+            return Ok(());
+        };
+
+        if let Some(metrics) = &self.metrics {
+            metrics.observe(pubkey, &account);
+        }
+
+        let update = AccountUpdate {
+            slot,
+            pubkey: bs58::encode(pubkey).into_string(),
+            is_startup,
+            account,
+        };
+        let json = serde_json::to_string(&update).map_err(|e| GeyserPluginError::AccountsUpdateError {
+            msg: e.to_string(),
+        })?;
+
+        // Sending only fails when no client is connected
+        let _ = self.sender.send(json);
+        Ok(())
+    }
+}
+
+// ============================================
+// Plugin
+// ============================================
+
+#[derive(Default, Debug)]
+pub struct BatchStreamPlugin {
+    publisher: Option<Publisher>,
+    runtime: Option<Runtime>,
+}
+
+impl GeyserPlugin for BatchStreamPlugin {
+    fn name(&self) -> &'static str {
+        "privacy-geyser-plugin"
+    }
+
+    fn on_load(&mut self, config_file: &str, _is_reload: bool) -> Result<()> {
+        let raw = fs::read_to_string(config_file).map_err(|e| GeyserPluginError::ConfigFileReadError {
+            msg: format!("Failed to read {config_file}: {e}"),
+        })?;
+        let config: PluginConfig = serde_json::from_str(&raw).map_err(|e| GeyserPluginError::ConfigFileReadError {
+            msg: format!("Failed to parse {config_file}: {e}"),
+        })?;
+
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .worker_threads(2)
+            .thread_name("privacy-geyser")
+            .enable_all()
+            .build()
+            .map_err(|e| GeyserPluginError::Custom(Box::new(e)))?;
+
+        self.publisher = Some(Publisher::start(&config, &runtime)?);
+        self.runtime = Some(runtime);
+        Ok(())
+    }
+
+    fn on_unload(&mut self) {
+        self.publisher = None;
+        if let Some(runtime) = self.runtime.take() {
+            runtime.shutdown_background();
+        }
+    }
+
+    fn update_account(&self, account: ReplicaAccountInfoVersions, slot: u64, is_startup: bool) -> Result<()> {
+        let Some(publisher) = &self.publisher else {
+            return Ok(());
+        };
+
+        let (pubkey, owner, data) = match account {
+            ReplicaAccountInfoVersions::V0_0_1(info) => (info.pubkey, info.owner, info.data),
+            ReplicaAccountInfoVersions::V0_0_2(info) => (info.pubkey, info.owner, info.data),
+            ReplicaAccountInfoVersions::V0_0_3(info) => (info.pubkey, info.owner, info.data),
+        };
+
+        publisher.publish(slot, pubkey, owner, data, is_startup)
+    }
+
+    fn account_data_notifications_enabled(&self) -> bool {
+        true
+    }
+
+    fn transaction_notifications_enabled(&self) -> bool {
+        false
+    }
+}
+
+/// # Safety
+///
+/// Called by the validator's plugin manager, which takes ownership of the box.
+#[no_mangle]
+#[allow(improper_ctypes_definitions)]
+pub unsafe extern "C" fn _create_plugin() -> *mut dyn GeyserPlugin {
+    let plugin: Box<dyn GeyserPlugin> = Box::<BatchStreamPlugin>::default();
+    Box::into_raw(plugin)
+}
@@ -0,0 +1,166 @@
+//! Prometheus metrics derived from the streamed account updates.
+
+use crate::DecodedAccount;
+use hyper::{
+    service::{make_service_fn, service_fn},
+    Body, Response, Server,
+};
+use prometheus::{
+    Encoder, Histogram, HistogramOpts, IntCounter, IntCounterVec, IntGaugeVec, Opts, Registry,
+    TextEncoder,
+};
+use std::{
+    collections::{HashMap, HashSet},
+    convert::Infallible,
+    net::SocketAddr,
+    sync::{Arc, Mutex},
+};
+
+pub struct Metrics {
+    registry: Registry,
+    batches: IntGaugeVec,
+    batch_orders: Histogram,
+    executed_usdc: IntCounter,
+    proof_records: IntCounterVec,
+    mpc_latency: Histogram,
+    /// Last seen status per batch, so gauges move on transitions only
+    batch_status: Mutex<HashMap<[u8; 32], &'static str>>,
+    /// Last seen `completed_at` per batch, so each callback is observed once
+    batch_completed_at: Mutex<HashMap<[u8; 32], u64>>,
+    seen_proofs: Mutex<HashSet<[u8; 32]>>,
+}
+
+impl std::fmt::Debug for Metrics {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Metrics").finish_non_exhaustive()
+    }
+}
+
+impl Metrics {
+    pub fn new() -> prometheus::Result<Self> {
+        let registry = Registry::new();
+
+        let batches = IntGaugeVec::new(
+            Opts::new("privacy_batches", "Trading batches by status"),
+            &["status"],
+        )?;
+        let batch_orders = Histogram::with_opts(
+            HistogramOpts::new("privacy_batch_orders", "Orders per executed batch")
+                .buckets(vec![1.0, 2.0, 4.0, 8.0, 16.0, 32.0]),
+        )?;
+        let executed_usdc = IntCounter::new(
+            "privacy_executed_usdc_total",
+            "USDC revealed by executed batches",
+        )?;
+        let proof_records = IntCounterVec::new(
+            Opts::new("privacy_proof_records_total", "Proof records created"),
+            &["verified"],
+        )?;
+        let mpc_latency = Histogram::with_opts(
+            HistogramOpts::new(
+                "privacy_mpc_latency_slots",
+                "Slots from queueing an MPC computation to its callback",
+            )
+            .buckets(vec![1.0, 2.0, 5.0, 10.0, 20.0, 50.0, 100.0, 200.0]),
+        )?;
+
+        registry.register(Box::new(batches.clone()))?;
+        registry.register(Box::new(batch_orders.clone()))?;
+        registry.register(Box::new(executed_usdc.clone()))?;
+        registry.register(Box::new(proof_records.clone()))?;
+        registry.register(Box::new(mpc_latency.clone()))?;
+
+        Ok(Self {
+            registry,
+            batches,
+            batch_orders,
+            executed_usdc,
+            proof_records,
+            mpc_latency,
+            batch_status: Mutex::new(HashMap::new()),
+            batch_completed_at: Mutex::new(HashMap::new()),
+            seen_proofs: Mutex::new(HashSet::new()),
+        })
+    }
+
+    /// Record a decoded account update
+    pub fn observe(&self, pubkey: &[u8], account: &DecodedAccount) {
+        let Ok(key) = <[u8; 32]>::try_from(pubkey) else {
+            return;
+        };
+
+        match account {
+            DecodedAccount::TradingBatch(batch) => {
+                // Each callback moves `completed_at`; until the next queue,
+                // `queued_at` still holds the slot its computation was queued at
+                let previous_completion = self
+                    .batch_completed_at
+                    .lock()
+                    .unwrap()
+                    .insert(key, batch.completed_at);
+                if batch.completed_at != 0
+                    && previous_completion != Some(batch.completed_at)
+                    && batch.completed_at >= batch.queued_at
+                {
+                    self.mpc_latency
+                        .observe((batch.completed_at - batch.queued_at) as f64);
+                }
+
+                let mut statuses = self.batch_status.lock().unwrap();
+                let previous = statuses.insert(key, batch.status);
+                if previous == Some(batch.status) {
+                    return;
+                }
+
+                if let Some(previous) = previous {
+                    self.batches.with_label_values(&[previous]).dec();
+                }
+                self.batches.with_label_values(&[batch.status]).inc();
+
+                // Count each batch once, on its Closed -> Executed transition
+                if batch.status == "executed" {
+                    self.batch_orders.observe(batch.order_count as f64);
+                    self.executed_usdc.inc_by(batch.total_usdc);
+                }
+            }
+            DecodedAccount::ProofRecord(record) => {
+                if self.seen_proofs.lock().unwrap().insert(key) {
+                    let verified = if record.verified { "true" } else { "false" };
+                    self.proof_records.with_label_values(&[verified]).inc();
+                }
+            }
+            DecodedAccount::OrderCommitment(_) => {}
+        }
+    }
+
+    /// The registry in the Prometheus text format
+    pub fn render(&self) -> Vec<u8> {
+        let mut buffer = Vec::new();
+        if let Err(e) = TextEncoder::new().encode(&self.registry.gather(), &mut buffer) {
+            log::warn!("Failed to encode metrics: {e}");
+        }
+        buffer
+    }
+}
+
+/// Serve `/metrics` (any path, really) in the Prometheus text format
+pub async fn serve(address: SocketAddr, metrics: Arc<Metrics>) {
+    let make_service = make_service_fn(move |_| {
+        let metrics = metrics.clone();
+        async move {
+            Ok::<_, Infallible>(service_fn(move |_request| {
+                let body = metrics.render();
+                async move { Ok::<_, Infallible>(Response::new(Body::from(body))) }
+            }))
+        }
+    });
+
+    match Server::try_bind(&address) {
+        Ok(server) => {
+            if let Err(e) = server.serve(make_service).await {
+                log::error!("Metrics server stopped: {e}");
+            }
+        }
+        Err(e) => log::error!("Failed to bind metrics server on {address}: {e}"),
+    }
+}
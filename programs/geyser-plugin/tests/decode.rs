@@ -0,0 +1,174 @@
+use anchor_lang::prelude::*;
+use privacy_geyser_plugin::{AccountFilter, DecodedAccount, PluginConfig};
+use sha2::{Digest, Sha256};
+use zk_verifier::{ProofRecord, ProofSystem};
+
+const PRIVACY_TRADING: [u8; 32] = [7; 32];
+const ZK_VERIFIER: [u8; 32] = [9; 32];
+
+fn filter() -> AccountFilter {
+    let config: PluginConfig = serde_json::from_value(serde_json::json!({
+        "bind_address": "127.0.0.1:0",
+        "privacy_trading_program": bs58::encode(PRIVACY_TRADING).into_string(),
+        "zk_verifier_program": bs58::encode(ZK_VERIFIER).into_string(),
+    }))
+    .unwrap();
+    AccountFilter::new(&config).unwrap()
+}
+
+fn discriminator(name: &str) -> Vec<u8> {
+    Sha256::digest(format!("account:{name}").as_bytes())[..8].to_vec()
+}
+
+fn proof_record() -> ProofRecord {
+    ProofRecord {
+        proof_id: "proof-1".to_string(),
+        query_commitment: [1; 32],
+        response_commitment: [2; 32],
+        merkle_root: [3; 32],
+        timestamp: 1_760_000_000,
+        verified: true,
+        verified_at: 1_760_000_042,
+        verifier: Pubkey::new_from_array([4; 32]),
+        bump: 254,
+        proof_system: ProofSystem::UltraHonk,
+    }
+}
+
+/// privacy-trading does not build outside the Arcium toolchain, so its
+/// `TradingBatch` is laid out by hand, field by field, including the fields
+/// after `completed_at` that the plugin skips
+fn trading_batch(status: u8) -> Vec<u8> {
+    let mut data = discriminator("TradingBatch");
+    data.push(253); // bump
+    data.extend_from_slice(&[5; 32]); // authority
+    data.extend_from_slice(&(u128::MAX - 1).to_le_bytes()); // state_nonce
+    data.extend_from_slice(&[0xaa; 32 * 8]); // encrypted_state
+    data.extend_from_slice(&6u32.to_le_bytes());
+    data.extend_from_slice(b"btc-1m"); // market_id
+    data.push(1); // side: No
+    data.push(status);
+    data.push(3); // order_count
+    data.extend_from_slice(&250_000_000u64.to_le_bytes()); // total_usdc
+    data.extend_from_slice(&[0xbb; 32]); // merkle_root
+    data.extend_from_slice(&1_000u64.to_le_bytes()); // queued_at
+    data.extend_from_slice(&1_012u64.to_le_bytes()); // completed_at
+    data.push(0); // required_credential: None
+    data.push(0); // eligibility_predicate: None
+    data.push(0); // staged_orders
+    data.push(0); // lane_count
+    data.push(0); // lanes_merged
+    data.push(0); // flow_auction: None
+    data.push(0); // pending_computation: None
+    for slot in [900u64, 990, 1_012, 0] {
+        data.extend_from_slice(&slot.to_le_bytes()); // status_updated_at
+    }
+    data.push(0); // queued_computation: None
+    data.push(0); // reference_condition: None
+    data.extend_from_slice(&5_000u64.to_le_bytes()); // reference_mid
+    data.extend_from_slice(&5_100u64.to_le_bytes()); // execution_price
+    data.push(0); // fill_published
+    data
+}
+
+fn order_commitment() -> Vec<u8> {
+    let mut data = discriminator("OrderCommitment");
+    data.push(252); // bump
+    data.extend_from_slice(&[0x11; 32]); // batch
+    data.extend_from_slice(&[0x22; 32]); // user
+    data.extend_from_slice(&[0x33; 32]); // commitment_hash
+    data.push(2); // index
+    data.push(1); // allocated
+    data.push(1); // eligibility_proof: Some
+    data.extend_from_slice(&[0x44; 32]);
+    data.push(0); // rejected
+    data
+}
+
+#[test]
+fn decodes_proof_record_serialized_by_zk_verifier() {
+    let mut data = Vec::new();
+    proof_record().try_serialize(&mut data).unwrap();
+
+    let Some(DecodedAccount::ProofRecord(record)) = filter().decode(&ZK_VERIFIER, &data) else {
+        panic!("ProofRecord not decoded");
+    };
+    assert_eq!(record.proof_id, "proof-1");
+    assert_eq!(record.query_commitment, hex::encode([1; 32]));
+    assert_eq!(record.response_commitment, hex::encode([2; 32]));
+    assert_eq!(record.merkle_root, hex::encode([3; 32]));
+    assert_eq!(record.timestamp, 1_760_000_000);
+    assert!(record.verified);
+    assert_eq!(record.verified_at, 1_760_000_042);
+    assert_eq!(record.verifier, bs58::encode([4; 32]).into_string());
+    assert_eq!(record.bump, 254);
+}
+
+#[test]
+fn discriminator_matches_anchor() {
+    let mut data = Vec::new();
+    proof_record().try_serialize(&mut data).unwrap();
+    assert_eq!(&data[..8], ProofRecord::DISCRIMINATOR);
+    assert_eq!(data[..8], discriminator("ProofRecord"));
+}
+
+#[test]
+fn decodes_trading_batch() {
+    let Some(DecodedAccount::TradingBatch(batch)) = filter().decode(&PRIVACY_TRADING, &trading_batch(2)) else {
+        panic!("TradingBatch not decoded");
+    };
+    assert_eq!(batch.bump, 253);
+    assert_eq!(batch.authority, bs58::encode([5; 32]).into_string());
+    assert_eq!(batch.market_id, "btc-1m");
+    assert_eq!(batch.side, "no");
+    assert_eq!(batch.status, "executed");
+    assert_eq!(batch.order_count, 3);
+    assert_eq!(batch.total_usdc, 250_000_000);
+    assert_eq!(batch.state_nonce, (u128::MAX - 1).to_string());
+    assert_eq!(batch.merkle_root, hex::encode([0xbb; 32]));
+    assert_eq!(batch.queued_at, 1_000);
+    assert_eq!(batch.completed_at, 1_012);
+}
+
+#[test]
+fn batch_state_sits_before_the_market_id() {
+    // `BATCH_ENCRYPTED_STATE_OFFSET`: discriminator + bump + authority +
+    // state_nonce, whatever the market id's length
+    let data = trading_batch(0);
+    assert_eq!(data[8 + 1 + 32 + 16..][..32 * 8], [0xaa; 32 * 8]);
+}
+
+#[test]
+fn decodes_order_commitment() {
+    let Some(DecodedAccount::OrderCommitment(order)) = filter().decode(&PRIVACY_TRADING, &order_commitment()) else {
+        panic!("OrderCommitment not decoded");
+    };
+    assert_eq!(order.bump, 252);
+    assert_eq!(order.batch, bs58::encode([0x11; 32]).into_string());
+    assert_eq!(order.user, bs58::encode([0x22; 32]).into_string());
+    assert_eq!(order.commitment_hash, hex::encode([0x33; 32]));
+    assert_eq!(order.index, 2);
+    assert!(order.allocated);
+}
+
+#[test]
+fn ignores_accounts_of_other_owners() {
+    let mut record = Vec::new();
+    proof_record().try_serialize(&mut record).unwrap();
+
+    let filter = filter();
+    assert!(filter.decode(&PRIVACY_TRADING, &record).is_none());
+    assert!(filter.decode(&ZK_VERIFIER, &trading_batch(0)).is_none());
+    assert!(filter.decode(&[0; 32], &order_commitment()).is_none());
+}
+
+#[test]
+fn rejects_truncated_accounts() {
+    let filter = filter();
+    let batch = trading_batch(0);
+    // Cut inside encrypted_state
+    assert!(filter.decode(&PRIVACY_TRADING, &batch[..batch.len() - 200]).is_none());
+    // Cut inside completed_at
+    assert!(filter.decode(&PRIVACY_TRADING, &batch[..batch.len() - 62]).is_none());
+    assert!(filter.decode(&PRIVACY_TRADING, &batch[..7]).is_none());
+}
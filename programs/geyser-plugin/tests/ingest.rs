@@ -0,0 +1,74 @@
+use base64::Engine;
+use privacy_geyser_plugin::ingest::{
+    webhook::touched_accounts, IndexerConfig, RawAccount, SourceConfig,
+};
+use serde_json::json;
+
+fn address(byte: u8) -> String {
+    bs58::encode([byte; 32]).into_string()
+}
+
+#[test]
+fn reads_base64_rpc_accounts() {
+    let data = base64::engine::general_purpose::STANDARD.encode([1, 2, 3]);
+    let account = json!({ "owner": address(7), "data": [data, "base64"], "lamports": 1 });
+
+    let raw = RawAccount::from_rpc(42, &address(5), &account).unwrap();
+    assert_eq!(raw.slot, 42);
+    assert_eq!(raw.pubkey, [5; 32]);
+    assert_eq!(raw.owner, [7; 32]);
+    assert_eq!(raw.data, [1, 2, 3]);
+}
+
+#[test]
+fn rejects_malformed_rpc_accounts() {
+    let data = base64::engine::general_purpose::STANDARD.encode([1]);
+    let bad_owner = json!({ "owner": "not-a-key", "data": [data, "base64"] });
+    let bad_data = json!({ "owner": address(7), "data": ["%%%", "base64"] });
+
+    assert!(RawAccount::from_rpc(1, &address(5), &bad_owner).is_err());
+    assert!(RawAccount::from_rpc(1, &address(5), &bad_data).is_err());
+    assert!(
+        RawAccount::from_rpc(1, "short", &json!({ "owner": address(7), "data": [data] })).is_err()
+    );
+}
+
+#[test]
+fn collects_accounts_of_enhanced_deliveries() {
+    let delivery = json!([
+        { "accountData": [{ "account": address(2) }, { "account": address(1) }] },
+        { "accountData": [{ "account": address(1) }] },
+    ]);
+    assert_eq!(touched_accounts(&delivery), [address(1), address(2)]);
+}
+
+#[test]
+fn collects_accounts_of_raw_deliveries() {
+    let delivery = json!([{
+        "transaction": { "message": { "accountKeys": [address(3), { "pubkey": address(4) }] } },
+        "meta": { "loadedAddresses": { "writable": [address(5)], "readonly": [address(6)] } },
+    }]);
+
+    let mut expected = [address(3), address(4), address(5), address(6)];
+    expected.sort();
+    assert_eq!(touched_accounts(&delivery), expected);
+}
+
+#[test]
+fn ignores_deliveries_that_are_not_lists() {
+    assert!(touched_accounts(&json!({ "accountData": [] })).is_empty());
+}
+
+#[test]
+fn indexer_config_names_its_source() {
+    let config: IndexerConfig = serde_json::from_value(json!({
+        "bind_address": "127.0.0.1:8900",
+        "source": { "webhook": { "listen_address": "127.0.0.1:8901", "rpc_url": "https://rpc.example" } },
+    }))
+    .unwrap();
+    let SourceConfig::Webhook(webhook) = config.source else {
+        panic!("webhook source not parsed");
+    };
+    assert_eq!(webhook.listen_address.port(), 8901);
+    assert_eq!(webhook.auth_header, None);
+}
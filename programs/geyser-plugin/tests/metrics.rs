@@ -0,0 +1,59 @@
+use privacy_geyser_plugin::{metrics::Metrics, DecodedAccount, TradingBatchView};
+
+const BATCH: [u8; 32] = [1; 32];
+
+fn batch(status: &'static str, queued_at: u64, completed_at: u64) -> DecodedAccount {
+    DecodedAccount::TradingBatch(TradingBatchView {
+        bump: 255,
+        authority: String::new(),
+        market_id: "btc-1m".to_string(),
+        side: "yes",
+        status,
+        order_count: 2,
+        total_usdc: 0,
+        state_nonce: "0".to_string(),
+        merkle_root: String::new(),
+        queued_at,
+        completed_at,
+    })
+}
+
+fn rendered(metrics: &Metrics) -> String {
+    String::from_utf8(metrics.render()).unwrap()
+}
+
+#[test]
+fn renders_mpc_latency_per_callback() {
+    let metrics = Metrics::new().unwrap();
+
+    // Queued, not yet called back
+    metrics.observe(&BATCH, &batch("open", 100, 0));
+    // init_batch callback after 3 slots, then the same account again
+    metrics.observe(&BATCH, &batch("open", 100, 103));
+    metrics.observe(&BATCH, &batch("open", 100, 103));
+    // add_order queued; completed_at is still the previous callback's
+    metrics.observe(&BATCH, &batch("open", 110, 103));
+    metrics.observe(&BATCH, &batch("open", 110, 140));
+
+    let text = rendered(&metrics);
+    assert!(text.contains("# TYPE privacy_mpc_latency_slots histogram"), "{text}");
+    assert!(text.contains("privacy_mpc_latency_slots_count 2"), "{text}");
+    assert!(text.contains("privacy_mpc_latency_slots_sum 33"), "{text}");
+    assert!(text.contains("privacy_mpc_latency_slots_bucket{le=\"5\"} 1"), "{text}");
+    assert!(text.contains("privacy_mpc_latency_slots_bucket{le=\"20\"} 1"), "{text}");
+    assert!(text.contains("privacy_mpc_latency_slots_bucket{le=\"50\"} 2"), "{text}");
+}
+
+#[test]
+fn renders_batch_status_transitions() {
+    let metrics = Metrics::new().unwrap();
+
+    metrics.observe(&BATCH, &batch("open", 0, 0));
+    metrics.observe(&BATCH, &batch("closed", 0, 0));
+    metrics.observe(&[2; 32], &batch("open", 0, 0));
+
+    let text = rendered(&metrics);
+    assert!(text.contains("privacy_batches{status=\"open\"} 1"), "{text}");
+    assert!(text.contains("privacy_batches{status=\"closed\"} 1"), "{text}");
+    assert!(text.contains("privacy_mpc_latency_slots_count 0"), "{text}");
+}
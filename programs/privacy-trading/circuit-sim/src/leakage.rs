@@ -0,0 +1,110 @@
+//! Leakage Audit
+//!
+//! Static scan of the `encrypted-ixs` source for control flow that could
+//! depend on secret data. MPC evaluates both sides of a select, but a
+//! `match`, early exit or short-circuiting operator that is not compiled
+//! obliviously would leak which branch ran. The audit only accepts:
+//!
+//! - single-line selects, `if cond { a } else { b }`
+//! - `for` loops over literal ranges
+//! - non-short-circuiting `&` / `|` on booleans
+
+/// Tokens that introduce data-dependent control flow
+const FORBIDDEN: &[(&str, &str)] = &[
+    ("match ", "match on a value"),
+    ("while ", "data-dependent loop"),
+    ("loop ", "data-dependent loop"),
+    ("return", "early return"),
+    ("break", "early exit"),
+    ("continue", "early exit"),
+    ("&&", "short-circuiting and"),
+    ("||", "short-circuiting or"),
+    ("?;", "early return"),
+];
+
+/// A line that failed the audit
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Finding {
+    pub line: usize,
+    pub reason: &'static str,
+}
+
+/// The encrypted instruction source this crate simulates
+pub const CIRCUIT_SOURCE: &str = include_str!("../../encrypted-ixs/src/lib.rs");
+
+/// Audit circuit source, returning every line with non-oblivious control flow
+pub fn audit(source: &str) -> Vec<Finding> {
+    let mut findings = Vec::new();
+
+    for (i, raw) in source.lines().enumerate() {
+        let line = strip_comment(raw).trim();
+        let line_no = i + 1;
+
+        for &(token, reason) in FORBIDDEN {
+            if line.contains(token) {
+                findings.push(Finding { line: line_no, reason });
+            }
+        }
+
+        if has_keyword(line, "if") && !is_select(line) {
+            findings.push(Finding {
+                line: line_no,
+                reason: "branch that is not a single-line select",
+            });
+        }
+
+        if has_keyword(line, "for") && !is_literal_range_loop(line) {
+            findings.push(Finding {
+                line: line_no,
+                reason: "loop over a non-literal range",
+            });
+        }
+    }
+
+    findings
+}
+
+fn strip_comment(line: &str) -> &str {
+    match line.find("//") {
+        Some(at) => &line[..at],
+        None => line,
+    }
+}
+
+fn has_keyword(line: &str, keyword: &str) -> bool {
+    line.split(|c: char| !c.is_alphanumeric() && c != '_')
+        .any(|word| word == keyword)
+}
+
+/// `... if cond { a } else { b }` with both arms on the line
+fn is_select(line: &str) -> bool {
+    let Some(at) = line.find("if ") else {
+        return false;
+    };
+    let rest = &line[at..];
+    let Some(else_at) = rest.find("} else {") else {
+        return false;
+    };
+
+    rest[..else_at].contains('{') && rest[else_at + "} else {".len()..].contains('}')
+}
+
+/// `for i in 0..16 {`
+fn is_literal_range_loop(line: &str) -> bool {
+    let Some(range) = line
+        .strip_prefix("for ")
+        .and_then(|rest| rest.split_once(" in "))
+        .and_then(|(_, rest)| rest.strip_suffix(" {"))
+    else {
+        return false;
+    };
+
+    match range.split_once("..") {
+        Some((start, end)) => is_integer(start) && is_integer(end),
+        None => false,
+    }
+}
+
+fn is_integer(s: &str) -> bool {
+    !s.is_empty() && s.chars().all(|c| c.is_ascii_digit() || c == '_')
+}
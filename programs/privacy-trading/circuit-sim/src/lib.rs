@@ -0,0 +1,550 @@
+//! Circuit Simulator
+//!
+//! Plaintext re-implementation of the `encrypted-ixs` circuits. Every function
+//! here performs the same arithmetic as its `#[instruction]` counterpart, minus
+//! the encryption, so circuit logic can be unit tested and MPC outputs can be
+//! replayed off-chain. The circuits' plain helpers are not mirrored but
+//! shared, from `trading_rules::circuit`.
+//!
+//! With the `leakage-audit` feature, [`leakage`] also scans the circuit
+//! source for secret-dependent control flow.
+
+#[cfg(feature = "leakage-audit")]
+pub mod leakage;
+
+pub use trading_rules::circuit::{
+    bind_wallet, check_expiry, check_total, compute_fill_notional, compute_fill_ratio_bps,
+    compute_order_hash, daily_remaining, hash_execution_params, pad_observation, sort_five,
+    update_merkle_root,
+};
+pub use trading_rules::median_pad_high_mask;
+
+// ============================================
+// State Structures
+// ============================================
+
+/// Plaintext mirror of the encrypted `BatchState`
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct BatchState {
+    pub total_amount: u64,
+    pub order_count: u8,
+    pub commitment_root: u128,
+    pub commitment_root_hi: u128,
+    pub order_hash_1: u128,
+    pub order_hash_2: u128,
+    pub order_hash_3: u128,
+    pub order_hash_4: u128,
+}
+
+/// Plaintext mirror of `ExecuteBatchOutputData`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ExecuteBatchOutput {
+    pub merkle_root: [u8; 32],
+    pub total_usdc: u64,
+}
+
+/// Plaintext mirror of `ExecutionPreview`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ExecutionPreview {
+    pub clearing_price: u64,
+    pub fill_ratio_bps: u64,
+    pub total_usdc: u64,
+}
+
+/// A single order as it enters `add_order` after decryption
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Order {
+    pub amount: u64,
+    pub wallet_lo: u128,
+    pub wallet_hi: u128,
+}
+
+impl Order {
+    /// Build an order from a wallet pubkey, split the same way clients encrypt it
+    pub fn new(amount: u64, wallet: &[u8; 32]) -> Self {
+        let (wallet_lo, wallet_hi) = split_wallet(wallet);
+        Self {
+            amount,
+            wallet_lo,
+            wallet_hi,
+        }
+    }
+}
+
+/// Plaintext mirror of the encrypted `SessionBudget`
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SessionBudget {
+    pub remaining: u64,
+}
+
+/// Plaintext mirror of the encrypted `RfqState`
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct RfqState {
+    pub size: u64,
+    pub side: u8,
+    pub best_price: u64,
+    pub best_maker: u8,
+    pub has_quote: bool,
+}
+
+/// Plaintext mirror of `MakerQuote`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MakerQuote {
+    pub yes_price: u64,
+    pub no_price: u64,
+}
+
+/// Plaintext mirror of `RfqSettlement`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RfqSettlement {
+    pub has_quote: bool,
+    pub best_maker: u8,
+    pub fill_notional: u64,
+}
+
+/// Plaintext mirror of the encrypted `ParlayState` (`weight_0..weight_3`)
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ParlayState {
+    pub amount: u64,
+    pub weights: [u64; 4],
+    pub wallet_lo: u128,
+    pub wallet_hi: u128,
+}
+
+/// Plaintext mirror of `FlowBid`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FlowBid {
+    pub improvement_bps: u64,
+    pub capacity: u64,
+}
+
+/// Plaintext mirror of the encrypted `FlowAuctionState`
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct FlowAuctionState {
+    pub best_improvement: u64,
+    pub best_maker: u8,
+    pub has_bid: bool,
+}
+
+/// Plaintext mirror of `FlowAuctionSettlement`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FlowAuctionSettlement {
+    pub has_bid: bool,
+    pub best_maker: u8,
+    pub improvement_bps: u64,
+}
+
+/// Plaintext mirror of the encrypted `OracleState` (`value_0..value_4`)
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct OracleState {
+    pub values: [u64; 5],
+}
+
+/// Plaintext mirror of the encrypted `HouseLedger`
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct HouseLedger {
+    pub pending_notional: u64,
+    pub cost_basis: u64,
+    pub inventory: u64,
+}
+
+/// Plaintext mirror of `HouseStatement`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct HouseStatement {
+    pub pending_notional: u64,
+    pub cost_basis: u64,
+    pub inventory: u64,
+    pub mark_value: u64,
+}
+
+// ============================================
+// Instructions
+// ============================================
+
+/// Simulate `init_batch`
+pub fn init_batch() -> BatchState {
+    BatchState::default()
+}
+
+/// Simulate the signer binding that `add_order`, `add_session_order` and
+/// `add_lane_order` apply before folding an order
+///
+/// Returns the order to fold and whether its wallet matched the signer.
+pub fn bind_order(order: &Order, signer: &[u8; 32]) -> (Order, bool) {
+    let (signer_lo, signer_hi) = split_wallet(signer);
+    let (amount, wallet_lo, wallet_hi, is_bound) =
+        bind_wallet(order.amount, order.wallet_lo, order.wallet_hi, signer_lo, signer_hi);
+    (Order { amount, wallet_lo, wallet_hi }, is_bound)
+}
+
+/// Simulate the deadline check the order circuits apply after `bind_order`
+///
+/// Returns the order to fold and the revealed `expired` flag.
+pub fn expire_order(order: &Order, expiry_slot: u64, current_slot: u64) -> (Order, bool) {
+    let (amount, expired) = check_expiry(order.amount, expiry_slot, current_slot);
+    (Order { amount, ..*order }, expired)
+}
+
+/// Simulate `add_order` (after `bind_order` and `expire_order`)
+pub fn add_order(state: &BatchState, order: &Order) -> BatchState {
+    fold_order(state, true, order)
+}
+
+/// Simulate `add_orders_bulk` (each order after `bind_order` and
+/// `expire_order`)
+///
+/// Slots from `order_count` on are folded as absent, whatever they hold.
+pub fn add_orders_bulk(state: &BatchState, orders: &[Order; 8], order_count: u8) -> BatchState {
+    orders
+        .iter()
+        .enumerate()
+        .fold(*state, |state, (i, order)| fold_order(&state, (i as u8) < order_count, order))
+}
+
+/// Simulate the circuits' `fold_order`: `add_order` for an order that may be
+/// absent
+fn fold_order(state: &BatchState, present: bool, order: &Order) -> BatchState {
+    let mut state = *state;
+    let (amount, _) = check_total(state.total_amount, order.amount);
+    let amount = if present { amount } else { 0 };
+
+    state.total_amount += amount;
+    state.order_count = state.order_count.wrapping_add(present as u8);
+
+    let order_hash = compute_order_hash(amount, order.wallet_lo, order.wallet_hi);
+    let leaf = if present { order_hash } else { 0 };
+
+    let (new_root_lo, new_root_hi) = update_merkle_root(
+        state.commitment_root,
+        state.commitment_root_hi,
+        leaf,
+    );
+    state.commitment_root = new_root_lo;
+    state.commitment_root_hi = new_root_hi;
+
+    match state.order_count {
+        1 if present => state.order_hash_1 = order_hash,
+        2 if present => state.order_hash_2 = order_hash,
+        3 if present => state.order_hash_3 = order_hash,
+        4 if present => state.order_hash_4 = order_hash,
+        _ => {}
+    }
+
+    state
+}
+
+/// Simulate `execute_batch`
+pub fn execute_batch(state: &BatchState, total_shares: u64, execution_price: u64) -> ExecuteBatchOutput {
+    let mut final_root = [0u8; 32];
+
+    final_root[..16].copy_from_slice(&state.commitment_root.to_le_bytes());
+    final_root[16..].copy_from_slice(&state.commitment_root_hi.to_le_bytes());
+
+    // Same as the circuit's per-byte `(exec_hash >> (i * 8)) & 0xFF` loop
+    let exec_hash = hash_execution_params(total_shares, execution_price, state.total_amount);
+    for (byte, exec_byte) in final_root.iter_mut().zip(exec_hash.to_le_bytes()) {
+        *byte ^= exec_byte;
+    }
+
+    ExecuteBatchOutput {
+        merkle_root: final_root,
+        total_usdc: state.total_amount,
+    }
+}
+
+/// Simulate `simulate_execute_batch` (before encryption to the authority)
+pub fn simulate_execute_batch(state: &BatchState, total_shares: u64, execution_price: u64) -> ExecutionPreview {
+    ExecutionPreview {
+        clearing_price: execution_price,
+        fill_ratio_bps: compute_fill_ratio_bps(state.total_amount, total_shares, execution_price),
+        total_usdc: state.total_amount,
+    }
+}
+
+/// Simulate `merge_lanes`
+///
+/// A lane total that would overflow the running total is zeroed;
+/// `lane_totals_overflow` is the revealed `total_overflow` flag.
+pub fn merge_lanes(lanes: &[BatchState; 4]) -> BatchState {
+    let (total, _) = merge_lane_totals(lanes);
+
+    BatchState {
+        total_amount: total,
+        order_count: lanes.iter().map(|l| l.order_count).fold(0, u8::wrapping_add),
+        commitment_root: lanes.iter().fold(0, |root, l| root ^ l.commitment_root),
+        commitment_root_hi: lanes
+            .iter()
+            .fold(0, |root, l| root.wrapping_add(l.commitment_root_hi)),
+        order_hash_1: lanes[0].order_hash_1,
+        order_hash_2: lanes[1].order_hash_1,
+        order_hash_3: lanes[2].order_hash_1,
+        order_hash_4: lanes[3].order_hash_1,
+    }
+}
+
+/// Simulate the `total_overflow` flag of `merge_lanes`
+pub fn lane_totals_overflow(lanes: &[BatchState; 4]) -> bool {
+    merge_lane_totals(lanes).1
+}
+
+/// Fold the lane totals through `check_total`, lane 0 first
+fn merge_lane_totals(lanes: &[BatchState; 4]) -> (u64, bool) {
+    lanes.iter().fold((0, false), |(total, overflowed), lane| {
+        let (amount, overflows) = check_total(total, lane.total_amount);
+        (total + amount, overflowed | overflows)
+    })
+}
+
+/// Simulate `init_session`
+pub fn init_session(max_notional: u64) -> SessionBudget {
+    SessionBudget {
+        remaining: max_notional,
+    }
+}
+
+/// Simulate `add_session_order` (after `bind_order` against the session owner)
+pub fn add_session_order(
+    state: &BatchState,
+    budget: &SessionBudget,
+    order: &Order,
+) -> (BatchState, SessionBudget) {
+    let (requested, _) = check_total(state.total_amount, order.amount);
+    let amount = if requested <= budget.remaining { requested } else { 0 };
+    let budget = SessionBudget {
+        remaining: budget.remaining - amount,
+    };
+
+    let state = add_order(state, &Order { amount, ..*order });
+    (state, budget)
+}
+
+/// Simulate `add_limited_order` (after `bind_order` against the profile owner)
+///
+/// `day_rolled` restarts the budget at `daily_cap` before this order;
+/// unlimited caps are `u64::MAX`, as the program passes them.
+pub fn add_limited_order(
+    state: &BatchState,
+    budget: &SessionBudget,
+    order: &Order,
+    max_order: u64,
+    daily_cap: u64,
+    day_rolled: bool,
+) -> (BatchState, SessionBudget) {
+    let remaining = daily_remaining(budget.remaining, daily_cap, day_rolled as u8);
+    let (requested, _) = check_total(state.total_amount, order.amount);
+    let amount = if requested <= max_order && requested <= remaining { requested } else { 0 };
+    let budget = SessionBudget {
+        remaining: remaining - amount,
+    };
+
+    let state = add_order(state, &Order { amount, ..*order });
+    (state, budget)
+}
+
+/// Simulate `add_limited_session_order`: `add_limited_order` that must also
+/// fit the session budget
+pub fn add_limited_session_order(
+    state: &BatchState,
+    budget: &SessionBudget,
+    session_budget: &SessionBudget,
+    order: &Order,
+    max_order: u64,
+    daily_cap: u64,
+    day_rolled: bool,
+) -> (BatchState, SessionBudget, SessionBudget) {
+    let remaining = daily_remaining(budget.remaining, daily_cap, day_rolled as u8);
+    let (requested, _) = check_total(state.total_amount, order.amount);
+    let fits =
+        requested <= max_order && requested <= remaining && requested <= session_budget.remaining;
+    let amount = if fits { requested } else { 0 };
+
+    let state = add_order(state, &Order { amount, ..*order });
+    let budget = SessionBudget {
+        remaining: remaining - amount,
+    };
+    let session_budget = SessionBudget {
+        remaining: session_budget.remaining - amount,
+    };
+    (state, budget, session_budget)
+}
+
+/// Run a full batch lifecycle: init, add every order, execute
+pub fn run_batch(orders: &[Order], total_shares: u64, execution_price: u64) -> (BatchState, ExecuteBatchOutput) {
+    let state = orders
+        .iter()
+        .fold(init_batch(), |state, order| add_order(&state, order));
+    let output = execute_batch(&state, total_shares, execution_price);
+    (state, output)
+}
+
+/// Simulate `init_rfq`
+pub fn init_rfq(size: u64, side: u8) -> RfqState {
+    RfqState {
+        size,
+        side,
+        ..RfqState::default()
+    }
+}
+
+/// Simulate `submit_quote`
+pub fn submit_quote(state: &RfqState, quote: &MakerQuote, maker_index: u8) -> RfqState {
+    let mut state = *state;
+
+    let price = if state.side == 0 { quote.yes_price } else { quote.no_price };
+    let is_valid = price <= 10_000;
+    let is_better = is_valid && (!state.has_quote || price < state.best_price);
+
+    if is_better {
+        state.best_price = price;
+        state.best_maker = maker_index;
+    }
+    state.has_quote = state.has_quote || is_better;
+
+    state
+}
+
+/// Simulate `settle_rfq`
+pub fn settle_rfq(state: &RfqState) -> RfqSettlement {
+    RfqSettlement {
+        has_quote: state.has_quote,
+        best_maker: state.best_maker,
+        fill_notional: compute_fill_notional(state.size, state.best_price),
+    }
+}
+
+/// Simulate `init_parlay` (after `bind_order` and `expire_order`); returns
+/// the state and `weights_valid`
+pub fn init_parlay(order: &Order, weights: [u64; 4], leg_count: u8) -> (ParlayState, bool) {
+    let unused_clear = (leg_count > 2 || weights[2] == 0) && (leg_count > 3 || weights[3] == 0);
+    let total_weight: u128 = weights.iter().map(|&w| w as u128).sum();
+    let weights_valid = unused_clear && total_weight == 10_000;
+
+    let state = ParlayState {
+        amount: if weights_valid { order.amount } else { 0 },
+        weights,
+        wallet_lo: order.wallet_lo,
+        wallet_hi: order.wallet_hi,
+    };
+    (state, weights_valid)
+}
+
+/// Simulate `add_parlay_leg`
+pub fn add_parlay_leg(state: &BatchState, parlay: &ParlayState, leg_index: u8) -> BatchState {
+    let weight = parlay.weights.get(leg_index as usize).copied().unwrap_or(0);
+    let leg_amount = (parlay.amount as u128 * weight as u128 / 10_000) as u64;
+
+    add_order(
+        state,
+        &Order {
+            amount: leg_amount,
+            wallet_lo: parlay.wallet_lo,
+            wallet_hi: parlay.wallet_hi,
+        },
+    )
+}
+
+/// Simulate `init_flow_auction`
+pub fn init_flow_auction() -> FlowAuctionState {
+    FlowAuctionState::default()
+}
+
+/// Simulate `submit_flow_bid` against the batch's state
+pub fn submit_flow_bid(
+    state: &FlowAuctionState,
+    bid: &FlowBid,
+    maker_index: u8,
+    batch: &BatchState,
+) -> FlowAuctionState {
+    let mut state = *state;
+
+    let is_valid = bid.improvement_bps <= 10_000 && bid.capacity >= batch.total_amount;
+    let is_better = is_valid && (!state.has_bid || bid.improvement_bps > state.best_improvement);
+
+    if is_better {
+        state.best_improvement = bid.improvement_bps;
+        state.best_maker = maker_index;
+    }
+    state.has_bid = state.has_bid || is_better;
+
+    state
+}
+
+/// Simulate `settle_flow_auction`
+pub fn settle_flow_auction(state: &FlowAuctionState) -> FlowAuctionSettlement {
+    FlowAuctionSettlement {
+        has_bid: state.has_bid,
+        best_maker: state.best_maker,
+        improvement_bps: state.best_improvement,
+    }
+}
+
+/// Simulate `init_oracle`
+pub fn init_oracle() -> OracleState {
+    OracleState::default()
+}
+
+/// Simulate `submit_observation`
+pub fn submit_observation(state: &OracleState, value: u64, provider_index: u8) -> OracleState {
+    let mut state = *state;
+    if let Some(slot) = state.values.get_mut(provider_index as usize) {
+        *slot = value;
+    }
+    state
+}
+
+/// Simulate `resolve_oracle`, returning the revealed outcome
+pub fn resolve_oracle(state: &OracleState, threshold: u64, submitted_mask: u8) -> bool {
+    let pad_high_mask = median_pad_high_mask(submitted_mask);
+    let [v0, v1, v2, v3, v4] = std::array::from_fn(|slot| {
+        pad_observation(state.values[slot], submitted_mask, pad_high_mask, slot as u8)
+    });
+    let (_, _, median, _, _) = sort_five(v0, v1, v2, v3, v4);
+    median >= threshold
+}
+
+/// Simulate `add_house_order` (after `bind_order` against the desk)
+///
+/// `ledger` is `None` for a ledger that was never written.
+pub fn add_house_order(state: &BatchState, ledger: Option<&HouseLedger>, order: &Order) -> (BatchState, HouseLedger) {
+    let (amount, _) = check_total(state.total_amount, order.amount);
+    let mut ledger = ledger.copied().unwrap_or_default();
+    ledger.pending_notional += amount;
+
+    let state = add_order(state, &Order { amount, ..*order });
+    (state, ledger)
+}
+
+/// Simulate `settle_house_batch`
+pub fn settle_house_batch(ledger: &HouseLedger, total_shares: u64, total_usdc: u64) -> HouseLedger {
+    let divisor = total_usdc.max(1) as u128;
+    let shares = ((ledger.pending_notional as u128) * (total_shares as u128) / divisor) as u64;
+    HouseLedger {
+        pending_notional: 0,
+        cost_basis: ledger.cost_basis + ledger.pending_notional,
+        inventory: ledger.inventory + shares,
+    }
+}
+
+/// Simulate `reveal_house_ledger`, before encryption to the authority
+pub fn reveal_house_ledger(ledger: &HouseLedger, mark_price: u64) -> HouseStatement {
+    let value = (ledger.inventory as u128) * (mark_price as u128);
+    HouseStatement {
+        pending_notional: ledger.pending_notional,
+        cost_basis: ledger.cost_basis,
+        inventory: ledger.inventory,
+        mark_value: u64::try_from(value).unwrap_or(u64::MAX),
+    }
+}
+
+// ============================================
+// Helper Functions
+// ============================================
+
+/// Split a wallet pubkey into the (lo, hi) halves passed as `encrypted_wallet_lo/hi`
+pub fn split_wallet(wallet: &[u8; 32]) -> (u128, u128) {
+    let mut lo = [0u8; 16];
+    let mut hi = [0u8; 16];
+    lo.copy_from_slice(&wallet[..16]);
+    hi.copy_from_slice(&wallet[16..]);
+    (u128::from_le_bytes(lo), u128::from_le_bytes(hi))
+}
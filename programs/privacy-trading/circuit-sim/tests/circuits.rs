@@ -0,0 +1,523 @@
+use circuit_sim::*;
+use proptest::prelude::*;
+
+/// Max orders per batch enforced by the program (`ErrorCode::BatchFull`)
+const MAX_ORDERS: usize = 32;
+
+fn order_strategy() -> impl Strategy<Value = Order> {
+    // Amounts are bounded so that a full batch cannot wrap the u64 total
+    (0u64..=u64::MAX / MAX_ORDERS as u64, any::<[u8; 32]>())
+        .prop_map(|(amount, wallet)| Order::new(amount, &wallet))
+}
+
+fn orders_strategy() -> impl Strategy<Value = Vec<Order>> {
+    prop::collection::vec(order_strategy(), 1..=MAX_ORDERS)
+}
+
+/// Orders for the known-answer tests, whose expected roots were worked out
+/// from the circuit's arithmetic (the same as `circuits/allocation`'s)
+fn known_orders() -> [Order; 3] {
+    [
+        Order::new(1, &[0; 32]),
+        Order::new(50_000_000, &[1; 32]),
+        Order::new(150_000_000, &[0xff; 32]),
+    ]
+}
+
+#[test]
+fn init_batch_is_empty() {
+    assert_eq!(init_batch(), BatchState::default());
+}
+
+#[test]
+fn split_wallet_round_trips() {
+    let mut wallet = [0u8; 32];
+    for (i, byte) in wallet.iter_mut().enumerate() {
+        *byte = i as u8;
+    }
+
+    let (lo, hi) = split_wallet(&wallet);
+    assert_eq!(lo.to_le_bytes(), wallet[..16]);
+    assert_eq!(hi.to_le_bytes(), wallet[16..]);
+}
+
+#[test]
+fn commitment_root_known_answer() {
+    // A zero wallet leaves the leaf at amount * 31 * 31 in both halves
+    let (single, _) = run_batch(&known_orders()[..1], 0, 0);
+    assert_eq!((single.commitment_root, single.commitment_root_hi), (961, 961));
+
+    let (state, _) = run_batch(&known_orders(), 0, 0);
+    assert_eq!(state.commitment_root, 0x1e1e_1e1e_1e1e_1e1e_1e1e_1e0a_dfe2_c2c1);
+    assert_eq!(state.commitment_root_hi, 0x1e1e_1e1e_1e1e_1e1e_1e1e_1e4c_dfe3_25fd);
+}
+
+#[test]
+fn execute_batch_root_known_answer() {
+    // (1000 * 31 + 5000) * 31 + 200_000_001 = 0x0bfc_c961 lands in the low
+    // four bytes of the root
+    let (_, output) = run_batch(&known_orders(), 1000, 5000);
+    assert_eq!(
+        output.merkle_root,
+        [
+            0xa0, 0x0b, 0x1e, 0xd4, 0x0a, 0x1e, 0x1e, 0x1e, 0x1e, 0x1e, 0x1e, 0x1e, 0x1e, 0x1e, 0x1e, 0x1e,
+            0xfd, 0x25, 0xe3, 0xdf, 0x4c, 0x1e, 0x1e, 0x1e, 0x1e, 0x1e, 0x1e, 0x1e, 0x1e, 0x1e, 0x1e, 0x1e,
+        ]
+    );
+    assert_eq!(output.total_usdc, 200_000_001);
+}
+
+#[test]
+fn settle_rfq_without_quotes_fills_nothing() {
+    let settlement = settle_rfq(&init_rfq(1_000_000, 0));
+    assert!(!settlement.has_quote);
+    assert_eq!(settlement.fill_notional, 0);
+}
+
+proptest! {
+    #[test]
+    fn add_order_conserves_notional(orders in orders_strategy()) {
+        let (state, output) = run_batch(&orders, 0, 0);
+        let expected: u64 = orders.iter().map(|o| o.amount).sum();
+
+        prop_assert_eq!(state.total_amount, expected);
+        prop_assert_eq!(output.total_usdc, expected);
+        prop_assert_eq!(state.order_count as usize, orders.len());
+    }
+
+    #[test]
+    fn first_four_order_hashes_are_stored(orders in orders_strategy()) {
+        let (state, _) = run_batch(&orders, 0, 0);
+        let slots = [state.order_hash_1, state.order_hash_2, state.order_hash_3, state.order_hash_4];
+
+        for (i, slot) in slots.iter().enumerate() {
+            let expected = orders
+                .get(i)
+                .map(|o| compute_order_hash(o.amount, o.wallet_lo, o.wallet_hi))
+                .unwrap_or(0);
+            prop_assert_eq!(*slot, expected);
+        }
+    }
+
+    #[test]
+    fn execute_batch_is_deterministic(
+        orders in orders_strategy(),
+        total_shares in any::<u64>(),
+        execution_price in any::<u64>(),
+    ) {
+        let first = run_batch(&orders, total_shares, execution_price);
+        let second = run_batch(&orders, total_shares, execution_price);
+        prop_assert_eq!(first, second);
+    }
+
+    #[test]
+    fn simulate_execute_matches_execute(
+        orders in orders_strategy(),
+        total_shares in any::<u64>(),
+        execution_price in any::<u64>(),
+    ) {
+        let (state, output) = run_batch(&orders, total_shares, execution_price);
+        let preview = simulate_execute_batch(&state, total_shares, execution_price);
+
+        prop_assert_eq!(preview.total_usdc, output.total_usdc);
+        prop_assert_eq!(preview.clearing_price, execution_price);
+        prop_assert!(preview.fill_ratio_bps <= 10_000);
+    }
+
+    #[test]
+    fn fill_ratio_is_full_when_covered(
+        total_shares in 1u64..=1_000_000_000,
+        execution_price in 1u64..=1_000_000_000,
+        surplus in 0u64..=1_000_000_000,
+    ) {
+        let required = total_shares * execution_price;
+        prop_assert_eq!(compute_fill_ratio_bps(required + surplus, total_shares, execution_price), 10_000);
+        if required > 1 {
+            prop_assert!(compute_fill_ratio_bps(required - 1, total_shares, execution_price) < 10_000);
+        }
+    }
+
+    /// The running root is XOR / wrapping-add of leaves, so it does not bind
+    /// insertion order. Pinned here so a switch to a real merkle tree shows up.
+    #[test]
+    fn commitment_root_ignores_order_sequence(orders in orders_strategy()) {
+        let mut reversed = orders.clone();
+        reversed.reverse();
+
+        let (_, forward) = run_batch(&orders, 1, 1);
+        let (_, backward) = run_batch(&reversed, 1, 1);
+        prop_assert_eq!(forward.merkle_root, backward.merkle_root);
+    }
+
+    #[test]
+    fn bulk_folds_match_single_orders(
+        orders in orders_strategy(),
+        total_shares in any::<u64>(),
+        execution_price in any::<u64>(),
+    ) {
+        // Up to eight orders per computation, the last one padded with
+        // repeats of its first order, as add_orders_bulk pads its inputs
+        let mut state = init_batch();
+        for chunk in orders.chunks(8) {
+            let mut slots = [chunk[0]; 8];
+            slots[..chunk.len()].copy_from_slice(chunk);
+            state = add_orders_bulk(&state, &slots, chunk.len() as u8);
+        }
+
+        let (single, single_output) = run_batch(&orders, total_shares, execution_price);
+        prop_assert_eq!(state, single);
+        prop_assert_eq!(execute_batch(&state, total_shares, execution_price), single_output);
+    }
+
+    #[test]
+    fn rfq_picks_cheapest_valid_quote(
+        size in any::<u64>(),
+        side in 0u8..=1,
+        quotes in prop::collection::vec((0u64..=20_000, 0u64..=20_000), 1usize..=8),
+    ) {
+        let state = quotes.iter().enumerate().fold(init_rfq(size, side), |state, (i, &(yes, no))| {
+            submit_quote(&state, &MakerQuote { yes_price: yes, no_price: no }, i as u8)
+        });
+        let settlement = settle_rfq(&state);
+
+        // First maker with the strictly lowest in-range price wins
+        let best = quotes
+            .iter()
+            .enumerate()
+            .map(|(i, &(yes, no))| (if side == 0 { yes } else { no }, i as u8))
+            .filter(|&(price, _)| price <= 10_000)
+            .min_by_key(|&(price, i)| (price, i));
+
+        prop_assert_eq!(settlement.has_quote, best.is_some());
+        if let Some((price, maker)) = best {
+            prop_assert_eq!(settlement.best_maker, maker);
+            prop_assert_eq!(settlement.fill_notional, compute_fill_notional(size, price));
+            prop_assert!(settlement.fill_notional <= size);
+        }
+    }
+
+    #[test]
+    fn overflowing_orders_fold_as_zero(
+        first in any::<u64>(),
+        second in any::<u64>(),
+        wallet in any::<[u8; 32]>(),
+    ) {
+        let state = add_order(&init_batch(), &Order::new(first, &wallet));
+        let next = add_order(&state, &Order::new(second, &wallet));
+
+        let (amount, overflow) = check_total(first, second);
+        prop_assert_eq!(overflow, first.checked_add(second).is_none());
+        prop_assert_eq!(next.total_amount, first + amount);
+        prop_assert_eq!(next.order_count, 2);
+    }
+
+    #[test]
+    fn merged_lane_totals_drop_overflowing_lanes(totals in any::<[u64; 4]>()) {
+        let mut lanes = [init_batch(); 4];
+        for (lane, total) in lanes.iter_mut().zip(totals) {
+            lane.total_amount = total;
+        }
+
+        // Each lane either adds its whole total or nothing
+        let mut expected = 0u64;
+        let mut overflowed = false;
+        for total in totals {
+            match expected.checked_add(total) {
+                Some(sum) => expected = sum,
+                None => overflowed = true,
+            }
+        }
+        let sum: u128 = totals.iter().map(|&t| t as u128).sum();
+        prop_assert_eq!(overflowed, sum > u64::MAX as u128);
+        prop_assert_eq!(lane_totals_overflow(&lanes), overflowed);
+        prop_assert_eq!(merge_lanes(&lanes).total_amount, expected);
+    }
+
+    #[test]
+    fn bind_order_keeps_signed_orders(order in order_strategy(), wallet in any::<[u8; 32]>()) {
+        let order = Order::new(order.amount, &wallet);
+        prop_assert_eq!(bind_order(&order, &wallet), (order, true));
+    }
+
+    #[test]
+    fn bind_order_voids_forged_wallets(
+        amount in any::<u64>(),
+        wallet in any::<[u8; 32]>(),
+        signer in any::<[u8; 32]>(),
+    ) {
+        prop_assume!(wallet != signer);
+
+        let (bound, is_bound) = bind_order(&Order::new(amount, &wallet), &signer);
+        prop_assert!(!is_bound);
+        prop_assert_eq!(bound, Order::new(0, &signer));
+        prop_assert_eq!(add_order(&init_batch(), &bound).total_amount, 0);
+    }
+
+    #[test]
+    fn expired_orders_fold_as_zero(
+        order in order_strategy(),
+        expiry_slot in any::<u64>(),
+        current_slot in any::<u64>(),
+    ) {
+        let (kept, expired) = expire_order(&order, expiry_slot, current_slot);
+        prop_assert_eq!(expired, expiry_slot < current_slot);
+        prop_assert_eq!(kept.amount, if expired { 0 } else { order.amount });
+        prop_assert_eq!((kept.wallet_lo, kept.wallet_hi), (order.wallet_lo, order.wallet_hi));
+    }
+
+    #[test]
+    fn session_orders_never_exceed_budget(
+        max_notional in any::<u64>(),
+        orders in orders_strategy(),
+    ) {
+        let (state, budget) = orders.iter().fold(
+            (init_batch(), init_session(max_notional)),
+            |(state, budget), order| add_session_order(&state, &budget, order),
+        );
+
+        prop_assert_eq!(state.total_amount + budget.remaining, max_notional);
+        prop_assert_eq!(state.order_count as usize, orders.len());
+    }
+
+    #[test]
+    fn session_order_within_budget_matches_add_order(
+        order in order_strategy(),
+        surplus in any::<u32>(),
+    ) {
+        let budget = init_session(order.amount + surplus as u64);
+        let (state, budget) = add_session_order(&init_batch(), &budget, &order);
+
+        prop_assert_eq!(state, add_order(&init_batch(), &order));
+        prop_assert_eq!(budget.remaining, surplus as u64);
+    }
+
+    #[test]
+    fn limited_orders_respect_order_and_daily_caps(
+        orders in orders_strategy(),
+        max_order in any::<u64>(),
+        daily_cap in any::<u64>(),
+    ) {
+        let mut state = init_batch();
+        let mut budget = SessionBudget { remaining: 0 };
+        for (i, order) in orders.iter().enumerate() {
+            let before = state.total_amount;
+            (state, budget) =
+                add_limited_order(&state, &budget, order, max_order, daily_cap, i == 0);
+            prop_assert!(state.total_amount - before <= max_order);
+        }
+
+        prop_assert_eq!(state.total_amount + budget.remaining, daily_cap);
+        prop_assert_eq!(state.order_count as usize, orders.len());
+    }
+
+    #[test]
+    fn rolled_day_restores_the_daily_cap(
+        order in order_strategy(),
+        spent in any::<u64>(),
+    ) {
+        let budget = SessionBudget { remaining: spent };
+        let (state, budget) =
+            add_limited_order(&init_batch(), &budget, &order, u64::MAX, u64::MAX, true);
+
+        prop_assert_eq!(state, add_order(&init_batch(), &order));
+        prop_assert_eq!(budget.remaining, u64::MAX - order.amount);
+    }
+
+    #[test]
+    fn limited_session_orders_spend_both_budgets(
+        orders in orders_strategy(),
+        daily_cap in any::<u64>(),
+        max_notional in any::<u64>(),
+    ) {
+        let mut state = init_batch();
+        let mut budget = SessionBudget { remaining: 0 };
+        let mut session_budget = init_session(max_notional);
+        for (i, order) in orders.iter().enumerate() {
+            (state, budget, session_budget) = add_limited_session_order(
+                &state, &budget, &session_budget, order, u64::MAX, daily_cap, i == 0,
+            );
+        }
+
+        prop_assert_eq!(state.total_amount + budget.remaining, daily_cap);
+        prop_assert_eq!(state.total_amount + session_budget.remaining, max_notional);
+    }
+
+    #[test]
+    fn tightened_daily_cap_applies_mid_day(
+        order in order_strategy(),
+        remaining in any::<u64>(),
+        daily_cap in any::<u64>(),
+    ) {
+        let budget = SessionBudget { remaining };
+        let (state, budget) =
+            add_limited_order(&init_batch(), &budget, &order, u64::MAX, daily_cap, false);
+
+        prop_assert!(state.total_amount + budget.remaining <= daily_cap);
+    }
+
+    #[test]
+    fn house_orders_fold_like_user_orders(
+        pairs in prop::collection::vec((order_strategy(), order_strategy()), 1..=MAX_ORDERS / 2),
+    ) {
+        let mut state = init_batch();
+        let mut plain = init_batch();
+        let mut ledger = None;
+        for (user, house) in &pairs {
+            state = add_order(&state, user);
+            plain = add_order(&plain, user);
+            let (next, booked) = add_house_order(&state, ledger.as_ref(), house);
+            state = next;
+            ledger = Some(booked);
+            plain = add_order(&plain, house);
+        }
+
+        // The ledger holds exactly the house share of the batch
+        let house_total: u64 = pairs.iter().map(|(_, house)| house.amount).sum();
+        prop_assert_eq!(state, plain);
+        prop_assert_eq!(ledger.unwrap().pending_notional, house_total);
+    }
+
+    #[test]
+    fn house_settlement_books_pro_rata_shares(
+        orders in orders_strategy(),
+        house in order_strategy(),
+        total_shares in any::<u64>(),
+        mark_price in any::<u64>(),
+    ) {
+        let (state, ledger) = add_house_order(&run_batch(&orders, 0, 0).0, None, &house);
+        prop_assume!(state.total_amount > 0);
+
+        let settled = settle_house_batch(&ledger, total_shares, state.total_amount);
+        let shares = (house.amount as u128 * total_shares as u128 / state.total_amount as u128) as u64;
+        prop_assert_eq!(settled, HouseLedger { pending_notional: 0, cost_basis: house.amount, inventory: shares });
+
+        let statement = reveal_house_ledger(&settled, mark_price);
+        prop_assert_eq!(statement.mark_value as u128, (shares as u128 * mark_price as u128).min(u64::MAX as u128));
+    }
+
+    #[test]
+    fn merged_lanes_match_single_state(
+        orders in orders_strategy(),
+        total_shares in any::<u64>(),
+        execution_price in any::<u64>(),
+    ) {
+        // Deal orders round-robin into four lanes, as add_lane_order would
+        let mut lanes = [init_batch(); 4];
+        for (i, order) in orders.iter().enumerate() {
+            lanes[i % 4] = add_order(&lanes[i % 4], order);
+        }
+        let merged = merge_lanes(&lanes);
+
+        let (single, single_output) = run_batch(&orders, total_shares, execution_price);
+        prop_assert_eq!(merged.total_amount, single.total_amount);
+        prop_assert_eq!(merged.order_count, single.order_count);
+        prop_assert_eq!(
+            execute_batch(&merged, total_shares, execution_price),
+            single_output
+        );
+    }
+
+    #[test]
+    fn sort_five_sorts(values in any::<[u64; 5]>()) {
+        let mut expected = values;
+        expected.sort_unstable();
+        let [v0, v1, v2, v3, v4] = values;
+        let (s0, s1, s2, s3, s4) = sort_five(v0, v1, v2, v3, v4);
+        prop_assert_eq!([s0, s1, s2, s3, s4], expected);
+    }
+
+    #[test]
+    fn oracle_resolves_on_lower_median_of_submissions(
+        observations in prop::collection::vec(any::<u64>(), 5),
+        submitted_mask in 1u8..32,
+        threshold in any::<u64>(),
+    ) {
+        let state = (0..5u8)
+            .filter(|slot| submitted_mask & (1 << slot) != 0)
+            .fold(init_oracle(), |state, slot| {
+                submit_observation(&state, observations[slot as usize], slot)
+            });
+
+        let mut submitted: Vec<u64> = (0..5)
+            .filter(|slot| submitted_mask & (1 << slot) != 0)
+            .map(|slot| observations[slot])
+            .collect();
+        submitted.sort_unstable();
+        let median = submitted[(submitted.len() - 1) / 2];
+
+        prop_assert_eq!(resolve_oracle(&state, threshold, submitted_mask), median >= threshold);
+    }
+
+    #[test]
+    fn flow_auction_picks_largest_covering_bid(
+        orders in orders_strategy(),
+        bids in prop::collection::vec((0u64..=20_000, any::<u64>()), 1usize..=8),
+    ) {
+        let batch = orders.iter().fold(init_batch(), |state, order| add_order(&state, order));
+        let state = bids.iter().enumerate().fold(init_flow_auction(), |state, (i, &(improvement, capacity))| {
+            let bid = FlowBid { improvement_bps: improvement, capacity };
+            submit_flow_bid(&state, &bid, i as u8, &batch)
+        });
+        let settlement = settle_flow_auction(&state);
+
+        // First maker with the largest in-range improvement able to take the whole flow wins
+        let best = bids
+            .iter()
+            .enumerate()
+            .filter(|&(_, &(improvement, capacity))| improvement <= 10_000 && capacity >= batch.total_amount)
+            .max_by_key(|&(i, &(improvement, _))| (improvement, std::cmp::Reverse(i)));
+
+        prop_assert_eq!(settlement.has_bid, best.is_some());
+        if let Some((maker, &(improvement, _))) = best {
+            prop_assert_eq!(settlement.best_maker, maker as u8);
+            prop_assert_eq!(settlement.improvement_bps, improvement);
+        }
+    }
+
+    #[test]
+    fn parlay_legs_split_the_order_by_weight(
+        order in order_strategy(),
+        mut cuts in prop::collection::vec(1u64..=10_000, 1usize..=3),
+    ) {
+        // Cut points in [0, 10_000] give leg_count weights summing to 10_000
+        cuts.sort_unstable();
+        cuts.dedup();
+        let leg_count = cuts.len() + 1;
+        let mut weights = [0u64; 4];
+        let mut previous = 0;
+        for (leg, &cut) in cuts.iter().chain(std::iter::once(&10_000)).enumerate() {
+            weights[leg] = cut - previous;
+            previous = cut;
+        }
+
+        let (parlay, weights_valid) = init_parlay(&order, weights, leg_count as u8);
+        prop_assert!(weights_valid);
+
+        let legs: Vec<BatchState> = (0..leg_count)
+            .map(|leg| add_parlay_leg(&init_batch(), &parlay, leg as u8))
+            .collect();
+        let committed: u64 = legs.iter().map(|state| state.total_amount).sum();
+
+        // Each leg rounds down, losing under one base unit
+        prop_assert!(committed <= order.amount);
+        prop_assert!(order.amount - committed < leg_count as u64);
+        prop_assert!(legs.iter().all(|state| state.order_count == 1));
+    }
+
+    #[test]
+    fn invalid_parlay_weights_fold_as_zero(
+        order in order_strategy(),
+        weights in prop::array::uniform4(0u64..=10_000),
+        leg_count in 2u8..=4,
+    ) {
+        let used: u64 = weights[..leg_count as usize].iter().sum();
+        let unused_clear = weights[leg_count as usize..].iter().all(|&w| w == 0);
+        prop_assume!(used != 10_000 || !unused_clear);
+
+        let (parlay, weights_valid) = init_parlay(&order, weights, leg_count);
+        prop_assert!(!weights_valid);
+        for leg in 0..leg_count {
+            prop_assert_eq!(add_parlay_leg(&init_batch(), &parlay, leg).total_amount, 0);
+        }
+    }
+}
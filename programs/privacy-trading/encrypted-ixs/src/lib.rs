@@ -31,6 +31,70 @@ mod circuits {
     pub struct AddOrderOutputData {
         pub ciphertexts: [[u8; 32]; 8],
         pub nonce: u128,
+        pub wallet_bound: bool,
+        pub amount_overflow: bool,
+        pub expired: bool,
+    }
+
+    /// An order as its user encrypts it for `stage_order`: the same four
+    /// values `add_order` takes, under one nonce
+    pub struct StagedOrder {
+        pub amount: u64,
+        pub wallet_lo: u128,
+        pub wallet_hi: u128,
+        pub expiry: u64,
+    }
+
+    /// Output from folding staged orders in bulk; the flags are per input
+    /// slot, and only the first `order_count` mean anything
+    pub struct AddOrdersBulkOutputData {
+        pub ciphertexts: [[u8; 32]; 8],
+        pub nonce: u128,
+        pub wallet_bound: [bool; 8],
+        pub amount_overflow: [bool; 8],
+        pub expired: [bool; 8],
+    }
+
+    /// Remaining notional a session key may still commit
+    pub struct SessionBudget {
+        pub remaining: u64,
+    }
+
+    /// Output from session initialization
+    pub struct SessionBudgetOutput {
+        pub ciphertexts: [[u8; 32]; 1],
+        pub nonce: u128,
+    }
+
+    /// Output from adding an order through a session key
+    pub struct SessionOrderOutput {
+        pub ciphertexts: [[u8; 32]; 8],
+        pub nonce: u128,
+        pub budget_ciphertexts: [[u8; 32]; 1],
+        pub budget_nonce: u128,
+        pub wallet_bound: bool,
+        pub amount_overflow: bool,
+        pub expired: bool,
+    }
+
+    /// Output from adding a session order under a risk profile
+    pub struct LimitedSessionOrderOutput {
+        pub ciphertexts: [[u8; 32]; 8],
+        pub nonce: u128,
+        pub budget_ciphertexts: [[u8; 32]; 1],
+        pub budget_nonce: u128,
+        pub session_budget_ciphertexts: [[u8; 32]; 1],
+        pub session_budget_nonce: u128,
+        pub wallet_bound: bool,
+        pub amount_overflow: bool,
+        pub expired: bool,
+    }
+
+    /// Output from merging order lanes
+    pub struct MergeLanesOutputData {
+        pub ciphertexts: [[u8; 32]; 8],
+        pub nonce: u128,
+        pub total_overflow: bool,
     }
 
     /// Output from batch execution
@@ -39,6 +103,167 @@ mod circuits {
         pub total_usdc: u64,
     }
 
+    /// Execution preview, encrypted to the batch authority
+    pub struct ExecutionPreview {
+        pub clearing_price: u64,
+        pub fill_ratio_bps: u64,
+        pub total_usdc: u64,
+    }
+
+    /// Output from an execution preview
+    pub struct SimulateExecuteBatchOutputData {
+        pub preview: Enc<Shared, ExecutionPreview>,
+        pub ciphertexts: [[u8; 32]; 8],
+        pub nonce: u128,
+    }
+
+    /// Taker request for quote, encrypted by the taker
+    pub struct RfqRequest {
+        pub size: u64, // Max payout in USDC base units
+        pub side: u8,  // 0 = YES, 1 = NO
+    }
+
+    /// Maker quote, encrypted by the maker (prices in bps of 1 USDC)
+    pub struct MakerQuote {
+        pub yes_price: u64,
+        pub no_price: u64,
+    }
+
+    /// Encrypted RFQ state: the request and the best quote so far
+    pub struct RfqState {
+        pub size: u64,
+        pub side: u8,
+        pub best_price: u64,
+        pub best_maker: u8,
+        pub has_quote: bool,
+    }
+
+    /// Output from RFQ initialization and quote submission
+    pub struct RfqStateOutput {
+        pub ciphertexts: [[u8; 32]; 5],
+        pub nonce: u128,
+    }
+
+    /// Revealed RFQ result
+    pub struct RfqSettlement {
+        pub has_quote: bool,
+        pub best_maker: u8,
+        pub fill_notional: u64,
+    }
+
+    /// Parlay leg weights in bps of the order amount
+    pub struct ParlayWeights {
+        pub weight_0: u64,
+        pub weight_1: u64,
+        pub weight_2: u64,
+        pub weight_3: u64,
+    }
+
+    /// Encrypted parlay order, folded into its leg batches one leg at a time
+    pub struct ParlayState {
+        pub amount: u64,
+        pub weight_0: u64,
+        pub weight_1: u64,
+        pub weight_2: u64,
+        pub weight_3: u64,
+        pub wallet_lo: u128,
+        pub wallet_hi: u128,
+    }
+
+    /// Output from parlay initialization
+    pub struct ParlayInitOutput {
+        pub ciphertexts: [[u8; 32]; 7],
+        pub nonce: u128,
+        pub wallet_bound: bool,
+        pub expired: bool,
+        pub weights_valid: bool,
+    }
+
+    /// Output from folding one parlay leg into its batch
+    pub struct ParlayLegOutput {
+        pub ciphertexts: [[u8; 32]; 8],
+        pub nonce: u128,
+        pub amount_overflow: bool,
+    }
+
+    /// Maker bid for a batch's order flow, encrypted by the maker
+    pub struct FlowBid {
+        pub improvement_bps: u64, // Improvement on the reference price
+        pub capacity: u64,        // Max USDC notional the maker will fill
+    }
+
+    /// Encrypted flow auction state: the best bid so far
+    pub struct FlowAuctionState {
+        pub best_improvement: u64,
+        pub best_maker: u8,
+        pub has_bid: bool,
+    }
+
+    /// Output from flow auction initialization and bid submission
+    pub struct FlowAuctionStateOutput {
+        pub ciphertexts: [[u8; 32]; 3],
+        pub nonce: u128,
+    }
+
+    /// Revealed flow auction result
+    pub struct FlowAuctionSettlement {
+        pub has_bid: bool,
+        pub best_maker: u8,
+        pub improvement_bps: u64,
+    }
+
+    /// Encrypted oracle state: one observation slot per provider
+    pub struct OracleState {
+        pub value_0: u64,
+        pub value_1: u64,
+        pub value_2: u64,
+        pub value_3: u64,
+        pub value_4: u64,
+    }
+
+    /// Output from oracle initialization and observation submission
+    pub struct OracleStateOutput {
+        pub ciphertexts: [[u8; 32]; 5],
+        pub nonce: u128,
+    }
+
+    /// Revealed oracle result
+    pub struct OracleResolution {
+        pub outcome: bool,
+    }
+
+    /// A house account's books, kept apart from user flow
+    pub struct HouseLedger {
+        pub pending_notional: u64, // Committed to the open batch, not yet settled
+        pub cost_basis: u64,       // Notional of every settled batch
+        pub inventory: u64,        // Shares the settled batches allocated
+    }
+
+    /// Output from settling a house account's batch
+    pub struct HouseLedgerOutput {
+        pub ciphertexts: [[u8; 32]; 3],
+        pub nonce: u128,
+    }
+
+    /// Output from adding a house order
+    pub struct HouseOrderOutput {
+        pub ciphertexts: [[u8; 32]; 8],
+        pub nonce: u128,
+        pub ledger_ciphertexts: [[u8; 32]; 3],
+        pub ledger_nonce: u128,
+        pub wallet_bound: bool,
+        pub amount_overflow: bool,
+        pub expired: bool,
+    }
+
+    /// House ledger marked to a price, encrypted to the house authority
+    pub struct HouseStatement {
+        pub pending_notional: u64,
+        pub cost_basis: u64,
+        pub inventory: u64,
+        pub mark_value: u64,
+    }
+
     // ============================================
     // Encrypted Instructions
     // ============================================
@@ -70,55 +295,118 @@ mod circuits {
     ///
     /// This function:
     /// 1. Decrypts the current batch state
-    /// 2. Adds the new order amount to total
-    /// 3. Updates the commitment merkle root
-    /// 4. Re-encrypts the state
+    /// 2. Binds the encrypted wallet to the plaintext signer
+    /// 3. Drops the order if its encrypted expiry is before `current_slot`
+    /// 4. Adds the new order amount to total, unless it would overflow
+    /// 5. Updates the commitment merkle root
+    /// 6. Re-encrypts the state
     #[instruction]
     pub fn add_order(
         user_amount: Enc<Shared, u64>,
         user_wallet_lo: Enc<Shared, u128>,
         user_wallet_hi: Enc<Shared, u128>,
+        user_expiry: Enc<Shared, u64>,
+        signer_lo: u128,
+        signer_hi: u128,
+        current_slot: u64,
         state_nonce: u128,
         current_state: Enc<Account, BatchState>,
     ) -> AddOrderOutputData {
         // Decrypt inputs
-        let amount = user_amount.to_arcis();
-        let wallet_lo = user_wallet_lo.to_arcis();
-        let wallet_hi = user_wallet_hi.to_arcis();
-        let mut state = current_state.decrypt(state_nonce);
+        let (amount, wallet_lo, wallet_hi, wallet_bound) = bind_wallet(
+            user_amount.to_arcis(),
+            user_wallet_lo.to_arcis(),
+            user_wallet_hi.to_arcis(),
+            signer_lo,
+            signer_hi,
+        );
+        let (amount, expired) = check_expiry(amount, user_expiry.to_arcis(), current_slot);
+        let state = current_state.decrypt(state_nonce);
+        let (amount, amount_overflow) = check_total(state.total_amount, amount);
 
-        // Update totals
-        state.total_amount = state.total_amount + amount;
-        state.order_count = state.order_count + 1;
+        let state = apply_order(state, amount, wallet_lo, wallet_hi);
 
-        // Compute order commitment hash (simplified poseidon-like)
-        let order_hash = compute_order_hash(amount, wallet_lo, wallet_hi);
+        // Re-encrypt state with new nonce
+        let new_nonce = ArcisRNG::u128();
+        let encrypted = state.encrypt(new_nonce);
 
-        // Update merkle root (running hash)
-        let (new_root_lo, new_root_hi) = update_merkle_root(
-            state.commitment_root,
-            state.commitment_root_hi,
-            order_hash,
-        );
-        state.commitment_root = new_root_lo;
-        state.commitment_root_hi = new_root_hi;
+        AddOrderOutputData {
+            ciphertexts: encrypted.ciphertexts,
+            nonce: new_nonce,
+            wallet_bound: wallet_bound.reveal(),
+            amount_overflow: amount_overflow.reveal(),
+            expired: expired.reveal(),
+        }
+    }
 
-        // Store order hash based on count
-        match state.order_count {
-            1 => state.order_hash_1 = order_hash,
-            2 => state.order_hash_2 = order_hash,
-            3 => state.order_hash_3 = order_hash,
-            4 => state.order_hash_4 = order_hash,
-            _ => {} // Additional orders use running root
+    /// Fold up to eight staged orders into the batch in one computation
+    ///
+    /// Each order gets the same checks as in `add_order`, against the
+    /// wallet that staged it and the slot it was staged at, so a slow
+    /// keeper cannot expire it. Orders are folded in staging order, so the
+    /// state matches adding them one at a time. Slots from `order_count` on
+    /// repeat a real order and are folded as absent.
+    #[instruction]
+    pub fn add_orders_bulk(
+        order_0: Enc<Shared, StagedOrder>,
+        order_1: Enc<Shared, StagedOrder>,
+        order_2: Enc<Shared, StagedOrder>,
+        order_3: Enc<Shared, StagedOrder>,
+        order_4: Enc<Shared, StagedOrder>,
+        order_5: Enc<Shared, StagedOrder>,
+        order_6: Enc<Shared, StagedOrder>,
+        order_7: Enc<Shared, StagedOrder>,
+        signers_lo: [u128; 8],
+        signers_hi: [u128; 8],
+        order_count: u8,
+        staged_at: [u64; 8],
+        state_nonce: u128,
+        current_state: Enc<Account, BatchState>,
+    ) -> AddOrdersBulkOutputData {
+        let orders = [
+            order_0.to_arcis(),
+            order_1.to_arcis(),
+            order_2.to_arcis(),
+            order_3.to_arcis(),
+            order_4.to_arcis(),
+            order_5.to_arcis(),
+            order_6.to_arcis(),
+            order_7.to_arcis(),
+        ];
+        let mut state = current_state.decrypt(state_nonce);
+
+        let mut wallet_bound = [false; 8];
+        let mut amount_overflow = [false; 8];
+        let mut expired = [false; 8];
+        for i in 0..8 {
+            let (amount, wallet_lo, wallet_hi, bound) = bind_wallet(
+                orders[i].amount,
+                orders[i].wallet_lo,
+                orders[i].wallet_hi,
+                signers_lo[i],
+                signers_hi[i],
+            );
+            let (amount, is_expired) = check_expiry(amount, orders[i].expiry, staged_at[i]);
+            let (amount, overflows) = check_total(state.total_amount, amount);
+
+            // `order_count` is plaintext, so this reveals nothing
+            let present = (i as u8) < order_count;
+            state = fold_order(state, present, amount, wallet_lo, wallet_hi);
+
+            wallet_bound[i] = bound.reveal();
+            amount_overflow[i] = overflows.reveal();
+            expired[i] = is_expired.reveal();
         }
 
-        // Re-encrypt state with new nonce
         let new_nonce = ArcisRNG::u128();
         let encrypted = state.encrypt(new_nonce);
 
-        AddOrderOutputData {
+        AddOrdersBulkOutputData {
             ciphertexts: encrypted.ciphertexts,
             nonce: new_nonce,
+            wallet_bound,
+            amount_overflow,
+            expired,
         }
     }
 
@@ -162,10 +450,939 @@ mod circuits {
         }
     }
 
+    /// Initialize the encrypted budget of a session key
+    #[instruction]
+    pub fn init_session(max_notional: Enc<Shared, u64>, nonce_input: u128) -> SessionBudgetOutput {
+        let budget = SessionBudget {
+            remaining: max_notional.to_arcis(),
+        };
+
+        let encrypted = budget.encrypt(nonce_input);
+
+        SessionBudgetOutput {
+            ciphertexts: encrypted.ciphertexts,
+            nonce: encrypted.nonce,
+        }
+    }
+
+    /// Add an order placed by a session key, spending its budget
+    ///
+    /// An order larger than the remaining budget is added with a zero
+    /// amount, so it still occupies its slot but commits no notional.
+    /// Whether it was accepted is not revealed. The wallet is bound to the
+    /// session owner, not the session key.
+    #[instruction]
+    pub fn add_session_order(
+        user_amount: Enc<Shared, u64>,
+        user_wallet_lo: Enc<Shared, u128>,
+        user_wallet_hi: Enc<Shared, u128>,
+        user_expiry: Enc<Shared, u64>,
+        owner_lo: u128,
+        owner_hi: u128,
+        current_slot: u64,
+        state_nonce: u128,
+        current_state: Enc<Account, BatchState>,
+        budget_nonce: u128,
+        current_budget: Enc<Account, SessionBudget>,
+    ) -> SessionOrderOutput {
+        let (requested, wallet_lo, wallet_hi, wallet_bound) = bind_wallet(
+            user_amount.to_arcis(),
+            user_wallet_lo.to_arcis(),
+            user_wallet_hi.to_arcis(),
+            owner_lo,
+            owner_hi,
+        );
+        let (requested, expired) = check_expiry(requested, user_expiry.to_arcis(), current_slot);
+        let state = current_state.decrypt(state_nonce);
+        let mut budget = current_budget.decrypt(budget_nonce);
+
+        // An expired or overflowing order spends no budget
+        let (requested, amount_overflow) = check_total(state.total_amount, requested);
+        let amount = if requested <= budget.remaining { requested } else { 0 };
+        budget.remaining = budget.remaining - amount;
+
+        let state = apply_order(state, amount, wallet_lo, wallet_hi);
+
+        let new_nonce = ArcisRNG::u128();
+        let encrypted = state.encrypt(new_nonce);
+
+        let new_budget_nonce = ArcisRNG::u128();
+        let encrypted_budget = budget.encrypt(new_budget_nonce);
+
+        SessionOrderOutput {
+            ciphertexts: encrypted.ciphertexts,
+            nonce: new_nonce,
+            budget_ciphertexts: encrypted_budget.ciphertexts,
+            budget_nonce: new_budget_nonce,
+            wallet_bound: wallet_bound.reveal(),
+            amount_overflow: amount_overflow.reveal(),
+            expired: expired.reveal(),
+        }
+    }
+
+    /// Add an order under its owner's `RiskProfile`, spending the daily budget
+    ///
+    /// An order above `max_order` or the day's remaining budget is added
+    /// with a zero amount, as with session budgets, and whether it was cut
+    /// is not revealed. `day_rolled` is 1 for the first order of a new day,
+    /// which restarts the budget at `daily_cap`. The wallet is bound to the
+    /// profile owner.
+    #[instruction]
+    pub fn add_limited_order(
+        user_amount: Enc<Shared, u64>,
+        user_wallet_lo: Enc<Shared, u128>,
+        user_wallet_hi: Enc<Shared, u128>,
+        user_expiry: Enc<Shared, u64>,
+        owner_lo: u128,
+        owner_hi: u128,
+        current_slot: u64,
+        state_nonce: u128,
+        current_state: Enc<Account, BatchState>,
+        max_order: u64,
+        daily_cap: u64,
+        day_rolled: u8,
+        budget_nonce: u128,
+        current_budget: Enc<Account, SessionBudget>,
+    ) -> SessionOrderOutput {
+        let (requested, wallet_lo, wallet_hi, wallet_bound) = bind_wallet(
+            user_amount.to_arcis(),
+            user_wallet_lo.to_arcis(),
+            user_wallet_hi.to_arcis(),
+            owner_lo,
+            owner_hi,
+        );
+        let (requested, expired) = check_expiry(requested, user_expiry.to_arcis(), current_slot);
+        let state = current_state.decrypt(state_nonce);
+        let mut budget = current_budget.decrypt(budget_nonce);
+        let remaining = daily_remaining(budget.remaining, daily_cap, day_rolled);
+
+        // An expired or overflowing order spends no budget
+        let (requested, amount_overflow) = check_total(state.total_amount, requested);
+        let amount = if requested <= max_order { requested } else { 0 };
+        let amount = if amount <= remaining { amount } else { 0 };
+        budget.remaining = remaining - amount;
+
+        let state = apply_order(state, amount, wallet_lo, wallet_hi);
+
+        let new_nonce = ArcisRNG::u128();
+        let encrypted = state.encrypt(new_nonce);
+
+        let new_budget_nonce = ArcisRNG::u128();
+        let encrypted_budget = budget.encrypt(new_budget_nonce);
+
+        SessionOrderOutput {
+            ciphertexts: encrypted.ciphertexts,
+            nonce: new_nonce,
+            budget_ciphertexts: encrypted_budget.ciphertexts,
+            budget_nonce: new_budget_nonce,
+            wallet_bound: wallet_bound.reveal(),
+            amount_overflow: amount_overflow.reveal(),
+            expired: expired.reveal(),
+        }
+    }
+
+    /// `add_limited_order` for a session key, spending both budgets
+    ///
+    /// The order must fit the owner's limits and the session's own budget;
+    /// otherwise it is added with a zero amount and neither is spent.
+    #[instruction]
+    pub fn add_limited_session_order(
+        user_amount: Enc<Shared, u64>,
+        user_wallet_lo: Enc<Shared, u128>,
+        user_wallet_hi: Enc<Shared, u128>,
+        user_expiry: Enc<Shared, u64>,
+        owner_lo: u128,
+        owner_hi: u128,
+        current_slot: u64,
+        state_nonce: u128,
+        current_state: Enc<Account, BatchState>,
+        max_order: u64,
+        daily_cap: u64,
+        day_rolled: u8,
+        budget_nonce: u128,
+        current_budget: Enc<Account, SessionBudget>,
+        session_budget_nonce: u128,
+        current_session_budget: Enc<Account, SessionBudget>,
+    ) -> LimitedSessionOrderOutput {
+        let (requested, wallet_lo, wallet_hi, wallet_bound) = bind_wallet(
+            user_amount.to_arcis(),
+            user_wallet_lo.to_arcis(),
+            user_wallet_hi.to_arcis(),
+            owner_lo,
+            owner_hi,
+        );
+        let (requested, expired) = check_expiry(requested, user_expiry.to_arcis(), current_slot);
+        let state = current_state.decrypt(state_nonce);
+        let mut budget = current_budget.decrypt(budget_nonce);
+        let mut session_budget = current_session_budget.decrypt(session_budget_nonce);
+        let remaining = daily_remaining(budget.remaining, daily_cap, day_rolled);
+
+        let (requested, amount_overflow) = check_total(state.total_amount, requested);
+        let amount = if requested <= max_order { requested } else { 0 };
+        let amount = if amount <= remaining { amount } else { 0 };
+        let amount = if amount <= session_budget.remaining { amount } else { 0 };
+        budget.remaining = remaining - amount;
+        session_budget.remaining = session_budget.remaining - amount;
+
+        let state = apply_order(state, amount, wallet_lo, wallet_hi);
+
+        let new_nonce = ArcisRNG::u128();
+        let encrypted = state.encrypt(new_nonce);
+
+        let new_budget_nonce = ArcisRNG::u128();
+        let encrypted_budget = budget.encrypt(new_budget_nonce);
+
+        let new_session_budget_nonce = ArcisRNG::u128();
+        let encrypted_session_budget = session_budget.encrypt(new_session_budget_nonce);
+
+        LimitedSessionOrderOutput {
+            ciphertexts: encrypted.ciphertexts,
+            nonce: new_nonce,
+            budget_ciphertexts: encrypted_budget.ciphertexts,
+            budget_nonce: new_budget_nonce,
+            session_budget_ciphertexts: encrypted_session_budget.ciphertexts,
+            session_budget_nonce: new_session_budget_nonce,
+            wallet_bound: wallet_bound.reveal(),
+            amount_overflow: amount_overflow.reveal(),
+            expired: expired.reveal(),
+        }
+    }
+
+    // ============================================
+    // Order Lane Instructions
+    // ============================================
+
+    /// Initialize an empty order lane (same state layout as a batch)
+    #[instruction]
+    pub fn init_lane(nonce_input: u128) -> BatchInitOutput {
+        let initial_state = BatchState {
+            total_amount: 0,
+            order_count: 0,
+            commitment_root: 0,
+            commitment_root_hi: 0,
+            order_hash_1: 0,
+            order_hash_2: 0,
+            order_hash_3: 0,
+            order_hash_4: 0,
+        };
+
+        let encrypted = initial_state.encrypt(nonce_input);
+
+        BatchInitOutput {
+            ciphertexts: encrypted.ciphertexts,
+            nonce: encrypted.nonce,
+        }
+    }
+
+    /// Add an order to one lane of a batch
+    #[instruction]
+    pub fn add_lane_order(
+        user_amount: Enc<Shared, u64>,
+        user_wallet_lo: Enc<Shared, u128>,
+        user_wallet_hi: Enc<Shared, u128>,
+        user_expiry: Enc<Shared, u64>,
+        signer_lo: u128,
+        signer_hi: u128,
+        current_slot: u64,
+        lane_nonce: u128,
+        current_lane: Enc<Account, BatchState>,
+    ) -> AddOrderOutputData {
+        let (amount, wallet_lo, wallet_hi, wallet_bound) = bind_wallet(
+            user_amount.to_arcis(),
+            user_wallet_lo.to_arcis(),
+            user_wallet_hi.to_arcis(),
+            signer_lo,
+            signer_hi,
+        );
+        let (amount, expired) = check_expiry(amount, user_expiry.to_arcis(), current_slot);
+        let lane = current_lane.decrypt(lane_nonce);
+        let (amount, amount_overflow) = check_total(lane.total_amount, amount);
+
+        let lane = apply_order(lane, amount, wallet_lo, wallet_hi);
+
+        let new_nonce = ArcisRNG::u128();
+        let encrypted = lane.encrypt(new_nonce);
+
+        AddOrderOutputData {
+            ciphertexts: encrypted.ciphertexts,
+            nonce: new_nonce,
+            wallet_bound: wallet_bound.reveal(),
+            amount_overflow: amount_overflow.reveal(),
+            expired: expired.reveal(),
+        }
+    }
+
+    /// Merge the four lanes of a batch into one batch state
+    ///
+    /// The running root is XOR / wrapping-add of leaves, so merging lanes
+    /// gives the same root as adding every order to a single state. The
+    /// stored hash slots take the first order of each lane. Each lane total
+    /// fits in a u64 but their sum may not: a lane whose total would
+    /// overflow the running total contributes nothing, as `check_total`
+    /// does for an order, and that is revealed as `total_overflow`.
+    #[instruction]
+    pub fn merge_lanes(
+        lane_nonce_0: u128,
+        lane_0: Enc<Account, BatchState>,
+        lane_nonce_1: u128,
+        lane_1: Enc<Account, BatchState>,
+        lane_nonce_2: u128,
+        lane_2: Enc<Account, BatchState>,
+        lane_nonce_3: u128,
+        lane_3: Enc<Account, BatchState>,
+    ) -> MergeLanesOutputData {
+        let l0 = lane_0.decrypt(lane_nonce_0);
+        let l1 = lane_1.decrypt(lane_nonce_1);
+        let l2 = lane_2.decrypt(lane_nonce_2);
+        let l3 = lane_3.decrypt(lane_nonce_3);
+
+        let total = l0.total_amount;
+        let (amount_1, overflow_1) = check_total(total, l1.total_amount);
+        let total = total + amount_1;
+        let (amount_2, overflow_2) = check_total(total, l2.total_amount);
+        let total = total + amount_2;
+        let (amount_3, overflow_3) = check_total(total, l3.total_amount);
+        let total = total + amount_3;
+        let total_overflow = overflow_1 | overflow_2 | overflow_3;
+
+        let merged = BatchState {
+            total_amount: total,
+            order_count: l0.order_count + l1.order_count + l2.order_count + l3.order_count,
+            commitment_root: l0.commitment_root
+                ^ l1.commitment_root
+                ^ l2.commitment_root
+                ^ l3.commitment_root,
+            commitment_root_hi: l0
+                .commitment_root_hi
+                .wrapping_add(l1.commitment_root_hi)
+                .wrapping_add(l2.commitment_root_hi)
+                .wrapping_add(l3.commitment_root_hi),
+            order_hash_1: l0.order_hash_1,
+            order_hash_2: l1.order_hash_1,
+            order_hash_3: l2.order_hash_1,
+            order_hash_4: l3.order_hash_1,
+        };
+
+        let new_nonce = ArcisRNG::u128();
+        let encrypted = merged.encrypt(new_nonce);
+
+        MergeLanesOutputData {
+            ciphertexts: encrypted.ciphertexts,
+            nonce: new_nonce,
+            total_overflow: total_overflow.reveal(),
+        }
+    }
+
+    /// Preview batch execution without changing the orders or totals
+    ///
+    /// Runs the same inputs as `execute_batch` but only returns the
+    /// clearing price, fill ratio and total notional encrypted to the
+    /// authority. Nothing is revealed. The unchanged state is re-encrypted
+    /// under a fresh nonce, so a preview cannot be told apart from an
+    /// order by watching the batch ciphertexts.
+    #[instruction]
+    pub fn simulate_execute_batch(
+        authority: Shared,
+        total_shares: u64,
+        execution_price: u64,
+        state_nonce: u128,
+        current_state: Enc<Account, BatchState>,
+    ) -> SimulateExecuteBatchOutputData {
+        let state = current_state.decrypt(state_nonce);
+
+        let preview = ExecutionPreview {
+            clearing_price: execution_price,
+            fill_ratio_bps: compute_fill_ratio_bps(state.total_amount, total_shares, execution_price),
+            total_usdc: state.total_amount,
+        };
+
+        let new_nonce = ArcisRNG::u128();
+        let encrypted = state.encrypt(new_nonce);
+
+        SimulateExecuteBatchOutputData {
+            preview: authority.from_arcis(preview),
+            ciphertexts: encrypted.ciphertexts,
+            nonce: new_nonce,
+        }
+    }
+
+    // ============================================
+    // RFQ Instructions
+    // ============================================
+
+    /// Initialize the encrypted RFQ state from the taker's request
+    #[instruction]
+    pub fn init_rfq(request: Enc<Shared, RfqRequest>, nonce_input: u128) -> RfqStateOutput {
+        let request = request.to_arcis();
+
+        let state = RfqState {
+            size: request.size,
+            side: request.side,
+            best_price: 0,
+            best_maker: 0,
+            has_quote: false,
+        };
+
+        let encrypted = state.encrypt(nonce_input);
+
+        RfqStateOutput {
+            ciphertexts: encrypted.ciphertexts,
+            nonce: encrypted.nonce,
+        }
+    }
+
+    /// Compare a maker quote against the best quote so far
+    ///
+    /// The quote for the taker's side wins when it is strictly cheaper,
+    /// so ties go to the earlier maker. Prices above 10_000 bps can never
+    /// win. Nothing is revealed.
+    #[instruction]
+    pub fn submit_quote(
+        quote: Enc<Shared, MakerQuote>,
+        maker_index: u8,
+        state_nonce: u128,
+        current_state: Enc<Account, RfqState>,
+    ) -> RfqStateOutput {
+        let quote = quote.to_arcis();
+        let mut state = current_state.decrypt(state_nonce);
+
+        let price = if state.side == 0 { quote.yes_price } else { quote.no_price };
+        let is_valid = price <= 10_000;
+        let is_better = is_valid & (!state.has_quote | (price < state.best_price));
+
+        state.best_price = if is_better { price } else { state.best_price };
+        state.best_maker = if is_better { maker_index } else { state.best_maker };
+        state.has_quote = state.has_quote | is_better;
+
+        let new_nonce = ArcisRNG::u128();
+        let encrypted = state.encrypt(new_nonce);
+
+        RfqStateOutput {
+            ciphertexts: encrypted.ciphertexts,
+            nonce: new_nonce,
+        }
+    }
+
+    /// Reveal the winning maker and fill notional
+    #[instruction]
+    pub fn settle_rfq(state_nonce: u128, current_state: Enc<Account, RfqState>) -> RfqSettlement {
+        let state = current_state.decrypt(state_nonce);
+
+        let fill_notional = compute_fill_notional(state.size, state.best_price);
+
+        RfqSettlement {
+            has_quote: state.has_quote.reveal(),
+            best_maker: state.best_maker.reveal(),
+            fill_notional: fill_notional.reveal(),
+        }
+    }
+
+    // ============================================
+    // Parlay Instructions
+    // ============================================
+
+    /// Encrypt a parlay order spanning `leg_count` batches
+    ///
+    /// The wallet is bound and the expiry checked as in `add_order`. Legs
+    /// past `leg_count` must carry zero weight and the rest must sum to
+    /// 10_000 bps; otherwise the amount is zeroed. Only the three flags
+    /// are revealed.
+    #[instruction]
+    pub fn init_parlay(
+        user_amount: Enc<Shared, u64>,
+        user_weights: Enc<Shared, ParlayWeights>,
+        user_wallet_lo: Enc<Shared, u128>,
+        user_wallet_hi: Enc<Shared, u128>,
+        user_expiry: Enc<Shared, u64>,
+        signer_lo: u128,
+        signer_hi: u128,
+        current_slot: u64,
+        leg_count: u8,
+        nonce_input: u128,
+    ) -> ParlayInitOutput {
+        let (amount, wallet_lo, wallet_hi, wallet_bound) = bind_wallet(
+            user_amount.to_arcis(),
+            user_wallet_lo.to_arcis(),
+            user_wallet_hi.to_arcis(),
+            signer_lo,
+            signer_hi,
+        );
+        let (amount, expired) = check_expiry(amount, user_expiry.to_arcis(), current_slot);
+        let weights = user_weights.to_arcis();
+
+        let unused_clear = ((leg_count > 2) | (weights.weight_2 == 0)) & ((leg_count > 3) | (weights.weight_3 == 0));
+        let total_weight = (weights.weight_0 as u128)
+            + (weights.weight_1 as u128)
+            + (weights.weight_2 as u128)
+            + (weights.weight_3 as u128);
+        let weights_valid = unused_clear & (total_weight == 10_000);
+        let amount = if weights_valid { amount } else { 0 };
+
+        let state = ParlayState {
+            amount,
+            weight_0: weights.weight_0,
+            weight_1: weights.weight_1,
+            weight_2: weights.weight_2,
+            weight_3: weights.weight_3,
+            wallet_lo,
+            wallet_hi,
+        };
+
+        let encrypted = state.encrypt(nonce_input);
+
+        ParlayInitOutput {
+            ciphertexts: encrypted.ciphertexts,
+            nonce: encrypted.nonce,
+            wallet_bound: wallet_bound.reveal(),
+            expired: expired.reveal(),
+            weights_valid: weights_valid.reveal(),
+        }
+    }
+
+    /// Fold leg `leg_index` of a parlay into that leg's batch
+    ///
+    /// The leg commits `amount * weight / 10_000` (rounded down, so the legs
+    /// may sum to a few base units under the amount) under the parlay's
+    /// wallet. The parlay state is only read.
+    #[instruction]
+    pub fn add_parlay_leg(
+        leg_index: u8,
+        parlay_nonce: u128,
+        parlay_state: Enc<Account, ParlayState>,
+        state_nonce: u128,
+        current_state: Enc<Account, BatchState>,
+    ) -> ParlayLegOutput {
+        let parlay = parlay_state.decrypt(parlay_nonce);
+        let state = current_state.decrypt(state_nonce);
+
+        // Every weight is selected so the leg does not show in the circuit
+        let weight_0 = if leg_index == 0 { parlay.weight_0 } else { 0 };
+        let weight_1 = if leg_index == 1 { parlay.weight_1 } else { 0 };
+        let weight_2 = if leg_index == 2 { parlay.weight_2 } else { 0 };
+        let weight_3 = if leg_index == 3 { parlay.weight_3 } else { 0 };
+        let weight = (weight_0 as u128) + (weight_1 as u128) + (weight_2 as u128) + (weight_3 as u128);
+
+        let leg_amount = ((parlay.amount as u128) * weight / 10_000) as u64;
+        let (leg_amount, amount_overflow) = check_total(state.total_amount, leg_amount);
+
+        let state = apply_order(state, leg_amount, parlay.wallet_lo, parlay.wallet_hi);
+
+        let new_nonce = ArcisRNG::u128();
+        let encrypted = state.encrypt(new_nonce);
+
+        ParlayLegOutput {
+            ciphertexts: encrypted.ciphertexts,
+            nonce: new_nonce,
+            amount_overflow: amount_overflow.reveal(),
+        }
+    }
+
+    /// Initialize an empty encrypted flow auction state
+    #[instruction]
+    pub fn init_flow_auction(nonce_input: u128) -> FlowAuctionStateOutput {
+        let state = FlowAuctionState {
+            best_improvement: 0,
+            best_maker: 0,
+            has_bid: false,
+        };
+
+        let encrypted = state.encrypt(nonce_input);
+
+        FlowAuctionStateOutput {
+            ciphertexts: encrypted.ciphertexts,
+            nonce: encrypted.nonce,
+        }
+    }
+
+    /// Compare a maker's bid for the batch flow against the best bid so far
+    ///
+    /// A bid only counts if its capacity covers the batch's encrypted total,
+    /// so the flow size never leaves MPC. The strictly larger improvement
+    /// wins and ties go to the earlier maker. Nothing is revealed.
+    #[instruction]
+    pub fn submit_flow_bid(
+        bid: Enc<Shared, FlowBid>,
+        maker_index: u8,
+        state_nonce: u128,
+        current_state: Enc<Account, FlowAuctionState>,
+        batch_nonce: u128,
+        batch_state: Enc<Account, BatchState>,
+    ) -> FlowAuctionStateOutput {
+        let bid = bid.to_arcis();
+        let mut state = current_state.decrypt(state_nonce);
+        let batch = batch_state.decrypt(batch_nonce);
+
+        let is_valid = (bid.improvement_bps <= 10_000) & (bid.capacity >= batch.total_amount);
+        let is_better = is_valid & (!state.has_bid | (bid.improvement_bps > state.best_improvement));
+
+        state.best_improvement = if is_better { bid.improvement_bps } else { state.best_improvement };
+        state.best_maker = if is_better { maker_index } else { state.best_maker };
+        state.has_bid = state.has_bid | is_better;
+
+        let new_nonce = ArcisRNG::u128();
+        let encrypted = state.encrypt(new_nonce);
+
+        FlowAuctionStateOutput {
+            ciphertexts: encrypted.ciphertexts,
+            nonce: new_nonce,
+        }
+    }
+
+    /// Reveal the winning maker and its price improvement
+    #[instruction]
+    pub fn settle_flow_auction(
+        state_nonce: u128,
+        current_state: Enc<Account, FlowAuctionState>,
+    ) -> FlowAuctionSettlement {
+        let state = current_state.decrypt(state_nonce);
+
+        FlowAuctionSettlement {
+            has_bid: state.has_bid.reveal(),
+            best_maker: state.best_maker.reveal(),
+            improvement_bps: state.best_improvement.reveal(),
+        }
+    }
+
+    /// Initialize an empty encrypted oracle state
+    #[instruction]
+    pub fn init_oracle(nonce_input: u128) -> OracleStateOutput {
+        let state = OracleState {
+            value_0: 0,
+            value_1: 0,
+            value_2: 0,
+            value_3: 0,
+            value_4: 0,
+        };
+
+        let encrypted = state.encrypt(nonce_input);
+
+        OracleStateOutput {
+            ciphertexts: encrypted.ciphertexts,
+            nonce: encrypted.nonce,
+        }
+    }
+
+    /// Store a provider's observation in its slot
+    ///
+    /// The slot index is public (the program enforces one submission per
+    /// provider); the value is not. Nothing is revealed.
+    #[instruction]
+    pub fn submit_observation(
+        observation: Enc<Shared, u64>,
+        provider_index: u8,
+        state_nonce: u128,
+        current_state: Enc<Account, OracleState>,
+    ) -> OracleStateOutput {
+        let value = observation.to_arcis();
+        let mut state = current_state.decrypt(state_nonce);
+
+        state.value_0 = if provider_index == 0 { value } else { state.value_0 };
+        state.value_1 = if provider_index == 1 { value } else { state.value_1 };
+        state.value_2 = if provider_index == 2 { value } else { state.value_2 };
+        state.value_3 = if provider_index == 3 { value } else { state.value_3 };
+        state.value_4 = if provider_index == 4 { value } else { state.value_4 };
+
+        let new_nonce = ArcisRNG::u128();
+        let encrypted = state.encrypt(new_nonce);
+
+        OracleStateOutput {
+            ciphertexts: encrypted.ciphertexts,
+            nonce: new_nonce,
+        }
+    }
+
+    /// Reveal whether the median observation reached `threshold`
+    ///
+    /// Empty slots are padded per the public masks (zero, or `u64::MAX` where
+    /// `pad_high_mask` is set) so the middle of the sorted five is the median
+    /// of the submitted values. Only the outcome bit is revealed.
+    #[instruction]
+    pub fn resolve_oracle(
+        threshold: u64,
+        submitted_mask: u8,
+        pad_high_mask: u8,
+        state_nonce: u128,
+        current_state: Enc<Account, OracleState>,
+    ) -> OracleResolution {
+        let state = current_state.decrypt(state_nonce);
+
+        let v0 = pad_observation(state.value_0, submitted_mask, pad_high_mask, 0);
+        let v1 = pad_observation(state.value_1, submitted_mask, pad_high_mask, 1);
+        let v2 = pad_observation(state.value_2, submitted_mask, pad_high_mask, 2);
+        let v3 = pad_observation(state.value_3, submitted_mask, pad_high_mask, 3);
+        let v4 = pad_observation(state.value_4, submitted_mask, pad_high_mask, 4);
+
+        let (_, _, median, _, _) = sort_five(v0, v1, v2, v3, v4);
+
+        OracleResolution {
+            outcome: (median >= threshold).reveal(),
+        }
+    }
+
+    // ============================================
+    // House Account Instructions
+    // ============================================
+
+    /// Add an order from a house desk, booking it on the house ledger
+    ///
+    /// Folds into the batch exactly as `add_order` does. The folded amount
+    /// is added to the ledger's pending notional for the batch. A ledger
+    /// that was never written (`ledger_fresh == 1`) starts empty.
+    #[instruction]
+    pub fn add_house_order(
+        user_amount: Enc<Shared, u64>,
+        user_wallet_lo: Enc<Shared, u128>,
+        user_wallet_hi: Enc<Shared, u128>,
+        user_expiry: Enc<Shared, u64>,
+        desk_lo: u128,
+        desk_hi: u128,
+        current_slot: u64,
+        state_nonce: u128,
+        current_state: Enc<Account, BatchState>,
+        ledger_fresh: u8,
+        ledger_nonce: u128,
+        current_ledger: Enc<Account, HouseLedger>,
+    ) -> HouseOrderOutput {
+        let (amount, wallet_lo, wallet_hi, wallet_bound) = bind_wallet(
+            user_amount.to_arcis(),
+            user_wallet_lo.to_arcis(),
+            user_wallet_hi.to_arcis(),
+            desk_lo,
+            desk_hi,
+        );
+        let (amount, expired) = check_expiry(amount, user_expiry.to_arcis(), current_slot);
+        let state = current_state.decrypt(state_nonce);
+        let (amount, amount_overflow) = check_total(state.total_amount, amount);
+        let mut ledger = open_ledger(current_ledger.decrypt(ledger_nonce), ledger_fresh);
+
+        // Pending notional is part of the batch total, so it cannot overflow
+        ledger.pending_notional = ledger.pending_notional + amount;
+        let state = apply_order(state, amount, wallet_lo, wallet_hi);
+
+        let new_nonce = ArcisRNG::u128();
+        let encrypted = state.encrypt(new_nonce);
+
+        let new_ledger_nonce = ArcisRNG::u128();
+        let encrypted_ledger = ledger.encrypt(new_ledger_nonce);
+
+        HouseOrderOutput {
+            ciphertexts: encrypted.ciphertexts,
+            nonce: new_nonce,
+            ledger_ciphertexts: encrypted_ledger.ciphertexts,
+            ledger_nonce: new_ledger_nonce,
+            wallet_bound: wallet_bound.reveal(),
+            amount_overflow: amount_overflow.reveal(),
+            expired: expired.reveal(),
+        }
+    }
+
+    /// Book an executed batch's house fill
+    ///
+    /// The pending notional moves into the cost basis and its pro-rata
+    /// shares, rounded down as the allocation circuit rounds them, into
+    /// inventory.
+    #[instruction]
+    pub fn settle_house_batch(
+        total_shares: u64,
+        total_usdc: u64,
+        ledger_nonce: u128,
+        current_ledger: Enc<Account, HouseLedger>,
+    ) -> HouseLedgerOutput {
+        let mut ledger = current_ledger.decrypt(ledger_nonce);
+
+        let divisor = if total_usdc == 0 { 1 } else { total_usdc as u128 };
+        let shares = ((ledger.pending_notional as u128) * (total_shares as u128) / divisor) as u64;
+        ledger.inventory = ledger.inventory + shares;
+        ledger.cost_basis = ledger.cost_basis + ledger.pending_notional;
+        ledger.pending_notional = 0;
+
+        let new_nonce = ArcisRNG::u128();
+        let encrypted = ledger.encrypt(new_nonce);
+
+        HouseLedgerOutput {
+            ciphertexts: encrypted.ciphertexts,
+            nonce: new_nonce,
+        }
+    }
+
+    /// Mark the house ledger to `mark_price` for the house authority
+    ///
+    /// `mark_price` is per share, in the units `execute_batch` takes, so
+    /// `mark_value - cost_basis` is the house P&L. Nothing is revealed.
+    #[instruction]
+    pub fn reveal_house_ledger(
+        authority: Shared,
+        mark_price: u64,
+        ledger_nonce: u128,
+        current_ledger: Enc<Account, HouseLedger>,
+    ) -> Enc<Shared, HouseStatement> {
+        let ledger = current_ledger.decrypt(ledger_nonce);
+
+        let value = (ledger.inventory as u128) * (mark_price as u128);
+        let mark_value = if value > (u64::MAX as u128) { u64::MAX } else { value as u64 };
+
+        authority.from_arcis(HouseStatement {
+            pending_notional: ledger.pending_notional,
+            cost_basis: ledger.cost_basis,
+            inventory: ledger.inventory,
+            mark_value,
+        })
+    }
+
     // ============================================
     // Helper Functions
     // ============================================
 
+    /// A ledger that was never written starts empty
+    fn open_ledger(ledger: HouseLedger, fresh: u8) -> HouseLedger {
+        HouseLedger {
+            pending_notional: if fresh == 1 { 0 } else { ledger.pending_notional },
+            cost_basis: if fresh == 1 { 0 } else { ledger.cost_basis },
+            inventory: if fresh == 1 { 0 } else { ledger.inventory },
+        }
+    }
+
+    /// Replace an empty oracle slot with its padding value
+    fn pad_observation(value: u64, submitted_mask: u8, pad_high_mask: u8, slot: u8) -> u64 {
+        let bit = 1u8 << slot;
+        let is_submitted = (submitted_mask & bit) != 0;
+        let pad = if (pad_high_mask & bit) != 0 { u64::MAX } else { 0 };
+        if is_submitted { value } else { pad }
+    }
+
+    /// Order two values, smaller first
+    fn sort_pair(a: u64, b: u64) -> (u64, u64) {
+        let swap = a > b;
+        let low = if swap { b } else { a };
+        let high = if swap { a } else { b };
+        (low, high)
+    }
+
+    /// Sort five values with a fixed 9-comparator network
+    fn sort_five(v0: u64, v1: u64, v2: u64, v3: u64, v4: u64) -> (u64, u64, u64, u64, u64) {
+        let (v0, v1) = sort_pair(v0, v1);
+        let (v3, v4) = sort_pair(v3, v4);
+        let (v2, v4) = sort_pair(v2, v4);
+        let (v2, v3) = sort_pair(v2, v3);
+        let (v0, v3) = sort_pair(v0, v3);
+        let (v0, v2) = sort_pair(v0, v2);
+        let (v1, v4) = sort_pair(v1, v4);
+        let (v1, v3) = sort_pair(v1, v3);
+        let (v1, v2) = sort_pair(v1, v2);
+        (v0, v1, v2, v3, v4)
+    }
+
+    /// Bind an order's encrypted wallet to the transaction signer
+    ///
+    /// A wallet that does not match the signer is replaced by the signer
+    /// with a zero amount, so a forged order still takes its slot but
+    /// commits no notional and never names someone else's wallet.
+    fn bind_wallet(
+        amount: u64,
+        wallet_lo: u128,
+        wallet_hi: u128,
+        signer_lo: u128,
+        signer_hi: u128,
+    ) -> (u64, u128, u128, bool) {
+        let is_bound = (wallet_lo == signer_lo) & (wallet_hi == signer_hi);
+        let amount = if is_bound { amount } else { 0 };
+        (amount, signer_lo, signer_hi, is_bound)
+    }
+
+    /// Zero an order whose expiry slot is before `current_slot`
+    ///
+    /// `current_slot` is the slot the order was queued at, so an order
+    /// relayed or placed by a session key after its owner's deadline
+    /// commits nothing. Returns the amount to fold and whether it expired.
+    fn check_expiry(amount: u64, expiry_slot: u64, current_slot: u64) -> (u64, bool) {
+        let expired = expiry_slot < current_slot;
+        let amount = if expired { 0 } else { amount };
+        (amount, expired)
+    }
+
+    /// What a risk profile may still commit today
+    ///
+    /// The first order of a day (`day_rolled == 1`) restarts at the cap, and
+    /// a cap tightened mid-day applies at once.
+    fn daily_remaining(remaining: u64, daily_cap: u64, day_rolled: u8) -> u64 {
+        let remaining = if day_rolled == 1 { daily_cap } else { remaining };
+        if remaining <= daily_cap { remaining } else { daily_cap }
+    }
+
+    /// Zero an order amount that would overflow the running total
+    ///
+    /// Returns the amount to fold and whether it overflowed.
+    fn check_total(total: u64, amount: u64) -> (u64, bool) {
+        let overflows = amount > u64::MAX - total;
+        let amount = if overflows { 0 } else { amount };
+        (amount, overflows)
+    }
+
+    /// Fold one order into a batch (or lane) state
+    ///
+    /// Callers bound `amount` with `check_total` first; `order_count` is
+    /// bounded on-chain by `BatchFull`.
+    fn apply_order(state: BatchState, amount: u64, wallet_lo: u128, wallet_hi: u128) -> BatchState {
+        fold_order(state, true, amount, wallet_lo, wallet_hi)
+    }
+
+    /// `apply_order` for an order that may be absent, which leaves the
+    /// state as it was
+    fn fold_order(
+        mut state: BatchState,
+        present: bool,
+        amount: u64,
+        wallet_lo: u128,
+        wallet_hi: u128,
+    ) -> BatchState {
+        // Update totals
+        let amount = if present { amount } else { 0 };
+        state.total_amount = state.total_amount + amount;
+        state.order_count = state.order_count + if present { 1 } else { 0 };
+
+        // Compute order commitment hash (simplified poseidon-like)
+        let order_hash = compute_order_hash(amount, wallet_lo, wallet_hi);
+
+        // Update merkle root (running hash); a zero leaf changes neither half
+        let leaf = if present { order_hash } else { 0 };
+        let (new_root_lo, new_root_hi) = update_merkle_root(
+            state.commitment_root,
+            state.commitment_root_hi,
+            leaf,
+        );
+        state.commitment_root = new_root_lo;
+        state.commitment_root_hi = new_root_hi;
+
+        // Store order hash based on count; every slot is selected so the
+        // written slot does not show in the circuit. Additional orders use
+        // the running root.
+        let count = state.order_count;
+        state.order_hash_1 = if present & (count == 1) { order_hash } else { state.order_hash_1 };
+        state.order_hash_2 = if present & (count == 2) { order_hash } else { state.order_hash_2 };
+        state.order_hash_3 = if present & (count == 3) { order_hash } else { state.order_hash_3 };
+        state.order_hash_4 = if present & (count == 4) { order_hash } else { state.order_hash_4 };
+
+        state
+    }
+
+    /// Notional paid for `size` of payout at `price_bps`
+    fn compute_fill_notional(size: u64, price_bps: u64) -> u64 {
+        ((size as u128) * (price_bps as u128) / 10_000) as u64
+    }
+
+    /// Share of the required notional (shares * price) covered by the batch, in bps
+    fn compute_fill_ratio_bps(total_usdc: u64, total_shares: u64, price: u64) -> u64 {
+        let required = (total_shares as u128) * (price as u128);
+        let available = total_usdc as u128;
+
+        // Both branches are evaluated in MPC, so keep the divisor non-zero
+        let divisor = if required == 0 { 1 } else { required };
+        let ratio = available * 10_000 / divisor;
+
+        let is_full = (required == 0) | (ratio > 10_000);
+        if is_full { 10_000 } else { ratio as u64 }
+    }
+
     /// Compute a hash of an order (simplified poseidon-like)
     fn compute_order_hash(amount: u64, wallet_lo: u128, wallet_hi: u128) -> u128 {
         let mut hash: u128 = 0;
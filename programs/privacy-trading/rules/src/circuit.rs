@@ -0,0 +1,158 @@
+//! Circuit Arithmetic
+//!
+//! The plain helpers of `encrypted-ixs`, copied as written there so the
+//! circuit simulator and the program run the circuits' own arithmetic.
+//! Arcis cannot call into another crate, so the circuits keep their copy;
+//! `tests/circuit_sync.rs` fails if the two drift apart.
+
+// Kept in the circuits' form, which Arcis compiles branch-free
+#![allow(clippy::assign_op_pattern)]
+
+/// Replace an empty oracle slot with its padding value
+pub fn pad_observation(value: u64, submitted_mask: u8, pad_high_mask: u8, slot: u8) -> u64 {
+    let bit = 1u8 << slot;
+    let is_submitted = (submitted_mask & bit) != 0;
+    let pad = if (pad_high_mask & bit) != 0 {
+        u64::MAX
+    } else {
+        0
+    };
+    if is_submitted {
+        value
+    } else {
+        pad
+    }
+}
+
+/// Order two values, smaller first
+pub fn sort_pair(a: u64, b: u64) -> (u64, u64) {
+    let swap = a > b;
+    let low = if swap { b } else { a };
+    let high = if swap { a } else { b };
+    (low, high)
+}
+
+/// Sort five values with a fixed 9-comparator network
+pub fn sort_five(v0: u64, v1: u64, v2: u64, v3: u64, v4: u64) -> (u64, u64, u64, u64, u64) {
+    let (v0, v1) = sort_pair(v0, v1);
+    let (v3, v4) = sort_pair(v3, v4);
+    let (v2, v4) = sort_pair(v2, v4);
+    let (v2, v3) = sort_pair(v2, v3);
+    let (v0, v3) = sort_pair(v0, v3);
+    let (v0, v2) = sort_pair(v0, v2);
+    let (v1, v4) = sort_pair(v1, v4);
+    let (v1, v3) = sort_pair(v1, v3);
+    let (v1, v2) = sort_pair(v1, v2);
+    (v0, v1, v2, v3, v4)
+}
+
+/// Bind an order's encrypted wallet to the transaction signer
+///
+/// A wallet that does not match the signer is replaced by the signer
+/// with a zero amount, so a forged order still takes its slot but
+/// commits no notional and never names someone else's wallet.
+pub fn bind_wallet(
+    amount: u64,
+    wallet_lo: u128,
+    wallet_hi: u128,
+    signer_lo: u128,
+    signer_hi: u128,
+) -> (u64, u128, u128, bool) {
+    let is_bound = (wallet_lo == signer_lo) & (wallet_hi == signer_hi);
+    let amount = if is_bound { amount } else { 0 };
+    (amount, signer_lo, signer_hi, is_bound)
+}
+
+/// Zero an order whose expiry slot is before `current_slot`
+///
+/// `current_slot` is the slot the order was queued at, so an order
+/// relayed or placed by a session key after its owner's deadline
+/// commits nothing. Returns the amount to fold and whether it expired.
+pub fn check_expiry(amount: u64, expiry_slot: u64, current_slot: u64) -> (u64, bool) {
+    let expired = expiry_slot < current_slot;
+    let amount = if expired { 0 } else { amount };
+    (amount, expired)
+}
+
+/// What a risk profile may still commit today
+///
+/// The first order of a day (`day_rolled == 1`) restarts at the cap, and
+/// a cap tightened mid-day applies at once.
+pub fn daily_remaining(remaining: u64, daily_cap: u64, day_rolled: u8) -> u64 {
+    let remaining = if day_rolled == 1 {
+        daily_cap
+    } else {
+        remaining
+    };
+    if remaining <= daily_cap {
+        remaining
+    } else {
+        daily_cap
+    }
+}
+
+/// Zero an order amount that would overflow the running total
+///
+/// Returns the amount to fold and whether it overflowed.
+pub fn check_total(total: u64, amount: u64) -> (u64, bool) {
+    let overflows = amount > u64::MAX - total;
+    let amount = if overflows { 0 } else { amount };
+    (amount, overflows)
+}
+
+/// Notional paid for `size` of payout at `price_bps`
+pub fn compute_fill_notional(size: u64, price_bps: u64) -> u64 {
+    ((size as u128) * (price_bps as u128) / 10_000) as u64
+}
+
+/// Share of the required notional (shares * price) covered by the batch, in bps
+pub fn compute_fill_ratio_bps(total_usdc: u64, total_shares: u64, price: u64) -> u64 {
+    let required = (total_shares as u128) * (price as u128);
+    let available = total_usdc as u128;
+
+    // Both branches are evaluated in MPC, so keep the divisor non-zero
+    let divisor = if required == 0 { 1 } else { required };
+    let ratio = available * 10_000 / divisor;
+
+    let is_full = (required == 0) | (ratio > 10_000);
+    if is_full {
+        10_000
+    } else {
+        ratio as u64
+    }
+}
+
+/// Compute a hash of an order (simplified poseidon-like)
+pub fn compute_order_hash(amount: u64, wallet_lo: u128, wallet_hi: u128) -> u128 {
+    let mut hash: u128 = 0;
+
+    // Mix amount
+    hash = hash.wrapping_add(amount as u128);
+    hash = hash.wrapping_mul(31);
+
+    // Mix wallet
+    hash = hash ^ wallet_lo;
+    hash = hash.wrapping_mul(31);
+    hash = hash ^ wallet_hi;
+
+    hash
+}
+
+/// Update merkle root with new leaf
+pub fn update_merkle_root(current_lo: u128, current_hi: u128, new_leaf: u128) -> (u128, u128) {
+    // Simple merkle update (hash of current || new_leaf)
+    let new_lo = current_lo ^ new_leaf;
+    let new_hi = current_hi.wrapping_add(new_leaf);
+    (new_lo, new_hi)
+}
+
+/// Hash execution parameters for verification
+pub fn hash_execution_params(total_shares: u64, price: u64, total_usdc: u64) -> u64 {
+    let mut hash: u64 = 0;
+    hash = hash.wrapping_add(total_shares);
+    hash = hash.wrapping_mul(31);
+    hash = hash.wrapping_add(price);
+    hash = hash.wrapping_mul(31);
+    hash = hash.wrapping_add(total_usdc);
+    hash
+}
@@ -0,0 +1,282 @@
+//! Trading Rules
+//!
+//! The plain decisions behind privacy-trading's instructions: batch status
+//! steps, risk limits and budget days, computation offsets, claim voucher
+//! digests, the agent dead-man switch and oracle padding. They have
+//! no Anchor or Arcium dependency, so they build and are tested natively;
+//! the program keeps its account types and calls into these. [`circuit`]
+//! holds the arithmetic shared with the encrypted instructions.
+
+use sha3::{Digest, Keccak256};
+
+pub mod circuit;
+
+// ============================================
+// Batch Lifecycle
+// ============================================
+
+/// `BatchStatus` indices, in variant order
+pub const STATUS_OPEN: usize = 0;
+pub const STATUS_CLOSED: usize = 1;
+pub const STATUS_EXECUTED: usize = 2;
+pub const STATUS_VERIFIED: usize = 3;
+pub const STATUS_COUNT: usize = 4;
+
+/// Whether a batch may move from status `from` to `to`
+///
+/// Open -> Closed -> Executed -> Verified, one step at a time.
+pub fn can_transition(from: usize, to: usize) -> bool {
+    to < STATUS_COUNT && from + 1 == to
+}
+
+/// Stamp `slot` as the slot status `to` was entered, if the batch may move
+/// there from `from`; returns whether it may
+pub fn enter_status(
+    status_updated_at: &mut [u64; STATUS_COUNT],
+    from: usize,
+    to: usize,
+    slot: u64,
+) -> bool {
+    if !can_transition(from, to) {
+        return false;
+    }
+    status_updated_at[to] = slot;
+    true
+}
+
+// ============================================
+// Risk Limits
+// ============================================
+
+/// Slots per risk-limit day (~24h at 400ms slots)
+pub const RISK_DAY_SLOTS: u64 = 216_000;
+/// Delay before loosened risk limits take effect, so whoever steals a
+/// wallet key cannot lift its limits and trade at once
+pub const RISK_LIMIT_DELAY_SLOTS: u64 = RISK_DAY_SLOTS;
+/// `budget_day` of a profile that has not spent yet; no day matches it
+pub const NO_BUDGET_DAY: u64 = u64::MAX;
+
+/// A `RiskLimits` as the rules read it; zero means no cap
+#[derive(Clone, Copy, Debug)]
+pub struct Limits<'a> {
+    pub max_order_size: u64,
+    pub daily_notional_cap: u64,
+    /// Empty allows all markets
+    pub allowed_markets: &'a [String],
+}
+
+impl Limits<'_> {
+    /// Whether any amount cap is set, which only the limited circuits check
+    pub fn caps_amounts(&self) -> bool {
+        self.max_order_size != 0 || self.daily_notional_cap != 0
+    }
+
+    pub fn allows(&self, market_id: &str) -> bool {
+        self.allowed_markets.is_empty() || self.allowed_markets.iter().any(|m| m == market_id)
+    }
+
+    /// Order and daily caps as the limited circuits take them
+    pub fn circuit_caps(&self) -> (u64, u64) {
+        let cap = |limit: u64| if limit == 0 { u64::MAX } else { limit };
+        (cap(self.max_order_size), cap(self.daily_notional_cap))
+    }
+
+    /// Whether these limits are no looser than `current` in any respect
+    pub fn within(&self, current: &Limits) -> bool {
+        let (max_order, daily_cap) = self.circuit_caps();
+        let (current_max_order, current_daily_cap) = current.circuit_caps();
+        let markets_within = if current.allowed_markets.is_empty() {
+            true
+        } else {
+            !self.allowed_markets.is_empty()
+                && self
+                    .allowed_markets
+                    .iter()
+                    .all(|market| current.allows(market))
+        };
+        max_order <= current_max_order && daily_cap <= current_daily_cap && markets_within
+    }
+}
+
+/// Risk day `slot` falls in
+pub fn budget_day(slot: u64) -> u64 {
+    slot / RISK_DAY_SLOTS
+}
+
+/// Whether an order at `slot` starts a fresh daily budget instead of
+/// spending what is left of `last_day`'s
+pub fn starts_new_day(last_day: u64, slot: u64) -> bool {
+    budget_day(slot) != last_day
+}
+
+/// Slot limits requested at `slot` take effect at: at once when they are
+/// within the current ones, `RISK_LIMIT_DELAY_SLOTS` later otherwise
+pub fn limits_effective_slot(within_current: bool, slot: u64) -> u64 {
+    if within_current {
+        slot
+    } else {
+        slot.saturating_add(RISK_LIMIT_DELAY_SLOTS)
+    }
+}
+
+/// Whether limits scheduled for `effective_slot` may be applied at `slot`
+pub fn limits_due(effective_slot: u64, slot: u64) -> bool {
+    slot >= effective_slot
+}
+
+// ============================================
+// Computation Offsets
+// ============================================
+
+/// First 8 bytes (LE) of
+/// `keccak256("computation_offset" || subject || sequence || slot)`
+pub fn computation_offset(subject: &[u8; 32], sequence: u64, slot: u64) -> u64 {
+    let hash = Keccak256::new()
+        .chain_update(b"computation_offset")
+        .chain_update(subject)
+        .chain_update(sequence.to_le_bytes())
+        .chain_update(slot.to_le_bytes())
+        .finalize();
+    u64::from_le_bytes(hash[..8].try_into().unwrap())
+}
+
+// ============================================
+// Claim Vouchers
+// ============================================
+
+/// Message a claimant signs to let a relayer claim a leaf for them:
+/// `keccak256("claim_voucher" || distributor || index || amount || fee || fee_account)`
+///
+/// `fee_account` is the token account paid `fee`, so whoever submits the
+/// voucher cannot send the fee elsewhere.
+pub fn claim_voucher_digest(
+    distributor: &[u8; 32],
+    index: u32,
+    amount: u64,
+    fee: u64,
+    fee_account: &[u8; 32],
+) -> [u8; 32] {
+    Keccak256::new()
+        .chain_update(b"claim_voucher")
+        .chain_update(distributor)
+        .chain_update(index.to_le_bytes())
+        .chain_update(amount.to_le_bytes())
+        .chain_update(fee.to_le_bytes())
+        .chain_update(fee_account)
+        .finalize()
+        .into()
+}
+
+// ============================================
+// Agent Dead-Man Switch
+// ============================================
+
+/// Why an `AgentPolicy` refuses a step
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AgentPolicyError {
+    Tripped,
+    NotTripped,
+    StillAlive,
+    ComputationPending,
+    BondsOutstanding,
+}
+
+/// Whether an agent last heard from at `last_heartbeat` has missed its
+/// heartbeat by `slot`
+///
+/// The agent has all of `timeout_slots`; the switch may trip from the
+/// slot after.
+pub fn heartbeat_missed(last_heartbeat: u64, timeout_slots: u64, slot: u64) -> bool {
+    slot > last_heartbeat.saturating_add(timeout_slots)
+}
+
+/// The switch has not tripped, so the agent may still heartbeat
+pub fn check_armed(tripped_at: Option<u64>) -> Result<(), AgentPolicyError> {
+    match tripped_at {
+        None => Ok(()),
+        Some(_) => Err(AgentPolicyError::Tripped),
+    }
+}
+
+/// The switch has tripped, so its RFQs, sessions and positions are fair game
+pub fn check_tripped(tripped_at: Option<u64>) -> Result<(), AgentPolicyError> {
+    match tripped_at {
+        Some(_) => Ok(()),
+        None => Err(AgentPolicyError::NotTripped),
+    }
+}
+
+/// The switch may trip at `slot`: it is armed and the agent missed its
+/// heartbeat
+pub fn check_trip(
+    tripped_at: Option<u64>,
+    last_heartbeat: u64,
+    timeout_slots: u64,
+    slot: u64,
+) -> Result<(), AgentPolicyError> {
+    check_armed(tripped_at)?;
+    if !heartbeat_missed(last_heartbeat, timeout_slots, slot) {
+        return Err(AgentPolicyError::StillAlive);
+    }
+    Ok(())
+}
+
+/// A tripped agent's RFQ may be closed once no computation is in flight
+/// and no maker bond is still held
+pub fn check_rfq_cancel(
+    tripped_at: Option<u64>,
+    pending_computation: bool,
+    outstanding_bonds: u64,
+) -> Result<(), AgentPolicyError> {
+    check_tripped(tripped_at)?;
+    if pending_computation {
+        return Err(AgentPolicyError::ComputationPending);
+    }
+    if outstanding_bonds != 0 {
+        return Err(AgentPolicyError::BondsOutstanding);
+    }
+    Ok(())
+}
+
+/// Outcome tokens recovery can sweep: what the agent delegated, capped by
+/// its balance, or `None` when that is nothing
+pub fn recoverable_amount(delegated_amount: u64, balance: u64) -> Option<u64> {
+    Some(delegated_amount.min(balance)).filter(|&amount| amount > 0)
+}
+
+/// Key that may close a policy: the agent while armed, the recovery key
+/// once it has tripped
+pub fn policy_closer<K>(tripped_at: Option<u64>, agent: K, recovery: K) -> K {
+    if tripped_at.is_some() {
+        recovery
+    } else {
+        agent
+    }
+}
+
+// ============================================
+// Confidential Oracle
+// ============================================
+
+/// Max data providers per confidential oracle (fixed by the `resolve_oracle`
+/// sorting network)
+pub const MAX_ORACLE_PROVIDERS: usize = 5;
+
+/// Slots `resolve_oracle` pads with `u64::MAX` rather than zero
+///
+/// Missing observations are padded alternately low and high (low first),
+/// so the middle of the five sorted slots is the median of the submitted
+/// values, the lower one for an even count.
+pub fn median_pad_high_mask(submitted_mask: u8) -> u8 {
+    let mut high_mask = 0;
+    let mut missing = 0;
+    for slot in 0..MAX_ORACLE_PROVIDERS {
+        if submitted_mask & (1 << slot) == 0 {
+            if missing % 2 == 1 {
+                high_mask |= 1 << slot;
+            }
+            missing += 1;
+        }
+    }
+    high_mask
+}
@@ -0,0 +1,81 @@
+use trading_rules::AgentPolicyError::*;
+use trading_rules::*;
+
+const LAST_HEARTBEAT: u64 = 1_000;
+const TIMEOUT: u64 = 150;
+
+#[test]
+fn heartbeat_is_missed_only_after_the_full_timeout() {
+    assert!(!heartbeat_missed(LAST_HEARTBEAT, TIMEOUT, LAST_HEARTBEAT));
+    assert!(!heartbeat_missed(
+        LAST_HEARTBEAT,
+        TIMEOUT,
+        LAST_HEARTBEAT + TIMEOUT
+    ));
+    assert!(heartbeat_missed(
+        LAST_HEARTBEAT,
+        TIMEOUT,
+        LAST_HEARTBEAT + TIMEOUT + 1
+    ));
+}
+
+#[test]
+fn huge_timeouts_never_trip() {
+    assert!(!heartbeat_missed(LAST_HEARTBEAT, u64::MAX, u64::MAX));
+}
+
+#[test]
+fn trips_once_after_a_missed_heartbeat() {
+    let deadline = LAST_HEARTBEAT + TIMEOUT;
+    assert_eq!(
+        check_trip(None, LAST_HEARTBEAT, TIMEOUT, deadline),
+        Err(StillAlive)
+    );
+    assert_eq!(
+        check_trip(None, LAST_HEARTBEAT, TIMEOUT, deadline + 1),
+        Ok(())
+    );
+    assert_eq!(
+        check_trip(Some(deadline + 1), LAST_HEARTBEAT, TIMEOUT, deadline + 2),
+        Err(Tripped)
+    );
+}
+
+#[test]
+fn heartbeats_stop_once_tripped() {
+    assert_eq!(check_armed(None), Ok(()));
+    assert_eq!(check_armed(Some(1_151)), Err(Tripped));
+}
+
+#[test]
+fn recovery_needs_a_tripped_switch() {
+    assert_eq!(check_tripped(None), Err(NotTripped));
+    assert_eq!(check_tripped(Some(1_151)), Ok(()));
+}
+
+#[test]
+fn recovers_the_delegation_capped_by_the_balance() {
+    assert_eq!(recoverable_amount(500, 300), Some(300));
+    assert_eq!(recoverable_amount(200, 300), Some(200));
+    assert_eq!(recoverable_amount(0, 300), None);
+    assert_eq!(recoverable_amount(500, 0), None);
+}
+
+#[test]
+fn rfqs_of_armed_agents_stay_open() {
+    assert_eq!(check_rfq_cancel(None, false, 0), Err(NotTripped));
+}
+
+#[test]
+fn rfqs_close_once_settled_and_unbonded() {
+    let tripped = Some(1_151);
+    assert_eq!(check_rfq_cancel(tripped, true, 0), Err(ComputationPending));
+    assert_eq!(check_rfq_cancel(tripped, false, 2), Err(BondsOutstanding));
+    assert_eq!(check_rfq_cancel(tripped, false, 0), Ok(()));
+}
+
+#[test]
+fn closer_moves_to_recovery_on_trip() {
+    assert_eq!(policy_closer(None, "agent", "recovery"), "agent");
+    assert_eq!(policy_closer(Some(1_151), "agent", "recovery"), "recovery");
+}
@@ -0,0 +1,85 @@
+//! `trading_rules::circuit` must stay a copy of the `encrypted-ixs` helpers
+
+const CIRCUITS: &str = include_str!("../../encrypted-ixs/src/lib.rs");
+const SHARED: &str = include_str!("../src/circuit.rs");
+
+/// Circuit helpers over encrypted structs, which only the circuits have
+const CIRCUIT_ONLY: [&str; 3] = ["open_ledger", "apply_order", "fold_order"];
+
+/// Every `fn` in `source` with its body from `fn` on, comments and
+/// whitespace stripped so visibility and layout do not count as drift
+fn helper_fns(source: &str) -> Vec<(String, String)> {
+    let code: String = source
+        .lines()
+        .filter(|line| !line.trim_start().starts_with("//"))
+        .map(|line| format!("{line}\n"))
+        .collect();
+
+    let mut fns = Vec::new();
+    let mut rest = code.as_str();
+    while let Some(at) = rest.find("fn ") {
+        let item = &rest[at..];
+        let name: String = item[3..]
+            .chars()
+            .take_while(|c| c.is_alphanumeric() || *c == '_')
+            .collect();
+        let open = item.find('{').expect("fn without a body");
+        let mut depth = 0;
+        let mut close = open;
+        for (i, c) in item[open..].char_indices() {
+            match c {
+                '{' => depth += 1,
+                '}' => depth -= 1,
+                _ => {}
+            }
+            if depth == 0 {
+                close = open + i;
+                break;
+            }
+        }
+        let body: String = item[..=close]
+            .chars()
+            .filter(|c| !c.is_whitespace())
+            .collect();
+        // rustfmt's trailing commas are layout too
+        let body = body.replace(",)", ")").replace(",}", "}");
+        fns.push((name, body));
+        rest = &item[close..];
+    }
+    fns
+}
+
+/// The helpers below the circuits' `Helper Functions` banner
+fn circuit_helpers() -> Vec<(String, String)> {
+    let start = CIRCUITS
+        .find("// Helper Functions")
+        .expect("encrypted-ixs has no helper section");
+    helper_fns(&CIRCUITS[start..])
+}
+
+#[test]
+fn shared_helpers_match_the_circuits() {
+    let circuits = circuit_helpers();
+    let shared = helper_fns(SHARED);
+    assert!(!shared.is_empty());
+
+    for (name, body) in &shared {
+        let circuit = circuits
+            .iter()
+            .find(|(circuit_name, _)| circuit_name == name)
+            .unwrap_or_else(|| panic!("`{name}` is not a circuit helper"));
+        assert_eq!(&circuit.1, body, "`{name}` differs from the circuit's");
+    }
+}
+
+#[test]
+fn every_plain_circuit_helper_is_shared() {
+    let shared = helper_fns(SHARED);
+    for (name, _) in circuit_helpers() {
+        assert!(
+            CIRCUIT_ONLY.contains(&name.as_str())
+                || shared.iter().any(|(shared_name, _)| *shared_name == name),
+            "circuit helper `{name}` is missing from trading_rules::circuit"
+        );
+    }
+}
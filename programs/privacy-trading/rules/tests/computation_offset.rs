@@ -0,0 +1,27 @@
+use trading_rules::computation_offset;
+
+#[test]
+fn matches_solana_keccak() {
+    // Pinned from `solana_program::keccak::hashv` over the same parts; the
+    // backend's `deriveComputationOffset` hashes the same layout
+    assert_eq!(
+        computation_offset(&[7; 32], 3, 250_000_000),
+        1_200_929_552_271_174_079
+    );
+}
+
+#[test]
+fn every_input_changes_the_offset() {
+    let offset = computation_offset(&[7; 32], 3, 250_000_000);
+    assert_ne!(computation_offset(&[8; 32], 3, 250_000_000), offset);
+    assert_ne!(computation_offset(&[7; 32], 4, 250_000_000), offset);
+    assert_ne!(computation_offset(&[7; 32], 3, 250_000_001), offset);
+}
+
+#[test]
+fn sequence_and_slot_are_not_interchangeable() {
+    assert_ne!(
+        computation_offset(&[7; 32], 1, 2),
+        computation_offset(&[7; 32], 2, 1)
+    );
+}
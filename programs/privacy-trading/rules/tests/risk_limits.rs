@@ -0,0 +1,92 @@
+use trading_rules::*;
+
+fn markets(ids: &[&str]) -> Vec<String> {
+    ids.iter().map(|id| id.to_string()).collect()
+}
+
+fn limits(max_order_size: u64, daily_notional_cap: u64, allowed_markets: &[String]) -> Limits<'_> {
+    Limits {
+        max_order_size,
+        daily_notional_cap,
+        allowed_markets,
+    }
+}
+
+#[test]
+fn zero_caps_do_not_cap_amounts() {
+    assert!(!limits(0, 0, &[]).caps_amounts());
+    assert!(limits(1, 0, &[]).caps_amounts());
+    assert!(limits(0, 1, &[]).caps_amounts());
+    assert_eq!(limits(0, 0, &[]).circuit_caps(), (u64::MAX, u64::MAX));
+    assert_eq!(limits(500, 0, &[]).circuit_caps(), (500, u64::MAX));
+}
+
+#[test]
+fn empty_allowlist_allows_every_market() {
+    assert!(limits(0, 0, &[]).allows("btc-1m"));
+
+    let allowed = markets(&["btc-1m", "eth-1m"]);
+    let limits = limits(0, 0, &allowed);
+    assert!(limits.allows("eth-1m"));
+    assert!(!limits.allows("sol-1m"));
+    assert!(!limits.allows("btc-1"));
+}
+
+#[test]
+fn tighter_limits_are_within_looser_ones() {
+    let one = markets(&["btc-1m"]);
+    let two = markets(&["btc-1m", "eth-1m"]);
+
+    assert!(limits(100, 1_000, &one).within(&limits(200, 1_000, &two)));
+    // Any cap is tighter than none, but lifting one is not
+    assert!(limits(100, 0, &[]).within(&limits(0, 0, &[])));
+    assert!(!limits(0, 0, &[]).within(&limits(100, 0, &[])));
+    assert!(!limits(100, 2_000, &one).within(&limits(100, 1_000, &one)));
+}
+
+#[test]
+fn widening_the_allowlist_is_not_within() {
+    let one = markets(&["btc-1m"]);
+    let two = markets(&["btc-1m", "eth-1m"]);
+
+    assert!(!limits(0, 0, &two).within(&limits(0, 0, &one)));
+    // Clearing it allows every market
+    assert!(!limits(0, 0, &[]).within(&limits(0, 0, &one)));
+    assert!(limits(0, 0, &one).within(&limits(0, 0, &[])));
+}
+
+#[test]
+fn budget_rolls_over_at_the_day_boundary() {
+    let last_slot = RISK_DAY_SLOTS * 5 - 1;
+    let day = budget_day(last_slot);
+    assert_eq!(day, 4);
+
+    assert!(!starts_new_day(day, last_slot));
+    assert!(!starts_new_day(day, RISK_DAY_SLOTS * 4));
+    assert!(starts_new_day(day, last_slot + 1));
+    // Days never come back around
+    assert!(starts_new_day(day, last_slot - RISK_DAY_SLOTS));
+}
+
+#[test]
+fn first_limited_order_starts_a_fresh_budget() {
+    assert!(starts_new_day(NO_BUDGET_DAY, 0));
+    assert!(starts_new_day(NO_BUDGET_DAY, u64::MAX - 1));
+}
+
+#[test]
+fn loosened_limits_wait_out_the_delay() {
+    let slot = 1_000;
+    assert_eq!(limits_effective_slot(true, slot), slot);
+
+    let effective = limits_effective_slot(false, slot);
+    assert_eq!(effective, slot + RISK_LIMIT_DELAY_SLOTS);
+    assert!(!limits_due(effective, effective - 1));
+    assert!(limits_due(effective, effective));
+    assert!(limits_due(effective, effective + 1));
+}
+
+#[test]
+fn delay_saturates_near_the_end_of_time() {
+    assert_eq!(limits_effective_slot(false, u64::MAX - 1), u64::MAX);
+}
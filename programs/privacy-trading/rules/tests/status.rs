@@ -0,0 +1,54 @@
+use trading_rules::*;
+
+const STATUSES: [usize; STATUS_COUNT] =
+    [STATUS_OPEN, STATUS_CLOSED, STATUS_EXECUTED, STATUS_VERIFIED];
+
+#[test]
+fn batches_move_one_step_forward() {
+    let allowed: Vec<_> = STATUSES
+        .iter()
+        .flat_map(|&from| STATUSES.iter().map(move |&to| (from, to)))
+        .filter(|&(from, to)| can_transition(from, to))
+        .collect();
+    assert_eq!(
+        allowed,
+        [
+            (STATUS_OPEN, STATUS_CLOSED),
+            (STATUS_CLOSED, STATUS_EXECUTED),
+            (STATUS_EXECUTED, STATUS_VERIFIED)
+        ]
+    );
+    assert!(!can_transition(STATUS_VERIFIED, STATUS_COUNT));
+}
+
+#[test]
+fn entering_a_status_stamps_its_slot() {
+    let mut stamps = [100, 0, 0, 0];
+    assert!(enter_status(&mut stamps, STATUS_OPEN, STATUS_CLOSED, 110));
+    assert!(enter_status(
+        &mut stamps,
+        STATUS_CLOSED,
+        STATUS_EXECUTED,
+        125
+    ));
+    assert_eq!(stamps, [100, 110, 125, 0]);
+}
+
+#[test]
+fn refused_steps_leave_the_stamps_alone() {
+    let mut stamps = [100, 110, 0, 0];
+    assert!(!enter_status(
+        &mut stamps,
+        STATUS_CLOSED,
+        STATUS_VERIFIED,
+        130
+    ));
+    assert!(!enter_status(&mut stamps, STATUS_CLOSED, STATUS_OPEN, 130));
+    assert!(!enter_status(
+        &mut stamps,
+        STATUS_CLOSED,
+        STATUS_CLOSED,
+        130
+    ));
+    assert_eq!(stamps, [100, 110, 0, 0]);
+}
@@ -1,4 +1,11 @@
 use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{
+    ed25519_program, keccak, sysvar::instructions as instructions_sysvar,
+};
+use anchor_spl::memo::{self, BuildMemo, Memo};
+use anchor_spl::token_interface::{
+    self, Burn, Mint, MintTo, TokenAccount, TokenInterface, TransferChecked,
+};
 use arcium_anchor::prelude::*;
 use arcium_client::idl::arcium::types::CallbackAccount;
 
@@ -7,6 +14,144 @@ const COMP_DEF_OFFSET_INIT_BATCH: u32 = comp_def_offset("init_batch");
 const COMP_DEF_OFFSET_ADD_ORDER: u32 = comp_def_offset("add_order");
 const COMP_DEF_OFFSET_EXECUTE_BATCH: u32 = comp_def_offset("execute_batch");
 const COMP_DEF_OFFSET_VERIFY_ALLOCATION: u32 = comp_def_offset("verify_allocation");
+const COMP_DEF_OFFSET_SIMULATE_EXECUTE_BATCH: u32 = comp_def_offset("simulate_execute_batch");
+const COMP_DEF_OFFSET_INIT_RFQ: u32 = comp_def_offset("init_rfq");
+const COMP_DEF_OFFSET_SUBMIT_QUOTE: u32 = comp_def_offset("submit_quote");
+const COMP_DEF_OFFSET_SETTLE_RFQ: u32 = comp_def_offset("settle_rfq");
+const COMP_DEF_OFFSET_INIT_SESSION: u32 = comp_def_offset("init_session");
+const COMP_DEF_OFFSET_ADD_SESSION_ORDER: u32 = comp_def_offset("add_session_order");
+const COMP_DEF_OFFSET_ADD_ORDERS_BULK: u32 = comp_def_offset("add_orders_bulk");
+const COMP_DEF_OFFSET_INIT_LANE: u32 = comp_def_offset("init_lane");
+const COMP_DEF_OFFSET_ADD_LANE_ORDER: u32 = comp_def_offset("add_lane_order");
+const COMP_DEF_OFFSET_MERGE_LANES: u32 = comp_def_offset("merge_lanes");
+const COMP_DEF_OFFSET_INIT_ORACLE: u32 = comp_def_offset("init_oracle");
+const COMP_DEF_OFFSET_SUBMIT_OBSERVATION: u32 = comp_def_offset("submit_observation");
+const COMP_DEF_OFFSET_RESOLVE_ORACLE: u32 = comp_def_offset("resolve_oracle");
+const COMP_DEF_OFFSET_INIT_FLOW_AUCTION: u32 = comp_def_offset("init_flow_auction");
+const COMP_DEF_OFFSET_SUBMIT_FLOW_BID: u32 = comp_def_offset("submit_flow_bid");
+const COMP_DEF_OFFSET_SETTLE_FLOW_AUCTION: u32 = comp_def_offset("settle_flow_auction");
+const COMP_DEF_OFFSET_INIT_PARLAY: u32 = comp_def_offset("init_parlay");
+const COMP_DEF_OFFSET_ADD_PARLAY_LEG: u32 = comp_def_offset("add_parlay_leg");
+const COMP_DEF_OFFSET_ADD_LIMITED_ORDER: u32 = comp_def_offset("add_limited_order");
+const COMP_DEF_OFFSET_ADD_LIMITED_SESSION_ORDER: u32 =
+    comp_def_offset("add_limited_session_order");
+const COMP_DEF_OFFSET_ADD_HOUSE_ORDER: u32 = comp_def_offset("add_house_order");
+const COMP_DEF_OFFSET_SETTLE_HOUSE_BATCH: u32 = comp_def_offset("settle_house_batch");
+const COMP_DEF_OFFSET_REVEAL_HOUSE_LEDGER: u32 = comp_def_offset("reveal_house_ledger");
+
+// MPC account reads take an encrypted field by a fixed byte offset, so every
+// account the circuits read keeps its fixed-size fields ahead of the
+// ciphertexts and everything variable-sized after them. The offsets below
+// count those leading fields and must change with the struct.
+
+/// `TradingBatch::encrypted_state` offset: discriminator + bump + authority + state_nonce
+const BATCH_ENCRYPTED_STATE_OFFSET: u32 = 8 + 1 + 32 + 16;
+const BATCH_ENCRYPTED_STATE_SIZE: u32 = 32 * 8;
+
+/// Max whitelisted makers per RFQ (bounded by `Rfq::quoted_mask`)
+pub const MAX_RFQ_MAKERS: usize = 8;
+/// `Rfq::encrypted_state` offset: discriminator + bump + taker + state_nonce
+const RFQ_ENCRYPTED_STATE_OFFSET: u32 = 8 + 1 + 32 + 16;
+const RFQ_ENCRYPTED_STATE_SIZE: u32 = 32 * 5;
+
+/// `Session::encrypted_budget` offset: discriminator + bump + owner + session_key + budget_nonce
+const SESSION_ENCRYPTED_BUDGET_OFFSET: u32 = 8 + 1 + 32 + 32 + 16;
+const SESSION_ENCRYPTED_BUDGET_SIZE: u32 = 32;
+
+/// Staged orders one `add_orders_bulk` computation folds (fixed by the circuit)
+pub const BULK_ORDER_CAPACITY: u8 = 8;
+
+/// `RiskProfile::encrypted_budget` offset: discriminator + bump + owner + budget_nonce
+const RISK_ENCRYPTED_BUDGET_OFFSET: u32 = 8 + 1 + 32 + 16;
+const RISK_ENCRYPTED_BUDGET_SIZE: u32 = 32;
+/// Max markets in a `RiskProfile` allowlist
+pub const MAX_RISK_MARKETS: usize = 8;
+pub use trading_rules::{RISK_DAY_SLOTS, RISK_LIMIT_DELAY_SLOTS};
+
+/// `HouseAccount::encrypted_ledger` offset: discriminator + bump + authority + desk + ledger_nonce
+const HOUSE_ENCRYPTED_LEDGER_OFFSET: u32 = 8 + 1 + 32 + 32 + 16;
+const HOUSE_ENCRYPTED_LEDGER_SIZE: u32 = 32 * 3;
+
+/// Lanes per laned batch (fixed by the `merge_lanes` circuit)
+pub const ORDER_LANES: u8 = 4;
+/// Orders per lane, so laned batches keep the 32-order cap
+pub const LANE_CAPACITY: u8 = 32 / ORDER_LANES;
+/// `OrderLane::encrypted_state` offset: discriminator + bump + batch + lane_index + state_nonce
+const LANE_ENCRYPTED_STATE_OFFSET: u32 = 8 + 1 + 32 + 1 + 16;
+const LANE_ENCRYPTED_STATE_SIZE: u32 = 32 * 8;
+
+pub use trading_rules::MAX_ORACLE_PROVIDERS;
+/// `ConfidentialOracle::encrypted_state` offset: discriminator + bump + creator + state_nonce
+const ORACLE_ENCRYPTED_STATE_OFFSET: u32 = 8 + 1 + 32 + 16;
+const ORACLE_ENCRYPTED_STATE_SIZE: u32 = 32 * MAX_ORACLE_PROVIDERS as u32;
+
+/// Max whitelisted makers per flow auction (bounded by `FlowAuction::bid_mask`)
+pub const MAX_FLOW_AUCTION_MAKERS: usize = 8;
+/// `FlowAuction::encrypted_state` offset: discriminator + bump + batch + state_nonce
+const FLOW_AUCTION_ENCRYPTED_STATE_OFFSET: u32 = 8 + 1 + 32 + 16;
+const FLOW_AUCTION_ENCRYPTED_STATE_SIZE: u32 = 32 * 3;
+
+/// Max batches one parlay spans (fixed by the `ParlayWeights` circuit input)
+pub const MAX_PARLAY_LEGS: usize = 4;
+/// `Parlay::encrypted_state` offset: discriminator + bump + user + state_nonce
+const PARLAY_ENCRYPTED_STATE_OFFSET: u32 = 8 + 1 + 32 + 16;
+const PARLAY_ENCRYPTED_STATE_SIZE: u32 = 32 * 7;
+
+/// Public inputs of the `circuits/allocation` proof: merkle_root_lo,
+/// merkle_root_hi, total_usdc, total_shares, execution_price, allocation_root
+pub const ALLOCATION_PUBLIC_INPUTS: usize = 6;
+const ALLOCATION_INPUT_ROOT_LO: usize = 0;
+const ALLOCATION_INPUT_ROOT_HI: usize = 1;
+const ALLOCATION_INPUT_TOTAL_USDC: usize = 2;
+const ALLOCATION_INPUT_ALLOCATION_ROOT: usize = 5;
+
+/// Max leaves per distribution (claim bitmap stays under the 10KB CPI init limit)
+pub const MAX_DISTRIBUTION_CLAIMS: u32 = 65_536;
+/// Max bytes of a reconciliation memo attached to a vault transfer
+pub const MAX_TRANSFER_MEMO_LEN: usize = 128;
+
+/// zk-verifier program, owner of `UniquenessAttestation` records
+pub const ZK_VERIFIER_PROGRAM_ID: Pubkey =
+    anchor_lang::solana_program::pubkey!("6n4EVsXYbKTz9aKcccCrsNVrnPrCNEHqMqan3G9AnDYN");
+/// zk-verifier `ProofSystem::TeeAttestation`, which uniqueness and
+/// eligibility records carry
+const ZK_PROOF_SYSTEM_TEE_ATTESTATION: u8 = 1;
+
+/// Solana Attestation Service program, owner of KYC attestations
+pub const SAS_PROGRAM_ID: Pubkey =
+    anchor_lang::solana_program::pubkey!("22zoJMtdu4tQc2PzL74ZUT7FrwgB1Udec8DdW4yw4BdG");
+/// SAS account discriminator for `Attestation`
+const SAS_ATTESTATION_DISCRIMINATOR: u8 = 2;
+
+
+/// Slots a batch computation may stay queued before its authority can abandon it
+pub const COMPUTATION_TIMEOUT_SLOTS: u64 = 3_000;
+
+/// Max swap fee of a fallback AMM pool, in bps
+pub const MAX_AMM_FEE_BPS: u16 = 1_000;
+
+/// Max bytes of venue-specific config stored with an execution adapter
+pub const MAX_ADAPTER_CONFIG_LEN: usize = 128;
+
+/// Slots after execution before a batch's fill may go on the bulletin;
+/// published execution slots are rounded down to this granularity too
+pub const FILL_BULLETIN_DELAY_SLOTS: u64 = 1_500;
+
+/// Min slots between two publications on one market's bulletin
+pub const FILL_BULLETIN_INTERVAL_SLOTS: u64 = 150;
+
+/// Fills a bulletin keeps, oldest overwritten first
+pub const FILL_TAPE_CAPACITY: usize = 64;
+
+/// Collateral base units per whole USDC, for fill size buckets
+const USDC_UNIT: u64 = 1_000_000;
+
+/// Shortest heartbeat timeout an agent policy accepts (~1 minute), so
+/// ordinary congestion does not trip the switch
+pub const MIN_AGENT_HEARTBEAT_TIMEOUT_SLOTS: u64 = 150;
+
+/// Retired x25519 keys a `UserEncryptionKey` remembers, oldest dropped first
+pub const MAX_RETIRED_ENCRYPTION_KEYS: usize = 8;
 
 declare_id!("3vfatmfrqUfPFRFKP9xTUWKYNYRL7X1wqg2Dz2z4zMQL");
 
@@ -26,6 +171,58 @@ pub enum BatchStatus {
     Verified,
 }
 
+impl BatchStatus {
+    /// Whether the batch lifecycle allows moving from `self` to `next`
+    ///
+    /// Open -> Closed -> Executed -> Verified, one step at a time.
+    pub fn can_transition_to(self, next: BatchStatus) -> bool {
+        trading_rules::can_transition(self as usize, next as usize)
+    }
+}
+
+/// Encrypted instruction a batch computation was queued for
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub enum ComputationKind {
+    InitBatch,
+    AddOrder,
+    ExecuteBatch,
+    AddOrdersBulk,
+    SimulateExecuteBatch,
+    AddLaneOrder,
+}
+
+/// Why a folded order was rejected by its circuit
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub enum OrderRejection {
+    /// Encrypted wallet did not match the signer
+    WalletMismatch,
+    /// Amount would have overflowed the batch (or lane) total
+    AmountOverflow,
+    /// Encrypted expiry slot was before the slot the order was queued at
+    Expired,
+}
+
+/// RFQ status
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub enum RfqStatus {
+    Open,
+    Settled,
+}
+
+/// Order flow auction status
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub enum FlowAuctionStatus {
+    Open,
+    Settled,
+}
+
+/// Confidential oracle status
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub enum OracleStatus {
+    Open,
+    Resolved,
+}
+
 #[arcium_program]
 pub mod privacy_trading {
     use super::*;
@@ -34,6 +231,13 @@ pub mod privacy_trading {
     // Computation Definition Initialization
     // ============================================
 
+    /// Create the Arcium signer PDA once, so queue instructions don't
+    /// carry `init_if_needed` (and its rent checks) on every call
+    pub fn init_sign_pda(ctx: Context<InitSignPda>) -> Result<()> {
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+        Ok(())
+    }
+
     pub fn init_batch_comp_def(ctx: Context<InitBatchCompDef>) -> Result<()> {
         init_comp_def(ctx.accounts, None, None)?;
         Ok(())
@@ -54,6 +258,137 @@ pub mod privacy_trading {
         Ok(())
     }
 
+    pub fn init_simulate_execute_batch_comp_def(
+        ctx: Context<InitSimulateExecuteBatchCompDef>,
+    ) -> Result<()> {
+        init_comp_def(ctx.accounts, None, None)?;
+        Ok(())
+    }
+
+    pub fn init_rfq_comp_def(ctx: Context<InitRfqCompDef>) -> Result<()> {
+        init_comp_def(ctx.accounts, None, None)?;
+        Ok(())
+    }
+
+    pub fn init_submit_quote_comp_def(ctx: Context<InitSubmitQuoteCompDef>) -> Result<()> {
+        init_comp_def(ctx.accounts, None, None)?;
+        Ok(())
+    }
+
+    pub fn init_settle_rfq_comp_def(ctx: Context<InitSettleRfqCompDef>) -> Result<()> {
+        init_comp_def(ctx.accounts, None, None)?;
+        Ok(())
+    }
+
+    pub fn init_session_comp_def(ctx: Context<InitSessionCompDef>) -> Result<()> {
+        init_comp_def(ctx.accounts, None, None)?;
+        Ok(())
+    }
+
+    pub fn init_add_session_order_comp_def(
+        ctx: Context<InitAddSessionOrderCompDef>,
+    ) -> Result<()> {
+        init_comp_def(ctx.accounts, None, None)?;
+        Ok(())
+    }
+
+    pub fn init_add_orders_bulk_comp_def(ctx: Context<InitAddOrdersBulkCompDef>) -> Result<()> {
+        init_comp_def(ctx.accounts, None, None)?;
+        Ok(())
+    }
+
+    pub fn init_lane_comp_def(ctx: Context<InitLaneCompDef>) -> Result<()> {
+        init_comp_def(ctx.accounts, None, None)?;
+        Ok(())
+    }
+
+    pub fn init_add_lane_order_comp_def(ctx: Context<InitAddLaneOrderCompDef>) -> Result<()> {
+        init_comp_def(ctx.accounts, None, None)?;
+        Ok(())
+    }
+
+    pub fn init_merge_lanes_comp_def(ctx: Context<InitMergeLanesCompDef>) -> Result<()> {
+        init_comp_def(ctx.accounts, None, None)?;
+        Ok(())
+    }
+
+    pub fn init_oracle_comp_def(ctx: Context<InitOracleCompDef>) -> Result<()> {
+        init_comp_def(ctx.accounts, None, None)?;
+        Ok(())
+    }
+
+    pub fn init_submit_observation_comp_def(
+        ctx: Context<InitSubmitObservationCompDef>,
+    ) -> Result<()> {
+        init_comp_def(ctx.accounts, None, None)?;
+        Ok(())
+    }
+
+    pub fn init_resolve_oracle_comp_def(ctx: Context<InitResolveOracleCompDef>) -> Result<()> {
+        init_comp_def(ctx.accounts, None, None)?;
+        Ok(())
+    }
+
+    pub fn init_flow_auction_comp_def(ctx: Context<InitFlowAuctionCompDef>) -> Result<()> {
+        init_comp_def(ctx.accounts, None, None)?;
+        Ok(())
+    }
+
+    pub fn init_submit_flow_bid_comp_def(ctx: Context<InitSubmitFlowBidCompDef>) -> Result<()> {
+        init_comp_def(ctx.accounts, None, None)?;
+        Ok(())
+    }
+
+    pub fn init_settle_flow_auction_comp_def(
+        ctx: Context<InitSettleFlowAuctionCompDef>,
+    ) -> Result<()> {
+        init_comp_def(ctx.accounts, None, None)?;
+        Ok(())
+    }
+
+    pub fn init_parlay_comp_def(ctx: Context<InitParlayCompDef>) -> Result<()> {
+        init_comp_def(ctx.accounts, None, None)?;
+        Ok(())
+    }
+
+    pub fn init_add_parlay_leg_comp_def(ctx: Context<InitAddParlayLegCompDef>) -> Result<()> {
+        init_comp_def(ctx.accounts, None, None)?;
+        Ok(())
+    }
+
+    pub fn init_add_limited_order_comp_def(
+        ctx: Context<InitAddLimitedOrderCompDef>,
+    ) -> Result<()> {
+        init_comp_def(ctx.accounts, None, None)?;
+        Ok(())
+    }
+
+    pub fn init_add_limited_session_order_comp_def(
+        ctx: Context<InitAddLimitedSessionOrderCompDef>,
+    ) -> Result<()> {
+        init_comp_def(ctx.accounts, None, None)?;
+        Ok(())
+    }
+
+    pub fn init_add_house_order_comp_def(ctx: Context<InitAddHouseOrderCompDef>) -> Result<()> {
+        init_comp_def(ctx.accounts, None, None)?;
+        Ok(())
+    }
+
+    pub fn init_settle_house_batch_comp_def(
+        ctx: Context<InitSettleHouseBatchCompDef>,
+    ) -> Result<()> {
+        init_comp_def(ctx.accounts, None, None)?;
+        Ok(())
+    }
+
+    pub fn init_reveal_house_ledger_comp_def(
+        ctx: Context<InitRevealHouseLedgerCompDef>,
+    ) -> Result<()> {
+        init_comp_def(ctx.accounts, None, None)?;
+        Ok(())
+    }
+
     // ============================================
     // Trading Operations
     // ============================================
@@ -65,20 +400,41 @@ pub mod privacy_trading {
         market_id: String,
         side: Side,
         nonce: u128,
+        required_credential: Option<Pubkey>,
+        eligibility_predicate: Option<[u8; 32]>,
     ) -> Result<()> {
+        check_computation_offset(&ctx.accounts.computation_account)?;
+
         let batch = &mut ctx.accounts.batch;
         batch.bump = ctx.bumps.batch;
         batch.authority = ctx.accounts.authority.key();
         batch.market_id = market_id;
         batch.side = side;
         batch.status = BatchStatus::Open;
+        batch.status_updated_at = [0; 4];
+        batch.status_updated_at[BatchStatus::Open as usize] = Clock::get()?.slot;
         batch.order_count = 0;
         batch.total_usdc = 0;
         batch.state_nonce = nonce;
         batch.encrypted_state = [[0u8; 32]; 8];
         batch.merkle_root = [0u8; 32];
-
-        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+        batch.completed_at = 0;
+        queue_batch_computation(
+            batch,
+            ComputationKind::InitBatch,
+            ctx.accounts.computation_account.key(),
+            Clock::get()?.slot,
+        );
+        batch.required_credential = required_credential;
+        batch.eligibility_predicate = eligibility_predicate;
+        batch.staged_orders = 0;
+        batch.lane_count = 0;
+        batch.lanes_merged = false;
+        batch.flow_auction = None;
+        batch.reference_condition = None;
+        batch.reference_mid = 0;
+        batch.execution_price = 0;
+        batch.fill_published = false;
 
         // Initialize encrypted batch state via MPC
         let args = ArgBuilder::new()
@@ -110,6 +466,11 @@ pub mod privacy_trading {
         ctx: Context<InitBatchCallback>,
         output: SignedComputationOutputs<InitBatchOutput>,
     ) -> Result<()> {
+        check_callback_origin(
+            ctx.accounts.batch.queued_computation,
+            &ctx.accounts.computation_account,
+        )?;
+
         let o = match output.verify_output(
             &ctx.accounts.cluster_account,
             &ctx.accounts.computation_account,
@@ -122,32 +483,55 @@ pub mod privacy_trading {
         batch.encrypted_state = o.ciphertexts;
         batch.state_nonce = o.nonce;
 
+        complete_batch_computation(batch, ComputationKind::InitBatch)?;
+
         emit!(BatchCreated {
             batch: batch.key(),
             market_id: batch.market_id.clone(),
             side: batch.side,
             authority: batch.authority,
+            required_credential: batch.required_credential,
+            eligibility_predicate: batch.eligibility_predicate,
         });
 
         Ok(())
     }
 
     /// Add an encrypted order to the batch
+    ///
+    /// `encrypted_expiry_slot` is the last slot the order may be queued at.
+    /// The circuit compares it to the current slot and folds a stale order
+    /// as zero (`OrderRejection::Expired`), so a delayed relay cannot slip
+    /// it in. The order lane and session variants take the same input.
     pub fn add_order(
         ctx: Context<AddOrder>,
         computation_offset: u64,
         encrypted_amount: [u8; 32],
         encrypted_wallet_lo: [u8; 32],
         encrypted_wallet_hi: [u8; 32],
+        encrypted_expiry_slot: [u8; 32],
         user_pubkey: [u8; 32],
         nonce: u128,
         commitment_hash: [u8; 32],
     ) -> Result<()> {
+        check_computation_offset(&ctx.accounts.computation_account)?;
+
         let batch = &ctx.accounts.batch;
         require!(batch.status == BatchStatus::Open, ErrorCode::BatchNotOpen);
+        require!(batch.pending_computation.is_none(), ErrorCode::ComputationPending);
         require!(batch.order_count < 32, ErrorCode::BatchFull);
+        require!(batch.lane_count == 0, ErrorCode::BatchUsesLanes);
+        require!(batch.staged_orders == 0, ErrorCode::StagedOrdersPending);
 
-        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+        check_risk_profile(&ctx.accounts.risk_profile, &batch.market_id)?;
+        check_encryption_key(&ctx.accounts.encryption_key, &user_pubkey)?;
+
+        let eligibility_proof = check_order_gates(
+            batch,
+            ctx.accounts.attestation.as_ref(),
+            ctx.accounts.eligibility_proof.as_ref(),
+            &ctx.accounts.user.key(),
+        )?;
 
         // Store order commitment for ZK proof verification
         let order = &mut ctx.accounts.order;
@@ -157,9 +541,10 @@ pub mod privacy_trading {
         order.commitment_hash = commitment_hash;
         order.index = batch.order_count;
         order.allocated = false;
+        order.eligibility_proof = eligibility_proof;
+        order.rejected = false;
 
-        const ENCRYPTED_STATE_OFFSET: u32 = 8 + 1 + 32 + 64 + 1 + 1 + 1 + 8 + 16; // Account header offset
-        const ENCRYPTED_STATE_SIZE: u32 = 32 * 8;
+        let (signer_lo, signer_hi) = split_pubkey(&ctx.accounts.user.key());
 
         let args = ArgBuilder::new()
             .x25519_pubkey(user_pubkey)
@@ -167,14 +552,19 @@ pub mod privacy_trading {
             .encrypted_u64(encrypted_amount)
             .encrypted_u128(encrypted_wallet_lo)
             .encrypted_u128(encrypted_wallet_hi)
+            .encrypted_u64(encrypted_expiry_slot)
+            .plaintext_u128(signer_lo)
+            .plaintext_u128(signer_hi)
+            .plaintext_u64(Clock::get()?.slot)
             .plaintext_u128(batch.state_nonce)
             .account(
                 ctx.accounts.batch.key(),
-                ENCRYPTED_STATE_OFFSET,
-                ENCRYPTED_STATE_SIZE,
+                BATCH_ENCRYPTED_STATE_OFFSET,
+                BATCH_ENCRYPTED_STATE_SIZE,
             )
             .build();
 
+        let payer_lamports = ctx.accounts.user.lamports();
         queue_computation(
             ctx.accounts,
             computation_offset,
@@ -198,7 +588,20 @@ pub mod privacy_trading {
             0,
         )?;
 
-        Ok(())
+        queue_batch_computation(
+            &mut ctx.accounts.batch,
+            ComputationKind::AddOrder,
+            ctx.accounts.computation_account.key(),
+            Clock::get()?.slot,
+        );
+
+        let fee = payer_lamports.saturating_sub(ctx.accounts.user.lamports());
+        draw_compute_fee(ctx.accounts.compute_pool.as_mut(), &ctx.accounts.user, fee)?;
+        refund_order_rent(
+            ctx.accounts.rent_sponsor.as_mut(),
+            &ctx.accounts.user,
+            ctx.accounts.order.key(),
+        )
     }
 
     #[arcium_callback(encrypted_ix = "add_order")]
@@ -206,6 +609,11 @@ pub mod privacy_trading {
         ctx: Context<AddOrderCallback>,
         output: SignedComputationOutputs<AddOrderOutput>,
     ) -> Result<()> {
+        check_callback_origin(
+            ctx.accounts.batch.queued_computation,
+            &ctx.accounts.computation_account,
+        )?;
+
         let o = match output.verify_output(
             &ctx.accounts.cluster_account,
             &ctx.accounts.computation_account,
@@ -217,25 +625,65 @@ pub mod privacy_trading {
         let batch = &mut ctx.accounts.batch;
         batch.encrypted_state = o.ciphertexts;
         batch.state_nonce = o.nonce;
-        batch.order_count += 1;
-
-        emit!(OrderAdded {
-            batch: batch.key(),
-            order: ctx.accounts.order.key(),
-            order_index: batch.order_count - 1,
-            commitment_hash: ctx.accounts.order.commitment_hash,
-        });
+        batch.order_count = batch
+            .order_count
+            .checked_add(1)
+            .ok_or(ErrorCode::OrderCountOverflow)?;
+
+        complete_batch_computation(batch, ComputationKind::AddOrder)?;
+
+        emit_order_result(
+            batch.key(),
+            &mut ctx.accounts.order,
+            batch.order_count - 1,
+            o.wallet_bound,
+            o.amount_overflow,
+            o.expired,
+        );
 
         Ok(())
     }
 
     /// Close the batch and compute merkle root
+    ///
+    /// Passing the market's AMM pool (pool, condition and both reserves)
+    /// records the pool's mid for the batch side as the reference that
+    /// `record_price_improvement` later measures the execution price against.
     pub fn close_batch(ctx: Context<CloseBatch>) -> Result<()> {
+        let accounts = &ctx.accounts;
+        let reference = match (
+            accounts.amm_pool.as_ref(),
+            accounts.condition.as_ref(),
+            accounts.amm_yes_reserve.as_ref(),
+            accounts.amm_no_reserve.as_ref(),
+        ) {
+            (Some(pool), Some(condition), Some(yes_reserve), Some(no_reserve)) => {
+                require!(
+                    pool.condition == condition.key()
+                        && pool.yes_reserve == yes_reserve.key()
+                        && pool.no_reserve == no_reserve.key()
+                        && condition.market_id == accounts.batch.market_id,
+                    ErrorCode::AmmPoolMismatch
+                );
+                amm_side_price(yes_reserve.amount, no_reserve.amount, accounts.batch.side)
+                    .map(|mid| (condition.key(), mid))
+            }
+            (None, None, None, None) => None,
+            _ => return err!(ErrorCode::AmmPoolMismatch),
+        };
+
         let batch = &mut ctx.accounts.batch;
         require!(batch.status == BatchStatus::Open, ErrorCode::BatchNotOpen);
         require!(batch.order_count > 0, ErrorCode::EmptyBatch);
+        require!(batch.pending_computation.is_none(), ErrorCode::ComputationPending);
+        require!(batch.staged_orders == 0, ErrorCode::StagedOrdersPending);
 
-        batch.status = BatchStatus::Closed;
+        if let Some((condition, mid)) = reference {
+            batch.reference_condition = Some(condition);
+            batch.reference_mid = mid;
+        }
+
+        transition_status(batch, BatchStatus::Closed)?;
 
         emit!(BatchClosed {
             batch: batch.key(),
@@ -252,13 +700,31 @@ pub mod privacy_trading {
         total_shares: u64,
         execution_price: u64,
     ) -> Result<()> {
+        check_computation_offset(&ctx.accounts.computation_account)?;
+
         let batch = &ctx.accounts.batch;
         require!(batch.status == BatchStatus::Closed, ErrorCode::BatchNotClosed);
-
-        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
-
-        const ENCRYPTED_STATE_OFFSET: u32 = 8 + 1 + 32 + 64 + 1 + 1 + 1 + 8 + 16;
-        const ENCRYPTED_STATE_SIZE: u32 = 32 * 8;
+        require!(
+            batch.lane_count == 0 || batch.lanes_merged,
+            ErrorCode::LanesNotMerged
+        );
+
+        // A batch whose flow was auctioned executes at the auction's price
+        if batch.flow_auction.is_some() {
+            let auction = ctx
+                .accounts
+                .flow_auction
+                .as_ref()
+                .ok_or(ErrorCode::FlowAuctionRequired)?;
+            require!(
+                auction.status == FlowAuctionStatus::Settled,
+                ErrorCode::FlowAuctionNotSettled
+            );
+            require!(
+                execution_price == improved_price(auction.reference_price, auction.improvement_bps),
+                ErrorCode::ExecutionPriceMismatch
+            );
+        }
 
         let args = ArgBuilder::new()
             .plaintext_u64(total_shares)
@@ -266,8 +732,8 @@ pub mod privacy_trading {
             .plaintext_u128(batch.state_nonce)
             .account(
                 ctx.accounts.batch.key(),
-                ENCRYPTED_STATE_OFFSET,
-                ENCRYPTED_STATE_SIZE,
+                BATCH_ENCRYPTED_STATE_OFFSET,
+                BATCH_ENCRYPTED_STATE_SIZE,
             )
             .build();
 
@@ -288,6 +754,14 @@ pub mod privacy_trading {
             0,
         )?;
 
+        ctx.accounts.batch.execution_price = execution_price;
+        queue_batch_computation(
+            &mut ctx.accounts.batch,
+            ComputationKind::ExecuteBatch,
+            ctx.accounts.computation_account.key(),
+            Clock::get()?.slot,
+        );
+
         Ok(())
     }
 
@@ -296,6 +770,11 @@ pub mod privacy_trading {
         ctx: Context<ExecuteBatchCallback>,
         output: SignedComputationOutputs<ExecuteBatchOutput>,
     ) -> Result<()> {
+        check_callback_origin(
+            ctx.accounts.batch.queued_computation,
+            &ctx.accounts.computation_account,
+        )?;
+
         let (merkle_root, total_usdc) = match output.verify_output(
             &ctx.accounts.cluster_account,
             &ctx.accounts.computation_account,
@@ -312,7 +791,9 @@ pub mod privacy_trading {
         let batch = &mut ctx.accounts.batch;
         batch.merkle_root = merkle_root;
         batch.total_usdc = total_usdc;
-        batch.status = BatchStatus::Executed;
+        transition_status(batch, BatchStatus::Executed)?;
+
+        complete_batch_computation(batch, ComputationKind::ExecuteBatch)?;
 
         emit!(BatchExecuted {
             batch: batch.key(),
@@ -324,6 +805,113 @@ pub mod privacy_trading {
         Ok(())
     }
 
+    /// Preview batch execution via MPC without changing the orders or totals
+    ///
+    /// The preview (clearing price, fill ratio, total) is encrypted to
+    /// `authority_pubkey` and emitted as an event. The callback writes the
+    /// batch state back re-encrypted under a fresh nonce, and nothing else.
+    pub fn simulate_execute_batch(
+        ctx: Context<SimulateExecuteBatch>,
+        computation_offset: u64,
+        total_shares: u64,
+        execution_price: u64,
+        authority_pubkey: [u8; 32],
+        nonce: u128,
+    ) -> Result<()> {
+        check_computation_offset(&ctx.accounts.computation_account)?;
+
+        let batch = &ctx.accounts.batch;
+        require!(
+            batch.status == BatchStatus::Open || batch.status == BatchStatus::Closed,
+            ErrorCode::BatchAlreadyExecuted
+        );
+        require!(
+            batch.lane_count == 0 || batch.lanes_merged,
+            ErrorCode::LanesNotMerged
+        );
+        require!(batch.pending_computation.is_none(), ErrorCode::ComputationPending);
+
+        let args = ArgBuilder::new()
+            .x25519_pubkey(authority_pubkey)
+            .plaintext_u128(nonce)
+            .plaintext_u64(total_shares)
+            .plaintext_u64(execution_price)
+            .plaintext_u128(batch.state_nonce)
+            .account(
+                ctx.accounts.batch.key(),
+                BATCH_ENCRYPTED_STATE_OFFSET,
+                BATCH_ENCRYPTED_STATE_SIZE,
+            )
+            .build();
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![SimulateExecuteBatchCallback::callback_ix(
+                computation_offset,
+                &ctx.accounts.mxe_account,
+                &[CallbackAccount {
+                    pubkey: ctx.accounts.batch.key(),
+                    is_writable: true,
+                }],
+            )?],
+            1,
+            0,
+        )?;
+
+        queue_batch_computation(
+            &mut ctx.accounts.batch,
+            ComputationKind::SimulateExecuteBatch,
+            ctx.accounts.computation_account.key(),
+            Clock::get()?.slot,
+        );
+
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "simulate_execute_batch")]
+    pub fn simulate_execute_batch_callback(
+        ctx: Context<SimulateExecuteBatchCallback>,
+        output: SignedComputationOutputs<SimulateExecuteBatchOutput>,
+    ) -> Result<()> {
+        check_callback_origin(
+            ctx.accounts.batch.queued_computation,
+            &ctx.accounts.computation_account,
+        )?;
+
+        let (preview, ciphertexts, nonce) = match output.verify_output(
+            &ctx.accounts.cluster_account,
+            &ctx.accounts.computation_account,
+        ) {
+            Ok(SimulateExecuteBatchOutput {
+                field_0: SimulateExecuteBatchOutputStruct0 {
+                    field_0: preview,
+                    field_1: ciphertexts,
+                    field_2: nonce,
+                },
+            }) => (preview, ciphertexts, nonce),
+            Err(_) => return Err(ErrorCode::AbortedComputation.into()),
+        };
+
+        // Nothing else can have written the state since the preview was
+        // queued: a later computation would have replaced `queued_computation`
+        let batch = &mut ctx.accounts.batch;
+        batch.encrypted_state = ciphertexts;
+        batch.state_nonce = nonce;
+
+        complete_batch_computation(batch, ComputationKind::SimulateExecuteBatch)?;
+
+        emit!(ExecutionPreviewed {
+            batch: batch.key(),
+            encrypted_preview: preview.ciphertexts,
+            nonce: preview.nonce,
+        });
+
+        Ok(())
+    }
+
     /// Verify share allocation with ZK proof
     pub fn verify_allocation(
         ctx: Context<VerifyAllocation>,
@@ -334,339 +922,10009 @@ pub mod privacy_trading {
         require!(batch.status == BatchStatus::Executed, ErrorCode::BatchNotExecuted);
 
         // Verify the ZK proof
-        require!(public_inputs.len() >= 4, ErrorCode::InvalidProof);
-
-        let proof_merkle_root = public_inputs[1];
-        require!(proof_merkle_root == batch.merkle_root, ErrorCode::MerkleRootMismatch);
+        require!(
+            public_inputs.len() >= ALLOCATION_PUBLIC_INPUTS,
+            ErrorCode::MissingPublicInputs
+        );
+
+        // The root is split into two u128 field elements (little-endian
+        // halves of the stored bytes)
+        let mut root_lo = [0u8; 16];
+        let mut root_hi = [0u8; 16];
+        root_lo.copy_from_slice(&batch.merkle_root[..16]);
+        root_hi.copy_from_slice(&batch.merkle_root[16..]);
+        require!(
+            field_to_u128(&public_inputs[ALLOCATION_INPUT_ROOT_LO]) == Some(u128::from_le_bytes(root_lo))
+                && field_to_u128(&public_inputs[ALLOCATION_INPUT_ROOT_HI])
+                    == Some(u128::from_le_bytes(root_hi)),
+            ErrorCode::MerkleRootMismatch
+        );
+        require!(
+            field_to_u128(&public_inputs[ALLOCATION_INPUT_TOTAL_USDC]) == Some(batch.total_usdc as u128),
+            ErrorCode::AllocationTotalMismatch
+        );
 
         // In production, call the ZK verifier program via CPI
         // For now, validate proof structure
         require!(proof_data.len() >= 64, ErrorCode::InvalidProofData);
 
         let batch = &mut ctx.accounts.batch;
-        batch.status = BatchStatus::Verified;
+        transition_status(batch, BatchStatus::Verified)?;
 
         emit!(AllocationVerified {
             batch: batch.key(),
             merkle_root: batch.merkle_root,
+            allocation_root: public_inputs[ALLOCATION_INPUT_ALLOCATION_ROOT],
         });
 
         Ok(())
     }
-}
 
-// ============================================
-// Account Structures
-// ============================================
+    // ============================================
+    // RFQ Operations
+    // ============================================
 
-#[account]
-#[derive(InitSpace)]
-pub struct TradingBatch {
-    pub bump: u8,
-    pub authority: Pubkey,
-    #[max_len(64)]
-    pub market_id: String,
-    pub side: Side,
-    pub status: BatchStatus,
-    pub order_count: u8,
-    pub total_usdc: u64,
-    pub state_nonce: u128,
-    pub encrypted_state: [[u8; 32]; 8],
-    pub merkle_root: [u8; 32],
-}
+    /// Open a private request for quote to a set of whitelisted makers
+    pub fn create_rfq(
+        ctx: Context<CreateRfq>,
+        computation_offset: u64,
+        rfq_id: u64,
+        market_id: String,
+        makers: Vec<Pubkey>,
+        window_slots: u64,
+        bond_lamports: u64,
+        settle_window_slots: u64,
+        encrypted_size: [u8; 32],
+        encrypted_side: [u8; 32],
+        taker_pubkey: [u8; 32],
+        nonce: u128,
+        state_nonce: u128,
+    ) -> Result<()> {
+        check_computation_offset(&ctx.accounts.computation_account)?;
+
+        require!(
+            !makers.is_empty() && makers.len() <= MAX_RFQ_MAKERS,
+            ErrorCode::InvalidMakerSet
+        );
+        require!(
+            makers.iter().enumerate().all(|(i, maker)| !makers[..i].contains(maker)),
+            ErrorCode::InvalidMakerSet
+        );
+        require!(window_slots > 0, ErrorCode::InvalidRfqWindow);
+        require!(settle_window_slots > 0, ErrorCode::InvalidRfqWindow);
+        require!(bond_lamports > 0, ErrorCode::InvalidBond);
+        check_risk_profile(&ctx.accounts.risk_profile, &market_id)?;
+        check_encryption_key(&ctx.accounts.encryption_key, &taker_pubkey)?;
+
+        let window_end_slot = Clock::get()?
+            .slot
+            .checked_add(window_slots)
+            .ok_or(ErrorCode::InvalidRfqWindow)?;
+
+        let rfq = &mut ctx.accounts.rfq;
+        rfq.bump = ctx.bumps.rfq;
+        rfq.taker = ctx.accounts.taker.key();
+        rfq.state_nonce = state_nonce;
+        rfq.encrypted_state = [[0u8; 32]; 5];
+        rfq.rfq_id = rfq_id;
+        rfq.market_id = market_id;
+        rfq.makers = makers;
+        rfq.quoted_mask = 0;
+        rfq.quote_count = 0;
+        rfq.window_end_slot = window_end_slot;
+        rfq.status = RfqStatus::Open;
+        rfq.pending_computation = true;
+        rfq.queued_computation = Some(ctx.accounts.computation_account.key());
+        rfq.winning_maker = None;
+        rfq.fill_notional = 0;
+        rfq.bond_lamports = bond_lamports;
+        rfq.settle_window_slots = settle_window_slots;
+        rfq.settlement_deadline_slot = 0;
+        rfq.maker_settled = false;
+        rfq.outstanding_bonds = 0;
 
-#[account]
-#[derive(InitSpace)]
-pub struct OrderCommitment {
-    pub bump: u8,
-    pub batch: Pubkey,
-    pub user: Pubkey,
-    pub commitment_hash: [u8; 32],
-    pub index: u8,
-    pub allocated: bool,
-}
+        let args = ArgBuilder::new()
+            .x25519_pubkey(taker_pubkey)
+            .plaintext_u128(nonce)
+            .encrypted_u64(encrypted_size)
+            .encrypted_u8(encrypted_side)
+            .plaintext_u128(state_nonce)
+            .build();
 
-// ============================================
-// Account Contexts
-// ============================================
-
-#[queue_computation_accounts("init_batch", authority)]
-#[derive(Accounts)]
-#[instruction(computation_offset: u64, market_id: String)]
-pub struct CreateBatch<'info> {
-    #[account(mut)]
-    pub authority: Signer<'info>,
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![InitRfqCallback::callback_ix(
+                computation_offset,
+                &ctx.accounts.mxe_account,
+                &[CallbackAccount {
+                    pubkey: ctx.accounts.rfq.key(),
+                    is_writable: true,
+                }],
+            )?],
+            1,
+            0,
+        )?;
 
-    #[account(
-        init,
-        payer = authority,
-        space = 8 + TradingBatch::INIT_SPACE,
-        seeds = [b"batch", market_id.as_bytes(), authority.key().as_ref()],
-        bump,
-    )]
-    pub batch: Account<'info, TradingBatch>,
+        Ok(())
+    }
 
-    #[account(
-        init_if_needed,
-        space = 9,
-        payer = authority,
-        seeds = [&SIGN_PDA_SEED],
-        bump,
-        address = derive_sign_pda!(),
-    )]
-    pub sign_pda_account: Account<'info, ArciumSignerAccount>,
+    #[arcium_callback(encrypted_ix = "init_rfq")]
+    pub fn init_rfq_callback(
+        ctx: Context<InitRfqCallback>,
+        output: SignedComputationOutputs<InitRfqOutput>,
+    ) -> Result<()> {
+        check_callback_origin(
+            ctx.accounts.rfq.queued_computation,
+            &ctx.accounts.computation_account,
+        )?;
 
-    #[account(address = derive_mxe_pda!())]
-    pub mxe_account: Account<'info, MXEAccount>,
+        let o = match output.verify_output(
+            &ctx.accounts.cluster_account,
+            &ctx.accounts.computation_account,
+        ) {
+            Ok(InitRfqOutput { field_0 }) => field_0,
+            Err(_) => return Err(ErrorCode::AbortedComputation.into()),
+        };
 
-    #[account(mut, address = derive_mempool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
-    /// CHECK: mempool_account
-    pub mempool_account: UncheckedAccount<'info>,
+        let rfq = &mut ctx.accounts.rfq;
+        rfq.encrypted_state = o.ciphertexts;
+        rfq.state_nonce = o.nonce;
+        rfq.pending_computation = false;
+        rfq.queued_computation = None;
+
+        emit!(RfqCreated {
+            rfq: rfq.key(),
+            taker: rfq.taker,
+            market_id: rfq.market_id.clone(),
+            makers: rfq.makers.clone(),
+            window_end_slot: rfq.window_end_slot,
+        });
 
-    #[account(mut, address = derive_execpool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
-    /// CHECK: executing_pool
-    pub executing_pool: UncheckedAccount<'info>,
+        Ok(())
+    }
 
-    #[account(mut, address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::ClusterNotSet))]
-    /// CHECK: computation_account
-    pub computation_account: UncheckedAccount<'info>,
+    /// Submit an encrypted two-sided quote as a whitelisted maker
+    ///
+    /// The maker posts `rfq.bond_lamports` into a `QuoteBond`, slashed to the
+    /// taker if they win and do not confirm the fill before the deadline.
+    pub fn submit_quote(
+        ctx: Context<SubmitQuote>,
+        computation_offset: u64,
+        encrypted_yes_price: [u8; 32],
+        encrypted_no_price: [u8; 32],
+        maker_pubkey: [u8; 32],
+        nonce: u128,
+    ) -> Result<()> {
+        check_computation_offset(&ctx.accounts.computation_account)?;
+
+        let rfq = &ctx.accounts.rfq;
+        require!(rfq.status == RfqStatus::Open, ErrorCode::RfqNotOpen);
+        require!(!rfq.pending_computation, ErrorCode::ComputationPending);
+        require!(
+            Clock::get()?.slot <= rfq.window_end_slot,
+            ErrorCode::RfqWindowClosed
+        );
+
+        let maker_index = rfq
+            .makers
+            .iter()
+            .position(|maker| *maker == ctx.accounts.maker.key())
+            .ok_or(ErrorCode::MakerNotWhitelisted)?;
+        require!(
+            rfq.quoted_mask & (1 << maker_index) == 0,
+            ErrorCode::QuoteAlreadySubmitted
+        );
+
+        let bond_lamports = rfq.bond_lamports;
+        anchor_lang::system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                anchor_lang::system_program::Transfer {
+                    from: ctx.accounts.maker.to_account_info(),
+                    to: ctx.accounts.quote_bond.to_account_info(),
+                },
+            ),
+            bond_lamports,
+        )?;
 
-    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_INIT_BATCH))]
-    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+        let quote_bond = &mut ctx.accounts.quote_bond;
+        quote_bond.bump = ctx.bumps.quote_bond;
+        quote_bond.rfq = ctx.accounts.rfq.key();
+        quote_bond.maker = ctx.accounts.maker.key();
+        quote_bond.amount = bond_lamports;
 
-    #[account(mut, address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
-    pub cluster_account: Account<'info, Cluster>,
+        let rfq = &ctx.accounts.rfq;
+        let args = ArgBuilder::new()
+            .x25519_pubkey(maker_pubkey)
+            .plaintext_u128(nonce)
+            .encrypted_u64(encrypted_yes_price)
+            .encrypted_u64(encrypted_no_price)
+            .plaintext_u8(maker_index as u8)
+            .plaintext_u128(rfq.state_nonce)
+            .account(
+                ctx.accounts.rfq.key(),
+                RFQ_ENCRYPTED_STATE_OFFSET,
+                RFQ_ENCRYPTED_STATE_SIZE,
+            )
+            .build();
 
-    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
-    pub pool_account: Account<'info, FeePool>,
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![SubmitQuoteCallback::callback_ix(
+                computation_offset,
+                &ctx.accounts.mxe_account,
+                &[CallbackAccount {
+                    pubkey: ctx.accounts.rfq.key(),
+                    is_writable: true,
+                }],
+            )?],
+            1,
+            0,
+        )?;
 
-    #[account(mut, address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
-    pub clock_account: Account<'info, ClockAccount>,
+        // Only one computation may hold the RFQ state at a time, otherwise
+        // concurrent quotes would overwrite each other's result
+        let rfq = &mut ctx.accounts.rfq;
+        rfq.quoted_mask |= 1 << maker_index;
+        rfq.outstanding_bonds += 1;
+        rfq.pending_computation = true;
+        rfq.queued_computation = Some(ctx.accounts.computation_account.key());
 
-    pub system_program: Program<'info, System>,
-    pub arcium_program: Program<'info, Arcium>,
-}
+        Ok(())
+    }
 
-#[callback_accounts("init_batch")]
-#[derive(Accounts)]
-pub struct InitBatchCallback<'info> {
-    pub arcium_program: Program<'info, Arcium>,
+    #[arcium_callback(encrypted_ix = "submit_quote")]
+    pub fn submit_quote_callback(
+        ctx: Context<SubmitQuoteCallback>,
+        output: SignedComputationOutputs<SubmitQuoteOutput>,
+    ) -> Result<()> {
+        check_callback_origin(
+            ctx.accounts.rfq.queued_computation,
+            &ctx.accounts.computation_account,
+        )?;
 
-    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_INIT_BATCH))]
-    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+        let o = match output.verify_output(
+            &ctx.accounts.cluster_account,
+            &ctx.accounts.computation_account,
+        ) {
+            Ok(SubmitQuoteOutput { field_0 }) => field_0,
+            Err(_) => return Err(ErrorCode::AbortedComputation.into()),
+        };
 
-    #[account(address = derive_mxe_pda!())]
-    pub mxe_account: Account<'info, MXEAccount>,
+        let rfq = &mut ctx.accounts.rfq;
+        rfq.encrypted_state = o.ciphertexts;
+        rfq.state_nonce = o.nonce;
+        rfq.quote_count += 1;
+        rfq.pending_computation = false;
+        rfq.queued_computation = None;
 
-    /// CHECK: computation_account
-    pub computation_account: UncheckedAccount<'info>,
+        emit!(QuoteReceived {
+            rfq: rfq.key(),
+            quote_count: rfq.quote_count,
+        });
 
-    #[account(address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
-    pub cluster_account: Account<'info, Cluster>,
+        Ok(())
+    }
 
-    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
-    /// CHECK: instructions_sysvar
-    pub instructions_sysvar: AccountInfo<'info>,
+    /// Select the best quote via MPC once the window closes (or every maker quoted)
+    ///
+    /// Only until the settlement window after the quote window runs out;
+    /// from then on the makers can reclaim their bonds instead.
+    pub fn settle_rfq(ctx: Context<SettleRfq>, computation_offset: u64) -> Result<()> {
+        check_computation_offset(&ctx.accounts.computation_account)?;
+
+        let rfq = &ctx.accounts.rfq;
+        let slot = Clock::get()?.slot;
+        require!(rfq.status == RfqStatus::Open, ErrorCode::RfqNotOpen);
+        require!(!rfq.pending_computation, ErrorCode::ComputationPending);
+        require!(
+            slot > rfq.window_end_slot || rfq.quote_count as usize == rfq.makers.len(),
+            ErrorCode::RfqWindowOpen
+        );
+        require!(
+            slot <= rfq.window_end_slot.saturating_add(rfq.settle_window_slots),
+            ErrorCode::RfqExpired
+        );
 
-    #[account(mut)]
-    pub batch: Account<'info, TradingBatch>,
-}
+        let args = ArgBuilder::new()
+            .plaintext_u128(rfq.state_nonce)
+            .account(
+                ctx.accounts.rfq.key(),
+                RFQ_ENCRYPTED_STATE_OFFSET,
+                RFQ_ENCRYPTED_STATE_SIZE,
+            )
+            .build();
 
-#[queue_computation_accounts("add_order", user)]
-#[derive(Accounts)]
-#[instruction(computation_offset: u64)]
-pub struct AddOrder<'info> {
-    #[account(mut)]
-    pub user: Signer<'info>,
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![SettleRfqCallback::callback_ix(
+                computation_offset,
+                &ctx.accounts.mxe_account,
+                &[CallbackAccount {
+                    pubkey: ctx.accounts.rfq.key(),
+                    is_writable: true,
+                }],
+            )?],
+            1,
+            0,
+        )?;
 
-    #[account(mut)]
-    pub batch: Account<'info, TradingBatch>,
+        ctx.accounts.rfq.pending_computation = true;
+        ctx.accounts.rfq.queued_computation = Some(ctx.accounts.computation_account.key());
 
-    #[account(
-        init,
-        payer = user,
-        space = 8 + OrderCommitment::INIT_SPACE,
-        seeds = [b"order", batch.key().as_ref(), &[batch.order_count]],
-        bump,
-    )]
-    pub order: Account<'info, OrderCommitment>,
+        Ok(())
+    }
 
-    #[account(
-        init_if_needed,
-        space = 9,
-        payer = user,
-        seeds = [&SIGN_PDA_SEED],
-        bump,
-        address = derive_sign_pda!(),
-    )]
-    pub sign_pda_account: Account<'info, ArciumSignerAccount>,
+    #[arcium_callback(encrypted_ix = "settle_rfq")]
+    pub fn settle_rfq_callback(
+        ctx: Context<SettleRfqCallback>,
+        output: SignedComputationOutputs<SettleRfqOutput>,
+    ) -> Result<()> {
+        check_callback_origin(
+            ctx.accounts.rfq.queued_computation,
+            &ctx.accounts.computation_account,
+        )?;
 
-    #[account(address = derive_mxe_pda!())]
-    pub mxe_account: Account<'info, MXEAccount>,
+        let (has_quote, best_maker, fill_notional) = match output.verify_output(
+            &ctx.accounts.cluster_account,
+            &ctx.accounts.computation_account,
+        ) {
+            Ok(SettleRfqOutput {
+                field_0: SettleRfqOutputStruct0 {
+                    field_0: has_quote,
+                    field_1: best_maker,
+                    field_2: fill_notional,
+                },
+            }) => (has_quote, best_maker, fill_notional),
+            Err(_) => return Err(ErrorCode::AbortedComputation.into()),
+        };
 
-    #[account(mut, address = derive_mempool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
-    /// CHECK: mempool_account
-    pub mempool_account: UncheckedAccount<'info>,
+        let rfq = &mut ctx.accounts.rfq;
+        rfq.status = RfqStatus::Settled;
+        rfq.pending_computation = false;
+        rfq.queued_computation = None;
+        rfq.winning_maker = if has_quote {
+            rfq.makers.get(best_maker as usize).copied()
+        } else {
+            None
+        };
+        rfq.fill_notional = fill_notional;
+        rfq.settlement_deadline_slot = Clock::get()?
+            .slot
+            .saturating_add(rfq.settle_window_slots);
+
+        emit!(RfqSettled {
+            rfq: rfq.key(),
+            winning_maker: rfq.winning_maker,
+            fill_notional,
+        });
 
-    #[account(mut, address = derive_execpool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
-    /// CHECK: executing_pool
-    pub executing_pool: UncheckedAccount<'info>,
+        Ok(())
+    }
 
-    #[account(mut, address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::ClusterNotSet))]
-    /// CHECK: computation_account
-    pub computation_account: UncheckedAccount<'info>,
+    /// Winning maker confirms the fill before the settlement deadline
+    pub fn confirm_rfq_fill(ctx: Context<ConfirmRfqFill>) -> Result<()> {
+        let rfq = &mut ctx.accounts.rfq;
+        require!(rfq.status == RfqStatus::Settled, ErrorCode::RfqNotSettled);
+        require!(
+            rfq.winning_maker == Some(ctx.accounts.maker.key()),
+            ErrorCode::NotWinningMaker
+        );
+        require!(!rfq.maker_settled, ErrorCode::RfqAlreadyFilled);
+        require!(
+            Clock::get()?.slot <= rfq.settlement_deadline_slot,
+            ErrorCode::SettlementDeadlinePassed
+        );
+
+        rfq.maker_settled = true;
+
+        emit!(RfqFillConfirmed {
+            rfq: rfq.key(),
+            maker: ctx.accounts.maker.key(),
+            fill_notional: rfq.fill_notional,
+        });
 
-    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_ADD_ORDER))]
-    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+        Ok(())
+    }
 
-    #[account(mut, address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
-    pub cluster_account: Account<'info, Cluster>,
+    /// Return a quote bond to its maker
+    ///
+    /// Losing makers can reclaim once the RFQ settles, the winner once the
+    /// fill is confirmed. If the taker never settles, every maker can
+    /// reclaim after the quote window plus the settlement window, which is
+    /// also when `settle_rfq` stops accepting the RFQ.
+    pub fn release_quote_bond(ctx: Context<ReleaseQuoteBond>) -> Result<()> {
+        let rfq = &mut ctx.accounts.rfq;
+        let maker = ctx.accounts.maker.key();
+        let slot = Clock::get()?.slot;
+
+        let releasable = match rfq.status {
+            RfqStatus::Settled => rfq.winning_maker != Some(maker) || rfq.maker_settled,
+            RfqStatus::Open => {
+                !rfq.pending_computation
+                    && slot > rfq.window_end_slot.saturating_add(rfq.settle_window_slots)
+            }
+        };
+        require!(releasable, ErrorCode::BondNotReleasable);
 
-    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
-    pub pool_account: Account<'info, FeePool>,
+        rfq.outstanding_bonds = rfq
+            .outstanding_bonds
+            .checked_sub(1)
+            .ok_or(ErrorCode::NoOutstandingBonds)?;
 
-    #[account(mut, address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
-    pub clock_account: Account<'info, ClockAccount>,
+        emit!(QuoteBondReleased {
+            rfq: rfq.key(),
+            maker,
+            amount: ctx.accounts.quote_bond.amount,
+        });
 
-    pub system_program: Program<'info, System>,
-    pub arcium_program: Program<'info, Arcium>,
-}
+        Ok(())
+    }
 
-#[callback_accounts("add_order")]
-#[derive(Accounts)]
-pub struct AddOrderCallback<'info> {
-    pub arcium_program: Program<'info, Arcium>,
+    /// Slash the winning maker's bond to the taker after a missed deadline
+    pub fn slash_quote_bond(ctx: Context<SlashQuoteBond>) -> Result<()> {
+        let rfq = &mut ctx.accounts.rfq;
+        require!(rfq.status == RfqStatus::Settled, ErrorCode::RfqNotSettled);
+        require!(
+            rfq.winning_maker == Some(ctx.accounts.quote_bond.maker),
+            ErrorCode::NotWinningMaker
+        );
+        require!(!rfq.maker_settled, ErrorCode::RfqAlreadyFilled);
+        require!(
+            Clock::get()?.slot > rfq.settlement_deadline_slot,
+            ErrorCode::SettlementWindowOpen
+        );
+
+        rfq.outstanding_bonds = rfq
+            .outstanding_bonds
+            .checked_sub(1)
+            .ok_or(ErrorCode::NoOutstandingBonds)?;
+
+        emit!(QuoteBondSlashed {
+            rfq: rfq.key(),
+            maker: ctx.accounts.quote_bond.maker,
+            taker: rfq.taker,
+            amount: ctx.accounts.quote_bond.amount,
+        });
 
-    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_ADD_ORDER))]
-    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+        Ok(())
+    }
 
-    #[account(address = derive_mxe_pda!())]
-    pub mxe_account: Account<'info, MXEAccount>,
+    /// Close an RFQ and reclaim its rent
+    pub fn cancel_rfq(ctx: Context<CancelRfq>) -> Result<()> {
+        require!(
+            !ctx.accounts.rfq.pending_computation,
+            ErrorCode::ComputationPending
+        );
+        require!(
+            ctx.accounts.rfq.outstanding_bonds == 0,
+            ErrorCode::BondsOutstanding
+        );
+
+        emit!(RfqCancelled {
+            rfq: ctx.accounts.rfq.key(),
+            status: ctx.accounts.rfq.status,
+        });
 
-    /// CHECK: computation_account
-    pub computation_account: UncheckedAccount<'info>,
+        Ok(())
+    }
 
-    #[account(address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
-    pub cluster_account: Account<'info, Cluster>,
+    // ============================================
+    // Order Lane Operations
+    // ============================================
 
-    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
-    /// CHECK: instructions_sysvar
-    pub instructions_sysvar: AccountInfo<'info>,
+    /// Open the next order lane of a batch
+    ///
+    /// A laned batch takes orders into `ORDER_LANES` independent encrypted
+    /// states, so orders on different lanes don't wait on each other's MPC
+    /// round trip. Lanes must be opened before the first order.
+    pub fn open_lane(
+        ctx: Context<OpenLane>,
+        computation_offset: u64,
+        lane_index: u8,
+        nonce: u128,
+    ) -> Result<()> {
+        check_computation_offset(&ctx.accounts.computation_account)?;
 
-    #[account(mut)]
-    pub batch: Account<'info, TradingBatch>,
+        let batch = &ctx.accounts.batch;
+        require!(batch.status == BatchStatus::Open, ErrorCode::BatchNotOpen);
+        require!(
+            batch.order_count == 0 && batch.staged_orders == 0,
+            ErrorCode::BatchHasOrders
+        );
+        require!(
+            lane_index == batch.lane_count && lane_index < ORDER_LANES,
+            ErrorCode::InvalidLaneIndex
+        );
+
+        let lane = &mut ctx.accounts.lane;
+        lane.bump = ctx.bumps.lane;
+        lane.batch = batch.key();
+        lane.lane_index = lane_index;
+        lane.state_nonce = nonce;
+        lane.encrypted_state = [[0u8; 32]; 8];
+        lane.order_count = 0;
+        lane.ready = false;
 
-    #[account(mut)]
-    pub order: Account<'info, OrderCommitment>,
-}
+        let args = ArgBuilder::new()
+            .plaintext_u128(nonce)
+            .build();
 
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![InitLaneCallback::callback_ix(
+                computation_offset,
+                &ctx.accounts.mxe_account,
+                &[CallbackAccount {
+                    pubkey: ctx.accounts.lane.key(),
+                    is_writable: true,
+                }],
+            )?],
+            1,
+            0,
+        )?;
+
+        ctx.accounts.lane.queued_computation = Some(ctx.accounts.computation_account.key());
+        ctx.accounts.lane.queued_at = Clock::get()?.slot;
+        ctx.accounts.batch.lane_count += 1;
+
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "init_lane")]
+    pub fn init_lane_callback(
+        ctx: Context<InitLaneCallback>,
+        output: SignedComputationOutputs<InitLaneOutput>,
+    ) -> Result<()> {
+        check_callback_origin(
+            ctx.accounts.lane.queued_computation,
+            &ctx.accounts.computation_account,
+        )?;
+
+        let o = match output.verify_output(
+            &ctx.accounts.cluster_account,
+            &ctx.accounts.computation_account,
+        ) {
+            Ok(InitLaneOutput { field_0 }) => field_0,
+            Err(_) => return Err(ErrorCode::AbortedComputation.into()),
+        };
+
+        let lane = &mut ctx.accounts.lane;
+        lane.encrypted_state = o.ciphertexts;
+        lane.state_nonce = o.nonce;
+        lane.ready = true;
+        lane.queued_computation = None;
+
+        emit!(LaneOpened {
+            batch: lane.batch,
+            lane: lane.key(),
+            lane_index: lane.lane_index,
+        });
+
+        Ok(())
+    }
+
+    /// Add an order to one lane of a laned batch
+    pub fn add_lane_order(
+        ctx: Context<AddLaneOrder>,
+        computation_offset: u64,
+        encrypted_amount: [u8; 32],
+        encrypted_wallet_lo: [u8; 32],
+        encrypted_wallet_hi: [u8; 32],
+        encrypted_expiry_slot: [u8; 32],
+        user_pubkey: [u8; 32],
+        nonce: u128,
+        commitment_hash: [u8; 32],
+    ) -> Result<()> {
+        check_computation_offset(&ctx.accounts.computation_account)?;
+
+        let batch = &ctx.accounts.batch;
+        require!(batch.status == BatchStatus::Open, ErrorCode::BatchNotOpen);
+        require!(batch.lane_count == ORDER_LANES, ErrorCode::LanesNotOpen);
+
+        let lane = &ctx.accounts.lane;
+        require!(lane.ready, ErrorCode::LanesNotOpen);
+        require!(lane.queued_computation.is_none(), ErrorCode::ComputationPending);
+        require!(lane.order_count < LANE_CAPACITY, ErrorCode::BatchFull);
+        check_risk_profile(&ctx.accounts.risk_profile, &batch.market_id)?;
+        check_encryption_key(&ctx.accounts.encryption_key, &user_pubkey)?;
+
+        let eligibility_proof = check_order_gates(
+            batch,
+            ctx.accounts.attestation.as_ref(),
+            ctx.accounts.eligibility_proof.as_ref(),
+            &ctx.accounts.user.key(),
+        )?;
+
+        let order = &mut ctx.accounts.order;
+        order.bump = ctx.bumps.order;
+        order.batch = batch.key();
+        order.user = ctx.accounts.user.key();
+        order.commitment_hash = commitment_hash;
+        order.index = lane.lane_index * LANE_CAPACITY + lane.order_count;
+        order.allocated = false;
+        order.eligibility_proof = eligibility_proof;
+        order.rejected = false;
+
+        let (signer_lo, signer_hi) = split_pubkey(&ctx.accounts.user.key());
+
+        let args = ArgBuilder::new()
+            .x25519_pubkey(user_pubkey)
+            .plaintext_u128(nonce)
+            .encrypted_u64(encrypted_amount)
+            .encrypted_u128(encrypted_wallet_lo)
+            .encrypted_u128(encrypted_wallet_hi)
+            .encrypted_u64(encrypted_expiry_slot)
+            .plaintext_u128(signer_lo)
+            .plaintext_u128(signer_hi)
+            .plaintext_u64(Clock::get()?.slot)
+            .plaintext_u128(lane.state_nonce)
+            .account(
+                ctx.accounts.lane.key(),
+                LANE_ENCRYPTED_STATE_OFFSET,
+                LANE_ENCRYPTED_STATE_SIZE,
+            )
+            .build();
+
+        let payer_lamports = ctx.accounts.user.lamports();
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![AddLaneOrderCallback::callback_ix(
+                computation_offset,
+                &ctx.accounts.mxe_account,
+                &[
+                    CallbackAccount {
+                        pubkey: ctx.accounts.batch.key(),
+                        is_writable: true,
+                    },
+                    CallbackAccount {
+                        pubkey: ctx.accounts.lane.key(),
+                        is_writable: true,
+                    },
+                    CallbackAccount {
+                        pubkey: ctx.accounts.order.key(),
+                        is_writable: true,
+                    },
+                ],
+            )?],
+            1,
+            0,
+        )?;
+
+        ctx.accounts.lane.queued_computation = Some(ctx.accounts.computation_account.key());
+        ctx.accounts.lane.queued_at = Clock::get()?.slot;
+
+        let fee = payer_lamports.saturating_sub(ctx.accounts.user.lamports());
+        draw_compute_fee(ctx.accounts.compute_pool.as_mut(), &ctx.accounts.user, fee)?;
+        refund_order_rent(
+            ctx.accounts.rent_sponsor.as_mut(),
+            &ctx.accounts.user,
+            ctx.accounts.order.key(),
+        )
+    }
+
+    #[arcium_callback(encrypted_ix = "add_lane_order")]
+    pub fn add_lane_order_callback(
+        ctx: Context<AddLaneOrderCallback>,
+        output: SignedComputationOutputs<AddLaneOrderOutput>,
+    ) -> Result<()> {
+        check_callback_origin(
+            ctx.accounts.lane.queued_computation,
+            &ctx.accounts.computation_account,
+        )?;
+
+        let o = match output.verify_output(
+            &ctx.accounts.cluster_account,
+            &ctx.accounts.computation_account,
+        ) {
+            Ok(AddLaneOrderOutput { field_0 }) => field_0,
+            Err(_) => return Err(ErrorCode::AbortedComputation.into()),
+        };
+        require!(ctx.accounts.batch.status == BatchStatus::Open, ErrorCode::BatchNotOpen);
+
+        let lane = &mut ctx.accounts.lane;
+        lane.encrypted_state = o.ciphertexts;
+        lane.state_nonce = o.nonce;
+        lane.queued_computation = None;
+        lane.order_count = lane
+            .order_count
+            .checked_add(1)
+            .ok_or(ErrorCode::OrderCountOverflow)?;
+
+        // Only the plaintext count is shared, so close_batch and BatchFull
+        // keep working on laned batches
+        let batch = &mut ctx.accounts.batch;
+        batch.order_count = batch
+            .order_count
+            .checked_add(1)
+            .ok_or(ErrorCode::OrderCountOverflow)?;
+
+        let order_index = ctx.accounts.order.index;
+        emit_order_result(
+            batch.key(),
+            &mut ctx.accounts.order,
+            order_index,
+            o.wallet_bound,
+            o.amount_overflow,
+            o.expired,
+        );
+
+        Ok(())
+    }
+
+    /// Merge all lanes of a closed batch into the batch state
+    pub fn merge_lanes(ctx: Context<MergeLanes>, computation_offset: u64) -> Result<()> {
+        check_computation_offset(&ctx.accounts.computation_account)?;
+
+        let batch = &ctx.accounts.batch;
+        require!(batch.status == BatchStatus::Closed, ErrorCode::BatchNotClosed);
+        require!(batch.lane_count == ORDER_LANES, ErrorCode::LanesNotOpen);
+        require!(!batch.lanes_merged, ErrorCode::LanesAlreadyMerged);
+
+        let lanes = [
+            &ctx.accounts.lane_0,
+            &ctx.accounts.lane_1,
+            &ctx.accounts.lane_2,
+            &ctx.accounts.lane_3,
+        ];
+        // A lane still being written would be missing from the merge
+        require!(lanes.iter().all(|lane| lane.ready), ErrorCode::LanesNotOpen);
+        require!(
+            lanes.iter().all(|lane| lane.queued_computation.is_none()),
+            ErrorCode::ComputationPending
+        );
+
+        let mut args = ArgBuilder::new();
+        for lane in lanes {
+            args = args.plaintext_u128(lane.state_nonce).account(
+                lane.key(),
+                LANE_ENCRYPTED_STATE_OFFSET,
+                LANE_ENCRYPTED_STATE_SIZE,
+            );
+        }
+        let args = args.build();
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![MergeLanesCallback::callback_ix(
+                computation_offset,
+                &ctx.accounts.mxe_account,
+                &[CallbackAccount {
+                    pubkey: ctx.accounts.batch.key(),
+                    is_writable: true,
+                }],
+            )?],
+            1,
+            0,
+        )?;
+
+        ctx.accounts.batch.queued_computation = Some(ctx.accounts.computation_account.key());
+
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "merge_lanes")]
+    pub fn merge_lanes_callback(
+        ctx: Context<MergeLanesCallback>,
+        output: SignedComputationOutputs<MergeLanesOutput>,
+    ) -> Result<()> {
+        check_callback_origin(
+            ctx.accounts.batch.queued_computation,
+            &ctx.accounts.computation_account,
+        )?;
+
+        let o = match output.verify_output(
+            &ctx.accounts.cluster_account,
+            &ctx.accounts.computation_account,
+        ) {
+            Ok(MergeLanesOutput { field_0 }) => field_0,
+            Err(_) => return Err(ErrorCode::AbortedComputation.into()),
+        };
+
+        let batch = &mut ctx.accounts.batch;
+        batch.encrypted_state = o.ciphertexts;
+        batch.state_nonce = o.nonce;
+        batch.lanes_merged = true;
+        batch.queued_computation = None;
+
+        emit!(LanesMerged {
+            batch: batch.key(),
+            order_count: batch.order_count,
+            total_overflow: o.total_overflow,
+        });
+
+        Ok(())
+    }
+
+    /// Give up on a lane order whose callback never landed
+    ///
+    /// After `COMPUTATION_TIMEOUT_SLOTS` the authority can clear the lane so
+    /// it takes orders again and `merge_lanes` can run. The stuck order
+    /// commitment is closed (rent to its user), which frees its index and
+    /// makes a late callback fail.
+    pub fn abandon_lane_order(ctx: Context<AbandonLaneOrder>) -> Result<()> {
+        let lane = &ctx.accounts.lane;
+        require!(lane.queued_computation.is_some(), ErrorCode::NoPendingComputation);
+        let abandoned_at = Clock::get()?.slot;
+        require!(
+            abandoned_at >= lane.queued_at.saturating_add(COMPUTATION_TIMEOUT_SLOTS),
+            ErrorCode::ComputationNotStale
+        );
+        require_keys_eq!(
+            ctx.accounts.order_user.key(),
+            ctx.accounts.order.user,
+            ErrorCode::Unauthorized
+        );
+
+        ctx.accounts
+            .order
+            .close(ctx.accounts.order_user.to_account_info())?;
+
+        let lane = &mut ctx.accounts.lane;
+        lane.queued_computation = None;
+
+        emit!(ComputationAbandoned {
+            batch: ctx.accounts.batch.key(),
+            computation: ComputationKind::AddLaneOrder,
+            queued_at: lane.queued_at,
+            abandoned_at,
+        });
+
+        Ok(())
+    }
+
+    // ============================================
+    // Bulk Order Ingestion
+    // ============================================
+
+    /// Stage an encrypted order for `add_orders_bulk` to fold
+    ///
+    /// Takes the same input as `add_order` and applies the same gates, with
+    /// the amount, wallet halves and expiry slot encrypted together under
+    /// `nonce`. The order's index is reserved now, but no computation is
+    /// queued until a keeper folds it along with the orders staged after it.
+    pub fn stage_order(
+        ctx: Context<StageOrder>,
+        encrypted_amount: [u8; 32],
+        encrypted_wallet_lo: [u8; 32],
+        encrypted_wallet_hi: [u8; 32],
+        encrypted_expiry_slot: [u8; 32],
+        user_pubkey: [u8; 32],
+        nonce: u128,
+        commitment_hash: [u8; 32],
+    ) -> Result<()> {
+        let batch = &ctx.accounts.batch;
+        require!(batch.status == BatchStatus::Open, ErrorCode::BatchNotOpen);
+        require!(batch.order_count + batch.staged_orders < 32, ErrorCode::BatchFull);
+        require!(batch.lane_count == 0, ErrorCode::BatchUsesLanes);
+
+        check_risk_profile(&ctx.accounts.risk_profile, &batch.market_id)?;
+        check_encryption_key(&ctx.accounts.encryption_key, &user_pubkey)?;
+
+        let eligibility_proof = check_order_gates(
+            batch,
+            ctx.accounts.attestation.as_ref(),
+            ctx.accounts.eligibility_proof.as_ref(),
+            &ctx.accounts.user.key(),
+        )?;
+
+        let index = batch.order_count + batch.staged_orders;
+
+        let order = &mut ctx.accounts.order;
+        order.bump = ctx.bumps.order;
+        order.batch = batch.key();
+        order.user = ctx.accounts.user.key();
+        order.commitment_hash = commitment_hash;
+        order.index = index;
+        order.allocated = false;
+        order.eligibility_proof = eligibility_proof;
+        order.rejected = false;
+
+        let staged = &mut ctx.accounts.staged_order;
+        staged.bump = ctx.bumps.staged_order;
+        staged.batch = batch.key();
+        staged.order = order.key();
+        staged.user = ctx.accounts.user.key();
+        staged.index = index;
+        staged.staged_at = Clock::get()?.slot;
+        staged.user_pubkey = user_pubkey;
+        staged.nonce = nonce;
+        staged.encrypted_order = [
+            encrypted_amount,
+            encrypted_wallet_lo,
+            encrypted_wallet_hi,
+            encrypted_expiry_slot,
+        ];
+
+        let batch = &mut ctx.accounts.batch;
+        batch.staged_orders += 1;
+
+        emit!(OrderStaged {
+            batch: batch.key(),
+            order: ctx.accounts.order.key(),
+            order_index: index,
+        });
+
+        refund_order_rent(
+            ctx.accounts.rent_sponsor.as_mut(),
+            &ctx.accounts.user,
+            ctx.accounts.order.key(),
+        )
+    }
+
+    /// Fold the batch's oldest staged orders in one computation
+    ///
+    /// Permissionless, so a keeper can coalesce orders as they are staged;
+    /// the compute pool refunds its fee as it would a user's.
+    /// `remaining_accounts` holds the `StagedOrder`s at the next
+    /// `1..=BULK_ORDER_CAPACITY` order indexes, in index order.
+    pub fn add_orders_bulk<'info>(
+        ctx: Context<'_, '_, 'info, 'info, AddOrdersBulk<'info>>,
+        computation_offset: u64,
+    ) -> Result<()> {
+        check_computation_offset(&ctx.accounts.computation_account)?;
+
+        let batch = &ctx.accounts.batch;
+        require!(batch.status == BatchStatus::Open, ErrorCode::BatchNotOpen);
+        require!(batch.pending_computation.is_none(), ErrorCode::ComputationPending);
+        let order_count = ctx.remaining_accounts.len();
+        require!(
+            (1..=BULK_ORDER_CAPACITY as usize).contains(&order_count)
+                && order_count <= batch.staged_orders as usize,
+            ErrorCode::InvalidBulkSize
+        );
+
+        let mut staged = Vec::with_capacity(order_count);
+        let mut callback_accounts = vec![CallbackAccount {
+            pubkey: batch.key(),
+            is_writable: true,
+        }];
+        for (i, account) in ctx.remaining_accounts.iter().enumerate() {
+            let order = Account::<StagedOrder>::try_from(account)?;
+            require!(
+                order.batch == batch.key() && order.index as usize == batch.order_count as usize + i,
+                ErrorCode::StagedOrderMismatch
+            );
+            for pubkey in [order.order, order.key(), order.user] {
+                callback_accounts.push(CallbackAccount {
+                    pubkey,
+                    is_writable: true,
+                });
+            }
+            staged.push(order);
+        }
+
+        // Unused slots repeat the first order; the circuit folds them as absent
+        let slots: Vec<&StagedOrder> = (0..BULK_ORDER_CAPACITY as usize)
+            .map(|i| &**staged.get(i).unwrap_or(&staged[0]))
+            .collect();
+        let signers: Vec<(u128, u128)> = slots.iter().map(|order| split_pubkey(&order.user)).collect();
+
+        let mut args = ArgBuilder::new();
+        for order in &slots {
+            args = args
+                .x25519_pubkey(order.user_pubkey)
+                .plaintext_u128(order.nonce)
+                .encrypted_u64(order.encrypted_order[0])
+                .encrypted_u128(order.encrypted_order[1])
+                .encrypted_u128(order.encrypted_order[2])
+                .encrypted_u64(order.encrypted_order[3]);
+        }
+        for (signer_lo, _) in &signers {
+            args = args.plaintext_u128(*signer_lo);
+        }
+        for (_, signer_hi) in &signers {
+            args = args.plaintext_u128(*signer_hi);
+        }
+        args = args.plaintext_u8(order_count as u8);
+        for order in &slots {
+            args = args.plaintext_u64(order.staged_at);
+        }
+        let args = args
+            .plaintext_u128(batch.state_nonce)
+            .account(batch.key(), BATCH_ENCRYPTED_STATE_OFFSET, BATCH_ENCRYPTED_STATE_SIZE)
+            .build();
+
+        let payer_lamports = ctx.accounts.payer.lamports();
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![AddOrdersBulkCallback::callback_ix(
+                computation_offset,
+                &ctx.accounts.mxe_account,
+                &callback_accounts,
+            )?],
+            1,
+            0,
+        )?;
+
+        queue_batch_computation(
+            &mut ctx.accounts.batch,
+            ComputationKind::AddOrdersBulk,
+            ctx.accounts.computation_account.key(),
+            Clock::get()?.slot,
+        );
+
+        let fee = payer_lamports.saturating_sub(ctx.accounts.payer.lamports());
+        draw_compute_fee(ctx.accounts.compute_pool.as_mut(), &ctx.accounts.payer, fee)
+    }
+
+    #[arcium_callback(encrypted_ix = "add_orders_bulk")]
+    pub fn add_orders_bulk_callback<'info>(
+        ctx: Context<'_, '_, 'info, 'info, AddOrdersBulkCallback<'info>>,
+        output: SignedComputationOutputs<AddOrdersBulkOutput>,
+    ) -> Result<()> {
+        check_callback_origin(
+            ctx.accounts.batch.queued_computation,
+            &ctx.accounts.computation_account,
+        )?;
+
+        let o = match output.verify_output(
+            &ctx.accounts.cluster_account,
+            &ctx.accounts.computation_account,
+        ) {
+            Ok(AddOrdersBulkOutput { field_0 }) => field_0,
+            Err(_) => return Err(ErrorCode::AbortedComputation.into()),
+        };
+
+        let batch = &mut ctx.accounts.batch;
+        let first_index = batch.order_count;
+        let folded = (ctx.remaining_accounts.len() / 3) as u8;
+
+        batch.encrypted_state = o.ciphertexts;
+        batch.state_nonce = o.nonce;
+        batch.order_count = batch
+            .order_count
+            .checked_add(folded)
+            .ok_or(ErrorCode::OrderCountOverflow)?;
+        batch.staged_orders -= folded;
+
+        complete_batch_computation(batch, ComputationKind::AddOrdersBulk)?;
+
+        // (order, staged order, user) per folded order, as queued
+        for (i, accounts) in ctx.remaining_accounts.chunks(3).enumerate() {
+            let mut order = Account::<OrderCommitment>::try_from(&accounts[0])?;
+            let staged = Account::<StagedOrder>::try_from(&accounts[1])?;
+            let order_index = first_index + i as u8;
+            require!(
+                staged.order == order.key()
+                    && staged.user == accounts[2].key()
+                    && staged.index == order_index,
+                ErrorCode::StagedOrderMismatch
+            );
+
+            emit_order_result(
+                batch.key(),
+                &mut order,
+                order_index,
+                o.wallet_bound[i],
+                o.amount_overflow[i],
+                o.expired[i],
+            );
+            order.exit(&crate::ID)?;
+            staged.close(accounts[2].clone())?;
+        }
+
+        Ok(())
+    }
+
+    // ============================================
+    // Session Operations
+    // ============================================
+
+    /// Authorize an ephemeral key to place orders on the owner's behalf
+    ///
+    /// The key is scoped to one market and expires at `expiry_slot`. Its
+    /// total notional is capped by an encrypted budget spent in MPC.
+    pub fn create_session(
+        ctx: Context<CreateSession>,
+        computation_offset: u64,
+        session_key: Pubkey,
+        market_id: String,
+        expiry_slot: u64,
+        encrypted_max_notional: [u8; 32],
+        user_pubkey: [u8; 32],
+        nonce: u128,
+        budget_nonce: u128,
+    ) -> Result<()> {
+        check_computation_offset(&ctx.accounts.computation_account)?;
+
+        require!(
+            expiry_slot > Clock::get()?.slot,
+            ErrorCode::InvalidSessionExpiry
+        );
+
+        let session = &mut ctx.accounts.session;
+        session.bump = ctx.bumps.session;
+        session.owner = ctx.accounts.owner.key();
+        session.session_key = session_key;
+        session.budget_nonce = budget_nonce;
+        session.encrypted_budget = [[0u8; 32]; 1];
+        session.expiry_slot = expiry_slot;
+        session.pending_computation = true;
+        session.order_count = 0;
+        session.market_id = market_id;
+
+        let args = ArgBuilder::new()
+            .x25519_pubkey(user_pubkey)
+            .plaintext_u128(nonce)
+            .encrypted_u64(encrypted_max_notional)
+            .plaintext_u128(budget_nonce)
+            .build();
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![InitSessionCallback::callback_ix(
+                computation_offset,
+                &ctx.accounts.mxe_account,
+                &[CallbackAccount {
+                    pubkey: ctx.accounts.session.key(),
+                    is_writable: true,
+                }],
+            )?],
+            1,
+            0,
+        )?;
+
+        ctx.accounts.session.queued_computation = Some(ctx.accounts.computation_account.key());
+        ctx.accounts.session.queued_at = Clock::get()?.slot;
+
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "init_session")]
+    pub fn init_session_callback(
+        ctx: Context<InitSessionCallback>,
+        output: SignedComputationOutputs<InitSessionOutput>,
+    ) -> Result<()> {
+        check_callback_origin(
+            ctx.accounts.session.queued_computation,
+            &ctx.accounts.computation_account,
+        )?;
+
+        let o = match output.verify_output(
+            &ctx.accounts.cluster_account,
+            &ctx.accounts.computation_account,
+        ) {
+            Ok(InitSessionOutput { field_0 }) => field_0,
+            Err(_) => return Err(ErrorCode::AbortedComputation.into()),
+        };
+
+        let session = &mut ctx.accounts.session;
+        session.encrypted_budget = o.ciphertexts;
+        session.budget_nonce = o.nonce;
+        session.pending_computation = false;
+        session.queued_computation = None;
+
+        emit!(SessionCreated {
+            session: session.key(),
+            owner: session.owner,
+            session_key: session.session_key,
+            market_id: session.market_id.clone(),
+            expiry_slot: session.expiry_slot,
+        });
+
+        Ok(())
+    }
+
+    /// Add an order signed by a session key
+    ///
+    /// The order is recorded for the session owner. Batch gates are checked
+    /// against the owner, not the session key.
+    pub fn add_order_with_session(
+        ctx: Context<AddOrderWithSession>,
+        computation_offset: u64,
+        encrypted_amount: [u8; 32],
+        encrypted_wallet_lo: [u8; 32],
+        encrypted_wallet_hi: [u8; 32],
+        encrypted_expiry_slot: [u8; 32],
+        user_pubkey: [u8; 32],
+        nonce: u128,
+        commitment_hash: [u8; 32],
+    ) -> Result<()> {
+        check_computation_offset(&ctx.accounts.computation_account)?;
+
+        let session = &ctx.accounts.session;
+        require!(!session.pending_computation, ErrorCode::ComputationPending);
+        require!(
+            Clock::get()?.slot <= session.expiry_slot,
+            ErrorCode::SessionExpired
+        );
+
+        let batch = &ctx.accounts.batch;
+        require!(
+            batch.market_id == session.market_id,
+            ErrorCode::SessionScopeMismatch
+        );
+        require!(batch.status == BatchStatus::Open, ErrorCode::BatchNotOpen);
+        require!(batch.pending_computation.is_none(), ErrorCode::ComputationPending);
+        require!(batch.order_count < 32, ErrorCode::BatchFull);
+        require!(batch.lane_count == 0, ErrorCode::BatchUsesLanes);
+        require!(batch.staged_orders == 0, ErrorCode::StagedOrdersPending);
+
+        check_risk_profile(&ctx.accounts.risk_profile, &batch.market_id)?;
+
+        let eligibility_proof = check_order_gates(
+            batch,
+            ctx.accounts.attestation.as_ref(),
+            ctx.accounts.eligibility_proof.as_ref(),
+            &session.owner,
+        )?;
+
+        let order = &mut ctx.accounts.order;
+        order.bump = ctx.bumps.order;
+        order.batch = batch.key();
+        order.user = session.owner;
+        order.commitment_hash = commitment_hash;
+        order.index = batch.order_count;
+        order.allocated = false;
+        order.eligibility_proof = eligibility_proof;
+        order.rejected = false;
+
+        let (owner_lo, owner_hi) = split_pubkey(&session.owner);
+
+        let args = ArgBuilder::new()
+            .x25519_pubkey(user_pubkey)
+            .plaintext_u128(nonce)
+            .encrypted_u64(encrypted_amount)
+            .encrypted_u128(encrypted_wallet_lo)
+            .encrypted_u128(encrypted_wallet_hi)
+            .encrypted_u64(encrypted_expiry_slot)
+            .plaintext_u128(owner_lo)
+            .plaintext_u128(owner_hi)
+            .plaintext_u64(Clock::get()?.slot)
+            .plaintext_u128(batch.state_nonce)
+            .account(
+                ctx.accounts.batch.key(),
+                BATCH_ENCRYPTED_STATE_OFFSET,
+                BATCH_ENCRYPTED_STATE_SIZE,
+            )
+            .plaintext_u128(session.budget_nonce)
+            .account(
+                ctx.accounts.session.key(),
+                SESSION_ENCRYPTED_BUDGET_OFFSET,
+                SESSION_ENCRYPTED_BUDGET_SIZE,
+            )
+            .build();
+
+        let payer_lamports = ctx.accounts.session_key.lamports();
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![AddSessionOrderCallback::callback_ix(
+                computation_offset,
+                &ctx.accounts.mxe_account,
+                &[
+                    CallbackAccount {
+                        pubkey: ctx.accounts.batch.key(),
+                        is_writable: true,
+                    },
+                    CallbackAccount {
+                        pubkey: ctx.accounts.order.key(),
+                        is_writable: true,
+                    },
+                    CallbackAccount {
+                        pubkey: ctx.accounts.session.key(),
+                        is_writable: true,
+                    },
+                ],
+            )?],
+            1,
+            0,
+        )?;
+
+        let slot = Clock::get()?.slot;
+        queue_batch_computation(
+            &mut ctx.accounts.batch,
+            ComputationKind::AddOrder,
+            ctx.accounts.computation_account.key(),
+            slot,
+        );
+        // The budget is single-writer until the callback lands
+        ctx.accounts.session.pending_computation = true;
+        ctx.accounts.session.queued_computation = Some(ctx.accounts.computation_account.key());
+        ctx.accounts.session.queued_at = slot;
+
+        let fee = payer_lamports.saturating_sub(ctx.accounts.session_key.lamports());
+        draw_compute_fee(ctx.accounts.compute_pool.as_mut(), &ctx.accounts.session_key, fee)?;
+        refund_order_rent(
+            ctx.accounts.rent_sponsor.as_mut(),
+            &ctx.accounts.session_key,
+            ctx.accounts.order.key(),
+        )
+    }
+
+    #[arcium_callback(encrypted_ix = "add_session_order")]
+    pub fn add_session_order_callback(
+        ctx: Context<AddSessionOrderCallback>,
+        output: SignedComputationOutputs<AddSessionOrderOutput>,
+    ) -> Result<()> {
+        check_callback_origin(
+            ctx.accounts.batch.queued_computation,
+            &ctx.accounts.computation_account,
+        )?;
+
+        let (
+            ciphertexts,
+            nonce,
+            budget_ciphertexts,
+            budget_nonce,
+            wallet_bound,
+            amount_overflow,
+            expired,
+        ) = match output
+            .verify_output(&ctx.accounts.cluster_account, &ctx.accounts.computation_account)
+        {
+            Ok(AddSessionOrderOutput {
+                field_0: AddSessionOrderOutputStruct0 {
+                    field_0: ciphertexts,
+                    field_1: nonce,
+                    field_2: budget_ciphertexts,
+                    field_3: budget_nonce,
+                    field_4: wallet_bound,
+                    field_5: amount_overflow,
+                    field_6: expired,
+                },
+            }) => (
+                ciphertexts,
+                nonce,
+                budget_ciphertexts,
+                budget_nonce,
+                wallet_bound,
+                amount_overflow,
+                expired,
+            ),
+            Err(_) => return Err(ErrorCode::AbortedComputation.into()),
+        };
+
+        let session = &mut ctx.accounts.session;
+        session.encrypted_budget = budget_ciphertexts;
+        session.budget_nonce = budget_nonce;
+        session.order_count = session
+            .order_count
+            .checked_add(1)
+            .ok_or(ErrorCode::OrderCountOverflow)?;
+        session.pending_computation = false;
+        session.queued_computation = None;
+
+        let batch = &mut ctx.accounts.batch;
+        batch.encrypted_state = ciphertexts;
+        batch.state_nonce = nonce;
+        batch.order_count = batch
+            .order_count
+            .checked_add(1)
+            .ok_or(ErrorCode::OrderCountOverflow)?;
+
+        complete_batch_computation(batch, ComputationKind::AddOrder)?;
+
+        emit_order_result(
+            batch.key(),
+            &mut ctx.accounts.order,
+            batch.order_count - 1,
+            wallet_bound,
+            amount_overflow,
+            expired,
+        );
+
+        Ok(())
+    }
+
+    /// Revoke a session key and reclaim its rent
+    ///
+    /// A session whose computation never called back can be revoked after
+    /// `COMPUTATION_TIMEOUT_SLOTS`; closing it makes a late callback fail.
+    pub fn revoke_session(ctx: Context<RevokeSession>) -> Result<()> {
+        let session = &ctx.accounts.session;
+        require!(
+            !session.pending_computation
+                || Clock::get()?.slot >= session.queued_at.saturating_add(COMPUTATION_TIMEOUT_SLOTS),
+            ErrorCode::ComputationPending
+        );
+
+        emit!(SessionRevoked {
+            session: ctx.accounts.session.key(),
+            owner: ctx.accounts.owner.key(),
+            session_key: ctx.accounts.session.session_key,
+        });
+
+        Ok(())
+    }
+
+    // ============================================
+    // Distribution Operations
+    // ============================================
+
+    /// Create a merkle distribution and fund its vault
+    ///
+    /// Leaves are `distribution_leaf(index, claimant, amount)` with indices
+    /// in `0..max_claims`. With `uniqueness_epoch` set, claims must go through
+    /// `claim_distribution_unique`.
+    ///
+    /// The mint may be SPL Token or Token-2022. Accounts a transfer hook
+    /// needs go in remaining accounts, and `memo` (e.g. market id and
+    /// batch) is written with SPL Memo just before the vault transfer.
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_distribution<'info>(
+        ctx: Context<'_, '_, '_, 'info, CreateDistribution<'info>>,
+        distribution_id: u64,
+        max_claims: u32,
+        merkle_root: [u8; 32],
+        total_amount: u64,
+        uniqueness_epoch: Option<u64>,
+        memo: Option<String>,
+    ) -> Result<()> {
+        require!(
+            max_claims > 0 && max_claims <= MAX_DISTRIBUTION_CLAIMS,
+            ErrorCode::InvalidDistribution
+        );
+        require!(total_amount > 0, ErrorCode::InvalidDistribution);
+
+        let distributor = &mut ctx.accounts.distributor;
+        distributor.bump = ctx.bumps.distributor;
+        distributor.authority = ctx.accounts.authority.key();
+        distributor.distribution_id = distribution_id;
+        distributor.mint = ctx.accounts.mint.key();
+        distributor.vault = ctx.accounts.vault.key();
+        distributor.merkle_root = merkle_root;
+        distributor.total_amount = total_amount;
+        distributor.claimed_amount = 0;
+        distributor.max_claims = max_claims;
+        distributor.claim_count = 0;
+        distributor.claimed_bitmap = vec![0u8; MerkleDistributor::bitmap_len(max_claims)];
+        distributor.uniqueness_epoch = uniqueness_epoch;
+
+        vault_transfer(
+            VaultTransfer {
+                token_program: &ctx.accounts.token_program,
+                mint: &ctx.accounts.mint,
+                from: ctx.accounts.authority_token_account.to_account_info(),
+                to: ctx.accounts.vault.to_account_info(),
+                authority: ctx.accounts.authority.to_account_info(),
+                memo_program: ctx.accounts.memo_program.as_ref(),
+                hook_accounts: ctx.remaining_accounts,
+            },
+            &[],
+            total_amount,
+            memo.as_deref(),
+        )?;
+
+        emit!(DistributionCreated {
+            distributor: distributor.key(),
+            authority: distributor.authority,
+            mint: distributor.mint,
+            merkle_root,
+            total_amount,
+            max_claims,
+        });
+
+        Ok(())
+    }
+
+    /// Claim a leaf of a distribution with its merkle proof
+    pub fn claim_distribution<'info>(
+        ctx: Context<'_, '_, '_, 'info, ClaimDistribution<'info>>,
+        index: u32,
+        amount: u64,
+        proof: Vec<[u8; 32]>,
+        memo: Option<String>,
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.distributor.uniqueness_epoch.is_none(),
+            ErrorCode::UniquenessRequired
+        );
+
+        let distributor_info = ctx.accounts.distributor.to_account_info();
+        pay_distribution_claim(
+            &mut ctx.accounts.distributor,
+            VaultTransfer {
+                token_program: &ctx.accounts.token_program,
+                mint: &ctx.accounts.mint,
+                from: ctx.accounts.vault.to_account_info(),
+                to: ctx.accounts.claimant_token_account.to_account_info(),
+                authority: distributor_info,
+                memo_program: ctx.accounts.memo_program.as_ref(),
+                hook_accounts: ctx.remaining_accounts,
+            },
+            None,
+            ctx.accounts.claimant.key(),
+            index,
+            amount,
+            &proof,
+            memo.as_deref(),
+        )
+    }
+
+    /// Claim from a sybil-gated distribution
+    ///
+    /// Requires a zk-verifier `UniquenessAttestation` for the distribution's
+    /// epoch, attested under the distributor authority's pinned key and
+    /// bound to the claimant. Each identity commitment claims once
+    /// per distribution, whichever wallet it attested with.
+    pub fn claim_distribution_unique<'info>(
+        ctx: Context<'_, '_, '_, 'info, ClaimDistributionUnique<'info>>,
+        index: u32,
+        amount: u64,
+        proof: Vec<[u8; 32]>,
+        identity_commitment: [u8; 32],
+        memo: Option<String>,
+    ) -> Result<()> {
+        let epoch = ctx
+            .accounts
+            .distributor
+            .uniqueness_epoch
+            .ok_or(ErrorCode::InvalidDistribution)?;
+
+        let attestation: UniquenessAttestationData = read_zk_verifier_account(
+            &ctx.accounts.attestation,
+            "UniquenessAttestation",
+        )
+        .map_err(|_| ErrorCode::InvalidUniquenessAttestation)?;
+        require!(
+            attestation.epoch == epoch
+                && attestation.identity_commitment == identity_commitment
+                && attestation.wallet == ctx.accounts.claimant.key()
+                && attestation.authority == ctx.accounts.distributor.authority
+                && attestation.proof_system == ZK_PROOF_SYSTEM_TEE_ATTESTATION,
+            ErrorCode::InvalidUniquenessAttestation
+        );
+
+        let uniqueness_claim = &mut ctx.accounts.uniqueness_claim;
+        uniqueness_claim.bump = ctx.bumps.uniqueness_claim;
+        uniqueness_claim.distributor = ctx.accounts.distributor.key();
+        uniqueness_claim.identity_commitment = identity_commitment;
+        uniqueness_claim.claimant = ctx.accounts.claimant.key();
+
+        let distributor_info = ctx.accounts.distributor.to_account_info();
+        pay_distribution_claim(
+            &mut ctx.accounts.distributor,
+            VaultTransfer {
+                token_program: &ctx.accounts.token_program,
+                mint: &ctx.accounts.mint,
+                from: ctx.accounts.vault.to_account_info(),
+                to: ctx.accounts.claimant_token_account.to_account_info(),
+                authority: distributor_info,
+                memo_program: ctx.accounts.memo_program.as_ref(),
+                hook_accounts: ctx.remaining_accounts,
+            },
+            None,
+            ctx.accounts.claimant.key(),
+            index,
+            amount,
+            &proof,
+            memo.as_deref(),
+        )
+    }
+
+    /// Claim a leaf for `claimant` with their signed voucher, paid for by
+    /// a relayer
+    ///
+    /// The claimant signs `trading_rules::claim_voucher_digest(distributor,
+    /// index, amount, fee, fee_account)` off-chain, so a winner without SOL
+    /// can still be paid; the ed25519 program instruction carrying that
+    /// signature must come right before this one. `fee` of the claim goes
+    /// to `fee_account`, the relayer token account the claimant signed
+    /// for, and the rest to the claimant's own token account.
+    #[allow(clippy::too_many_arguments)]
+    pub fn claim_distribution_voucher<'info>(
+        ctx: Context<'_, '_, '_, 'info, ClaimDistributionVoucher<'info>>,
+        claimant: Pubkey,
+        index: u32,
+        amount: u64,
+        proof: Vec<[u8; 32]>,
+        fee: u64,
+        fee_account: Pubkey,
+        memo: Option<String>,
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.distributor.uniqueness_epoch.is_none(),
+            ErrorCode::UniquenessRequired
+        );
+        require!(fee < amount, ErrorCode::InvalidVoucherFee);
+        require_keys_eq!(
+            ctx.accounts.claimant_token_account.owner,
+            claimant,
+            ErrorCode::InvalidVoucher
+        );
+        require_keys_eq!(
+            ctx.accounts.relayer_token_account.key(),
+            fee_account,
+            ErrorCode::InvalidVoucher
+        );
+
+        let digest = trading_rules::claim_voucher_digest(
+            &ctx.accounts.distributor.key().to_bytes(),
+            index,
+            amount,
+            fee,
+            &fee_account.to_bytes(),
+        );
+        verify_voucher_signature(&ctx.accounts.instructions, &claimant, &digest)?;
+
+        let distributor_info = ctx.accounts.distributor.to_account_info();
+        pay_distribution_claim(
+            &mut ctx.accounts.distributor,
+            VaultTransfer {
+                token_program: &ctx.accounts.token_program,
+                mint: &ctx.accounts.mint,
+                from: ctx.accounts.vault.to_account_info(),
+                to: ctx.accounts.claimant_token_account.to_account_info(),
+                authority: distributor_info.clone(),
+                memo_program: ctx.accounts.memo_program.as_ref(),
+                hook_accounts: ctx.remaining_accounts,
+            },
+            Some((
+                VaultTransfer {
+                    token_program: &ctx.accounts.token_program,
+                    mint: &ctx.accounts.mint,
+                    from: ctx.accounts.vault.to_account_info(),
+                    to: ctx.accounts.relayer_token_account.to_account_info(),
+                    authority: distributor_info,
+                    memo_program: None,
+                    hook_accounts: ctx.remaining_accounts,
+                },
+                fee,
+            )),
+            claimant,
+            index,
+            amount,
+            &proof,
+            memo.as_deref(),
+        )?;
+
+        emit!(VoucherRedeemed {
+            distributor: ctx.accounts.distributor.key(),
+            claimant,
+            relayer: ctx.accounts.relayer.key(),
+            index,
+            fee,
+        });
+
+        Ok(())
+    }
+
+    // ============================================
+    // External Execution
+    // ============================================
+
+    /// Register the venue adapter a market's executed batches route to
+    ///
+    /// `adapter_config` is opaque to this program and handed to the adapter
+    /// on every `execute` call (e.g. the venue market to trade on).
+    pub fn register_execution_adapter(
+        ctx: Context<RegisterExecutionAdapter>,
+        market_id: String,
+        program_id: Pubkey,
+        adapter_config: Vec<u8>,
+    ) -> Result<()> {
+        require!(
+            adapter_config.len() <= MAX_ADAPTER_CONFIG_LEN,
+            ErrorCode::InvalidAdapterConfig
+        );
+        require!(
+            program_id != crate::ID && program_id != ZK_VERIFIER_PROGRAM_ID,
+            ErrorCode::InvalidAdapterProgram
+        );
+
+        let adapter = &mut ctx.accounts.adapter;
+        adapter.bump = ctx.bumps.adapter;
+        adapter.authority = ctx.accounts.authority.key();
+        adapter.market_id = market_id;
+        adapter.program_id = program_id;
+        adapter.config = adapter_config;
+
+        emit!(ExecutionAdapterRegistered {
+            adapter: adapter.key(),
+            authority: adapter.authority,
+            market_id: adapter.market_id.clone(),
+            program_id,
+        });
+
+        Ok(())
+    }
+
+    /// Remove a market's execution adapter so another can be registered
+    pub fn remove_execution_adapter(ctx: Context<RemoveExecutionAdapter>) -> Result<()> {
+        emit!(ExecutionAdapterRemoved {
+            adapter: ctx.accounts.adapter.key(),
+            market_id: ctx.accounts.adapter.market_id.clone(),
+        });
+
+        Ok(())
+    }
+
+    /// Route an executed batch's revealed size to the market's adapter
+    ///
+    /// CPIs `execution::ExecuteRequest` into the adapter with the remaining
+    /// accounts passed through as-is (signers stay signers, so the authority
+    /// can fund the venue order), then records the adapter's
+    /// `execution::ExecutionReport` from return data. A batch routes once.
+    pub fn route_batch<'info>(
+        ctx: Context<'_, '_, '_, 'info, RouteBatch<'info>>,
+        limit_price: u64,
+    ) -> Result<()> {
+        let batch = &ctx.accounts.batch;
+        require!(
+            matches!(batch.status, BatchStatus::Executed | BatchStatus::Verified),
+            ErrorCode::BatchNotExecuted
+        );
+
+        let adapter = &ctx.accounts.adapter;
+        require_keys_eq!(
+            ctx.accounts.adapter_program.key(),
+            adapter.program_id,
+            ErrorCode::InvalidAdapterProgram
+        );
+
+        let request = execution::ExecuteRequest {
+            batch: batch.key(),
+            net_size: batch.total_usdc,
+            side: batch.side,
+            limit_price,
+            config: adapter.config.clone(),
+        };
+        let report = execution::execute(
+            &ctx.accounts.adapter_program,
+            ctx.remaining_accounts,
+            &request,
+        )?;
+        require!(
+            report.filled_size <= request.net_size,
+            ErrorCode::InvalidExecutionReport
+        );
+        require!(
+            report.filled_size == 0 || report.average_price <= limit_price,
+            ErrorCode::LimitPriceExceeded
+        );
+
+        let fill = &mut ctx.accounts.fill;
+        fill.bump = ctx.bumps.fill;
+        fill.batch = batch.key();
+        fill.adapter_program = adapter.program_id;
+        fill.net_size = request.net_size;
+        fill.filled_size = report.filled_size;
+        fill.average_price = report.average_price;
+        fill.limit_price = limit_price;
+        fill.routed_at = Clock::get()?.slot;
+
+        emit!(BatchRouted {
+            batch: batch.key(),
+            adapter_program: adapter.program_id,
+            net_size: request.net_size,
+            filled_size: report.filled_size,
+            average_price: report.average_price,
+        });
+
+        Ok(())
+    }
+
+    // ============================================
+    // Rent Sponsorship
+    // ============================================
+
+    /// Open a rent sponsor for the authority's batches
+    ///
+    /// Anyone may top the sponsor up with a plain lamport transfer. Orders
+    /// placed in the authority's batches that pass it get their
+    /// `OrderCommitment` rent refunded, so users only front the computation
+    /// fee. Sessions aren't sponsored: revoking one returns its rent to the
+    /// owner.
+    pub fn create_rent_sponsor(ctx: Context<CreateRentSponsor>) -> Result<()> {
+        let sponsor = &mut ctx.accounts.rent_sponsor;
+        sponsor.bump = ctx.bumps.rent_sponsor;
+        sponsor.authority = ctx.accounts.authority.key();
+        sponsor.refunded = 0;
+        sponsor.sponsored_orders = 0;
+        Ok(())
+    }
+
+    /// Close a rent sponsor, returning its balance to the authority
+    pub fn close_rent_sponsor(_ctx: Context<CloseRentSponsor>) -> Result<()> {
+        Ok(())
+    }
+
+    // ============================================
+    // Confidential Oracle Operations
+    // ============================================
+
+    /// Open a confidential oracle resolving `market_id` on private data
+    ///
+    /// Each whitelisted provider submits one encrypted observation; the
+    /// `resolve_oracle` circuit takes their median and reveals only whether
+    /// it reached `threshold`. No single provider's value is published.
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_oracle(
+        ctx: Context<CreateOracle>,
+        computation_offset: u64,
+        oracle_id: u64,
+        market_id: String,
+        providers: Vec<Pubkey>,
+        min_observations: u8,
+        threshold: u64,
+        window_slots: u64,
+        state_nonce: u128,
+    ) -> Result<()> {
+        check_computation_offset(&ctx.accounts.computation_account)?;
+
+        require!(
+            !providers.is_empty() && providers.len() <= MAX_ORACLE_PROVIDERS,
+            ErrorCode::InvalidProviderSet
+        );
+        require!(
+            providers
+                .iter()
+                .enumerate()
+                .all(|(i, provider)| !providers[..i].contains(provider)),
+            ErrorCode::InvalidProviderSet
+        );
+        require!(
+            min_observations > 0 && min_observations as usize <= providers.len(),
+            ErrorCode::InvalidProviderSet
+        );
+        require!(window_slots > 0, ErrorCode::InvalidOracleWindow);
+
+        let window_end_slot = Clock::get()?
+            .slot
+            .checked_add(window_slots)
+            .ok_or(ErrorCode::InvalidOracleWindow)?;
+
+        let oracle = &mut ctx.accounts.oracle;
+        oracle.bump = ctx.bumps.oracle;
+        oracle.creator = ctx.accounts.creator.key();
+        oracle.state_nonce = state_nonce;
+        oracle.encrypted_state = [[0u8; 32]; MAX_ORACLE_PROVIDERS];
+        oracle.oracle_id = oracle_id;
+        oracle.market_id = market_id;
+        oracle.providers = providers;
+        oracle.submitted_mask = 0;
+        oracle.observation_count = 0;
+        oracle.min_observations = min_observations;
+        oracle.threshold = threshold;
+        oracle.window_end_slot = window_end_slot;
+        oracle.status = OracleStatus::Open;
+        oracle.pending_computation = true;
+        oracle.queued_computation = None;
+        oracle.outcome = None;
+
+        let args = ArgBuilder::new().plaintext_u128(state_nonce).build();
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![InitOracleCallback::callback_ix(
+                computation_offset,
+                &ctx.accounts.mxe_account,
+                &[CallbackAccount {
+                    pubkey: ctx.accounts.oracle.key(),
+                    is_writable: true,
+                }],
+            )?],
+            1,
+            0,
+        )?;
+
+        ctx.accounts.oracle.queued_computation = Some(ctx.accounts.computation_account.key());
+
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "init_oracle")]
+    pub fn init_oracle_callback(
+        ctx: Context<InitOracleCallback>,
+        output: SignedComputationOutputs<InitOracleOutput>,
+    ) -> Result<()> {
+        check_callback_origin(
+            ctx.accounts.oracle.queued_computation,
+            &ctx.accounts.computation_account,
+        )?;
+
+        let o = match output.verify_output(
+            &ctx.accounts.cluster_account,
+            &ctx.accounts.computation_account,
+        ) {
+            Ok(InitOracleOutput { field_0 }) => field_0,
+            Err(_) => return Err(ErrorCode::AbortedComputation.into()),
+        };
+
+        let oracle = &mut ctx.accounts.oracle;
+        oracle.encrypted_state = o.ciphertexts;
+        oracle.state_nonce = o.nonce;
+        oracle.pending_computation = false;
+        oracle.queued_computation = None;
+
+        emit!(OracleCreated {
+            oracle: oracle.key(),
+            creator: oracle.creator,
+            market_id: oracle.market_id.clone(),
+            providers: oracle.providers.clone(),
+            min_observations: oracle.min_observations,
+            window_end_slot: oracle.window_end_slot,
+        });
+
+        Ok(())
+    }
+
+    /// Submit an encrypted observation as a whitelisted provider
+    pub fn submit_observation(
+        ctx: Context<SubmitObservation>,
+        computation_offset: u64,
+        encrypted_value: [u8; 32],
+        provider_pubkey: [u8; 32],
+        nonce: u128,
+    ) -> Result<()> {
+        check_computation_offset(&ctx.accounts.computation_account)?;
+
+        let oracle = &ctx.accounts.oracle;
+        require!(oracle.status == OracleStatus::Open, ErrorCode::OracleNotOpen);
+        require!(!oracle.pending_computation, ErrorCode::ComputationPending);
+        require!(
+            Clock::get()?.slot <= oracle.window_end_slot,
+            ErrorCode::OracleWindowClosed
+        );
+
+        let provider_index = oracle
+            .providers
+            .iter()
+            .position(|provider| *provider == ctx.accounts.provider.key())
+            .ok_or(ErrorCode::ProviderNotWhitelisted)?;
+        require!(
+            oracle.submitted_mask & (1 << provider_index) == 0,
+            ErrorCode::ObservationAlreadySubmitted
+        );
+
+        let args = ArgBuilder::new()
+            .x25519_pubkey(provider_pubkey)
+            .plaintext_u128(nonce)
+            .encrypted_u64(encrypted_value)
+            .plaintext_u8(provider_index as u8)
+            .plaintext_u128(oracle.state_nonce)
+            .account(
+                ctx.accounts.oracle.key(),
+                ORACLE_ENCRYPTED_STATE_OFFSET,
+                ORACLE_ENCRYPTED_STATE_SIZE,
+            )
+            .build();
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![SubmitObservationCallback::callback_ix(
+                computation_offset,
+                &ctx.accounts.mxe_account,
+                &[CallbackAccount {
+                    pubkey: ctx.accounts.oracle.key(),
+                    is_writable: true,
+                }],
+            )?],
+            1,
+            0,
+        )?;
+
+        // Single writer until the callback lands, as with RFQ quotes
+        let oracle = &mut ctx.accounts.oracle;
+        oracle.submitted_mask |= 1 << provider_index;
+        oracle.pending_computation = true;
+        oracle.queued_computation = Some(ctx.accounts.computation_account.key());
+
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "submit_observation")]
+    pub fn submit_observation_callback(
+        ctx: Context<SubmitObservationCallback>,
+        output: SignedComputationOutputs<SubmitObservationOutput>,
+    ) -> Result<()> {
+        check_callback_origin(
+            ctx.accounts.oracle.queued_computation,
+            &ctx.accounts.computation_account,
+        )?;
+
+        let o = match output.verify_output(
+            &ctx.accounts.cluster_account,
+            &ctx.accounts.computation_account,
+        ) {
+            Ok(SubmitObservationOutput { field_0 }) => field_0,
+            Err(_) => return Err(ErrorCode::AbortedComputation.into()),
+        };
+
+        let oracle = &mut ctx.accounts.oracle;
+        oracle.encrypted_state = o.ciphertexts;
+        oracle.state_nonce = o.nonce;
+        oracle.observation_count += 1;
+        oracle.pending_computation = false;
+        oracle.queued_computation = None;
+
+        emit!(ObservationReceived {
+            oracle: oracle.key(),
+            observation_count: oracle.observation_count,
+        });
+
+        Ok(())
+    }
+
+    /// Resolve the oracle via MPC once the window closes (or every provider
+    /// submitted) with at least `min_observations`
+    ///
+    /// Permissionless, so a creator cannot sit on an unwelcome result.
+    pub fn resolve_oracle(ctx: Context<ResolveOracle>, computation_offset: u64) -> Result<()> {
+        check_computation_offset(&ctx.accounts.computation_account)?;
+
+        let oracle = &ctx.accounts.oracle;
+        require!(oracle.status == OracleStatus::Open, ErrorCode::OracleNotOpen);
+        require!(!oracle.pending_computation, ErrorCode::ComputationPending);
+        require!(
+            Clock::get()?.slot > oracle.window_end_slot
+                || oracle.observation_count as usize == oracle.providers.len(),
+            ErrorCode::OracleWindowOpen
+        );
+        require!(
+            oracle.observation_count >= oracle.min_observations,
+            ErrorCode::NotEnoughObservations
+        );
+
+        let args = ArgBuilder::new()
+            .plaintext_u64(oracle.threshold)
+            .plaintext_u8(oracle.submitted_mask)
+            .plaintext_u8(trading_rules::median_pad_high_mask(oracle.submitted_mask))
+            .plaintext_u128(oracle.state_nonce)
+            .account(
+                ctx.accounts.oracle.key(),
+                ORACLE_ENCRYPTED_STATE_OFFSET,
+                ORACLE_ENCRYPTED_STATE_SIZE,
+            )
+            .build();
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![ResolveOracleCallback::callback_ix(
+                computation_offset,
+                &ctx.accounts.mxe_account,
+                &[CallbackAccount {
+                    pubkey: ctx.accounts.oracle.key(),
+                    is_writable: true,
+                }],
+            )?],
+            1,
+            0,
+        )?;
+
+        ctx.accounts.oracle.pending_computation = true;
+        ctx.accounts.oracle.queued_computation = Some(ctx.accounts.computation_account.key());
+
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "resolve_oracle")]
+    pub fn resolve_oracle_callback(
+        ctx: Context<ResolveOracleCallback>,
+        output: SignedComputationOutputs<ResolveOracleOutput>,
+    ) -> Result<()> {
+        check_callback_origin(
+            ctx.accounts.oracle.queued_computation,
+            &ctx.accounts.computation_account,
+        )?;
+
+        let outcome = match output.verify_output(
+            &ctx.accounts.cluster_account,
+            &ctx.accounts.computation_account,
+        ) {
+            Ok(ResolveOracleOutput {
+                field_0: ResolveOracleOutputStruct0 { field_0: outcome },
+            }) => outcome,
+            Err(_) => return Err(ErrorCode::AbortedComputation.into()),
+        };
+
+        let oracle = &mut ctx.accounts.oracle;
+        oracle.status = OracleStatus::Resolved;
+        oracle.pending_computation = false;
+        oracle.queued_computation = None;
+        oracle.outcome = Some(outcome);
+
+        emit!(OracleResolved {
+            oracle: oracle.key(),
+            market_id: oracle.market_id.clone(),
+            outcome,
+            observation_count: oracle.observation_count,
+        });
+
+        Ok(())
+    }
+
+    // ============================================
+    // Order Flow Auction Operations
+    // ============================================
+
+    /// Auction a closed batch's order flow to whitelisted makers
+    ///
+    /// Makers bid a price improvement on `reference_price` inside MPC, and a
+    /// bid only counts if it can take the batch's whole (encrypted) total.
+    /// The batch then executes at the improved price, so every participant
+    /// shares the improvement pro rata through the normal allocation.
+    pub fn create_flow_auction(
+        ctx: Context<CreateFlowAuction>,
+        computation_offset: u64,
+        makers: Vec<Pubkey>,
+        reference_price: u64,
+        window_slots: u64,
+        state_nonce: u128,
+    ) -> Result<()> {
+        check_computation_offset(&ctx.accounts.computation_account)?;
+
+        let batch = &ctx.accounts.batch;
+        require!(batch.status == BatchStatus::Closed, ErrorCode::BatchNotClosed);
+        require!(
+            batch.lane_count == 0 || batch.lanes_merged,
+            ErrorCode::LanesNotMerged
+        );
+        require!(
+            !makers.is_empty() && makers.len() <= MAX_FLOW_AUCTION_MAKERS,
+            ErrorCode::InvalidMakerSet
+        );
+        require!(
+            makers.iter().enumerate().all(|(i, maker)| !makers[..i].contains(maker)),
+            ErrorCode::InvalidMakerSet
+        );
+        require!(reference_price > 0, ErrorCode::InvalidReferencePrice);
+        require!(window_slots > 0, ErrorCode::InvalidFlowAuctionWindow);
+
+        let window_end_slot = Clock::get()?
+            .slot
+            .checked_add(window_slots)
+            .ok_or(ErrorCode::InvalidFlowAuctionWindow)?;
+
+        let auction = &mut ctx.accounts.flow_auction;
+        auction.bump = ctx.bumps.flow_auction;
+        auction.batch = ctx.accounts.batch.key();
+        auction.state_nonce = state_nonce;
+        auction.encrypted_state = [[0u8; 32]; 3];
+        auction.makers = makers;
+        auction.bid_mask = 0;
+        auction.bid_count = 0;
+        auction.reference_price = reference_price;
+        auction.window_end_slot = window_end_slot;
+        auction.status = FlowAuctionStatus::Open;
+        auction.pending_computation = true;
+        auction.queued_computation = None;
+        auction.winning_maker = None;
+        auction.improvement_bps = 0;
+
+        ctx.accounts.batch.flow_auction = Some(ctx.accounts.flow_auction.key());
+
+        let args = ArgBuilder::new().plaintext_u128(state_nonce).build();
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![InitFlowAuctionCallback::callback_ix(
+                computation_offset,
+                &ctx.accounts.mxe_account,
+                &[CallbackAccount {
+                    pubkey: ctx.accounts.flow_auction.key(),
+                    is_writable: true,
+                }],
+            )?],
+            1,
+            0,
+        )?;
+
+        ctx.accounts.flow_auction.queued_computation = Some(ctx.accounts.computation_account.key());
+
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "init_flow_auction")]
+    pub fn init_flow_auction_callback(
+        ctx: Context<InitFlowAuctionCallback>,
+        output: SignedComputationOutputs<InitFlowAuctionOutput>,
+    ) -> Result<()> {
+        check_callback_origin(
+            ctx.accounts.flow_auction.queued_computation,
+            &ctx.accounts.computation_account,
+        )?;
+
+        let o = match output.verify_output(
+            &ctx.accounts.cluster_account,
+            &ctx.accounts.computation_account,
+        ) {
+            Ok(InitFlowAuctionOutput { field_0 }) => field_0,
+            Err(_) => return Err(ErrorCode::AbortedComputation.into()),
+        };
+
+        let auction = &mut ctx.accounts.flow_auction;
+        auction.encrypted_state = o.ciphertexts;
+        auction.state_nonce = o.nonce;
+        auction.pending_computation = false;
+        auction.queued_computation = None;
+
+        emit!(FlowAuctionCreated {
+            flow_auction: auction.key(),
+            batch: auction.batch,
+            makers: auction.makers.clone(),
+            reference_price: auction.reference_price,
+            window_end_slot: auction.window_end_slot,
+        });
+
+        Ok(())
+    }
+
+    /// Submit an encrypted bid for the batch's flow as a whitelisted maker
+    pub fn submit_flow_bid(
+        ctx: Context<SubmitFlowBid>,
+        computation_offset: u64,
+        encrypted_improvement_bps: [u8; 32],
+        encrypted_capacity: [u8; 32],
+        maker_pubkey: [u8; 32],
+        nonce: u128,
+    ) -> Result<()> {
+        check_computation_offset(&ctx.accounts.computation_account)?;
+
+        let auction = &ctx.accounts.flow_auction;
+        require!(
+            auction.status == FlowAuctionStatus::Open,
+            ErrorCode::FlowAuctionNotOpen
+        );
+        require!(!auction.pending_computation, ErrorCode::ComputationPending);
+        require!(
+            Clock::get()?.slot <= auction.window_end_slot,
+            ErrorCode::FlowAuctionWindowClosed
+        );
+
+        let maker_index = auction
+            .makers
+            .iter()
+            .position(|maker| *maker == ctx.accounts.maker.key())
+            .ok_or(ErrorCode::MakerNotWhitelisted)?;
+        require!(
+            auction.bid_mask & (1 << maker_index) == 0,
+            ErrorCode::FlowBidAlreadySubmitted
+        );
+
+        let args = ArgBuilder::new()
+            .x25519_pubkey(maker_pubkey)
+            .plaintext_u128(nonce)
+            .encrypted_u64(encrypted_improvement_bps)
+            .encrypted_u64(encrypted_capacity)
+            .plaintext_u8(maker_index as u8)
+            .plaintext_u128(auction.state_nonce)
+            .account(
+                ctx.accounts.flow_auction.key(),
+                FLOW_AUCTION_ENCRYPTED_STATE_OFFSET,
+                FLOW_AUCTION_ENCRYPTED_STATE_SIZE,
+            )
+            .plaintext_u128(ctx.accounts.batch.state_nonce)
+            .account(
+                ctx.accounts.batch.key(),
+                BATCH_ENCRYPTED_STATE_OFFSET,
+                BATCH_ENCRYPTED_STATE_SIZE,
+            )
+            .build();
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![SubmitFlowBidCallback::callback_ix(
+                computation_offset,
+                &ctx.accounts.mxe_account,
+                &[CallbackAccount {
+                    pubkey: ctx.accounts.flow_auction.key(),
+                    is_writable: true,
+                }],
+            )?],
+            1,
+            0,
+        )?;
+
+        let auction = &mut ctx.accounts.flow_auction;
+        auction.bid_mask |= 1 << maker_index;
+        auction.pending_computation = true;
+        auction.queued_computation = Some(ctx.accounts.computation_account.key());
+
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "submit_flow_bid")]
+    pub fn submit_flow_bid_callback(
+        ctx: Context<SubmitFlowBidCallback>,
+        output: SignedComputationOutputs<SubmitFlowBidOutput>,
+    ) -> Result<()> {
+        check_callback_origin(
+            ctx.accounts.flow_auction.queued_computation,
+            &ctx.accounts.computation_account,
+        )?;
+
+        let o = match output.verify_output(
+            &ctx.accounts.cluster_account,
+            &ctx.accounts.computation_account,
+        ) {
+            Ok(SubmitFlowBidOutput { field_0 }) => field_0,
+            Err(_) => return Err(ErrorCode::AbortedComputation.into()),
+        };
+
+        let auction = &mut ctx.accounts.flow_auction;
+        auction.encrypted_state = o.ciphertexts;
+        auction.state_nonce = o.nonce;
+        auction.bid_count += 1;
+        auction.pending_computation = false;
+        auction.queued_computation = None;
+
+        emit!(FlowBidReceived {
+            flow_auction: auction.key(),
+            bid_count: auction.bid_count,
+        });
+
+        Ok(())
+    }
+
+    /// Reveal the winning maker and improvement once bidding is over
+    pub fn settle_flow_auction(
+        ctx: Context<SettleFlowAuction>,
+        computation_offset: u64,
+    ) -> Result<()> {
+        check_computation_offset(&ctx.accounts.computation_account)?;
+
+        let auction = &ctx.accounts.flow_auction;
+        require!(
+            auction.status == FlowAuctionStatus::Open,
+            ErrorCode::FlowAuctionNotOpen
+        );
+        require!(!auction.pending_computation, ErrorCode::ComputationPending);
+        require!(
+            Clock::get()?.slot > auction.window_end_slot
+                || auction.bid_count as usize == auction.makers.len(),
+            ErrorCode::FlowAuctionWindowOpen
+        );
+
+        let args = ArgBuilder::new()
+            .plaintext_u128(auction.state_nonce)
+            .account(
+                ctx.accounts.flow_auction.key(),
+                FLOW_AUCTION_ENCRYPTED_STATE_OFFSET,
+                FLOW_AUCTION_ENCRYPTED_STATE_SIZE,
+            )
+            .build();
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![SettleFlowAuctionCallback::callback_ix(
+                computation_offset,
+                &ctx.accounts.mxe_account,
+                &[CallbackAccount {
+                    pubkey: ctx.accounts.flow_auction.key(),
+                    is_writable: true,
+                }],
+            )?],
+            1,
+            0,
+        )?;
+
+        ctx.accounts.flow_auction.pending_computation = true;
+        ctx.accounts.flow_auction.queued_computation = Some(ctx.accounts.computation_account.key());
+
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "settle_flow_auction")]
+    pub fn settle_flow_auction_callback(
+        ctx: Context<SettleFlowAuctionCallback>,
+        output: SignedComputationOutputs<SettleFlowAuctionOutput>,
+    ) -> Result<()> {
+        check_callback_origin(
+            ctx.accounts.flow_auction.queued_computation,
+            &ctx.accounts.computation_account,
+        )?;
+
+        let (has_bid, best_maker, improvement_bps) = match output.verify_output(
+            &ctx.accounts.cluster_account,
+            &ctx.accounts.computation_account,
+        ) {
+            Ok(SettleFlowAuctionOutput {
+                field_0: SettleFlowAuctionOutputStruct0 {
+                    field_0: has_bid,
+                    field_1: best_maker,
+                    field_2: improvement_bps,
+                },
+            }) => (has_bid, best_maker, improvement_bps),
+            Err(_) => return Err(ErrorCode::AbortedComputation.into()),
+        };
+
+        let auction = &mut ctx.accounts.flow_auction;
+        auction.status = FlowAuctionStatus::Settled;
+        auction.pending_computation = false;
+        auction.queued_computation = None;
+        auction.winning_maker = if has_bid {
+            auction.makers.get(best_maker as usize).copied()
+        } else {
+            None
+        };
+        // No winner leaves the reference price untouched
+        auction.improvement_bps = if auction.winning_maker.is_some() {
+            improvement_bps
+        } else {
+            0
+        };
+
+        emit!(FlowAuctionSettled {
+            flow_auction: auction.key(),
+            batch: auction.batch,
+            winning_maker: auction.winning_maker,
+            improvement_bps: auction.improvement_bps,
+            execution_price: improved_price(auction.reference_price, auction.improvement_bps),
+        });
+
+        Ok(())
+    }
+
+    // ============================================
+    // Computation Recovery
+    // ============================================
+
+    /// Give up on a batch computation whose callback never landed
+    ///
+    /// After `COMPUTATION_TIMEOUT_SLOTS` the authority can clear it so the
+    /// batch can move again. A stuck `add_order` has its order commitment
+    /// closed (rent to the order's user; session, risk profile and house
+    /// account released), which frees the slot and makes a late callback
+    /// fail. A stuck `add_orders_bulk` leaves its orders staged for the next
+    /// one, and a stuck `simulate_execute_batch` is just dropped. A stuck
+    /// `execute_batch` can then be queued again, and a stuck `init_batch`
+    /// through `requeue_init_batch`. Re-queued work runs on whichever
+    /// cluster the MXE is assigned by then, which is how a failover cluster
+    /// picks it up. A session or risk profile the authority does not pass
+    /// is released by its owner (`revoke_session`, `release_risk_profile`).
+    pub fn abandon_computation(ctx: Context<AbandonComputation>) -> Result<()> {
+        let batch = &ctx.accounts.batch;
+        let computation = batch
+            .pending_computation
+            .ok_or(ErrorCode::NoPendingComputation)?;
+        let abandoned_at = Clock::get()?.slot;
+        require!(
+            abandoned_at >= batch.queued_at.saturating_add(COMPUTATION_TIMEOUT_SLOTS),
+            ErrorCode::ComputationNotStale
+        );
+
+        if computation == ComputationKind::AddOrder {
+            let order = ctx
+                .accounts
+                .order
+                .as_ref()
+                .ok_or(ErrorCode::PendingOrderRequired)?;
+            let order_user = ctx
+                .accounts
+                .order_user
+                .as_ref()
+                .ok_or(ErrorCode::PendingOrderRequired)?;
+            require_keys_eq!(order_user.key(), order.user, ErrorCode::Unauthorized);
+
+            // Each must be pending on this very computation, so one left
+            // pending by another batch is not released
+            if let Some(session) = ctx.accounts.session.as_mut() {
+                require!(
+                    session.owner == order.user
+                        && session.pending_computation
+                        && session.queued_computation == batch.queued_computation,
+                    ErrorCode::SessionScopeMismatch
+                );
+                session.pending_computation = false;
+                session.queued_computation = None;
+            }
+            if let Some(profile) = ctx.accounts.risk_profile.as_mut() {
+                require!(
+                    profile.owner == order.user
+                        && profile.pending_computation
+                        && profile.queued_computation == batch.queued_computation,
+                    ErrorCode::RiskProfileMismatch
+                );
+                profile.pending_computation = false;
+                profile.queued_computation = None;
+            }
+            if let Some(house) = ctx.accounts.house_account.as_mut() {
+                require!(
+                    house.desk == order.user
+                        && house.queued_computation == batch.queued_computation,
+                    ErrorCode::HouseAccountMismatch
+                );
+                house.queued_computation = None;
+            }
+
+            order.close(order_user.to_account_info())?;
+        }
+
+        let batch = &mut ctx.accounts.batch;
+        batch.pending_computation = None;
+        batch.queued_computation = None;
+
+        emit!(ComputationAbandoned {
+            batch: batch.key(),
+            computation,
+            queued_at: batch.queued_at,
+            abandoned_at,
+        });
+
+        Ok(())
+    }
+
+    /// Queue `init_batch` again for a batch whose first one was abandoned
+    pub fn requeue_init_batch(
+        ctx: Context<RequeueInitBatch>,
+        computation_offset: u64,
+        nonce: u128,
+    ) -> Result<()> {
+        check_computation_offset(&ctx.accounts.computation_account)?;
+
+        let batch = &mut ctx.accounts.batch;
+        require!(batch.completed_at == 0, ErrorCode::BatchAlreadyInitialized);
+        require!(batch.pending_computation.is_none(), ErrorCode::ComputationPending);
+
+        batch.state_nonce = nonce;
+        queue_batch_computation(
+            batch,
+            ComputationKind::InitBatch,
+            ctx.accounts.computation_account.key(),
+            Clock::get()?.slot,
+        );
+
+        let args = ArgBuilder::new()
+            .plaintext_u128(nonce)
+            .build();
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![InitBatchCallback::callback_ix(
+                computation_offset,
+                &ctx.accounts.mxe_account,
+                &[CallbackAccount {
+                    pubkey: ctx.accounts.batch.key(),
+                    is_writable: true,
+                }],
+            )?],
+            1,
+            0,
+        )?;
+
+        Ok(())
+    }
+
+    // ============================================
+    // Parlay Operations
+    // ============================================
+
+    /// Place one encrypted order across 2 to `MAX_PARLAY_LEGS` batches
+    ///
+    /// The amount is split by encrypted weights (bps, summing to 10_000), so
+    /// observers see which batches a parlay touches but not how it leans.
+    /// `init_parlay` binds the wallet, checks the expiry and weights, and
+    /// zeroes a rejected order; each leg is then folded into its batch with
+    /// `add_parlay_leg`. The parlay wins only if every leg does.
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_parlay(
+        ctx: Context<CreateParlay>,
+        computation_offset: u64,
+        parlay_id: u64,
+        batches: Vec<Pubkey>,
+        encrypted_amount: [u8; 32],
+        encrypted_weights: [[u8; 32]; MAX_PARLAY_LEGS],
+        encrypted_wallet_lo: [u8; 32],
+        encrypted_wallet_hi: [u8; 32],
+        encrypted_expiry_slot: [u8; 32],
+        user_pubkey: [u8; 32],
+        nonce: u128,
+        state_nonce: u128,
+    ) -> Result<()> {
+        check_computation_offset(&ctx.accounts.computation_account)?;
+        check_encryption_key(&ctx.accounts.encryption_key, &user_pubkey)?;
+
+        require!(
+            batches.len() >= 2 && batches.len() <= MAX_PARLAY_LEGS,
+            ErrorCode::InvalidParlayLegs
+        );
+        require!(
+            batches
+                .iter()
+                .enumerate()
+                .all(|(i, batch)| !batches[..i].contains(batch)),
+            ErrorCode::InvalidParlayLegs
+        );
+
+        let leg_count = batches.len() as u8;
+
+        let parlay = &mut ctx.accounts.parlay;
+        parlay.bump = ctx.bumps.parlay;
+        parlay.user = ctx.accounts.user.key();
+        parlay.state_nonce = state_nonce;
+        parlay.encrypted_state = [[0u8; 32]; 7];
+        parlay.parlay_id = parlay_id;
+        parlay.batches = batches;
+        parlay.ready = false;
+        parlay.rejected = false;
+        parlay.legs_added = 0;
+        parlay.outcome = None;
+
+        let (signer_lo, signer_hi) = split_pubkey(&ctx.accounts.user.key());
+
+        let args = ArgBuilder::new()
+            .x25519_pubkey(user_pubkey)
+            .plaintext_u128(nonce)
+            .encrypted_u64(encrypted_amount)
+            .encrypted_u64(encrypted_weights[0])
+            .encrypted_u64(encrypted_weights[1])
+            .encrypted_u64(encrypted_weights[2])
+            .encrypted_u64(encrypted_weights[3])
+            .encrypted_u128(encrypted_wallet_lo)
+            .encrypted_u128(encrypted_wallet_hi)
+            .encrypted_u64(encrypted_expiry_slot)
+            .plaintext_u128(signer_lo)
+            .plaintext_u128(signer_hi)
+            .plaintext_u64(Clock::get()?.slot)
+            .plaintext_u8(leg_count)
+            .plaintext_u128(state_nonce)
+            .build();
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![InitParlayCallback::callback_ix(
+                computation_offset,
+                &ctx.accounts.mxe_account,
+                &[CallbackAccount {
+                    pubkey: ctx.accounts.parlay.key(),
+                    is_writable: true,
+                }],
+            )?],
+            1,
+            0,
+        )?;
+
+        ctx.accounts.parlay.queued_computation = Some(ctx.accounts.computation_account.key());
+
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "init_parlay")]
+    pub fn init_parlay_callback(
+        ctx: Context<InitParlayCallback>,
+        output: SignedComputationOutputs<InitParlayOutput>,
+    ) -> Result<()> {
+        check_callback_origin(
+            ctx.accounts.parlay.queued_computation,
+            &ctx.accounts.computation_account,
+        )?;
+
+        let o = match output.verify_output(
+            &ctx.accounts.cluster_account,
+            &ctx.accounts.computation_account,
+        ) {
+            Ok(InitParlayOutput { field_0 }) => field_0,
+            Err(_) => return Err(ErrorCode::AbortedComputation.into()),
+        };
+
+        let parlay = &mut ctx.accounts.parlay;
+        parlay.encrypted_state = o.ciphertexts;
+        parlay.state_nonce = o.nonce;
+        parlay.ready = true;
+        parlay.queued_computation = None;
+        parlay.rejected = !o.wallet_bound || o.expired || !o.weights_valid;
+
+        emit!(ParlayCreated {
+            parlay: parlay.key(),
+            user: parlay.user,
+            batches: parlay.batches.clone(),
+            rejected: parlay.rejected,
+        });
+
+        Ok(())
+    }
+
+    /// Fold leg `leg_index` of a parlay into its batch as an order
+    ///
+    /// The leg takes the batch's next order slot and passes the batch's
+    /// gates like any other order, so legs can be added in any order and
+    /// a leg whose computation is abandoned can simply be added again.
+    pub fn add_parlay_leg(
+        ctx: Context<AddParlayLeg>,
+        computation_offset: u64,
+        leg_index: u8,
+        commitment_hash: [u8; 32],
+    ) -> Result<()> {
+        check_computation_offset(&ctx.accounts.computation_account)?;
+
+        let parlay = &ctx.accounts.parlay;
+        require!(parlay.ready, ErrorCode::ParlayNotReady);
+        require!(!parlay.rejected, ErrorCode::ParlayRejected);
+        require!(
+            parlay.legs_added & (1 << leg_index) == 0,
+            ErrorCode::ParlayLegAlreadyAdded
+        );
+
+        let batch = &ctx.accounts.batch;
+        require!(batch.status == BatchStatus::Open, ErrorCode::BatchNotOpen);
+        require!(batch.pending_computation.is_none(), ErrorCode::ComputationPending);
+        require!(batch.order_count < 32, ErrorCode::BatchFull);
+        require!(batch.lane_count == 0, ErrorCode::BatchUsesLanes);
+        require!(batch.staged_orders == 0, ErrorCode::StagedOrdersPending);
+
+        check_risk_profile(&ctx.accounts.risk_profile, &batch.market_id)?;
+
+        let eligibility_proof = check_order_gates(
+            batch,
+            ctx.accounts.attestation.as_ref(),
+            ctx.accounts.eligibility_proof.as_ref(),
+            &ctx.accounts.user.key(),
+        )?;
+
+        let order = &mut ctx.accounts.order;
+        order.bump = ctx.bumps.order;
+        order.batch = batch.key();
+        order.user = ctx.accounts.user.key();
+        order.commitment_hash = commitment_hash;
+        order.index = batch.order_count;
+        order.allocated = false;
+        order.eligibility_proof = eligibility_proof;
+        order.rejected = false;
+
+        let args = ArgBuilder::new()
+            .plaintext_u8(leg_index)
+            .plaintext_u128(parlay.state_nonce)
+            .account(
+                ctx.accounts.parlay.key(),
+                PARLAY_ENCRYPTED_STATE_OFFSET,
+                PARLAY_ENCRYPTED_STATE_SIZE,
+            )
+            .plaintext_u128(batch.state_nonce)
+            .account(
+                ctx.accounts.batch.key(),
+                BATCH_ENCRYPTED_STATE_OFFSET,
+                BATCH_ENCRYPTED_STATE_SIZE,
+            )
+            .build();
+
+        let payer_lamports = ctx.accounts.user.lamports();
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![AddParlayLegCallback::callback_ix(
+                computation_offset,
+                &ctx.accounts.mxe_account,
+                &[
+                    CallbackAccount {
+                        pubkey: ctx.accounts.batch.key(),
+                        is_writable: true,
+                    },
+                    CallbackAccount {
+                        pubkey: ctx.accounts.order.key(),
+                        is_writable: true,
+                    },
+                    CallbackAccount {
+                        pubkey: ctx.accounts.parlay.key(),
+                        is_writable: true,
+                    },
+                ],
+            )?],
+            1,
+            0,
+        )?;
+
+        queue_batch_computation(
+            &mut ctx.accounts.batch,
+            ComputationKind::AddOrder,
+            ctx.accounts.computation_account.key(),
+            Clock::get()?.slot,
+        );
+
+        let fee = payer_lamports.saturating_sub(ctx.accounts.user.lamports());
+        draw_compute_fee(ctx.accounts.compute_pool.as_mut(), &ctx.accounts.user, fee)?;
+        refund_order_rent(
+            ctx.accounts.rent_sponsor.as_mut(),
+            &ctx.accounts.user,
+            ctx.accounts.order.key(),
+        )
+    }
+
+    #[arcium_callback(encrypted_ix = "add_parlay_leg")]
+    pub fn add_parlay_leg_callback(
+        ctx: Context<AddParlayLegCallback>,
+        output: SignedComputationOutputs<AddParlayLegOutput>,
+    ) -> Result<()> {
+        check_callback_origin(
+            ctx.accounts.batch.queued_computation,
+            &ctx.accounts.computation_account,
+        )?;
+
+        let o = match output.verify_output(
+            &ctx.accounts.cluster_account,
+            &ctx.accounts.computation_account,
+        ) {
+            Ok(AddParlayLegOutput { field_0 }) => field_0,
+            Err(_) => return Err(ErrorCode::AbortedComputation.into()),
+        };
+
+        let batch = &mut ctx.accounts.batch;
+        batch.encrypted_state = o.ciphertexts;
+        batch.state_nonce = o.nonce;
+        batch.order_count = batch
+            .order_count
+            .checked_add(1)
+            .ok_or(ErrorCode::OrderCountOverflow)?;
+
+        complete_batch_computation(batch, ComputationKind::AddOrder)?;
+
+        let parlay = &mut ctx.accounts.parlay;
+        let leg_index = parlay
+            .batches
+            .iter()
+            .position(|leg| *leg == batch.key())
+            .ok_or(ErrorCode::ParlayLegMismatch)?;
+        parlay.legs_added |= 1 << leg_index;
+
+        emit!(ParlayLegAdded {
+            parlay: parlay.key(),
+            batch: batch.key(),
+            order: ctx.accounts.order.key(),
+            leg_index: leg_index as u8,
+        });
+
+        // The wallet was bound and the expiry checked once, in `init_parlay`
+        emit_order_result(
+            batch.key(),
+            &mut ctx.accounts.order,
+            batch.order_count - 1,
+            true,
+            o.amount_overflow,
+            false,
+        );
+
+        Ok(())
+    }
+
+    /// Compose a parlay's outcome from its legs' resolved markets
+    ///
+    /// `remaining_accounts` holds a (batch, oracle) pair per leg, in leg
+    /// order. Each oracle must be resolved by the batch authority for the
+    /// batch's market; a leg wins when the outcome matches the batch side.
+    /// Permissionless once every leg is in.
+    pub fn settle_parlay<'info>(
+        ctx: Context<'_, '_, 'info, 'info, SettleParlay<'info>>,
+    ) -> Result<()> {
+        let parlay = &ctx.accounts.parlay;
+        require!(parlay.outcome.is_none(), ErrorCode::ParlayAlreadySettled);
+        require!(
+            parlay.legs_added as usize == (1 << parlay.batches.len()) - 1,
+            ErrorCode::ParlayIncomplete
+        );
+        require!(
+            ctx.remaining_accounts.len() == parlay.batches.len() * 2,
+            ErrorCode::ParlayLegMismatch
+        );
+
+        let mut won = true;
+        for (leg, accounts) in parlay.batches.iter().zip(ctx.remaining_accounts.chunks(2)) {
+            let batch = Account::<TradingBatch>::try_from(&accounts[0])?;
+            let oracle = Account::<ConfidentialOracle>::try_from(&accounts[1])?;
+
+            require_keys_eq!(batch.key(), *leg, ErrorCode::ParlayLegMismatch);
+            require!(
+                matches!(batch.status, BatchStatus::Executed | BatchStatus::Verified),
+                ErrorCode::BatchNotExecuted
+            );
+            require!(
+                oracle.creator == batch.authority && oracle.market_id == batch.market_id,
+                ErrorCode::ParlayOracleMismatch
+            );
+            let outcome = oracle.outcome.ok_or(ErrorCode::OracleNotResolved)?;
+
+            won &= outcome == (batch.side == Side::Yes);
+        }
+
+        let parlay = &mut ctx.accounts.parlay;
+        parlay.outcome = Some(won);
+
+        emit!(ParlaySettled {
+            parlay: parlay.key(),
+            user: parlay.user,
+            won,
+        });
+
+        Ok(())
+    }
+
+    // ============================================
+    // Conditional Tokens
+    // ============================================
+
+    /// Set up YES/NO outcome mints backed by `collateral_mint` for the
+    /// market a confidential oracle resolves
+    ///
+    /// One condition per oracle, so anyone may prepare it. Outcome mints
+    /// share the collateral's decimals and token program, and only the
+    /// condition PDA can mint them.
+    pub fn prepare_condition(ctx: Context<PrepareCondition>) -> Result<()> {
+        let condition = &mut ctx.accounts.condition;
+        condition.bump = ctx.bumps.condition;
+        condition.oracle = ctx.accounts.oracle.key();
+        condition.collateral_mint = ctx.accounts.collateral_mint.key();
+        condition.vault = ctx.accounts.vault.key();
+        condition.yes_mint = ctx.accounts.yes_mint.key();
+        condition.no_mint = ctx.accounts.no_mint.key();
+        condition.market_id = ctx.accounts.oracle.market_id.clone();
+        condition.improvement_batches = 0;
+        condition.cumulative_improvement_bps = 0;
+        condition.cumulative_improvement_usdc = 0;
+
+        emit!(ConditionPrepared {
+            condition: condition.key(),
+            oracle: condition.oracle,
+            market_id: condition.market_id.clone(),
+            collateral_mint: condition.collateral_mint,
+            yes_mint: condition.yes_mint,
+            no_mint: condition.no_mint,
+        });
+
+        Ok(())
+    }
+
+    /// Lock `amount` of collateral for `amount` YES and `amount` NO tokens
+    ///
+    /// A complete set is always worth one unit of collateral, which pins
+    /// YES + NO to par and lets arbitrageurs trade against batch prices.
+    pub fn split_position<'info>(
+        ctx: Context<'_, '_, '_, 'info, SplitPosition<'info>>,
+        amount: u64,
+    ) -> Result<()> {
+        require!(amount > 0, ErrorCode::InvalidPositionAmount);
+
+        vault_transfer(
+            VaultTransfer {
+                token_program: &ctx.accounts.token_program,
+                mint: &ctx.accounts.collateral_mint,
+                from: ctx.accounts.user_collateral.to_account_info(),
+                to: ctx.accounts.vault.to_account_info(),
+                authority: ctx.accounts.user.to_account_info(),
+                memo_program: None,
+                hook_accounts: ctx.remaining_accounts,
+            },
+            &[],
+            amount,
+            None,
+        )?;
+
+        let condition = &ctx.accounts.condition;
+        let bump = [condition.bump];
+        let signer_seeds: &[&[&[u8]]] = &[&[b"condition", condition.oracle.as_ref(), &bump]];
+
+        for (mint, to) in [
+            (&ctx.accounts.yes_mint, &ctx.accounts.user_yes),
+            (&ctx.accounts.no_mint, &ctx.accounts.user_no),
+        ] {
+            token_interface::mint_to(
+                CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    MintTo {
+                        mint: mint.to_account_info(),
+                        to: to.to_account_info(),
+                        authority: condition.to_account_info(),
+                    },
+                    signer_seeds,
+                ),
+                amount,
+            )?;
+        }
+
+        emit!(PositionSplit {
+            condition: condition.key(),
+            user: ctx.accounts.user.key(),
+            amount,
+        });
+
+        Ok(())
+    }
+
+    /// Burn `amount` complete sets (YES and NO) for `amount` of collateral
+    pub fn merge_positions<'info>(
+        ctx: Context<'_, '_, '_, 'info, MergePositions<'info>>,
+        amount: u64,
+    ) -> Result<()> {
+        require!(amount > 0, ErrorCode::InvalidPositionAmount);
+
+        for (mint, from) in [
+            (&ctx.accounts.yes_mint, &ctx.accounts.user_yes),
+            (&ctx.accounts.no_mint, &ctx.accounts.user_no),
+        ] {
+            token_interface::burn(
+                CpiContext::new(
+                    ctx.accounts.token_program.to_account_info(),
+                    Burn {
+                        mint: mint.to_account_info(),
+                        from: from.to_account_info(),
+                        authority: ctx.accounts.user.to_account_info(),
+                    },
+                ),
+                amount,
+            )?;
+        }
+
+        let condition = &ctx.accounts.condition;
+        let bump = [condition.bump];
+        let signer_seeds: &[&[&[u8]]] = &[&[b"condition", condition.oracle.as_ref(), &bump]];
+
+        vault_transfer(
+            VaultTransfer {
+                token_program: &ctx.accounts.token_program,
+                mint: &ctx.accounts.collateral_mint,
+                from: ctx.accounts.vault.to_account_info(),
+                to: ctx.accounts.user_collateral.to_account_info(),
+                authority: condition.to_account_info(),
+                memo_program: None,
+                hook_accounts: ctx.remaining_accounts,
+            },
+            signer_seeds,
+            amount,
+            None,
+        )?;
+
+        emit!(PositionsMerged {
+            condition: condition.key(),
+            user: ctx.accounts.user.key(),
+            amount,
+        });
+
+        Ok(())
+    }
+
+    /// Burn `amount` winning outcome tokens for `amount` of collateral once
+    /// the condition's oracle has resolved
+    pub fn redeem_positions<'info>(
+        ctx: Context<'_, '_, '_, 'info, RedeemPositions<'info>>,
+        amount: u64,
+    ) -> Result<()> {
+        require!(amount > 0, ErrorCode::InvalidPositionAmount);
+
+        let condition = &ctx.accounts.condition;
+        let outcome = ctx.accounts.oracle.outcome.ok_or(ErrorCode::OracleNotResolved)?;
+        let winning_mint = if outcome { condition.yes_mint } else { condition.no_mint };
+        require_keys_eq!(
+            ctx.accounts.outcome_mint.key(),
+            winning_mint,
+            ErrorCode::NotWinningOutcome
+        );
+
+        token_interface::burn(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Burn {
+                    mint: ctx.accounts.outcome_mint.to_account_info(),
+                    from: ctx.accounts.user_outcome.to_account_info(),
+                    authority: ctx.accounts.user.to_account_info(),
+                },
+            ),
+            amount,
+        )?;
+
+        let bump = [condition.bump];
+        let signer_seeds: &[&[&[u8]]] = &[&[b"condition", condition.oracle.as_ref(), &bump]];
+
+        vault_transfer(
+            VaultTransfer {
+                token_program: &ctx.accounts.token_program,
+                mint: &ctx.accounts.collateral_mint,
+                from: ctx.accounts.vault.to_account_info(),
+                to: ctx.accounts.user_collateral.to_account_info(),
+                authority: condition.to_account_info(),
+                memo_program: None,
+                hook_accounts: ctx.remaining_accounts,
+            },
+            signer_seeds,
+            amount,
+            None,
+        )?;
+
+        emit!(PositionsRedeemed {
+            condition: condition.key(),
+            user: ctx.accounts.user.key(),
+            outcome,
+            amount,
+        });
+
+        Ok(())
+    }
+
+    // ============================================
+    // Fallback AMM
+    // ============================================
+
+    /// Open a public constant-product YES/NO pool for a condition
+    ///
+    /// The pool is an instant, non-private venue next to the batches.
+    /// Buying YES with collateral is `split_position` plus a NO -> YES
+    /// swap; selling is a YES -> NO swap plus `merge_positions`, so both
+    /// fit in one transaction.
+    pub fn create_amm_pool(ctx: Context<CreateAmmPool>, fee_bps: u16) -> Result<()> {
+        require!(fee_bps <= MAX_AMM_FEE_BPS, ErrorCode::InvalidAmmFee);
+
+        let pool = &mut ctx.accounts.pool;
+        pool.bump = ctx.bumps.pool;
+        pool.condition = ctx.accounts.condition.key();
+        pool.yes_reserve = ctx.accounts.yes_reserve.key();
+        pool.no_reserve = ctx.accounts.no_reserve.key();
+        pool.lp_mint = ctx.accounts.lp_mint.key();
+        pool.fee_bps = fee_bps;
+
+        emit!(AmmPoolCreated {
+            pool: pool.key(),
+            condition: pool.condition,
+            fee_bps,
+        });
+
+        Ok(())
+    }
+
+    /// Deposit YES and NO tokens for LP shares
+    ///
+    /// The first deposit sets the pool price and mints `max_yes + max_no`
+    /// shares. Later deposits take both sides at the pool ratio, up to
+    /// `max_yes`/`max_no`, and mint shares pro rata.
+    pub fn add_liquidity(ctx: Context<AddLiquidity>, max_yes: u64, max_no: u64) -> Result<()> {
+        let yes_reserve = ctx.accounts.yes_reserve.amount;
+        let no_reserve = ctx.accounts.no_reserve.amount;
+        let supply = ctx.accounts.lp_mint.supply;
+
+        let (yes_in, no_in, shares) = if supply == 0 {
+            let shares = max_yes
+                .checked_add(max_no)
+                .ok_or(ErrorCode::InvalidLiquidityAmount)?;
+            (max_yes, max_no, shares)
+        } else {
+            require!(yes_reserve > 0 && no_reserve > 0, ErrorCode::EmptyPool);
+            let shares = mul_div(max_yes, supply, yes_reserve)
+                .min(mul_div(max_no, supply, no_reserve));
+            (
+                mul_div_ceil(shares, yes_reserve, supply),
+                mul_div_ceil(shares, no_reserve, supply),
+                shares,
+            )
+        };
+        require!(
+            shares > 0 && yes_in > 0 && no_in > 0 && yes_in <= max_yes && no_in <= max_no,
+            ErrorCode::InvalidLiquidityAmount
+        );
+
+        for (mint, from, to, amount) in [
+            (&ctx.accounts.yes_mint, &ctx.accounts.user_yes, &ctx.accounts.yes_reserve, yes_in),
+            (&ctx.accounts.no_mint, &ctx.accounts.user_no, &ctx.accounts.no_reserve, no_in),
+        ] {
+            vault_transfer(
+                VaultTransfer {
+                    token_program: &ctx.accounts.token_program,
+                    mint,
+                    from: from.to_account_info(),
+                    to: to.to_account_info(),
+                    authority: ctx.accounts.user.to_account_info(),
+                    memo_program: None,
+                    hook_accounts: &[],
+                },
+                &[],
+                amount,
+                None,
+            )?;
+        }
+
+        let pool = &ctx.accounts.pool;
+        let bump = [pool.bump];
+        let signer_seeds: &[&[&[u8]]] = &[&[b"amm_pool", pool.condition.as_ref(), &bump]];
+
+        token_interface::mint_to(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                MintTo {
+                    mint: ctx.accounts.lp_mint.to_account_info(),
+                    to: ctx.accounts.user_lp.to_account_info(),
+                    authority: pool.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            shares,
+        )?;
+
+        emit!(LiquidityAdded {
+            pool: pool.key(),
+            provider: ctx.accounts.user.key(),
+            yes_amount: yes_in,
+            no_amount: no_in,
+            shares,
+        });
+
+        Ok(())
+    }
+
+    /// Burn LP shares for a pro-rata cut of both reserves
+    pub fn remove_liquidity(
+        ctx: Context<RemoveLiquidity>,
+        shares: u64,
+        min_yes: u64,
+        min_no: u64,
+    ) -> Result<()> {
+        let supply = ctx.accounts.lp_mint.supply;
+        require!(shares > 0 && shares <= supply, ErrorCode::InvalidLiquidityAmount);
+
+        let yes_out = mul_div(shares, ctx.accounts.yes_reserve.amount, supply);
+        let no_out = mul_div(shares, ctx.accounts.no_reserve.amount, supply);
+        require!(yes_out >= min_yes && no_out >= min_no, ErrorCode::SlippageExceeded);
+
+        token_interface::burn(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Burn {
+                    mint: ctx.accounts.lp_mint.to_account_info(),
+                    from: ctx.accounts.user_lp.to_account_info(),
+                    authority: ctx.accounts.user.to_account_info(),
+                },
+            ),
+            shares,
+        )?;
+
+        let pool = &ctx.accounts.pool;
+        let bump = [pool.bump];
+        let signer_seeds: &[&[&[u8]]] = &[&[b"amm_pool", pool.condition.as_ref(), &bump]];
+
+        for (mint, from, to, amount) in [
+            (&ctx.accounts.yes_mint, &ctx.accounts.yes_reserve, &ctx.accounts.user_yes, yes_out),
+            (&ctx.accounts.no_mint, &ctx.accounts.no_reserve, &ctx.accounts.user_no, no_out),
+        ] {
+            if amount == 0 {
+                continue;
+            }
+            vault_transfer(
+                VaultTransfer {
+                    token_program: &ctx.accounts.token_program,
+                    mint,
+                    from: from.to_account_info(),
+                    to: to.to_account_info(),
+                    authority: pool.to_account_info(),
+                    memo_program: None,
+                    hook_accounts: &[],
+                },
+                signer_seeds,
+                amount,
+                None,
+            )?;
+        }
+
+        emit!(LiquidityRemoved {
+            pool: pool.key(),
+            provider: ctx.accounts.user.key(),
+            yes_amount: yes_out,
+            no_amount: no_out,
+            shares,
+        });
+
+        Ok(())
+    }
+
+    /// Swap YES for NO (`yes_in`) or NO for YES against the pool
+    ///
+    /// The fee stays in the pool, so it accrues to LP shares.
+    pub fn amm_swap(
+        ctx: Context<AmmSwap>,
+        yes_in: bool,
+        amount_in: u64,
+        min_out: u64,
+    ) -> Result<()> {
+        require!(amount_in > 0, ErrorCode::InvalidLiquidityAmount);
+
+        let accounts = &ctx.accounts;
+        let (mint_in, user_in, reserve_in, mint_out, reserve_out, user_out) = if yes_in {
+            (
+                &accounts.yes_mint,
+                &accounts.user_yes,
+                &accounts.yes_reserve,
+                &accounts.no_mint,
+                &accounts.no_reserve,
+                &accounts.user_no,
+            )
+        } else {
+            (
+                &accounts.no_mint,
+                &accounts.user_no,
+                &accounts.no_reserve,
+                &accounts.yes_mint,
+                &accounts.yes_reserve,
+                &accounts.user_yes,
+            )
+        };
+        require!(
+            reserve_in.amount > 0 && reserve_out.amount > 0,
+            ErrorCode::EmptyPool
+        );
+
+        let pool = &accounts.pool;
+        let amount_out = amm_swap_out(
+            reserve_in.amount,
+            reserve_out.amount,
+            amount_in,
+            pool.fee_bps,
+        );
+        require!(amount_out > 0 && amount_out >= min_out, ErrorCode::SlippageExceeded);
+
+        vault_transfer(
+            VaultTransfer {
+                token_program: &accounts.token_program,
+                mint: mint_in,
+                from: user_in.to_account_info(),
+                to: reserve_in.to_account_info(),
+                authority: accounts.user.to_account_info(),
+                memo_program: None,
+                hook_accounts: &[],
+            },
+            &[],
+            amount_in,
+            None,
+        )?;
+
+        let bump = [pool.bump];
+        let signer_seeds: &[&[&[u8]]] = &[&[b"amm_pool", pool.condition.as_ref(), &bump]];
+
+        vault_transfer(
+            VaultTransfer {
+                token_program: &accounts.token_program,
+                mint: mint_out,
+                from: reserve_out.to_account_info(),
+                to: user_out.to_account_info(),
+                authority: pool.to_account_info(),
+                memo_program: None,
+                hook_accounts: &[],
+            },
+            signer_seeds,
+            amount_out,
+            None,
+        )?;
+
+        emit!(AmmSwapped {
+            pool: pool.key(),
+            user: accounts.user.key(),
+            yes_in,
+            amount_in,
+            amount_out,
+        });
+
+        Ok(())
+    }
+
+    /// Add an executed batch's price improvement to its market's stats
+    ///
+    /// Improvement is the AMM mid recorded at close less the execution
+    /// price (positive when the batch bought cheaper than the pool), in
+    /// bps of par and in collateral saved on the batch's notional.
+    /// Permissionless, once per batch.
+    pub fn record_price_improvement(ctx: Context<RecordPriceImprovement>) -> Result<()> {
+        let batch = &mut ctx.accounts.batch;
+        require!(
+            matches!(batch.status, BatchStatus::Executed | BatchStatus::Verified),
+            ErrorCode::BatchNotExecuted
+        );
+        require!(
+            batch.reference_condition == Some(ctx.accounts.condition.key()),
+            ErrorCode::NoPriceReference
+        );
+
+        let improvement_bps = batch.reference_mid as i64 - batch.execution_price as i64;
+        let improvement_usdc = if batch.execution_price == 0 {
+            0
+        } else {
+            (batch.total_usdc as i128 * improvement_bps as i128 / batch.execution_price as i128)
+                as i64
+        };
+        batch.reference_condition = None;
+
+        let condition = &mut ctx.accounts.condition;
+        condition.improvement_batches = condition.improvement_batches.saturating_add(1);
+        condition.cumulative_improvement_bps =
+            condition.cumulative_improvement_bps.saturating_add(improvement_bps);
+        condition.cumulative_improvement_usdc =
+            condition.cumulative_improvement_usdc.saturating_add(improvement_usdc);
+
+        emit!(PriceImprovement {
+            batch: batch.key(),
+            condition: condition.key(),
+            reference_mid: batch.reference_mid,
+            execution_price: batch.execution_price,
+            improvement_bps,
+            improvement_usdc,
+        });
+
+        Ok(())
+    }
+
+    // ============================================
+    // Fill Bulletin
+    // ============================================
+
+    /// Open a market's public fill tape. Permissionless, one per market.
+    pub fn create_fill_bulletin(ctx: Context<CreateFillBulletin>, market_id: String) -> Result<()> {
+        let bulletin = &mut ctx.accounts.bulletin;
+        bulletin.bump = ctx.bumps.bulletin;
+        bulletin.market_id = market_id;
+        bulletin.last_published_at = 0;
+        bulletin.published = 0;
+
+        emit!(FillBulletinCreated {
+            bulletin: bulletin.key(),
+            market_id: bulletin.market_id.clone(),
+        });
+
+        Ok(())
+    }
+
+    /// Publish an executed batch's fill to its market's bulletin
+    ///
+    /// Only what the chain already reveals goes out, coarsened: the side,
+    /// the clearing price, the notional as a decade bucket and the
+    /// execution slot rounded down to `FILL_BULLETIN_DELAY_SLOTS`. No order
+    /// count, wallet or batch address is recorded. Permissionless, once
+    /// per batch, no sooner than `FILL_BULLETIN_DELAY_SLOTS` after
+    /// execution and `FILL_BULLETIN_INTERVAL_SLOTS` after the bulletin's
+    /// last fill.
+    pub fn publish_fill(ctx: Context<PublishFill>) -> Result<()> {
+        let batch = &mut ctx.accounts.batch;
+        require!(
+            matches!(batch.status, BatchStatus::Executed | BatchStatus::Verified),
+            ErrorCode::BatchNotExecuted
+        );
+        require!(!batch.fill_published, ErrorCode::FillAlreadyPublished);
+
+        let slot = Clock::get()?.slot;
+        let executed_at = batch.status_updated_at[BatchStatus::Executed as usize];
+        require!(
+            slot >= executed_at.saturating_add(FILL_BULLETIN_DELAY_SLOTS),
+            ErrorCode::FillNotDue
+        );
+
+        let bulletin = &mut ctx.accounts.bulletin;
+        require!(
+            bulletin.published == 0
+                || slot >= bulletin.last_published_at.saturating_add(FILL_BULLETIN_INTERVAL_SLOTS),
+            ErrorCode::BulletinRateLimited
+        );
+
+        let fill = PublicFill {
+            side: batch.side,
+            execution_price: batch.execution_price,
+            size_bucket: fill_size_bucket(batch.total_usdc),
+            epoch_slot: executed_at - executed_at % FILL_BULLETIN_DELAY_SLOTS,
+        };
+        bulletin.fills[(bulletin.published % FILL_TAPE_CAPACITY as u64) as usize] = fill;
+        bulletin.published += 1;
+        bulletin.last_published_at = slot;
+        batch.fill_published = true;
+
+        emit!(FillPublished {
+            bulletin: bulletin.key(),
+            sequence: bulletin.published - 1,
+            fill,
+        });
+
+        Ok(())
+    }
+
+    // ============================================
+    // Risk Profiles
+    // ============================================
+
+    /// Create the signer's `RiskProfile`
+    ///
+    /// From then on every order path enforces the allowlist, and while the
+    /// profile caps amounts orders must go through `add_limited_order` (or
+    /// `add_limited_order_with_session`), whose circuits check the caps.
+    /// Profiles cannot be closed; limits can only be loosened after
+    /// `RISK_LIMIT_DELAY_SLOTS`.
+    pub fn create_risk_profile(ctx: Context<CreateRiskProfile>, limits: RiskLimits) -> Result<()> {
+        limits.validate()?;
+
+        let profile = &mut ctx.accounts.risk_profile;
+        profile.bump = ctx.bumps.risk_profile;
+        profile.owner = ctx.accounts.owner.key();
+        profile.budget_nonce = 0;
+        profile.encrypted_budget = [[0u8; 32]; 1];
+        // No day matches, so the first limited order starts a fresh budget
+        profile.budget_day = trading_rules::NO_BUDGET_DAY;
+        profile.pending_day = trading_rules::NO_BUDGET_DAY;
+        profile.pending_computation = false;
+        profile.limits = limits;
+        profile.pending_limits = None;
+        profile.pending_effective_slot = 0;
+        profile.queued_computation = None;
+        profile.queued_at = 0;
+
+        emit!(RiskLimitsUpdated {
+            risk_profile: profile.key(),
+            owner: profile.owner,
+            limits: profile.limits.clone(),
+            effective_slot: Clock::get()?.slot,
+        });
+
+        Ok(())
+    }
+
+    /// Change the signer's risk limits
+    ///
+    /// Limits that are no looser than the current ones apply at once and
+    /// drop any scheduled change. Anything looser is scheduled for
+    /// `RISK_LIMIT_DELAY_SLOTS` from now and applied by `apply_risk_limits`.
+    pub fn update_risk_limits(ctx: Context<UpdateRiskLimits>, limits: RiskLimits) -> Result<()> {
+        limits.validate()?;
+
+        let slot = Clock::get()?.slot;
+        let profile = &mut ctx.accounts.risk_profile;
+        let within = limits.within(&profile.limits);
+        let effective_slot = trading_rules::limits_effective_slot(within, slot);
+        if within {
+            profile.limits = limits.clone();
+            profile.pending_limits = None;
+        } else {
+            profile.pending_limits = Some(limits.clone());
+            profile.pending_effective_slot = effective_slot;
+        }
+
+        emit!(RiskLimitsUpdated {
+            risk_profile: profile.key(),
+            owner: profile.owner,
+            limits,
+            effective_slot,
+        });
+
+        Ok(())
+    }
+
+    /// Apply loosened limits once their delay has passed
+    pub fn apply_risk_limits(ctx: Context<UpdateRiskLimits>) -> Result<()> {
+        let profile = &mut ctx.accounts.risk_profile;
+        require!(
+            trading_rules::limits_due(profile.pending_effective_slot, Clock::get()?.slot),
+            ErrorCode::RiskLimitsNotDue
+        );
+        profile.limits = profile
+            .pending_limits
+            .take()
+            .ok_or(ErrorCode::RiskLimitsNotDue)?;
+
+        Ok(())
+    }
+
+    /// Release a profile whose limited order never called back
+    ///
+    /// After `COMPUTATION_TIMEOUT_SLOTS` the owner can clear it, whether or
+    /// not the batch authority abandons the order. The budget stays as it
+    /// was before that order, and a late callback fails.
+    pub fn release_risk_profile(ctx: Context<UpdateRiskLimits>) -> Result<()> {
+        let profile = &mut ctx.accounts.risk_profile;
+        require!(profile.pending_computation, ErrorCode::NoPendingComputation);
+        let released_at = Clock::get()?.slot;
+        require!(
+            released_at >= profile.queued_at.saturating_add(COMPUTATION_TIMEOUT_SLOTS),
+            ErrorCode::ComputationNotStale
+        );
+
+        profile.pending_computation = false;
+        profile.queued_computation = None;
+
+        emit!(RiskProfileReleased {
+            risk_profile: profile.key(),
+            owner: profile.owner,
+            queued_at: profile.queued_at,
+            released_at,
+        });
+
+        Ok(())
+    }
+
+    /// Add an order under the signer's `RiskProfile`
+    ///
+    /// The allowlist is checked here; the circuit zeroes an order above
+    /// `max_order_size` or the day's remaining `daily_notional_cap`, like
+    /// a session order over budget, without revealing which. Laned batches
+    /// have no limited variant.
+    pub fn add_limited_order(
+        ctx: Context<AddLimitedOrder>,
+        computation_offset: u64,
+        encrypted_amount: [u8; 32],
+        encrypted_wallet_lo: [u8; 32],
+        encrypted_wallet_hi: [u8; 32],
+        encrypted_expiry_slot: [u8; 32],
+        user_pubkey: [u8; 32],
+        nonce: u128,
+        commitment_hash: [u8; 32],
+    ) -> Result<()> {
+        check_computation_offset(&ctx.accounts.computation_account)?;
+
+        let profile = &ctx.accounts.risk_profile;
+        require!(!profile.pending_computation, ErrorCode::ComputationPending);
+
+        let batch = &ctx.accounts.batch;
+        require!(batch.status == BatchStatus::Open, ErrorCode::BatchNotOpen);
+        require!(batch.pending_computation.is_none(), ErrorCode::ComputationPending);
+        require!(batch.order_count < 32, ErrorCode::BatchFull);
+        require!(batch.lane_count == 0, ErrorCode::BatchUsesLanes);
+        require!(batch.staged_orders == 0, ErrorCode::StagedOrdersPending);
+        require!(
+            profile.limits.allows(&batch.market_id),
+            ErrorCode::MarketNotAllowed
+        );
+        check_encryption_key(&ctx.accounts.encryption_key, &user_pubkey)?;
+
+        let eligibility_proof = check_order_gates(
+            batch,
+            ctx.accounts.attestation.as_ref(),
+            ctx.accounts.eligibility_proof.as_ref(),
+            &ctx.accounts.user.key(),
+        )?;
+
+        let order = &mut ctx.accounts.order;
+        order.bump = ctx.bumps.order;
+        order.batch = batch.key();
+        order.user = ctx.accounts.user.key();
+        order.commitment_hash = commitment_hash;
+        order.index = batch.order_count;
+        order.allocated = false;
+        order.eligibility_proof = eligibility_proof;
+        order.rejected = false;
+
+        let (signer_lo, signer_hi) = split_pubkey(&ctx.accounts.user.key());
+        let (max_order, daily_cap) = profile.limits.circuit_caps();
+        let slot = Clock::get()?.slot;
+        let day = trading_rules::budget_day(slot);
+
+        let args = ArgBuilder::new()
+            .x25519_pubkey(user_pubkey)
+            .plaintext_u128(nonce)
+            .encrypted_u64(encrypted_amount)
+            .encrypted_u128(encrypted_wallet_lo)
+            .encrypted_u128(encrypted_wallet_hi)
+            .encrypted_u64(encrypted_expiry_slot)
+            .plaintext_u128(signer_lo)
+            .plaintext_u128(signer_hi)
+            .plaintext_u64(slot)
+            .plaintext_u128(batch.state_nonce)
+            .account(
+                ctx.accounts.batch.key(),
+                BATCH_ENCRYPTED_STATE_OFFSET,
+                BATCH_ENCRYPTED_STATE_SIZE,
+            )
+            .plaintext_u64(max_order)
+            .plaintext_u64(daily_cap)
+            .plaintext_u8(trading_rules::starts_new_day(profile.budget_day, slot) as u8)
+            .plaintext_u128(profile.budget_nonce)
+            .account(
+                ctx.accounts.risk_profile.key(),
+                RISK_ENCRYPTED_BUDGET_OFFSET,
+                RISK_ENCRYPTED_BUDGET_SIZE,
+            )
+            .build();
+
+        let payer_lamports = ctx.accounts.user.lamports();
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![AddLimitedOrderCallback::callback_ix(
+                computation_offset,
+                &ctx.accounts.mxe_account,
+                &[
+                    CallbackAccount {
+                        pubkey: ctx.accounts.batch.key(),
+                        is_writable: true,
+                    },
+                    CallbackAccount {
+                        pubkey: ctx.accounts.order.key(),
+                        is_writable: true,
+                    },
+                    CallbackAccount {
+                        pubkey: ctx.accounts.risk_profile.key(),
+                        is_writable: true,
+                    },
+                ],
+            )?],
+            1,
+            0,
+        )?;
+
+        queue_batch_computation(
+            &mut ctx.accounts.batch,
+            ComputationKind::AddOrder,
+            ctx.accounts.computation_account.key(),
+            slot,
+        );
+        // The budget is single-writer until the callback lands
+        ctx.accounts.risk_profile.pending_computation = true;
+        ctx.accounts.risk_profile.pending_day = day;
+        ctx.accounts.risk_profile.queued_computation = Some(ctx.accounts.computation_account.key());
+        ctx.accounts.risk_profile.queued_at = slot;
+
+        let fee = payer_lamports.saturating_sub(ctx.accounts.user.lamports());
+        draw_compute_fee(ctx.accounts.compute_pool.as_mut(), &ctx.accounts.user, fee)?;
+        refund_order_rent(
+            ctx.accounts.rent_sponsor.as_mut(),
+            &ctx.accounts.user,
+            ctx.accounts.order.key(),
+        )
+    }
+
+    #[arcium_callback(encrypted_ix = "add_limited_order")]
+    pub fn add_limited_order_callback(
+        ctx: Context<AddLimitedOrderCallback>,
+        output: SignedComputationOutputs<AddLimitedOrderOutput>,
+    ) -> Result<()> {
+        check_callback_origin(
+            ctx.accounts.batch.queued_computation,
+            &ctx.accounts.computation_account,
+        )?;
+        check_callback_origin(
+            ctx.accounts.risk_profile.queued_computation,
+            &ctx.accounts.computation_account,
+        )?;
+
+        let (
+            ciphertexts,
+            nonce,
+            budget_ciphertexts,
+            budget_nonce,
+            wallet_bound,
+            amount_overflow,
+            expired,
+        ) = match output
+            .verify_output(&ctx.accounts.cluster_account, &ctx.accounts.computation_account)
+        {
+            Ok(AddLimitedOrderOutput {
+                field_0: AddLimitedOrderOutputStruct0 {
+                    field_0: ciphertexts,
+                    field_1: nonce,
+                    field_2: budget_ciphertexts,
+                    field_3: budget_nonce,
+                    field_4: wallet_bound,
+                    field_5: amount_overflow,
+                    field_6: expired,
+                },
+            }) => (
+                ciphertexts,
+                nonce,
+                budget_ciphertexts,
+                budget_nonce,
+                wallet_bound,
+                amount_overflow,
+                expired,
+            ),
+            Err(_) => return Err(ErrorCode::AbortedComputation.into()),
+        };
+
+        ctx.accounts
+            .risk_profile
+            .settle_budget(budget_ciphertexts, budget_nonce);
+        fold_order_output(
+            &mut ctx.accounts.batch,
+            &mut ctx.accounts.order,
+            ciphertexts,
+            nonce,
+            wallet_bound,
+            amount_overflow,
+            expired,
+        )
+    }
+
+    /// `add_limited_order` signed by one of the owner's session keys
+    ///
+    /// The order must fit both the owner's limits and the session budget,
+    /// and spends both.
+    pub fn add_limited_order_with_session(
+        ctx: Context<AddLimitedOrderWithSession>,
+        computation_offset: u64,
+        encrypted_amount: [u8; 32],
+        encrypted_wallet_lo: [u8; 32],
+        encrypted_wallet_hi: [u8; 32],
+        encrypted_expiry_slot: [u8; 32],
+        user_pubkey: [u8; 32],
+        nonce: u128,
+        commitment_hash: [u8; 32],
+    ) -> Result<()> {
+        check_computation_offset(&ctx.accounts.computation_account)?;
+
+        let slot = Clock::get()?.slot;
+        let session = &ctx.accounts.session;
+        require!(!session.pending_computation, ErrorCode::ComputationPending);
+        require!(slot <= session.expiry_slot, ErrorCode::SessionExpired);
+
+        let profile = &ctx.accounts.risk_profile;
+        require!(!profile.pending_computation, ErrorCode::ComputationPending);
+
+        let batch = &ctx.accounts.batch;
+        require!(
+            batch.market_id == session.market_id,
+            ErrorCode::SessionScopeMismatch
+        );
+        require!(batch.status == BatchStatus::Open, ErrorCode::BatchNotOpen);
+        require!(batch.pending_computation.is_none(), ErrorCode::ComputationPending);
+        require!(batch.order_count < 32, ErrorCode::BatchFull);
+        require!(batch.lane_count == 0, ErrorCode::BatchUsesLanes);
+        require!(batch.staged_orders == 0, ErrorCode::StagedOrdersPending);
+        require!(
+            profile.limits.allows(&batch.market_id),
+            ErrorCode::MarketNotAllowed
+        );
+
+        let eligibility_proof = check_order_gates(
+            batch,
+            ctx.accounts.attestation.as_ref(),
+            ctx.accounts.eligibility_proof.as_ref(),
+            &session.owner,
+        )?;
+
+        let order = &mut ctx.accounts.order;
+        order.bump = ctx.bumps.order;
+        order.batch = batch.key();
+        order.user = session.owner;
+        order.commitment_hash = commitment_hash;
+        order.index = batch.order_count;
+        order.allocated = false;
+        order.eligibility_proof = eligibility_proof;
+        order.rejected = false;
+
+        let (owner_lo, owner_hi) = split_pubkey(&session.owner);
+        let (max_order, daily_cap) = profile.limits.circuit_caps();
+        let day = trading_rules::budget_day(slot);
+
+        let args = ArgBuilder::new()
+            .x25519_pubkey(user_pubkey)
+            .plaintext_u128(nonce)
+            .encrypted_u64(encrypted_amount)
+            .encrypted_u128(encrypted_wallet_lo)
+            .encrypted_u128(encrypted_wallet_hi)
+            .encrypted_u64(encrypted_expiry_slot)
+            .plaintext_u128(owner_lo)
+            .plaintext_u128(owner_hi)
+            .plaintext_u64(slot)
+            .plaintext_u128(batch.state_nonce)
+            .account(
+                ctx.accounts.batch.key(),
+                BATCH_ENCRYPTED_STATE_OFFSET,
+                BATCH_ENCRYPTED_STATE_SIZE,
+            )
+            .plaintext_u64(max_order)
+            .plaintext_u64(daily_cap)
+            .plaintext_u8(trading_rules::starts_new_day(profile.budget_day, slot) as u8)
+            .plaintext_u128(profile.budget_nonce)
+            .account(
+                ctx.accounts.risk_profile.key(),
+                RISK_ENCRYPTED_BUDGET_OFFSET,
+                RISK_ENCRYPTED_BUDGET_SIZE,
+            )
+            .plaintext_u128(session.budget_nonce)
+            .account(
+                ctx.accounts.session.key(),
+                SESSION_ENCRYPTED_BUDGET_OFFSET,
+                SESSION_ENCRYPTED_BUDGET_SIZE,
+            )
+            .build();
+
+        let payer_lamports = ctx.accounts.session_key.lamports();
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![AddLimitedSessionOrderCallback::callback_ix(
+                computation_offset,
+                &ctx.accounts.mxe_account,
+                &[
+                    CallbackAccount {
+                        pubkey: ctx.accounts.batch.key(),
+                        is_writable: true,
+                    },
+                    CallbackAccount {
+                        pubkey: ctx.accounts.order.key(),
+                        is_writable: true,
+                    },
+                    CallbackAccount {
+                        pubkey: ctx.accounts.risk_profile.key(),
+                        is_writable: true,
+                    },
+                    CallbackAccount {
+                        pubkey: ctx.accounts.session.key(),
+                        is_writable: true,
+                    },
+                ],
+            )?],
+            1,
+            0,
+        )?;
+
+        queue_batch_computation(
+            &mut ctx.accounts.batch,
+            ComputationKind::AddOrder,
+            ctx.accounts.computation_account.key(),
+            slot,
+        );
+        ctx.accounts.risk_profile.pending_computation = true;
+        ctx.accounts.risk_profile.pending_day = day;
+        ctx.accounts.risk_profile.queued_computation = Some(ctx.accounts.computation_account.key());
+        ctx.accounts.risk_profile.queued_at = slot;
+        ctx.accounts.session.pending_computation = true;
+        ctx.accounts.session.queued_computation = Some(ctx.accounts.computation_account.key());
+        ctx.accounts.session.queued_at = slot;
+
+        let fee = payer_lamports.saturating_sub(ctx.accounts.session_key.lamports());
+        draw_compute_fee(ctx.accounts.compute_pool.as_mut(), &ctx.accounts.session_key, fee)?;
+        refund_order_rent(
+            ctx.accounts.rent_sponsor.as_mut(),
+            &ctx.accounts.session_key,
+            ctx.accounts.order.key(),
+        )
+    }
+
+    #[arcium_callback(encrypted_ix = "add_limited_session_order")]
+    pub fn add_limited_session_order_callback(
+        ctx: Context<AddLimitedSessionOrderCallback>,
+        output: SignedComputationOutputs<AddLimitedSessionOrderOutput>,
+    ) -> Result<()> {
+        check_callback_origin(
+            ctx.accounts.batch.queued_computation,
+            &ctx.accounts.computation_account,
+        )?;
+        check_callback_origin(
+            ctx.accounts.risk_profile.queued_computation,
+            &ctx.accounts.computation_account,
+        )?;
+        check_callback_origin(
+            ctx.accounts.session.queued_computation,
+            &ctx.accounts.computation_account,
+        )?;
+
+        let (
+            ciphertexts,
+            nonce,
+            budget_ciphertexts,
+            budget_nonce,
+            session_budget_ciphertexts,
+            session_budget_nonce,
+            wallet_bound,
+            amount_overflow,
+            expired,
+        ) = match output
+            .verify_output(&ctx.accounts.cluster_account, &ctx.accounts.computation_account)
+        {
+            Ok(AddLimitedSessionOrderOutput {
+                field_0: AddLimitedSessionOrderOutputStruct0 {
+                    field_0: ciphertexts,
+                    field_1: nonce,
+                    field_2: budget_ciphertexts,
+                    field_3: budget_nonce,
+                    field_4: session_budget_ciphertexts,
+                    field_5: session_budget_nonce,
+                    field_6: wallet_bound,
+                    field_7: amount_overflow,
+                    field_8: expired,
+                },
+            }) => (
+                ciphertexts,
+                nonce,
+                budget_ciphertexts,
+                budget_nonce,
+                session_budget_ciphertexts,
+                session_budget_nonce,
+                wallet_bound,
+                amount_overflow,
+                expired,
+            ),
+            Err(_) => return Err(ErrorCode::AbortedComputation.into()),
+        };
+
+        ctx.accounts
+            .risk_profile
+            .settle_budget(budget_ciphertexts, budget_nonce);
+
+        let session = &mut ctx.accounts.session;
+        session.encrypted_budget = session_budget_ciphertexts;
+        session.budget_nonce = session_budget_nonce;
+        session.order_count = session
+            .order_count
+            .checked_add(1)
+            .ok_or(ErrorCode::OrderCountOverflow)?;
+        session.pending_computation = false;
+        session.queued_computation = None;
+
+        fold_order_output(
+            &mut ctx.accounts.batch,
+            &mut ctx.accounts.order,
+            ciphertexts,
+            nonce,
+            wallet_bound,
+            amount_overflow,
+            expired,
+        )
+    }
+
+    // ============================================
+    // Agent Dead-Man Switch
+    // ============================================
+
+    /// Arm a dead-man switch over the signing agent's wallet
+    ///
+    /// The agent must call `agent_heartbeat` at least every `timeout_slots`.
+    /// Once it misses one anyone can trip the switch, after which its open
+    /// RFQs and session keys can be cancelled by anyone and `recovery` can
+    /// sweep the outcome tokens the agent delegated to the policy PDA.
+    pub fn create_agent_policy(
+        ctx: Context<CreateAgentPolicy>,
+        recovery: Pubkey,
+        timeout_slots: u64,
+    ) -> Result<()> {
+        require!(
+            timeout_slots >= MIN_AGENT_HEARTBEAT_TIMEOUT_SLOTS,
+            ErrorCode::InvalidHeartbeatTimeout
+        );
+
+        let policy = &mut ctx.accounts.agent_policy;
+        policy.bump = ctx.bumps.agent_policy;
+        policy.agent = ctx.accounts.agent.key();
+        policy.recovery = recovery;
+        policy.timeout_slots = timeout_slots;
+        policy.last_heartbeat = Clock::get()?.slot;
+        policy.tripped_at = None;
+
+        emit!(AgentPolicyCreated {
+            agent_policy: policy.key(),
+            agent: policy.agent,
+            recovery,
+            timeout_slots,
+        });
+
+        Ok(())
+    }
+
+    /// Prove the agent is still running
+    pub fn agent_heartbeat(ctx: Context<AgentHeartbeat>) -> Result<()> {
+        let policy = &mut ctx.accounts.agent_policy;
+        trading_rules::check_armed(policy.tripped_at).map_err(ErrorCode::from)?;
+        policy.last_heartbeat = Clock::get()?.slot;
+        Ok(())
+    }
+
+    /// Trip the switch of an agent that missed its heartbeat. Permissionless.
+    pub fn trip_agent_policy(ctx: Context<TripAgentPolicy>) -> Result<()> {
+        let policy = &mut ctx.accounts.agent_policy;
+        let slot = Clock::get()?.slot;
+        trading_rules::check_trip(policy.tripped_at, policy.last_heartbeat, policy.timeout_slots, slot)
+            .map_err(ErrorCode::from)?;
+        policy.tripped_at = Some(slot);
+
+        emit!(AgentPolicyTripped {
+            agent_policy: policy.key(),
+            agent: policy.agent,
+            last_heartbeat: policy.last_heartbeat,
+            tripped_at: slot,
+        });
+
+        Ok(())
+    }
+
+    /// Cancel an open RFQ of a tripped agent, rent back to the agent.
+    /// Permissionless.
+    pub fn cancel_agent_rfq(ctx: Context<CancelAgentRfq>) -> Result<()> {
+        let rfq = &ctx.accounts.rfq;
+        trading_rules::check_rfq_cancel(
+            ctx.accounts.agent_policy.tripped_at,
+            rfq.pending_computation,
+            rfq.outstanding_bonds,
+        )
+        .map_err(ErrorCode::from)?;
+
+        emit!(RfqCancelled {
+            rfq: ctx.accounts.rfq.key(),
+            status: ctx.accounts.rfq.status,
+        });
+
+        Ok(())
+    }
+
+    /// Revoke a session key of a tripped agent, rent back to the agent.
+    /// Permissionless.
+    pub fn revoke_agent_session(ctx: Context<RevokeAgentSession>) -> Result<()> {
+        trading_rules::check_tripped(ctx.accounts.agent_policy.tripped_at).map_err(ErrorCode::from)?;
+        require!(
+            !ctx.accounts.session.pending_computation,
+            ErrorCode::ComputationPending
+        );
+
+        emit!(SessionRevoked {
+            session: ctx.accounts.session.key(),
+            owner: ctx.accounts.session.owner,
+            session_key: ctx.accounts.session.session_key,
+        });
+
+        Ok(())
+    }
+
+    /// Move a tripped agent's outcome tokens to the recovery key
+    ///
+    /// Sweeps what the agent delegated to the policy PDA (an SPL `approve`
+    /// made while it was alive), capped by the account's balance. Works
+    /// for resolved and unresolved conditions alike.
+    pub fn recover_position<'info>(
+        ctx: Context<'_, '_, '_, 'info, RecoverPosition<'info>>,
+    ) -> Result<()> {
+        let policy = &ctx.accounts.agent_policy;
+        trading_rules::check_tripped(policy.tripped_at).map_err(ErrorCode::from)?;
+
+        let agent_outcome = &ctx.accounts.agent_outcome;
+        require!(
+            Option::<Pubkey>::from(agent_outcome.delegate) == Some(policy.key()),
+            ErrorCode::PositionNotDelegated
+        );
+        let amount = trading_rules::recoverable_amount(agent_outcome.delegated_amount, agent_outcome.amount)
+            .ok_or(ErrorCode::InvalidPositionAmount)?;
+
+        let bump = [policy.bump];
+        let signer_seeds: &[&[&[u8]]] = &[&[b"agent_policy", policy.agent.as_ref(), &bump]];
+
+        vault_transfer(
+            VaultTransfer {
+                token_program: &ctx.accounts.token_program,
+                mint: &ctx.accounts.outcome_mint,
+                from: agent_outcome.to_account_info(),
+                to: ctx.accounts.recovery_outcome.to_account_info(),
+                authority: policy.to_account_info(),
+                memo_program: None,
+                hook_accounts: ctx.remaining_accounts,
+            },
+            signer_seeds,
+            amount,
+            None,
+        )?;
+
+        emit!(AgentPositionRecovered {
+            agent_policy: policy.key(),
+            condition: ctx.accounts.condition.key(),
+            mint: ctx.accounts.outcome_mint.key(),
+            amount,
+        });
+
+        Ok(())
+    }
+
+    /// Disarm a policy and reclaim its rent: the agent while the switch is
+    /// armed, the recovery key once it has tripped
+    pub fn close_agent_policy(ctx: Context<CloseAgentPolicy>) -> Result<()> {
+        let policy = &ctx.accounts.agent_policy;
+        let closer = trading_rules::policy_closer(policy.tripped_at, policy.agent, policy.recovery);
+        require_keys_eq!(
+            ctx.accounts.authority.key(),
+            closer,
+            ErrorCode::Unauthorized
+        );
+        Ok(())
+    }
+
+    // ============================================
+    // Encryption Keys
+    // ============================================
+
+    /// Register the x25519 key the signer encrypts orders under
+    ///
+    /// Once registered, `add_order`, `add_lane_order`, `add_limited_order`,
+    /// `create_rfq` and `create_parlay` only accept inputs encrypted with
+    /// it, so every order the wallet signs can be decrypted later with a
+    /// key it keeps rather than a throwaway one. Session keys encrypt under
+    /// their own keys and are not bound.
+    pub fn register_encryption_key(
+        ctx: Context<RegisterEncryptionKey>,
+        x25519_pubkey: [u8; 32],
+    ) -> Result<()> {
+        let slot = Clock::get()?.slot;
+        let key = &mut ctx.accounts.encryption_key;
+        key.bump = ctx.bumps.encryption_key;
+        key.owner = ctx.accounts.owner.key();
+        key.current = x25519_pubkey;
+        key.registered_at = slot;
+        key.retired = Vec::new();
+
+        emit!(EncryptionKeyRotated {
+            encryption_key: key.key(),
+            owner: key.owner,
+            previous: None,
+            current: x25519_pubkey,
+            slot,
+        });
+
+        Ok(())
+    }
+
+    /// Replace the registered key, keeping the old one in the history
+    ///
+    /// The history records when each key stopped being current, which is
+    /// how a client picks the key for an old order.
+    pub fn rotate_encryption_key(
+        ctx: Context<RotateEncryptionKey>,
+        x25519_pubkey: [u8; 32],
+    ) -> Result<()> {
+        let slot = Clock::get()?.slot;
+        let key = &mut ctx.accounts.encryption_key;
+        require!(x25519_pubkey != key.current, ErrorCode::EncryptionKeyUnchanged);
+
+        if key.retired.len() == MAX_RETIRED_ENCRYPTION_KEYS {
+            key.retired.remove(0);
+        }
+        let previous = key.current;
+        key.retired.push(RetiredEncryptionKey {
+            x25519_pubkey: previous,
+            registered_at: key.registered_at,
+            retired_at: slot,
+        });
+        key.current = x25519_pubkey;
+        key.registered_at = slot;
+
+        emit!(EncryptionKeyRotated {
+            encryption_key: key.key(),
+            owner: key.owner,
+            previous: Some(previous),
+            current: x25519_pubkey,
+            slot,
+        });
+
+        Ok(())
+    }
+
+    // ============================================
+    // Compute Pools
+    // ============================================
+
+    /// Open a pool that pays the Arcium computation fee of orders placed in
+    /// the authority's `market_id` batches
+    ///
+    /// Orders that pass the pool are refunded whatever queueing their
+    /// computation cost the payer. A pool too low to cover a fee emits
+    /// `ComputePoolLow` and leaves the fee with the payer instead of failing
+    /// the order, as does a pool that is not passed. `low_balance_lamports`
+    /// is the spendable balance under which every draw also emits it.
+    pub fn create_compute_pool(
+        ctx: Context<CreateComputePool>,
+        market_id: String,
+        low_balance_lamports: u64,
+    ) -> Result<()> {
+        let pool = &mut ctx.accounts.compute_pool;
+        pool.bump = ctx.bumps.compute_pool;
+        pool.authority = ctx.accounts.authority.key();
+        pool.market_id = market_id;
+        pool.low_balance_lamports = low_balance_lamports;
+        pool.drawn = 0;
+        pool.sponsored_computations = 0;
+        Ok(())
+    }
+
+    /// Top a compute pool up; anyone may fund any market's pool
+    pub fn fund_compute_pool(ctx: Context<FundComputePool>, lamports: u64) -> Result<()> {
+        require!(lamports > 0, ErrorCode::InvalidFundingAmount);
+        anchor_lang::system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                anchor_lang::system_program::Transfer {
+                    from: ctx.accounts.funder.to_account_info(),
+                    to: ctx.accounts.compute_pool.to_account_info(),
+                },
+            ),
+            lamports,
+        )?;
+
+        let pool = &ctx.accounts.compute_pool;
+        emit!(ComputePoolFunded {
+            compute_pool: pool.key(),
+            funder: ctx.accounts.funder.key(),
+            lamports,
+            available: compute_pool_available(pool)?,
+        });
+
+        Ok(())
+    }
+
+    /// Change the balance under which a pool reports itself low
+    pub fn set_compute_pool_low_balance(
+        ctx: Context<SetComputePoolLowBalance>,
+        low_balance_lamports: u64,
+    ) -> Result<()> {
+        ctx.accounts.compute_pool.low_balance_lamports = low_balance_lamports;
+        Ok(())
+    }
+
+    /// Close a compute pool, returning its balance to the authority
+    pub fn close_compute_pool(_ctx: Context<CloseComputePool>) -> Result<()> {
+        Ok(())
+    }
+
+    // ============================================
+    // House Accounts
+    // ============================================
+
+    /// Open the authority's house account for `market_id`
+    ///
+    /// `desk` is the wallet the operator backstops its thin batches from.
+    /// Its orders go through `add_house_order`, which books them on an
+    /// encrypted ledger of its own, so house inventory and P&L never mix
+    /// with user flow. The desk's `OrderCommitment`s and the
+    /// `HouseOrderAdded` events tell house orders apart in settlement, and
+    /// house orders pay their own rent and computation fees.
+    pub fn create_house_account(
+        ctx: Context<CreateHouseAccount>,
+        market_id: String,
+        desk: Pubkey,
+    ) -> Result<()> {
+        let house = &mut ctx.accounts.house_account;
+        house.bump = ctx.bumps.house_account;
+        house.authority = ctx.accounts.authority.key();
+        house.desk = desk;
+        house.ledger_nonce = 0;
+        house.encrypted_ledger = [[0u8; 32]; 3];
+        house.ledger_written = false;
+        house.open_batch = None;
+        house.queued_computation = None;
+        house.queued_at = 0;
+        house.house_orders = 0;
+        house.market_id = market_id;
+        Ok(())
+    }
+
+    /// Add an order from the house desk to one of the authority's batches
+    ///
+    /// The desk commits to one batch at a time: its notional is pending on
+    /// the ledger until `settle_house_batch` books that batch's fill. The
+    /// batch's credential and eligibility gates are the authority's own and
+    /// do not apply to its desk.
+    #[allow(clippy::too_many_arguments)]
+    pub fn add_house_order(
+        ctx: Context<AddHouseOrder>,
+        computation_offset: u64,
+        encrypted_amount: [u8; 32],
+        encrypted_wallet_lo: [u8; 32],
+        encrypted_wallet_hi: [u8; 32],
+        encrypted_expiry_slot: [u8; 32],
+        desk_pubkey: [u8; 32],
+        nonce: u128,
+        commitment_hash: [u8; 32],
+    ) -> Result<()> {
+        check_computation_offset(&ctx.accounts.computation_account)?;
+
+        let house = &ctx.accounts.house_account;
+        require!(house.queued_computation.is_none(), ErrorCode::ComputationPending);
+
+        let batch = &ctx.accounts.batch;
+        require!(
+            house.open_batch.unwrap_or(batch.key()) == batch.key(),
+            ErrorCode::HouseBatchOpen
+        );
+        require!(batch.status == BatchStatus::Open, ErrorCode::BatchNotOpen);
+        require!(batch.pending_computation.is_none(), ErrorCode::ComputationPending);
+        require!(batch.order_count < 32, ErrorCode::BatchFull);
+        require!(batch.lane_count == 0, ErrorCode::BatchUsesLanes);
+        require!(batch.staged_orders == 0, ErrorCode::StagedOrdersPending);
+
+        let order = &mut ctx.accounts.order;
+        order.bump = ctx.bumps.order;
+        order.batch = batch.key();
+        order.user = ctx.accounts.desk.key();
+        order.commitment_hash = commitment_hash;
+        order.index = batch.order_count;
+        order.allocated = false;
+        order.eligibility_proof = None;
+        order.rejected = false;
+
+        let (desk_lo, desk_hi) = split_pubkey(&ctx.accounts.desk.key());
+        let slot = Clock::get()?.slot;
+
+        let args = ArgBuilder::new()
+            .x25519_pubkey(desk_pubkey)
+            .plaintext_u128(nonce)
+            .encrypted_u64(encrypted_amount)
+            .encrypted_u128(encrypted_wallet_lo)
+            .encrypted_u128(encrypted_wallet_hi)
+            .encrypted_u64(encrypted_expiry_slot)
+            .plaintext_u128(desk_lo)
+            .plaintext_u128(desk_hi)
+            .plaintext_u64(slot)
+            .plaintext_u128(batch.state_nonce)
+            .account(
+                ctx.accounts.batch.key(),
+                BATCH_ENCRYPTED_STATE_OFFSET,
+                BATCH_ENCRYPTED_STATE_SIZE,
+            )
+            .plaintext_u8(!house.ledger_written as u8)
+            .plaintext_u128(house.ledger_nonce)
+            .account(
+                ctx.accounts.house_account.key(),
+                HOUSE_ENCRYPTED_LEDGER_OFFSET,
+                HOUSE_ENCRYPTED_LEDGER_SIZE,
+            )
+            .build();
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![AddHouseOrderCallback::callback_ix(
+                computation_offset,
+                &ctx.accounts.mxe_account,
+                &[
+                    CallbackAccount {
+                        pubkey: ctx.accounts.batch.key(),
+                        is_writable: true,
+                    },
+                    CallbackAccount {
+                        pubkey: ctx.accounts.order.key(),
+                        is_writable: true,
+                    },
+                    CallbackAccount {
+                        pubkey: ctx.accounts.house_account.key(),
+                        is_writable: true,
+                    },
+                ],
+            )?],
+            1,
+            0,
+        )?;
+
+        queue_batch_computation(
+            &mut ctx.accounts.batch,
+            ComputationKind::AddOrder,
+            ctx.accounts.computation_account.key(),
+            slot,
+        );
+        let house = &mut ctx.accounts.house_account;
+        house.open_batch = Some(ctx.accounts.batch.key());
+        house.queued_computation = Some(ctx.accounts.computation_account.key());
+        house.queued_at = slot;
+
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "add_house_order")]
+    pub fn add_house_order_callback(
+        ctx: Context<AddHouseOrderCallback>,
+        output: SignedComputationOutputs<AddHouseOrderOutput>,
+    ) -> Result<()> {
+        check_callback_origin(
+            ctx.accounts.batch.queued_computation,
+            &ctx.accounts.computation_account,
+        )?;
+
+        let (
+            ciphertexts,
+            nonce,
+            ledger_ciphertexts,
+            ledger_nonce,
+            wallet_bound,
+            amount_overflow,
+            expired,
+        ) = match output
+            .verify_output(&ctx.accounts.cluster_account, &ctx.accounts.computation_account)
+        {
+            Ok(AddHouseOrderOutput {
+                field_0: AddHouseOrderOutputStruct0 {
+                    field_0: ciphertexts,
+                    field_1: nonce,
+                    field_2: ledger_ciphertexts,
+                    field_3: ledger_nonce,
+                    field_4: wallet_bound,
+                    field_5: amount_overflow,
+                    field_6: expired,
+                },
+            }) => (
+                ciphertexts,
+                nonce,
+                ledger_ciphertexts,
+                ledger_nonce,
+                wallet_bound,
+                amount_overflow,
+                expired,
+            ),
+            Err(_) => return Err(ErrorCode::AbortedComputation.into()),
+        };
+
+        let house = &mut ctx.accounts.house_account;
+        house.encrypted_ledger = ledger_ciphertexts;
+        house.ledger_nonce = ledger_nonce;
+        house.ledger_written = true;
+        house.queued_computation = None;
+        house.house_orders += 1;
+
+        emit!(HouseOrderAdded {
+            house_account: house.key(),
+            batch: ctx.accounts.batch.key(),
+            order: ctx.accounts.order.key(),
+            order_index: ctx.accounts.batch.order_count,
+        });
+
+        fold_order_output(
+            &mut ctx.accounts.batch,
+            &mut ctx.accounts.order,
+            ciphertexts,
+            nonce,
+            wallet_bound,
+            amount_overflow,
+            expired,
+        )
+    }
+
+    /// Book the house's fill in its executed open batch
+    ///
+    /// `total_shares` is the fill `execute_batch` was given; the ledger
+    /// takes the house notional's pro-rata shares of it. These are the
+    /// authority's own books, so it is trusted to pass the same value. A
+    /// settlement whose callback never landed can be queued again after
+    /// `COMPUTATION_TIMEOUT_SLOTS`.
+    pub fn settle_house_batch(
+        ctx: Context<SettleHouseBatch>,
+        computation_offset: u64,
+        total_shares: u64,
+    ) -> Result<()> {
+        check_computation_offset(&ctx.accounts.computation_account)?;
+
+        let slot = Clock::get()?.slot;
+        let house = &ctx.accounts.house_account;
+        require!(
+            house.queued_computation.is_none()
+                || slot >= house.queued_at.saturating_add(COMPUTATION_TIMEOUT_SLOTS),
+            ErrorCode::ComputationPending
+        );
+
+        let batch = &ctx.accounts.batch;
+        require!(
+            house.open_batch == Some(batch.key()),
+            ErrorCode::HouseBatchMismatch
+        );
+        require!(
+            matches!(batch.status, BatchStatus::Executed | BatchStatus::Verified),
+            ErrorCode::BatchNotExecuted
+        );
+
+        let args = ArgBuilder::new()
+            .plaintext_u64(total_shares)
+            .plaintext_u64(batch.total_usdc)
+            .plaintext_u128(house.ledger_nonce)
+            .account(
+                ctx.accounts.house_account.key(),
+                HOUSE_ENCRYPTED_LEDGER_OFFSET,
+                HOUSE_ENCRYPTED_LEDGER_SIZE,
+            )
+            .build();
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![SettleHouseBatchCallback::callback_ix(
+                computation_offset,
+                &ctx.accounts.mxe_account,
+                &[CallbackAccount {
+                    pubkey: ctx.accounts.house_account.key(),
+                    is_writable: true,
+                }],
+            )?],
+            1,
+            0,
+        )?;
+
+        let house = &mut ctx.accounts.house_account;
+        house.queued_computation = Some(ctx.accounts.computation_account.key());
+        house.queued_at = slot;
+
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "settle_house_batch")]
+    pub fn settle_house_batch_callback(
+        ctx: Context<SettleHouseBatchCallback>,
+        output: SignedComputationOutputs<SettleHouseBatchOutput>,
+    ) -> Result<()> {
+        check_callback_origin(
+            ctx.accounts.house_account.queued_computation,
+            &ctx.accounts.computation_account,
+        )?;
+
+        let (ciphertexts, nonce) = match output.verify_output(
+            &ctx.accounts.cluster_account,
+            &ctx.accounts.computation_account,
+        ) {
+            Ok(SettleHouseBatchOutput {
+                field_0: SettleHouseBatchOutputStruct0 {
+                    field_0: ciphertexts,
+                    field_1: nonce,
+                },
+            }) => (ciphertexts, nonce),
+            Err(_) => return Err(ErrorCode::AbortedComputation.into()),
+        };
+
+        let house = &mut ctx.accounts.house_account;
+        house.encrypted_ledger = ciphertexts;
+        house.ledger_nonce = nonce;
+        house.queued_computation = None;
+        let batch = house.open_batch.take().ok_or(ErrorCode::HouseBatchMismatch)?;
+
+        emit!(HouseBatchSettled {
+            house_account: house.key(),
+            batch,
+        });
+
+        Ok(())
+    }
+
+    /// Mark the house ledger to `mark_price` for the authority
+    ///
+    /// The statement (pending notional, cost basis, inventory and its
+    /// value at `mark_price`) is encrypted to `authority_pubkey` and
+    /// emitted as an event; the ledger itself is unchanged. Like a
+    /// settlement, a reveal whose callback never landed can be queued
+    /// again after `COMPUTATION_TIMEOUT_SLOTS`.
+    pub fn reveal_house_ledger(
+        ctx: Context<RevealHouseLedger>,
+        computation_offset: u64,
+        mark_price: u64,
+        authority_pubkey: [u8; 32],
+        nonce: u128,
+    ) -> Result<()> {
+        check_computation_offset(&ctx.accounts.computation_account)?;
+
+        let slot = Clock::get()?.slot;
+        let house = &ctx.accounts.house_account;
+        require!(house.ledger_written, ErrorCode::HouseLedgerEmpty);
+        require!(
+            house.queued_computation.is_none()
+                || slot >= house.queued_at.saturating_add(COMPUTATION_TIMEOUT_SLOTS),
+            ErrorCode::ComputationPending
+        );
+
+        let args = ArgBuilder::new()
+            .x25519_pubkey(authority_pubkey)
+            .plaintext_u128(nonce)
+            .plaintext_u64(mark_price)
+            .plaintext_u128(house.ledger_nonce)
+            .account(
+                ctx.accounts.house_account.key(),
+                HOUSE_ENCRYPTED_LEDGER_OFFSET,
+                HOUSE_ENCRYPTED_LEDGER_SIZE,
+            )
+            .build();
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![RevealHouseLedgerCallback::callback_ix(
+                computation_offset,
+                &ctx.accounts.mxe_account,
+                &[CallbackAccount {
+                    pubkey: ctx.accounts.house_account.key(),
+                    is_writable: true,
+                }],
+            )?],
+            1,
+            0,
+        )?;
+
+        let house = &mut ctx.accounts.house_account;
+        house.queued_computation = Some(ctx.accounts.computation_account.key());
+        house.queued_at = slot;
+
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "reveal_house_ledger")]
+    pub fn reveal_house_ledger_callback(
+        ctx: Context<RevealHouseLedgerCallback>,
+        output: SignedComputationOutputs<RevealHouseLedgerOutput>,
+    ) -> Result<()> {
+        check_callback_origin(
+            ctx.accounts.house_account.queued_computation,
+            &ctx.accounts.computation_account,
+        )?;
+
+        let statement = match output.verify_output(
+            &ctx.accounts.cluster_account,
+            &ctx.accounts.computation_account,
+        ) {
+            Ok(RevealHouseLedgerOutput { field_0: statement }) => statement,
+            Err(_) => return Err(ErrorCode::AbortedComputation.into()),
+        };
+
+        ctx.accounts.house_account.queued_computation = None;
+
+        emit!(HouseLedgerRevealed {
+            house_account: ctx.accounts.house_account.key(),
+            encrypted_statement: statement.ciphertexts,
+            nonce: statement.nonce,
+        });
+
+        Ok(())
+    }
+}
+
+/// Decade bucket of a notional: 0 below 1 USDC, then 1 for [1, 10),
+/// 2 for [10, 100) and so on
+pub fn fill_size_bucket(total_usdc: u64) -> u8 {
+    (total_usdc / USDC_UNIT).checked_ilog10().map_or(0, |digits| digits as u8 + 1)
+}
+
+// ============================================
+// External Execution Interface
+// ============================================
+
+/// Standard CPI interface between the batch program and venue adapters
+///
+/// An adapter program exposes one instruction, `execute`, taking an
+/// `ExecuteRequest` and setting an `ExecutionReport` as return data. It
+/// receives the accounts `route_batch` was given beyond its own, in order.
+/// Adapters built with Anchor get the matching discriminator from an
+/// instruction named `execute` with a single `ExecuteRequest` argument.
+pub mod execution {
+    use super::*;
+    use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+    use anchor_lang::solana_program::program::{get_return_data, invoke};
+
+    /// Anchor discriminator of the adapter's `execute` instruction
+    pub fn execute_discriminator() -> [u8; 8] {
+        let hash = anchor_lang::solana_program::hash::hash(b"global:execute").to_bytes();
+        let mut discriminator = [0u8; 8];
+        discriminator.copy_from_slice(&hash[..8]);
+        discriminator
+    }
+
+    #[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+    pub struct ExecuteRequest {
+        /// Batch being routed, for the adapter's own records
+        pub batch: Pubkey,
+        /// Revealed batch total to fill on the venue
+        pub net_size: u64,
+        /// Outcome the batch buys
+        pub side: Side,
+        /// Worst acceptable average price, in the batch's price units
+        pub limit_price: u64,
+        /// `ExecutionAdapter::config`
+        pub config: Vec<u8>,
+    }
+
+    #[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+    pub struct ExecutionReport {
+        pub filled_size: u64,
+        pub average_price: u64,
+    }
+
+    /// Invoke `adapter_program` and read back its report
+    pub fn execute<'info>(
+        adapter_program: &AccountInfo<'info>,
+        accounts: &[AccountInfo<'info>],
+        request: &ExecuteRequest,
+    ) -> Result<ExecutionReport> {
+        let mut data = execute_discriminator().to_vec();
+        request
+            .serialize(&mut data)
+            .map_err(|_| ErrorCode::InvalidAdapterConfig)?;
+
+        let ix = Instruction {
+            program_id: adapter_program.key(),
+            accounts: accounts
+                .iter()
+                .map(|account| AccountMeta {
+                    pubkey: account.key(),
+                    is_signer: account.is_signer,
+                    is_writable: account.is_writable,
+                })
+                .collect(),
+            data,
+        };
+        invoke(&ix, accounts)?;
+
+        let (program_id, report) = get_return_data().ok_or(ErrorCode::InvalidExecutionReport)?;
+        require_keys_eq!(
+            program_id,
+            adapter_program.key(),
+            ErrorCode::InvalidExecutionReport
+        );
+        ExecutionReport::try_from_slice(&report).map_err(|_| ErrorCode::InvalidExecutionReport.into())
+    }
+}
+
+// ============================================
+// PDA Helpers
+// ============================================
+
+/// PDA derivations for clients and CPI callers (mirror the account seeds)
+pub mod pda {
+    use super::*;
+
+    pub fn batch(market_id: &str, authority: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[b"batch", market_id.as_bytes(), authority.as_ref()], &ID)
+    }
+
+    /// Order at `index` (`batch.order_count` at submission,
+    /// `batch.order_count + batch.staged_orders` when staged, or
+    /// `lane_index * LANE_CAPACITY + lane.order_count` for laned batches)
+    pub fn order(batch: &Pubkey, index: u8) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[b"order", batch.as_ref(), &[index]], &ID)
+    }
+
+    pub fn staged_order(batch: &Pubkey, index: u8) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[b"staged_order", batch.as_ref(), &[index]], &ID)
+    }
+
+    pub fn lane(batch: &Pubkey, lane_index: u8) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[b"lane", batch.as_ref(), &[lane_index]], &ID)
+    }
+
+    pub fn session(owner: &Pubkey, session_key: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[b"session", owner.as_ref(), session_key.as_ref()], &ID)
+    }
+
+    pub fn flow_auction(batch: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[b"flow_auction", batch.as_ref()], &ID)
+    }
+
+    pub fn parlay(user: &Pubkey, parlay_id: u64) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[b"parlay", user.as_ref(), &parlay_id.to_le_bytes()], &ID)
+    }
+
+    pub fn fill_bulletin(market_id: &str) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[b"fill_bulletin", market_id.as_bytes()], &ID)
+    }
+
+    pub fn risk_profile(owner: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[b"risk_profile", owner.as_ref()], &ID)
+    }
+
+    pub fn agent_policy(agent: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[b"agent_policy", agent.as_ref()], &ID)
+    }
+
+    pub fn encryption_key(owner: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[b"encryption_key", owner.as_ref()], &ID)
+    }
+
+    pub fn compute_pool(market_id: &str, authority: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(
+            &[b"compute_pool", market_id.as_bytes(), authority.as_ref()],
+            &ID,
+        )
+    }
+
+    pub fn house_account(market_id: &str, authority: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(
+            &[b"house_account", market_id.as_bytes(), authority.as_ref()],
+            &ID,
+        )
+    }
+
+    pub fn oracle(creator: &Pubkey, oracle_id: u64) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[b"oracle", creator.as_ref(), &oracle_id.to_le_bytes()], &ID)
+    }
+
+    pub fn rfq(taker: &Pubkey, rfq_id: u64) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[b"rfq", taker.as_ref(), &rfq_id.to_le_bytes()], &ID)
+    }
+
+    pub fn quote_bond(rfq: &Pubkey, maker: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[b"quote_bond", rfq.as_ref(), maker.as_ref()], &ID)
+    }
+
+    pub fn distributor(authority: &Pubkey, distribution_id: u64) -> (Pubkey, u8) {
+        Pubkey::find_program_address(
+            &[b"distributor", authority.as_ref(), &distribution_id.to_le_bytes()],
+            &ID,
+        )
+    }
+
+    pub fn distributor_vault(distributor: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[b"distributor_vault", distributor.as_ref()], &ID)
+    }
+
+    pub fn uniqueness_claim(distributor: &Pubkey, identity_commitment: &[u8; 32]) -> (Pubkey, u8) {
+        Pubkey::find_program_address(
+            &[b"uniqueness_claim", distributor.as_ref(), identity_commitment],
+            &ID,
+        )
+    }
+
+    pub fn execution_adapter(market_id: &str, authority: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(
+            &[b"execution_adapter", market_id.as_bytes(), authority.as_ref()],
+            &ID,
+        )
+    }
+
+    pub fn execution_fill(batch: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[b"execution_fill", batch.as_ref()], &ID)
+    }
+
+    pub fn condition(oracle: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[b"condition", oracle.as_ref()], &ID)
+    }
+
+    pub fn condition_vault(condition: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[b"condition_vault", condition.as_ref()], &ID)
+    }
+
+    pub fn yes_mint(condition: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[b"yes_mint", condition.as_ref()], &ID)
+    }
+
+    pub fn no_mint(condition: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[b"no_mint", condition.as_ref()], &ID)
+    }
+
+    pub fn amm_pool(condition: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[b"amm_pool", condition.as_ref()], &ID)
+    }
+
+    pub fn rent_sponsor(authority: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[b"rent_sponsor", authority.as_ref()], &ID)
+    }
+
+    /// Arcium signer PDA created by `init_sign_pda`
+    pub fn sign_pda() -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[&SIGN_PDA_SEED], &ID)
+    }
+}
+
+// ============================================
+// Computation Offset Helpers
+// ============================================
+
+/// Derive a reproducible `computation_offset`
+///
+/// `subject` is the account the computation updates (batch, lane, RFQ,
+/// session) and `sequence` its running counter, e.g. `order_count`.
+/// Mixing in the slot keeps retries of the same step from colliding.
+pub fn derive_computation_offset(subject: &Pubkey, sequence: u64, slot: u64) -> u64 {
+    trading_rules::computation_offset(&subject.to_bytes(), sequence, slot)
+}
+
+/// Fail early, with a clear error, when the computation PDA for an
+/// offset already exists
+fn check_computation_offset(computation_account: &UncheckedAccount) -> Result<()> {
+    require!(
+        computation_account.data_is_empty(),
+        ErrorCode::ComputationOffsetInUse
+    );
+    Ok(())
+}
+
+/// Fail unless a callback comes from the computation its account recorded
+/// at queue time
+///
+/// `verify_output` only proves the cluster signed this computation's
+/// output; any computation of the same circuit could name the account.
+fn check_callback_origin(
+    queued_computation: Option<Pubkey>,
+    computation_account: &UncheckedAccount,
+) -> Result<()> {
+    require!(
+        queued_computation == Some(computation_account.key()),
+        ErrorCode::UnexpectedComputation
+    );
+    Ok(())
+}
+
+/// Split a pubkey into the (lo, hi) halves clients encrypt as
+/// `encrypted_wallet_lo/hi`, for the circuits' signer binding
+fn split_pubkey(key: &Pubkey) -> (u128, u128) {
+    let bytes = key.to_bytes();
+    let mut lo = [0u8; 16];
+    let mut hi = [0u8; 16];
+    lo.copy_from_slice(&bytes[..16]);
+    hi.copy_from_slice(&bytes[16..]);
+    (u128::from_le_bytes(lo), u128::from_le_bytes(hi))
+}
+
+/// Mark `kind` in flight on `batch` until its callback lands
+fn queue_batch_computation(
+    batch: &mut TradingBatch,
+    kind: ComputationKind,
+    computation: Pubkey,
+    slot: u64,
+) {
+    batch.queued_at = slot;
+    batch.pending_computation = Some(kind);
+    batch.queued_computation = Some(computation);
+}
+
+/// Clear the batch's in-flight `kind` computation and report its
+/// queue-to-callback latency
+fn complete_batch_computation(batch: &mut Account<'_, TradingBatch>, kind: ComputationKind) -> Result<()> {
+    let completed_at = Clock::get()?.slot;
+    batch.completed_at = completed_at;
+    batch.pending_computation = None;
+    batch.queued_computation = None;
+
+    emit!(ComputationLatency {
+        batch: batch.key(),
+        computation: kind,
+        queued_at: batch.queued_at,
+        completed_at,
+        latency_slots: completed_at.saturating_sub(batch.queued_at),
+    });
+    Ok(())
+}
+
+/// Fold an order computation's output into its batch, as
+/// `add_order_callback` does
+fn fold_order_output(
+    batch: &mut Account<'_, TradingBatch>,
+    order: &mut Account<'_, OrderCommitment>,
+    ciphertexts: [[u8; 32]; 8],
+    nonce: u128,
+    wallet_bound: bool,
+    amount_overflow: bool,
+    expired: bool,
+) -> Result<()> {
+    batch.encrypted_state = ciphertexts;
+    batch.state_nonce = nonce;
+    batch.order_count = batch
+        .order_count
+        .checked_add(1)
+        .ok_or(ErrorCode::OrderCountOverflow)?;
+
+    complete_batch_computation(batch, ComputationKind::AddOrder)?;
+
+    emit_order_result(
+        batch.key(),
+        order,
+        batch.order_count - 1,
+        wallet_bound,
+        amount_overflow,
+        expired,
+    );
+
+    Ok(())
+}
+
+/// Record the circuit's checks on a freshly folded order
+///
+/// A rejected order keeps its slot (the circuit folded it with a zero
+/// amount) but is flagged and announced with `OrderRejected`.
+fn emit_order_result(
+    batch: Pubkey,
+    order: &mut Account<'_, OrderCommitment>,
+    order_index: u8,
+    wallet_bound: bool,
+    amount_overflow: bool,
+    expired: bool,
+) {
+    let reason = if !wallet_bound {
+        OrderRejection::WalletMismatch
+    } else if expired {
+        OrderRejection::Expired
+    } else if amount_overflow {
+        OrderRejection::AmountOverflow
+    } else {
+        emit!(OrderAdded {
+            batch,
+            order: order.key(),
+            order_index,
+            commitment_hash: order.commitment_hash,
+        });
+        return;
+    };
+
+    order.rejected = true;
+    emit!(OrderRejected {
+        batch,
+        order: order.key(),
+        order_index,
+        reason,
+    });
+}
+
+/// Move a batch along its lifecycle, stamping the slot and emitting
+/// `BatchStatusHistory`
+fn transition_status(batch: &mut Account<'_, TradingBatch>, next: BatchStatus) -> Result<()> {
+    let slot = Clock::get()?.slot;
+    let previous = batch.status;
+    require!(
+        trading_rules::enter_status(&mut batch.status_updated_at, previous as usize, next as usize, slot),
+        ErrorCode::InvalidStatusTransition
+    );
+    batch.status = next;
+
+    emit!(BatchStatusHistory {
+        batch: batch.key(),
+        from: previous,
+        to: next,
+        slot,
+    });
+
+    Ok(())
+}
+
+/// Reference price less a flow auction's improvement (in bps)
+pub fn improved_price(reference_price: u64, improvement_bps: u64) -> u64 {
+    let improvement_bps = improvement_bps.min(10_000) as u128;
+    (reference_price as u128 * (10_000 - improvement_bps) / 10_000) as u64
+}
+
+/// Output of a constant-product swap of `amount_in` (less `fee_bps`)
+pub fn amm_swap_out(reserve_in: u64, reserve_out: u64, amount_in: u64, fee_bps: u16) -> u64 {
+    let amount_in = amount_in as u128 * (10_000 - fee_bps.min(10_000) as u128) / 10_000;
+    (reserve_out as u128 * amount_in / (reserve_in as u128 + amount_in)) as u64
+}
+
+/// Mid price of `side` in a YES/NO pool, in bps of par (`None` when empty)
+pub fn amm_side_price(yes_reserve: u64, no_reserve: u64, side: Side) -> Option<u64> {
+    let total = yes_reserve as u128 + no_reserve as u128;
+    if total == 0 {
+        return None;
+    }
+    // A side is priced by the other side's share of the reserves
+    let other = match side {
+        Side::Yes => no_reserve,
+        Side::No => yes_reserve,
+    };
+    Some((other as u128 * 10_000 / total) as u64)
+}
+
+/// `a * b / c` rounded down, in u128
+fn mul_div(a: u64, b: u64, c: u64) -> u64 {
+    (a as u128 * b as u128 / c as u128) as u64
+}
+
+/// `a * b / c` rounded up, in u128
+fn mul_div_ceil(a: u64, b: u64, c: u64) -> u64 {
+    (a as u128 * b as u128).div_ceil(c as u128) as u64
+}
+
+/// Refund a new order's rent to its payer out of the batch's rent sponsor
+fn refund_order_rent(
+    sponsor: Option<&mut Account<'_, RentSponsor>>,
+    payer: &AccountInfo,
+    order: Pubkey,
+) -> Result<()> {
+    let Some(sponsor) = sponsor else {
+        return Ok(());
+    };
+
+    let rent = Rent::get()?;
+    let refund = rent.minimum_balance(8 + OrderCommitment::INIT_SPACE);
+    let available = sponsor
+        .get_lamports()
+        .saturating_sub(rent.minimum_balance(8 + RentSponsor::INIT_SPACE));
+    require!(available >= refund, ErrorCode::RentSponsorExhausted);
+
+    sponsor.sub_lamports(refund)?;
+    payer.add_lamports(refund)?;
+    sponsor.refunded = sponsor.refunded.saturating_add(refund);
+    sponsor.sponsored_orders += 1;
+
+    emit!(RentSponsored {
+        sponsor: sponsor.key(),
+        payer: payer.key(),
+        order,
+        lamports: refund,
+    });
+
+    Ok(())
+}
+
+/// Lamports a compute pool can spend without dropping below rent exemption
+fn compute_pool_available(pool: &Account<'_, ComputePool>) -> Result<u64> {
+    let rent = Rent::get()?.minimum_balance(8 + ComputePool::INIT_SPACE);
+    Ok(pool.get_lamports().saturating_sub(rent))
+}
+
+/// Refund the `fee` an order's payer spent queueing its computation out of
+/// the batch's compute pool
+///
+/// Never fails the order over the pool's balance: a pool that can't cover
+/// the fee reports itself low and the payer keeps paying.
+fn draw_compute_fee(
+    pool: Option<&mut Account<'_, ComputePool>>,
+    payer: &AccountInfo,
+    fee: u64,
+) -> Result<()> {
+    let Some(pool) = pool else {
+        return Ok(());
+    };
+    if fee == 0 {
+        return Ok(());
+    }
+
+    let available = compute_pool_available(pool)?;
+    if available < fee {
+        emit!(ComputePoolLow {
+            compute_pool: pool.key(),
+            market_id: pool.market_id.clone(),
+            available,
+            unpaid_fee: fee,
+        });
+        return Ok(());
+    }
+
+    pool.sub_lamports(fee)?;
+    payer.add_lamports(fee)?;
+    pool.drawn = pool.drawn.saturating_add(fee);
+    pool.sponsored_computations += 1;
+
+    emit!(ComputeFeeSponsored {
+        compute_pool: pool.key(),
+        payer: payer.key(),
+        lamports: fee,
+    });
+
+    let available = available - fee;
+    if available < pool.low_balance_lamports {
+        emit!(ComputePoolLow {
+            compute_pool: pool.key(),
+            market_id: pool.market_id.clone(),
+            available,
+            unpaid_fee: 0,
+        });
+    }
+
+    Ok(())
+}
+
+// ============================================
+// Attestation Helpers
+// ============================================
+
+/// Apply a batch's credential and eligibility gates to an order from `user`
+///
+/// Returns the eligibility proof the order was admitted with, if any.
+fn check_order_gates(
+    batch: &TradingBatch,
+    attestation: Option<&UncheckedAccount>,
+    eligibility_proof: Option<&UncheckedAccount>,
+    user: &Pubkey,
+) -> Result<Option<Pubkey>> {
+    // Permissioned batches only accept orders from attested users
+    if let Some(credential) = batch.required_credential {
+        let attestation = attestation.ok_or(ErrorCode::AttestationRequired)?;
+        check_sas_attestation(attestation, &credential, user)?;
+    }
+
+    // Eligibility-gated batches need a zk-verifier predicate proof
+    match batch.eligibility_predicate {
+        Some(predicate) => {
+            let account = eligibility_proof.ok_or(ErrorCode::EligibilityRequired)?;
+            let eligibility: EligibilityProofData =
+                read_zk_verifier_account(account, "EligibilityProof")?;
+            require!(
+                eligibility.predicate == predicate,
+                ErrorCode::EligibilityPredicateMismatch
+            );
+            require!(eligibility.wallet == *user, ErrorCode::EligibilityWalletMismatch);
+            require!(
+                eligibility.authority == batch.authority
+                    && eligibility.proof_system == ZK_PROOF_SYSTEM_TEE_ATTESTATION,
+                ErrorCode::InvalidZkVerifierAccount
+            );
+            Ok(Some(account.key()))
+        }
+        None => Ok(None),
+    }
+}
+
+/// Enforce `risk_profile`, if its owner created one, on an order path other
+/// than the limited ones
+///
+/// The allowlist is checked here. Amount caps can only be checked by the
+/// limited circuits, so a profile that sets any refuses the order.
+fn check_risk_profile(risk_profile: &UncheckedAccount, market_id: &str) -> Result<()> {
+    if risk_profile.data_is_empty() {
+        return Ok(());
+    }
+    require_keys_eq!(*risk_profile.owner, crate::ID, ErrorCode::RiskProfileMismatch);
+
+    let profile = RiskProfile::try_deserialize(&mut &risk_profile.try_borrow_data()?[..])?;
+    require!(profile.limits.allows(market_id), ErrorCode::MarketNotAllowed);
+    require!(
+        !profile.limits.caps_amounts(),
+        ErrorCode::RiskLimitedOrderRequired
+    );
+
+    Ok(())
+}
+
+/// Require `x25519_pubkey` to be the key in `encryption_key`, if its owner
+/// registered one
+fn check_encryption_key(encryption_key: &UncheckedAccount, x25519_pubkey: &[u8; 32]) -> Result<()> {
+    if encryption_key.data_is_empty() {
+        return Ok(());
+    }
+    require_keys_eq!(*encryption_key.owner, crate::ID, ErrorCode::EncryptionKeyMismatch);
+
+    let registered =
+        UserEncryptionKey::try_deserialize(&mut &encryption_key.try_borrow_data()?[..])?;
+    require!(
+        registered.current == *x25519_pubkey,
+        ErrorCode::EncryptionKeyMismatch
+    );
+
+    Ok(())
+}
+
+/// Layout of a SAS `Attestation` account after its 1-byte discriminator
+#[derive(AnchorDeserialize)]
+struct SasAttestation {
+    /// Subject of the attestation (the attested wallet)
+    nonce: Pubkey,
+    credential: Pubkey,
+    _schema: Pubkey,
+    _data: Vec<u8>,
+    _signer: Pubkey,
+    expiry: i64,
+    _token_account: Pubkey,
+}
+
+/// Check that `user` holds an unexpired SAS attestation under `credential`
+fn check_sas_attestation(account: &AccountInfo, credential: &Pubkey, user: &Pubkey) -> Result<()> {
+    require_keys_eq!(*account.owner, SAS_PROGRAM_ID, ErrorCode::InvalidAttestation);
+
+    let data = account.try_borrow_data()?;
+    require!(
+        data.first() == Some(&SAS_ATTESTATION_DISCRIMINATOR),
+        ErrorCode::InvalidAttestation
+    );
+
+    let attestation = SasAttestation::deserialize(&mut &data[1..])
+        .map_err(|_| ErrorCode::InvalidAttestation)?;
+    require!(
+        attestation.credential == *credential,
+        ErrorCode::AttestationCredentialMismatch
+    );
+    require!(attestation.nonce == *user, ErrorCode::AttestationSubjectMismatch);
+    require!(
+        attestation.expiry == 0 || attestation.expiry > Clock::get()?.unix_timestamp,
+        ErrorCode::AttestationExpired
+    );
+
+    Ok(())
+}
+
+// ============================================
+// Distribution Helpers
+// ============================================
+
+/// Token accounts of a transfer into or out of a distribution vault
+struct VaultTransfer<'a, 'info> {
+    token_program: &'a Interface<'info, TokenInterface>,
+    mint: &'a InterfaceAccount<'info, Mint>,
+    from: AccountInfo<'info>,
+    to: AccountInfo<'info>,
+    authority: AccountInfo<'info>,
+    memo_program: Option<&'a Program<'info, Memo>>,
+    /// Extra accounts the mint's transfer hook resolves
+    hook_accounts: &'a [AccountInfo<'info>],
+}
+
+/// `transfer_checked` (so Token-2022 hooks run), preceded by the memo if any
+///
+/// The memo lands as the sibling instruction right before the transfer,
+/// which is what Token-2022's required-memo extension checks for.
+fn vault_transfer(
+    transfer: VaultTransfer<'_, '_>,
+    signer_seeds: &[&[&[u8]]],
+    amount: u64,
+    memo: Option<&str>,
+) -> Result<()> {
+    if let Some(memo) = memo {
+        require!(
+            !memo.is_empty() && memo.len() <= MAX_TRANSFER_MEMO_LEN,
+            ErrorCode::InvalidTransferMemo
+        );
+        let memo_program = transfer
+            .memo_program
+            .ok_or(ErrorCode::MemoProgramRequired)?;
+        memo::build_memo(
+            CpiContext::new(memo_program.to_account_info(), BuildMemo {}),
+            memo.as_bytes(),
+        )?;
+    }
+
+    token_interface::transfer_checked(
+        CpiContext::new_with_signer(
+            transfer.token_program.to_account_info(),
+            TransferChecked {
+                from: transfer.from,
+                mint: transfer.mint.to_account_info(),
+                to: transfer.to,
+                authority: transfer.authority,
+            },
+            signer_seeds,
+        )
+        .with_remaining_accounts(transfer.hook_accounts.to_vec()),
+        amount,
+        transfer.mint.decimals,
+    )
+}
+
+/// Verify a claim against the distributor and pay it out of the vault
+///
+/// With `fee`, that much of `amount` goes through the fee transfer instead.
+#[allow(clippy::too_many_arguments)]
+fn pay_distribution_claim(
+    distributor: &mut Account<'_, MerkleDistributor>,
+    transfer: VaultTransfer<'_, '_>,
+    fee: Option<(VaultTransfer<'_, '_>, u64)>,
+    claimant: Pubkey,
+    index: u32,
+    amount: u64,
+    proof: &[[u8; 32]],
+    memo: Option<&str>,
+) -> Result<()> {
+    require!(index < distributor.max_claims, ErrorCode::InvalidDistributionProof);
+    require!(!distributor.is_claimed(index), ErrorCode::AlreadyClaimed);
+
+    let leaf = distribution_leaf(index, &claimant, amount);
+    require!(
+        verify_merkle_proof(proof, distributor.merkle_root, leaf),
+        ErrorCode::InvalidDistributionProof
+    );
+
+    let claimed_amount = distributor
+        .claimed_amount
+        .checked_add(amount)
+        .filter(|claimed| *claimed <= distributor.total_amount)
+        .ok_or(ErrorCode::DistributionExhausted)?;
+
+    let authority = distributor.authority;
+    let distribution_id = distributor.distribution_id.to_le_bytes();
+    let bump = [distributor.bump];
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        b"distributor",
+        authority.as_ref(),
+        &distribution_id,
+        &bump,
+    ]];
+
+    let fee_amount = fee.as_ref().map_or(0, |(_, fee)| *fee);
+    vault_transfer(transfer, signer_seeds, amount - fee_amount, memo)?;
+    if let Some((fee_transfer, fee)) = fee.filter(|(_, fee)| *fee > 0) {
+        vault_transfer(fee_transfer, signer_seeds, fee, None)?;
+    }
+
+    distributor.set_claimed(index);
+    distributor.claimed_amount = claimed_amount;
+    distributor.claim_count += 1;
+
+    emit!(DistributionClaimed {
+        distributor: distributor.key(),
+        claimant,
+        index,
+        amount,
+    });
+
+    Ok(())
+}
+
+/// Check that the instruction before this one is a single-signature
+/// ed25519 program instruction by `claimant` over `digest`
+///
+/// The precompile already verified the signature; this confirms which key
+/// and message it covered, all read from that instruction's own data.
+fn verify_voucher_signature(
+    instructions: &UncheckedAccount,
+    claimant: &Pubkey,
+    digest: &[u8; 32],
+) -> Result<()> {
+    let current = instructions_sysvar::load_current_index_checked(instructions)?;
+    require!(current > 0, ErrorCode::InvalidVoucher);
+    let ix = instructions_sysvar::load_instruction_at_checked(current as usize - 1, instructions)?;
+    require_keys_eq!(ix.program_id, ed25519_program::ID, ErrorCode::InvalidVoucher);
+
+    let signed = ed25519_instruction::parse(&ix.data).ok_or(ErrorCode::InvalidVoucher)?;
+    require!(signed.pubkey == claimant.as_ref(), ErrorCode::InvalidVoucher);
+    require!(signed.message == digest, ErrorCode::InvalidVoucher);
+
+    Ok(())
+}
+
+/// Layout of zk-verifier's `UniquenessAttestation` account
+#[derive(AnchorDeserialize)]
+struct UniquenessAttestationData {
+    epoch: u64,
+    identity_commitment: [u8; 32],
+    _attestation_commitment: [u8; 32],
+    wallet: Pubkey,
+    _verified_at: i64,
+    _bump: u8,
+    proof_system: u8,
+    authority: Pubkey,
+}
+
+/// Layout of zk-verifier's `EligibilityProof` account
+#[derive(AnchorDeserialize)]
+struct EligibilityProofData {
+    predicate: [u8; 32],
+    _nullifier: [u8; 32],
+    wallet: Pubkey,
+    _verified_at: i64,
+    _bump: u8,
+    proof_system: u8,
+    authority: Pubkey,
+}
+
+/// Read an account of type `name` owned by the zk-verifier program
+fn read_zk_verifier_account<T: AnchorDeserialize>(account: &AccountInfo, name: &str) -> Result<T> {
+    require_keys_eq!(
+        *account.owner,
+        ZK_VERIFIER_PROGRAM_ID,
+        ErrorCode::InvalidZkVerifierAccount
+    );
+
+    let data = account.try_borrow_data()?;
+    let discriminator =
+        anchor_lang::solana_program::hash::hash(format!("account:{name}").as_bytes()).to_bytes();
+    require!(
+        data.len() >= 8 && data[..8] == discriminator[..8],
+        ErrorCode::InvalidZkVerifierAccount
+    );
+
+    T::deserialize(&mut &data[8..]).map_err(|_| ErrorCode::InvalidZkVerifierAccount.into())
+}
+
+// ============================================
+// Merkle Helpers
+// ============================================
+
+/// Leaf hash for a distribution entry
+///
+/// Leaves and inner nodes use distinct prefixes so an inner node can
+/// never be passed off as a leaf.
+pub fn distribution_leaf(index: u32, claimant: &Pubkey, amount: u64) -> [u8; 32] {
+    keccak::hashv(&[
+        &[0u8],
+        &index.to_le_bytes(),
+        claimant.as_ref(),
+        &amount.to_le_bytes(),
+    ])
+    .to_bytes()
+}
+
+/// Verify a proof built from sorted sibling pairs
+fn verify_merkle_proof(proof: &[[u8; 32]], root: [u8; 32], leaf: [u8; 32]) -> bool {
+    let computed = proof.iter().fold(leaf, |node, sibling| {
+        let (left, right) = if node <= *sibling {
+            (node, *sibling)
+        } else {
+            (*sibling, node)
+        };
+        keccak::hashv(&[&[1u8], &left, &right]).to_bytes()
+    });
+
+    computed == root
+}
+
+/// Read a big-endian field element public input that holds a u128
+fn field_to_u128(input: &[u8; 32]) -> Option<u128> {
+    let (high, low) = input.split_at(16);
+    if high.iter().any(|&b| b != 0) {
+        return None;
+    }
+    Some(u128::from_be_bytes(low.try_into().ok()?))
+}
+
+// ============================================
+// Account Structures
+// ============================================
+
+#[account]
+#[derive(InitSpace)]
+pub struct TradingBatch {
+    pub bump: u8,
+    pub authority: Pubkey,
+    pub state_nonce: u128,
+    pub encrypted_state: [[u8; 32]; 8],
+    #[max_len(64)]
+    pub market_id: String,
+    pub side: Side,
+    pub status: BatchStatus,
+    pub order_count: u8,
+    pub total_usdc: u64,
+    pub merkle_root: [u8; 32],
+    /// Slot the most recent MPC computation was queued at
+    pub queued_at: u64,
+    /// Slot the most recent MPC callback landed at
+    pub completed_at: u64,
+    /// SAS credential order signers must hold an attestation under
+    pub required_credential: Option<Pubkey>,
+    /// zk-verifier eligibility predicate order signers must have proven,
+    /// under `authority`'s pinned attestation key
+    pub eligibility_predicate: Option<[u8; 32]>,
+    /// Orders staged for `add_orders_bulk` and not yet folded; they hold
+    /// the indexes after `order_count`
+    pub staged_orders: u8,
+    /// Lanes opened so far (0 for a single-state batch)
+    pub lane_count: u8,
+    /// Set once `merge_lanes` has written the lanes into `encrypted_state`
+    pub lanes_merged: bool,
+    /// Flow auction the batch must execute through, once one is opened
+    pub flow_auction: Option<Pubkey>,
+    /// Batch computation queued and not yet called back
+    pub pending_computation: Option<ComputationKind>,
+    /// Slot each status was entered at, indexed by `BatchStatus`
+    pub status_updated_at: [u64; 4],
+    /// Computation account of the last state-writing computation queued;
+    /// only its callback may write the batch
+    pub queued_computation: Option<Pubkey>,
+    /// Condition whose AMM mid was recorded at close, until the batch's
+    /// price improvement is recorded against it
+    pub reference_condition: Option<Pubkey>,
+    /// AMM mid for the batch side at close, in bps of par
+    pub reference_mid: u64,
+    /// Price the batch was executed at
+    pub execution_price: u64,
+    /// Set once the batch's fill is on its market's bulletin
+    pub fill_published: bool,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct OrderCommitment {
+    pub bump: u8,
+    pub batch: Pubkey,
+    pub user: Pubkey,
+    pub commitment_hash: [u8; 32],
+    pub index: u8,
+    pub allocated: bool,
+    /// zk-verifier `EligibilityProof` the order was admitted with
+    pub eligibility_proof: Option<Pubkey>,
+    /// Set when the circuit rejected the order (see `OrderRejected`); it
+    /// was folded with a zero amount
+    pub rejected: bool,
+}
+
+/// An order waiting for `add_orders_bulk`, closed to its user once folded
+#[account]
+#[derive(InitSpace)]
+pub struct StagedOrder {
+    pub bump: u8,
+    pub batch: Pubkey,
+    /// The order's `OrderCommitment`
+    pub order: Pubkey,
+    pub user: Pubkey,
+    pub index: u8,
+    /// Slot the order was staged at, which its expiry is checked against
+    pub staged_at: u64,
+    pub user_pubkey: [u8; 32],
+    pub nonce: u128,
+    /// Amount, wallet lo/hi and expiry slot, encrypted under `nonce`
+    pub encrypted_order: [[u8; 32]; 4],
+}
+
+/// Private request for quote
+#[account]
+#[derive(InitSpace)]
+pub struct Rfq {
+    pub bump: u8,
+    pub taker: Pubkey,
+    pub state_nonce: u128,
+    pub encrypted_state: [[u8; 32]; 5],
+    pub rfq_id: u64,
+    #[max_len(64)]
+    pub market_id: String,
+    #[max_len(8)]
+    pub makers: Vec<Pubkey>,
+    /// Bit i set once `makers[i]` has quoted
+    pub quoted_mask: u8,
+    pub quote_count: u8,
+    pub window_end_slot: u64,
+    pub status: RfqStatus,
+    /// Set while a queued computation has not called back yet
+    pub pending_computation: bool,
+    /// Computation account of that computation; only its callback may
+    /// write the RFQ
+    pub queued_computation: Option<Pubkey>,
+    pub winning_maker: Option<Pubkey>,
+    pub fill_notional: u64,
+    /// Lamports each maker bonds per quote
+    pub bond_lamports: u64,
+    /// Slots the winner has to confirm after settlement
+    pub settle_window_slots: u64,
+    pub settlement_deadline_slot: u64,
+    pub maker_settled: bool,
+    /// Quote bonds not yet released or slashed
+    pub outstanding_bonds: u8,
+}
+
+/// Maker bond backing a submitted quote
+#[account]
+#[derive(InitSpace)]
+pub struct QuoteBond {
+    pub bump: u8,
+    pub rfq: Pubkey,
+    pub maker: Pubkey,
+    pub amount: u64,
+}
+
+/// One of `ORDER_LANES` independent encrypted sub-states of a batch
+#[account]
+#[derive(InitSpace)]
+pub struct OrderLane {
+    pub bump: u8,
+    pub batch: Pubkey,
+    pub lane_index: u8,
+    pub state_nonce: u128,
+    pub encrypted_state: [[u8; 32]; 8],
+    pub order_count: u8,
+    /// Set once `init_lane` has encrypted the empty state
+    pub ready: bool,
+    /// Computation account of the last computation queued on the lane
+    pub queued_computation: Option<Pubkey>,
+    /// Slot that computation was queued at
+    pub queued_at: u64,
+}
+
+/// Ephemeral key allowed to place orders for its owner
+#[account]
+#[derive(InitSpace)]
+pub struct Session {
+    pub bump: u8,
+    pub owner: Pubkey,
+    pub session_key: Pubkey,
+    pub budget_nonce: u128,
+    /// Remaining notional the key may commit, encrypted
+    pub encrypted_budget: [[u8; 32]; 1],
+    pub expiry_slot: u64,
+    /// Set while a queued computation has not called back yet
+    pub pending_computation: bool,
+    /// Computation account of that computation; only its callback may
+    /// write the budget
+    pub queued_computation: Option<Pubkey>,
+    pub order_count: u32,
+    /// Only batches of this market accept the key
+    #[max_len(64)]
+    pub market_id: String,
+    /// Slot that computation was queued at
+    pub queued_at: u64,
+}
+
+/// Merkle distribution of SPL tokens from a program-owned vault
+#[account]
+pub struct MerkleDistributor {
+    pub bump: u8,
+    pub authority: Pubkey,
+    pub distribution_id: u64,
+    pub mint: Pubkey,
+    pub vault: Pubkey,
+    pub merkle_root: [u8; 32],
+    pub total_amount: u64,
+    pub claimed_amount: u64,
+    pub max_claims: u32,
+    pub claim_count: u32,
+    /// Bit i set once leaf i has been claimed
+    pub claimed_bitmap: Vec<u8>,
+    /// Epoch of the zk-verifier uniqueness attestation required to claim,
+    /// under `authority`'s pinned attestation key
+    pub uniqueness_epoch: Option<u64>,
+}
+
+impl MerkleDistributor {
+    pub fn bitmap_len(max_claims: u32) -> usize {
+        (max_claims as usize).div_ceil(8)
+    }
+
+    pub fn space(max_claims: u32) -> usize {
+        8 + 1 + 32 + 8 + 32 + 32 + 32 + 8 + 8 + 4 + 4 + 4 + Self::bitmap_len(max_claims) + 9
+    }
+
+    pub fn is_claimed(&self, index: u32) -> bool {
+        self.claimed_bitmap[index as usize / 8] & (1 << (index % 8)) != 0
+    }
+
+    fn set_claimed(&mut self, index: u32) {
+        self.claimed_bitmap[index as usize / 8] |= 1 << (index % 8);
+    }
+}
+
+/// Marks an identity commitment as having claimed from a distribution
+#[account]
+#[derive(InitSpace)]
+pub struct UniquenessClaim {
+    pub bump: u8,
+    pub distributor: Pubkey,
+    pub identity_commitment: [u8; 32],
+    pub claimant: Pubkey,
+}
+
+/// Sealed-bid auction of a closed batch's order flow
+#[account]
+#[derive(InitSpace)]
+pub struct FlowAuction {
+    pub bump: u8,
+    pub batch: Pubkey,
+    pub state_nonce: u128,
+    pub encrypted_state: [[u8; 32]; 3],
+    #[max_len(MAX_FLOW_AUCTION_MAKERS)]
+    pub makers: Vec<Pubkey>,
+    /// Bit i set once `makers[i]` has bid
+    pub bid_mask: u8,
+    pub bid_count: u8,
+    /// Price the batch executes at without improvement
+    pub reference_price: u64,
+    pub window_end_slot: u64,
+    pub status: FlowAuctionStatus,
+    /// Set while a queued computation has not called back yet
+    pub pending_computation: bool,
+    /// Computation account of that computation; only its callback may
+    /// write the auction
+    pub queued_computation: Option<Pubkey>,
+    pub winning_maker: Option<Pubkey>,
+    pub improvement_bps: u64,
+}
+
+/// Market resolution from encrypted provider observations
+#[account]
+#[derive(InitSpace)]
+pub struct ConfidentialOracle {
+    pub bump: u8,
+    pub creator: Pubkey,
+    pub state_nonce: u128,
+    /// One encrypted observation slot per provider
+    pub encrypted_state: [[u8; 32]; MAX_ORACLE_PROVIDERS],
+    pub oracle_id: u64,
+    #[max_len(64)]
+    pub market_id: String,
+    #[max_len(MAX_ORACLE_PROVIDERS)]
+    pub providers: Vec<Pubkey>,
+    /// Bit i set once `providers[i]` has submitted
+    pub submitted_mask: u8,
+    pub observation_count: u8,
+    pub min_observations: u8,
+    /// The outcome is YES when the median observation is at least this
+    pub threshold: u64,
+    pub window_end_slot: u64,
+    pub status: OracleStatus,
+    /// Set while a queued computation has not called back yet
+    pub pending_computation: bool,
+    /// Computation account of that computation; only its callback may
+    /// write the oracle
+    pub queued_computation: Option<Pubkey>,
+    pub outcome: Option<bool>,
+}
+
+/// One encrypted order split across several market batches
+#[account]
+#[derive(InitSpace)]
+pub struct Parlay {
+    pub bump: u8,
+    pub user: Pubkey,
+    pub state_nonce: u128,
+    /// Amount, leg weights and wallet, encrypted
+    pub encrypted_state: [[u8; 32]; 7],
+    pub parlay_id: u64,
+    /// Leg i folds into `batches[i]`
+    #[max_len(MAX_PARLAY_LEGS)]
+    pub batches: Vec<Pubkey>,
+    /// Set once `init_parlay` has encrypted the order
+    pub ready: bool,
+    /// Computation account of `init_parlay`; only its callback may set
+    /// `ready`
+    pub queued_computation: Option<Pubkey>,
+    /// Set when `init_parlay` rejected the order (wallet, expiry or weights)
+    pub rejected: bool,
+    /// Bit i set once leg i has been folded into its batch
+    pub legs_added: u8,
+    /// Whether every leg won, once settled
+    pub outcome: Option<bool>,
+}
+
+/// YES/NO outcome tokens for a market, fully backed by collateral
+///
+/// Every YES and NO token in circulation was minted as half of a complete
+/// set against one unit of collateral in `vault`.
+#[account]
+#[derive(InitSpace)]
+pub struct Condition {
+    pub bump: u8,
+    /// Confidential oracle whose outcome resolves the condition
+    pub oracle: Pubkey,
+    pub collateral_mint: Pubkey,
+    pub vault: Pubkey,
+    pub yes_mint: Pubkey,
+    pub no_mint: Pubkey,
+    #[max_len(64)]
+    pub market_id: String,
+    /// Batches whose price improvement has been recorded
+    pub improvement_batches: u32,
+    /// Sum of those batches' improvement over the AMM mid, in bps of par
+    pub cumulative_improvement_bps: i64,
+    /// Sum of the collateral those batches saved against the AMM mid
+    pub cumulative_improvement_usdc: i64,
+}
+
+/// Constant-product YES/NO pool over a condition's outcome tokens
+#[account]
+#[derive(InitSpace)]
+pub struct AmmPool {
+    pub bump: u8,
+    pub condition: Pubkey,
+    /// Pool-owned YES token account
+    pub yes_reserve: Pubkey,
+    /// Pool-owned NO token account
+    pub no_reserve: Pubkey,
+    pub lp_mint: Pubkey,
+    pub fee_bps: u16,
+}
+
+/// A fill as it appears on the public bulletin, with no wallet linkage
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub struct PublicFill {
+    pub side: Side,
+    pub execution_price: u64,
+    /// See `fill_size_bucket`
+    pub size_bucket: u8,
+    /// Execution slot rounded down to `FILL_BULLETIN_DELAY_SLOTS`
+    pub epoch_slot: u64,
+}
+
+/// Delayed public tape of a market's executed batches
+#[account]
+#[derive(InitSpace)]
+pub struct FillBulletin {
+    pub bump: u8,
+    #[max_len(64)]
+    pub market_id: String,
+    /// Slot the last fill was published at
+    pub last_published_at: u64,
+    /// Fills published so far; the next one goes to
+    /// `fills[published % FILL_TAPE_CAPACITY]`
+    pub published: u64,
+    pub fills: [PublicFill; FILL_TAPE_CAPACITY],
+}
+
+/// Limits a user sets on their own orders; zero means no cap
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, InitSpace)]
+pub struct RiskLimits {
+    /// Largest single order notional
+    pub max_order_size: u64,
+    /// Notional committed per `RISK_DAY_SLOTS`
+    pub daily_notional_cap: u64,
+    /// Markets orders may go to; empty allows all
+    #[max_len(MAX_RISK_MARKETS, 64)]
+    pub allowed_markets: Vec<String>,
+}
+
+impl RiskLimits {
+    fn validate(&self) -> Result<()> {
+        require!(
+            self.allowed_markets.len() <= MAX_RISK_MARKETS
+                && self.allowed_markets.iter().all(|market| market.len() <= 64),
+            ErrorCode::InvalidRiskLimits
+        );
+        Ok(())
+    }
+
+    fn rules(&self) -> trading_rules::Limits<'_> {
+        trading_rules::Limits {
+            max_order_size: self.max_order_size,
+            daily_notional_cap: self.daily_notional_cap,
+            allowed_markets: &self.allowed_markets,
+        }
+    }
+
+    fn caps_amounts(&self) -> bool {
+        self.rules().caps_amounts()
+    }
+
+    fn allows(&self, market_id: &str) -> bool {
+        self.rules().allows(market_id)
+    }
+
+    /// Order and daily caps as the limited circuits take them
+    fn circuit_caps(&self) -> (u64, u64) {
+        self.rules().circuit_caps()
+    }
+
+    /// Whether these limits are no looser than `current` in any respect
+    fn within(&self, current: &RiskLimits) -> bool {
+        self.rules().within(&current.rules())
+    }
+}
+
+/// A user's own trading limits, enforced on every order path
+#[account]
+#[derive(InitSpace)]
+pub struct RiskProfile {
+    pub bump: u8,
+    pub owner: Pubkey,
+    pub budget_nonce: u128,
+    /// Notional still allowed today, encrypted
+    pub encrypted_budget: [[u8; 32]; 1],
+    /// Day (`slot / RISK_DAY_SLOTS`) the budget was last spent in
+    pub budget_day: u64,
+    /// Day of the order in flight, recorded as `budget_day` by its callback
+    pub pending_day: u64,
+    /// Set while a queued computation has not called back yet
+    pub pending_computation: bool,
+    pub limits: RiskLimits,
+    /// Looser limits waiting out `RISK_LIMIT_DELAY_SLOTS`
+    pub pending_limits: Option<RiskLimits>,
+    pub pending_effective_slot: u64,
+    /// Computation account of the order in flight; only its callback may
+    /// write the budget
+    pub queued_computation: Option<Pubkey>,
+    /// Slot that computation was queued at
+    pub queued_at: u64,
+}
+
+impl RiskProfile {
+    /// Store the budget a limited order's callback returned
+    fn settle_budget(&mut self, ciphertexts: [[u8; 32]; 1], nonce: u128) {
+        self.encrypted_budget = ciphertexts;
+        self.budget_nonce = nonce;
+        self.budget_day = self.pending_day;
+        self.pending_computation = false;
+        self.queued_computation = None;
+    }
+}
+
+/// Dead-man switch over an autonomous agent's wallet
+#[account]
+#[derive(InitSpace)]
+pub struct AgentPolicy {
+    pub bump: u8,
+    pub agent: Pubkey,
+    /// Receives the agent's delegated positions once the switch trips
+    pub recovery: Pubkey,
+    /// Longest gap between heartbeats before the switch may trip
+    pub timeout_slots: u64,
+    pub last_heartbeat: u64,
+    pub tripped_at: Option<u64>,
+}
+
+impl From<trading_rules::AgentPolicyError> for ErrorCode {
+    fn from(error: trading_rules::AgentPolicyError) -> Self {
+        use trading_rules::AgentPolicyError;
+        match error {
+            AgentPolicyError::Tripped => ErrorCode::AgentPolicyTripped,
+            AgentPolicyError::NotTripped => ErrorCode::AgentPolicyNotTripped,
+            AgentPolicyError::StillAlive => ErrorCode::AgentStillAlive,
+            AgentPolicyError::ComputationPending => ErrorCode::ComputationPending,
+            AgentPolicyError::BondsOutstanding => ErrorCode::BondsOutstanding,
+        }
+    }
+}
+
+/// A wallet's x25519 key that its orders must be encrypted under
+#[account]
+#[derive(InitSpace)]
+pub struct UserEncryptionKey {
+    pub bump: u8,
+    pub owner: Pubkey,
+    pub current: [u8; 32],
+    /// Slot `current` was registered at
+    pub registered_at: u64,
+    /// Most recent retired keys, oldest first
+    #[max_len(MAX_RETIRED_ENCRYPTION_KEYS)]
+    pub retired: Vec<RetiredEncryptionKey>,
+}
+
+/// A key that was current for orders queued in `[registered_at, retired_at)`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub struct RetiredEncryptionKey {
+    pub x25519_pubkey: [u8; 32],
+    pub registered_at: u64,
+    pub retired_at: u64,
+}
+
+/// Operator liquidity account for one market, booked apart from user flow
+#[account]
+#[derive(InitSpace)]
+pub struct HouseAccount {
+    pub bump: u8,
+    pub authority: Pubkey,
+    /// Wallet that signs the house's orders
+    pub desk: Pubkey,
+    pub ledger_nonce: u128,
+    /// `HouseLedger` (pending notional, cost basis, inventory), encrypted
+    pub encrypted_ledger: [[u8; 32]; 3],
+    /// Set once a computation has written the ledger
+    pub ledger_written: bool,
+    /// Batch the pending notional was committed to, until it is settled
+    pub open_batch: Option<Pubkey>,
+    /// Ledger computation queued and not yet called back
+    pub queued_computation: Option<Pubkey>,
+    pub queued_at: u64,
+    pub house_orders: u64,
+    #[max_len(64)]
+    pub market_id: String,
+}
+
+/// Lamports that pay the computation fees of orders in one market's
+/// batches, topped up by its authority or anyone else
+#[account]
+#[derive(InitSpace)]
+pub struct ComputePool {
+    pub bump: u8,
+    pub authority: Pubkey,
+    #[max_len(64)]
+    pub market_id: String,
+    /// Spendable balance under which draws emit `ComputePoolLow`
+    pub low_balance_lamports: u64,
+    /// Total lamports refunded to order payers
+    pub drawn: u64,
+    pub sponsored_computations: u64,
+}
+
+/// Lamports a batch authority sets aside to refund its users' order rent
+#[account]
+#[derive(InitSpace)]
+pub struct RentSponsor {
+    pub bump: u8,
+    pub authority: Pubkey,
+    /// Total lamports refunded to order payers
+    pub refunded: u64,
+    pub sponsored_orders: u64,
+}
+
+/// Venue adapter executed batches of a market route to
+#[account]
+#[derive(InitSpace)]
+pub struct ExecutionAdapter {
+    pub bump: u8,
+    pub authority: Pubkey,
+    #[max_len(64)]
+    pub market_id: String,
+    pub program_id: Pubkey,
+    #[max_len(MAX_ADAPTER_CONFIG_LEN)]
+    pub config: Vec<u8>,
+}
+
+/// Venue fill recorded when a batch was routed
+#[account]
+#[derive(InitSpace)]
+pub struct ExecutionFill {
+    pub bump: u8,
+    pub batch: Pubkey,
+    pub adapter_program: Pubkey,
+    pub net_size: u64,
+    pub filled_size: u64,
+    pub average_price: u64,
+    pub limit_price: u64,
+    pub routed_at: u64,
+}
+
+// ============================================
+// Account Contexts
+// ============================================
+
+#[queue_computation_accounts("init_batch", authority)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64, market_id: String)]
+pub struct CreateBatch<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + TradingBatch::INIT_SPACE,
+        seeds = [b"batch", market_id.as_bytes(), authority.key().as_ref()],
+        bump,
+    )]
+    pub batch: Account<'info, TradingBatch>,
+
+    #[account(
+        mut,
+        seeds = [&SIGN_PDA_SEED],
+        bump = sign_pda_account.bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, ArciumSignerAccount>,
+
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+
+    #[account(mut, address = derive_mempool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: mempool_account
+    pub mempool_account: UncheckedAccount<'info>,
+
+    #[account(mut, address = derive_execpool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: executing_pool
+    pub executing_pool: UncheckedAccount<'info>,
+
+    #[account(mut, address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_INIT_BATCH))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+
+    #[account(mut, address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+
+    #[account(mut, address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+}
+
+#[callback_accounts("init_batch")]
+#[derive(Accounts)]
+pub struct InitBatchCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_INIT_BATCH))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+
+    #[account(address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: instructions_sysvar
+    pub instructions_sysvar: AccountInfo<'info>,
+
+    #[account(mut)]
+    pub batch: Account<'info, TradingBatch>,
+}
+
+#[queue_computation_accounts("add_order", user)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64)]
+pub struct AddOrder<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(mut)]
+    pub batch: Account<'info, TradingBatch>,
+
+    #[account(
+        init,
+        payer = user,
+        space = 8 + OrderCommitment::INIT_SPACE,
+        seeds = [b"order", batch.key().as_ref(), &[batch.order_count]],
+        bump,
+    )]
+    pub order: Account<'info, OrderCommitment>,
+
+    /// CHECK: `user`'s `RiskProfile` PDA, empty if they never created one
+    #[account(seeds = [b"risk_profile", user.key().as_ref()], bump)]
+    pub risk_profile: UncheckedAccount<'info>,
+
+    /// CHECK: `user`'s `UserEncryptionKey` PDA, empty if they never registered one
+    #[account(seeds = [b"encryption_key", user.key().as_ref()], bump)]
+    pub encryption_key: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"rent_sponsor", batch.authority.as_ref()],
+        bump = rent_sponsor.bump,
+    )]
+    pub rent_sponsor: Option<Account<'info, RentSponsor>>,
+
+    #[account(
+        mut,
+        seeds = [b"compute_pool", batch.market_id.as_bytes(), batch.authority.as_ref()],
+        bump = compute_pool.bump,
+    )]
+    pub compute_pool: Option<Account<'info, ComputePool>>,
+
+    /// CHECK: SAS attestation, required when `batch.required_credential` is set
+    pub attestation: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: zk-verifier eligibility proof, required when `batch.eligibility_predicate` is set
+    pub eligibility_proof: Option<UncheckedAccount<'info>>,
+
+    #[account(
+        mut,
+        seeds = [&SIGN_PDA_SEED],
+        bump = sign_pda_account.bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, ArciumSignerAccount>,
+
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+
+    #[account(mut, address = derive_mempool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: mempool_account
+    pub mempool_account: UncheckedAccount<'info>,
+
+    #[account(mut, address = derive_execpool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: executing_pool
+    pub executing_pool: UncheckedAccount<'info>,
+
+    #[account(mut, address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_ADD_ORDER))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+
+    #[account(mut, address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+
+    #[account(mut, address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+}
+
+#[callback_accounts("add_order")]
+#[derive(Accounts)]
+pub struct AddOrderCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_ADD_ORDER))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+
+    #[account(address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: instructions_sysvar
+    pub instructions_sysvar: AccountInfo<'info>,
+
+    #[account(mut)]
+    pub batch: Account<'info, TradingBatch>,
+
+    /// The order this callback folds: the batch's next slot
+    #[account(
+        mut,
+        has_one = batch @ ErrorCode::OrderBatchMismatch,
+        seeds = [b"order", batch.key().as_ref(), &[batch.order_count]],
+        bump = order.bump,
+    )]
+    pub order: Account<'info, OrderCommitment>,
+}
+
+#[derive(Accounts)]
+pub struct StageOrder<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(mut)]
+    pub batch: Account<'info, TradingBatch>,
+
+    #[account(
+        init,
+        payer = user,
+        space = 8 + OrderCommitment::INIT_SPACE,
+        seeds = [b"order", batch.key().as_ref(), &[batch.order_count + batch.staged_orders]],
+        bump,
+    )]
+    pub order: Account<'info, OrderCommitment>,
+
+    #[account(
+        init,
+        payer = user,
+        space = 8 + StagedOrder::INIT_SPACE,
+        seeds = [b"staged_order", batch.key().as_ref(), &[batch.order_count + batch.staged_orders]],
+        bump,
+    )]
+    pub staged_order: Account<'info, StagedOrder>,
+
+    /// CHECK: `user`'s `RiskProfile` PDA, empty if they never created one
+    #[account(seeds = [b"risk_profile", user.key().as_ref()], bump)]
+    pub risk_profile: UncheckedAccount<'info>,
+
+    /// CHECK: `user`'s `UserEncryptionKey` PDA, empty if they never registered one
+    #[account(seeds = [b"encryption_key", user.key().as_ref()], bump)]
+    pub encryption_key: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"rent_sponsor", batch.authority.as_ref()],
+        bump = rent_sponsor.bump,
+    )]
+    pub rent_sponsor: Option<Account<'info, RentSponsor>>,
+
+    /// CHECK: SAS attestation, required when `batch.required_credential` is set
+    pub attestation: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: zk-verifier eligibility proof, required when `batch.eligibility_predicate` is set
+    pub eligibility_proof: Option<UncheckedAccount<'info>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[queue_computation_accounts("add_orders_bulk", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64)]
+pub struct AddOrdersBulk<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(mut)]
+    pub batch: Account<'info, TradingBatch>,
+
+    #[account(
+        mut,
+        seeds = [b"compute_pool", batch.market_id.as_bytes(), batch.authority.as_ref()],
+        bump = compute_pool.bump,
+    )]
+    pub compute_pool: Option<Account<'info, ComputePool>>,
+
+    #[account(
+        mut,
+        seeds = [&SIGN_PDA_SEED],
+        bump = sign_pda_account.bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, ArciumSignerAccount>,
+
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+
+    #[account(mut, address = derive_mempool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: mempool_account
+    pub mempool_account: UncheckedAccount<'info>,
+
+    #[account(mut, address = derive_execpool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: executing_pool
+    pub executing_pool: UncheckedAccount<'info>,
+
+    #[account(mut, address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_ADD_ORDERS_BULK))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+
+    #[account(mut, address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+
+    #[account(mut, address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+}
+
+/// Each folded order's (order, staged order, user) follow in
+/// `remaining_accounts`
+#[callback_accounts("add_orders_bulk")]
+#[derive(Accounts)]
+pub struct AddOrdersBulkCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_ADD_ORDERS_BULK))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+
+    #[account(address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: instructions_sysvar
+    pub instructions_sysvar: AccountInfo<'info>,
+
+    #[account(mut)]
+    pub batch: Account<'info, TradingBatch>,
+}
+
+#[derive(Accounts)]
+pub struct CloseBatch<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = authority @ ErrorCode::Unauthorized,
+    )]
+    pub batch: Account<'info, TradingBatch>,
+
+    /// The market's AMM pool, to record its mid as the price reference
+    pub amm_pool: Option<Account<'info, AmmPool>>,
+    pub condition: Option<Account<'info, Condition>>,
+    pub amm_yes_reserve: Option<InterfaceAccount<'info, TokenAccount>>,
+    pub amm_no_reserve: Option<InterfaceAccount<'info, TokenAccount>>,
+}
+
+#[queue_computation_accounts("execute_batch", authority)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64)]
+pub struct ExecuteBatch<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(mut, has_one = authority @ ErrorCode::Unauthorized)]
+    pub batch: Account<'info, TradingBatch>,
+
+    /// Required once the batch's flow has been auctioned
+    #[account(
+        seeds = [b"flow_auction", batch.key().as_ref()],
+        bump = flow_auction.bump,
+    )]
+    pub flow_auction: Option<Account<'info, FlowAuction>>,
+
+    #[account(
+        mut,
+        seeds = [&SIGN_PDA_SEED],
+        bump = sign_pda_account.bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, ArciumSignerAccount>,
+
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+
+    #[account(mut, address = derive_mempool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: mempool_account
+    pub mempool_account: UncheckedAccount<'info>,
+
+    #[account(mut, address = derive_execpool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: executing_pool
+    pub executing_pool: UncheckedAccount<'info>,
+
+    #[account(mut, address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_EXECUTE_BATCH))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+
+    #[account(mut, address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+
+    #[account(mut, address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+}
+
+#[callback_accounts("execute_batch")]
+#[derive(Accounts)]
+pub struct ExecuteBatchCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_EXECUTE_BATCH))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+
+    #[account(address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: instructions_sysvar
+    pub instructions_sysvar: AccountInfo<'info>,
+
+    #[account(mut)]
+    pub batch: Account<'info, TradingBatch>,
+}
+
+#[queue_computation_accounts("simulate_execute_batch", authority)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64)]
+pub struct SimulateExecuteBatch<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(mut, has_one = authority @ ErrorCode::Unauthorized)]
+    pub batch: Account<'info, TradingBatch>,
+
+    #[account(
+        mut,
+        seeds = [&SIGN_PDA_SEED],
+        bump = sign_pda_account.bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, ArciumSignerAccount>,
+
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+
+    #[account(mut, address = derive_mempool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: mempool_account
+    pub mempool_account: UncheckedAccount<'info>,
+
+    #[account(mut, address = derive_execpool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: executing_pool
+    pub executing_pool: UncheckedAccount<'info>,
+
+    #[account(mut, address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_SIMULATE_EXECUTE_BATCH))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+
+    #[account(mut, address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+
+    #[account(mut, address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+}
+
+#[callback_accounts("simulate_execute_batch")]
+#[derive(Accounts)]
+pub struct SimulateExecuteBatchCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_SIMULATE_EXECUTE_BATCH))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+
+    #[account(address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: instructions_sysvar
+    pub instructions_sysvar: AccountInfo<'info>,
+
+    #[account(mut)]
+    pub batch: Account<'info, TradingBatch>,
+}
+
+#[derive(Accounts)]
+pub struct VerifyAllocation<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = authority @ ErrorCode::Unauthorized,
+    )]
+    pub batch: Account<'info, TradingBatch>,
+}
+
+#[queue_computation_accounts("init_rfq", taker)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64, rfq_id: u64)]
+pub struct CreateRfq<'info> {
+    #[account(mut)]
+    pub taker: Signer<'info>,
+
+    #[account(
+        init,
+        payer = taker,
+        space = 8 + Rfq::INIT_SPACE,
+        seeds = [b"rfq", taker.key().as_ref(), &rfq_id.to_le_bytes()],
+        bump,
+    )]
+    pub rfq: Account<'info, Rfq>,
+
+    /// CHECK: `taker`'s `RiskProfile` PDA, empty if they never created one
+    #[account(seeds = [b"risk_profile", taker.key().as_ref()], bump)]
+    pub risk_profile: UncheckedAccount<'info>,
+
+    /// CHECK: `taker`'s `UserEncryptionKey` PDA, empty if they never registered one
+    #[account(seeds = [b"encryption_key", taker.key().as_ref()], bump)]
+    pub encryption_key: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [&SIGN_PDA_SEED],
+        bump = sign_pda_account.bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, ArciumSignerAccount>,
+
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+
+    #[account(mut, address = derive_mempool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: mempool_account
+    pub mempool_account: UncheckedAccount<'info>,
+
+    #[account(mut, address = derive_execpool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: executing_pool
+    pub executing_pool: UncheckedAccount<'info>,
+
+    #[account(mut, address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_INIT_RFQ))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+
+    #[account(mut, address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+
+    #[account(mut, address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+}
+
+#[callback_accounts("init_rfq")]
+#[derive(Accounts)]
+pub struct InitRfqCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_INIT_RFQ))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+
+    #[account(address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: instructions_sysvar
+    pub instructions_sysvar: AccountInfo<'info>,
+
+    #[account(mut)]
+    pub rfq: Account<'info, Rfq>,
+}
+
+#[queue_computation_accounts("submit_quote", maker)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64)]
+pub struct SubmitQuote<'info> {
+    #[account(mut)]
+    pub maker: Signer<'info>,
+
+    #[account(mut)]
+    pub rfq: Account<'info, Rfq>,
+
+    #[account(
+        init,
+        payer = maker,
+        space = 8 + QuoteBond::INIT_SPACE,
+        seeds = [b"quote_bond", rfq.key().as_ref(), maker.key().as_ref()],
+        bump,
+    )]
+    pub quote_bond: Account<'info, QuoteBond>,
+
+    #[account(
+        mut,
+        seeds = [&SIGN_PDA_SEED],
+        bump = sign_pda_account.bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, ArciumSignerAccount>,
+
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+
+    #[account(mut, address = derive_mempool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: mempool_account
+    pub mempool_account: UncheckedAccount<'info>,
+
+    #[account(mut, address = derive_execpool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: executing_pool
+    pub executing_pool: UncheckedAccount<'info>,
+
+    #[account(mut, address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_SUBMIT_QUOTE))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+
+    #[account(mut, address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+
+    #[account(mut, address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+}
+
+#[callback_accounts("submit_quote")]
+#[derive(Accounts)]
+pub struct SubmitQuoteCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_SUBMIT_QUOTE))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+
+    #[account(address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: instructions_sysvar
+    pub instructions_sysvar: AccountInfo<'info>,
+
+    #[account(mut)]
+    pub rfq: Account<'info, Rfq>,
+}
+
+#[queue_computation_accounts("settle_rfq", taker)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64)]
+pub struct SettleRfq<'info> {
+    #[account(mut)]
+    pub taker: Signer<'info>,
+
+    #[account(mut, has_one = taker @ ErrorCode::Unauthorized)]
+    pub rfq: Account<'info, Rfq>,
+
+    #[account(
+        mut,
+        seeds = [&SIGN_PDA_SEED],
+        bump = sign_pda_account.bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, ArciumSignerAccount>,
+
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+
+    #[account(mut, address = derive_mempool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: mempool_account
+    pub mempool_account: UncheckedAccount<'info>,
+
+    #[account(mut, address = derive_execpool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: executing_pool
+    pub executing_pool: UncheckedAccount<'info>,
+
+    #[account(mut, address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_SETTLE_RFQ))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+
+    #[account(mut, address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+
+    #[account(mut, address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+}
+
+#[callback_accounts("settle_rfq")]
+#[derive(Accounts)]
+pub struct SettleRfqCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_SETTLE_RFQ))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+
+    #[account(address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: instructions_sysvar
+    pub instructions_sysvar: AccountInfo<'info>,
+
+    #[account(mut)]
+    pub rfq: Account<'info, Rfq>,
+}
+
+#[derive(Accounts)]
+pub struct ConfirmRfqFill<'info> {
+    pub maker: Signer<'info>,
+
+    #[account(mut)]
+    pub rfq: Account<'info, Rfq>,
+}
+
+#[derive(Accounts)]
+pub struct ReleaseQuoteBond<'info> {
+    #[account(mut)]
+    pub maker: Signer<'info>,
+
+    #[account(mut)]
+    pub rfq: Account<'info, Rfq>,
+
+    #[account(
+        mut,
+        close = maker,
+        seeds = [b"quote_bond", rfq.key().as_ref(), maker.key().as_ref()],
+        bump = quote_bond.bump,
+    )]
+    pub quote_bond: Account<'info, QuoteBond>,
+}
+
+#[derive(Accounts)]
+pub struct SlashQuoteBond<'info> {
+    #[account(mut)]
+    pub taker: Signer<'info>,
+
+    #[account(mut, has_one = taker @ ErrorCode::Unauthorized)]
+    pub rfq: Account<'info, Rfq>,
+
+    #[account(
+        mut,
+        close = taker,
+        seeds = [b"quote_bond", rfq.key().as_ref(), quote_bond.maker.as_ref()],
+        bump = quote_bond.bump,
+    )]
+    pub quote_bond: Account<'info, QuoteBond>,
+}
+
+#[derive(Accounts)]
+pub struct CancelRfq<'info> {
+    #[account(mut)]
+    pub taker: Signer<'info>,
+
+    #[account(
+        mut,
+        close = taker,
+        has_one = taker @ ErrorCode::Unauthorized,
+    )]
+    pub rfq: Account<'info, Rfq>,
+}
+
+#[queue_computation_accounts("init_lane", authority)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64, lane_index: u8)]
+pub struct OpenLane<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(mut, has_one = authority @ ErrorCode::Unauthorized)]
+    pub batch: Account<'info, TradingBatch>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + OrderLane::INIT_SPACE,
+        seeds = [b"lane", batch.key().as_ref(), &[lane_index]],
+        bump,
+    )]
+    pub lane: Account<'info, OrderLane>,
+
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = authority,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, ArciumSignerAccount>,
+
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+
+    #[account(mut, address = derive_mempool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: mempool_account
+    pub mempool_account: UncheckedAccount<'info>,
+
+    #[account(mut, address = derive_execpool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: executing_pool
+    pub executing_pool: UncheckedAccount<'info>,
+
+    #[account(mut, address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_INIT_LANE))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+
+    #[account(mut, address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+
+    #[account(mut, address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+}
+
+#[callback_accounts("init_lane")]
+#[derive(Accounts)]
+pub struct InitLaneCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_INIT_LANE))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+
+    #[account(address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: instructions_sysvar
+    pub instructions_sysvar: AccountInfo<'info>,
+
+    #[account(mut)]
+    pub lane: Account<'info, OrderLane>,
+}
+
+#[queue_computation_accounts("add_lane_order", user)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64)]
+pub struct AddLaneOrder<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    pub batch: Account<'info, TradingBatch>,
+
+    #[account(mut, has_one = batch)]
+    pub lane: Account<'info, OrderLane>,
+
+    #[account(
+        init,
+        payer = user,
+        space = 8 + OrderCommitment::INIT_SPACE,
+        seeds = [
+            b"order",
+            batch.key().as_ref(),
+            &[lane.lane_index * LANE_CAPACITY + lane.order_count],
+        ],
+        bump,
+    )]
+    pub order: Account<'info, OrderCommitment>,
+
+    /// CHECK: `user`'s `RiskProfile` PDA, empty if they never created one
+    #[account(seeds = [b"risk_profile", user.key().as_ref()], bump)]
+    pub risk_profile: UncheckedAccount<'info>,
+
+    /// CHECK: `user`'s `UserEncryptionKey` PDA, empty if they never registered one
+    #[account(seeds = [b"encryption_key", user.key().as_ref()], bump)]
+    pub encryption_key: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"rent_sponsor", batch.authority.as_ref()],
+        bump = rent_sponsor.bump,
+    )]
+    pub rent_sponsor: Option<Account<'info, RentSponsor>>,
+
+    #[account(
+        mut,
+        seeds = [b"compute_pool", batch.market_id.as_bytes(), batch.authority.as_ref()],
+        bump = compute_pool.bump,
+    )]
+    pub compute_pool: Option<Account<'info, ComputePool>>,
+
+    /// CHECK: SAS attestation, required when `batch.required_credential` is set
+    pub attestation: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: zk-verifier eligibility proof, required when `batch.eligibility_predicate` is set
+    pub eligibility_proof: Option<UncheckedAccount<'info>>,
+
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = user,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, ArciumSignerAccount>,
+
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+
+    #[account(mut, address = derive_mempool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: mempool_account
+    pub mempool_account: UncheckedAccount<'info>,
+
+    #[account(mut, address = derive_execpool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: executing_pool
+    pub executing_pool: UncheckedAccount<'info>,
+
+    #[account(mut, address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_ADD_LANE_ORDER))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+
+    #[account(mut, address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+
+    #[account(mut, address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+}
+
+#[callback_accounts("add_lane_order")]
+#[derive(Accounts)]
+pub struct AddLaneOrderCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_ADD_LANE_ORDER))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+
+    #[account(address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: instructions_sysvar
+    pub instructions_sysvar: AccountInfo<'info>,
+
+    #[account(mut)]
+    pub batch: Account<'info, TradingBatch>,
+
+    #[account(mut, has_one = batch @ ErrorCode::OrderBatchMismatch)]
+    pub lane: Account<'info, OrderLane>,
+
+    /// The order this callback folds: the lane's next slot
+    #[account(
+        mut,
+        has_one = batch @ ErrorCode::OrderBatchMismatch,
+        seeds = [
+            b"order",
+            batch.key().as_ref(),
+            &[lane.lane_index * LANE_CAPACITY + lane.order_count],
+        ],
+        bump = order.bump,
+    )]
+    pub order: Account<'info, OrderCommitment>,
+}
+
+#[queue_computation_accounts("merge_lanes", authority)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64)]
+pub struct MergeLanes<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(mut, has_one = authority @ ErrorCode::Unauthorized)]
+    pub batch: Account<'info, TradingBatch>,
+
+    #[account(
+        seeds = [b"lane", batch.key().as_ref(), &[0]],
+        bump = lane_0.bump,
+    )]
+    pub lane_0: Account<'info, OrderLane>,
+
+    #[account(
+        seeds = [b"lane", batch.key().as_ref(), &[1]],
+        bump = lane_1.bump,
+    )]
+    pub lane_1: Account<'info, OrderLane>,
+
+    #[account(
+        seeds = [b"lane", batch.key().as_ref(), &[2]],
+        bump = lane_2.bump,
+    )]
+    pub lane_2: Account<'info, OrderLane>,
+
+    #[account(
+        seeds = [b"lane", batch.key().as_ref(), &[3]],
+        bump = lane_3.bump,
+    )]
+    pub lane_3: Account<'info, OrderLane>,
+
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = authority,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, ArciumSignerAccount>,
+
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+
+    #[account(mut, address = derive_mempool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: mempool_account
+    pub mempool_account: UncheckedAccount<'info>,
+
+    #[account(mut, address = derive_execpool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: executing_pool
+    pub executing_pool: UncheckedAccount<'info>,
+
+    #[account(mut, address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_MERGE_LANES))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+
+    #[account(mut, address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+
+    #[account(mut, address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+}
+
+#[callback_accounts("merge_lanes")]
+#[derive(Accounts)]
+pub struct MergeLanesCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_MERGE_LANES))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+
+    #[account(address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: instructions_sysvar
+    pub instructions_sysvar: AccountInfo<'info>,
+
+    #[account(mut)]
+    pub batch: Account<'info, TradingBatch>,
+}
+
+#[queue_computation_accounts("init_session", owner)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64, session_key: Pubkey)]
+pub struct CreateSession<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + Session::INIT_SPACE,
+        seeds = [b"session", owner.key().as_ref(), session_key.as_ref()],
+        bump,
+    )]
+    pub session: Account<'info, Session>,
+
+    #[account(
+        mut,
+        seeds = [&SIGN_PDA_SEED],
+        bump = sign_pda_account.bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, ArciumSignerAccount>,
+
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+
+    #[account(mut, address = derive_mempool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: mempool_account
+    pub mempool_account: UncheckedAccount<'info>,
+
+    #[account(mut, address = derive_execpool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: executing_pool
+    pub executing_pool: UncheckedAccount<'info>,
+
+    #[account(mut, address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_INIT_SESSION))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+
+    #[account(mut, address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+
+    #[account(mut, address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+}
+
+#[callback_accounts("init_session")]
+#[derive(Accounts)]
+pub struct InitSessionCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_INIT_SESSION))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+
+    #[account(address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: instructions_sysvar
+    pub instructions_sysvar: AccountInfo<'info>,
+
+    #[account(mut)]
+    pub session: Account<'info, Session>,
+}
+
+#[queue_computation_accounts("add_session_order", session_key)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64)]
+pub struct AddOrderWithSession<'info> {
+    #[account(mut)]
+    pub session_key: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"session", session.owner.as_ref(), session_key.key().as_ref()],
+        bump = session.bump,
+    )]
+    pub session: Account<'info, Session>,
+
+    #[account(mut)]
+    pub batch: Account<'info, TradingBatch>,
+
+    #[account(
+        init,
+        payer = session_key,
+        space = 8 + OrderCommitment::INIT_SPACE,
+        seeds = [b"order", batch.key().as_ref(), &[batch.order_count]],
+        bump,
+    )]
+    pub order: Account<'info, OrderCommitment>,
+
+    /// CHECK: the session owner's `RiskProfile` PDA, empty if they never created one
+    #[account(seeds = [b"risk_profile", session.owner.as_ref()], bump)]
+    pub risk_profile: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"rent_sponsor", batch.authority.as_ref()],
+        bump = rent_sponsor.bump,
+    )]
+    pub rent_sponsor: Option<Account<'info, RentSponsor>>,
+
+    #[account(
+        mut,
+        seeds = [b"compute_pool", batch.market_id.as_bytes(), batch.authority.as_ref()],
+        bump = compute_pool.bump,
+    )]
+    pub compute_pool: Option<Account<'info, ComputePool>>,
+
+    /// CHECK: SAS attestation, required when `batch.required_credential` is set
+    pub attestation: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: zk-verifier eligibility proof, required when `batch.eligibility_predicate` is set
+    pub eligibility_proof: Option<UncheckedAccount<'info>>,
+
+    #[account(
+        mut,
+        seeds = [&SIGN_PDA_SEED],
+        bump = sign_pda_account.bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, ArciumSignerAccount>,
+
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+
+    #[account(mut, address = derive_mempool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: mempool_account
+    pub mempool_account: UncheckedAccount<'info>,
+
+    #[account(mut, address = derive_execpool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: executing_pool
+    pub executing_pool: UncheckedAccount<'info>,
+
+    #[account(mut, address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_ADD_SESSION_ORDER))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+
+    #[account(mut, address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+
+    #[account(mut, address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+}
+
+#[callback_accounts("add_session_order")]
+#[derive(Accounts)]
+pub struct AddSessionOrderCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_ADD_SESSION_ORDER))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+
+    #[account(address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: instructions_sysvar
+    pub instructions_sysvar: AccountInfo<'info>,
+
+    #[account(mut)]
+    pub batch: Account<'info, TradingBatch>,
+
+    /// The order this callback folds: the batch's next slot
+    #[account(
+        mut,
+        has_one = batch @ ErrorCode::OrderBatchMismatch,
+        seeds = [b"order", batch.key().as_ref(), &[batch.order_count]],
+        bump = order.bump,
+    )]
+    pub order: Account<'info, OrderCommitment>,
+
+    #[account(mut, constraint = session.owner == order.user @ ErrorCode::SessionScopeMismatch)]
+    pub session: Account<'info, Session>,
+}
+
+#[derive(Accounts)]
+pub struct RevokeSession<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        close = owner,
+        has_one = owner @ ErrorCode::Unauthorized,
+    )]
+    pub session: Account<'info, Session>,
+}
+
+#[derive(Accounts)]
+#[instruction(distribution_id: u64, max_claims: u32)]
+pub struct CreateDistribution<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = MerkleDistributor::space(max_claims),
+        seeds = [b"distributor", authority.key().as_ref(), &distribution_id.to_le_bytes()],
+        bump,
+    )]
+    pub distributor: Account<'info, MerkleDistributor>,
+
+    #[account(mint::token_program = token_program)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        init,
+        payer = authority,
+        seeds = [b"distributor_vault", distributor.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = distributor,
+        token::token_program = token_program,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut, token::mint = mint, token::authority = authority)]
+    pub authority_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub memo_program: Option<Program<'info, Memo>>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimDistribution<'info> {
+    pub claimant: Signer<'info>,
+
+    #[account(mut, has_one = vault)]
+    pub distributor: Account<'info, MerkleDistributor>,
+
+    #[account(address = distributor.mint, mint::token_program = token_program)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(mut)]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut, token::mint = distributor.mint)]
+    pub claimant_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub memo_program: Option<Program<'info, Memo>>,
+}
+
+#[derive(Accounts)]
+#[instruction(index: u32, amount: u64, proof: Vec<[u8; 32]>, identity_commitment: [u8; 32])]
+pub struct ClaimDistributionUnique<'info> {
+    #[account(mut)]
+    pub claimant: Signer<'info>,
+
+    #[account(mut, has_one = vault)]
+    pub distributor: Account<'info, MerkleDistributor>,
+
+    #[account(address = distributor.mint, mint::token_program = token_program)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(mut)]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut, token::mint = distributor.mint)]
+    pub claimant_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: owner and layout checked in `read_zk_verifier_account`
+    pub attestation: UncheckedAccount<'info>,
+
+    #[account(
+        init,
+        payer = claimant,
+        space = 8 + UniquenessClaim::INIT_SPACE,
+        seeds = [b"uniqueness_claim", distributor.key().as_ref(), identity_commitment.as_ref()],
+        bump,
+    )]
+    pub uniqueness_claim: Account<'info, UniquenessClaim>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub memo_program: Option<Program<'info, Memo>>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimDistributionVoucher<'info> {
+    /// Submits the claim and pays its fees
+    pub relayer: Signer<'info>,
+
+    #[account(mut, has_one = vault)]
+    pub distributor: Account<'info, MerkleDistributor>,
+
+    #[account(address = distributor.mint, mint::token_program = token_program)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(mut)]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    /// Owned by the voucher's claimant
+    #[account(mut, token::mint = distributor.mint)]
+    pub claimant_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// The voucher's `fee_account`
+    #[account(mut, token::mint = distributor.mint)]
+    pub relayer_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: instructions sysvar, for the claimant's ed25519 signature
+    #[account(address = instructions_sysvar::ID)]
+    pub instructions: UncheckedAccount<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub memo_program: Option<Program<'info, Memo>>,
+}
+
+#[queue_computation_accounts("init_oracle", creator)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64, oracle_id: u64)]
+pub struct CreateOracle<'info> {
+    #[account(mut)]
+    pub creator: Signer<'info>,
+
+    #[account(
+        init,
+        payer = creator,
+        space = 8 + ConfidentialOracle::INIT_SPACE,
+        seeds = [b"oracle", creator.key().as_ref(), &oracle_id.to_le_bytes()],
+        bump,
+    )]
+    pub oracle: Account<'info, ConfidentialOracle>,
+
+    #[account(
+        mut,
+        seeds = [&SIGN_PDA_SEED],
+        bump = sign_pda_account.bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, ArciumSignerAccount>,
+
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+
+    #[account(mut, address = derive_mempool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: mempool_account
+    pub mempool_account: UncheckedAccount<'info>,
+
+    #[account(mut, address = derive_execpool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: executing_pool
+    pub executing_pool: UncheckedAccount<'info>,
+
+    #[account(mut, address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_INIT_ORACLE))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+
+    #[account(mut, address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+
+    #[account(mut, address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+}
+
+#[callback_accounts("init_oracle")]
+#[derive(Accounts)]
+pub struct InitOracleCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_INIT_ORACLE))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+
+    #[account(address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: instructions_sysvar
+    pub instructions_sysvar: AccountInfo<'info>,
+
+    #[account(mut)]
+    pub oracle: Account<'info, ConfidentialOracle>,
+}
+
+#[queue_computation_accounts("submit_observation", provider)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64)]
+pub struct SubmitObservation<'info> {
+    #[account(mut)]
+    pub provider: Signer<'info>,
+
+    #[account(mut)]
+    pub oracle: Account<'info, ConfidentialOracle>,
+
+    #[account(
+        mut,
+        seeds = [&SIGN_PDA_SEED],
+        bump = sign_pda_account.bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, ArciumSignerAccount>,
+
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+
+    #[account(mut, address = derive_mempool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: mempool_account
+    pub mempool_account: UncheckedAccount<'info>,
+
+    #[account(mut, address = derive_execpool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: executing_pool
+    pub executing_pool: UncheckedAccount<'info>,
+
+    #[account(mut, address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_SUBMIT_OBSERVATION))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+
+    #[account(mut, address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+
+    #[account(mut, address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+}
+
+#[callback_accounts("submit_observation")]
+#[derive(Accounts)]
+pub struct SubmitObservationCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_SUBMIT_OBSERVATION))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+
+    #[account(address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: instructions_sysvar
+    pub instructions_sysvar: AccountInfo<'info>,
+
+    #[account(mut)]
+    pub oracle: Account<'info, ConfidentialOracle>,
+}
+
+#[queue_computation_accounts("resolve_oracle", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64)]
+pub struct ResolveOracle<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(mut)]
+    pub oracle: Account<'info, ConfidentialOracle>,
+
+    #[account(
+        mut,
+        seeds = [&SIGN_PDA_SEED],
+        bump = sign_pda_account.bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, ArciumSignerAccount>,
+
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+
+    #[account(mut, address = derive_mempool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: mempool_account
+    pub mempool_account: UncheckedAccount<'info>,
+
+    #[account(mut, address = derive_execpool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: executing_pool
+    pub executing_pool: UncheckedAccount<'info>,
+
+    #[account(mut, address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_RESOLVE_ORACLE))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+
+    #[account(mut, address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+
+    #[account(mut, address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+}
+
+#[callback_accounts("resolve_oracle")]
+#[derive(Accounts)]
+pub struct ResolveOracleCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_RESOLVE_ORACLE))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+
+    #[account(address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: instructions_sysvar
+    pub instructions_sysvar: AccountInfo<'info>,
+
+    #[account(mut)]
+    pub oracle: Account<'info, ConfidentialOracle>,
+}
+
+#[queue_computation_accounts("init_flow_auction", authority)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64)]
+pub struct CreateFlowAuction<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(mut, has_one = authority @ ErrorCode::Unauthorized)]
+    pub batch: Account<'info, TradingBatch>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + FlowAuction::INIT_SPACE,
+        seeds = [b"flow_auction", batch.key().as_ref()],
+        bump,
+    )]
+    pub flow_auction: Account<'info, FlowAuction>,
+
+    #[account(
+        mut,
+        seeds = [&SIGN_PDA_SEED],
+        bump = sign_pda_account.bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, ArciumSignerAccount>,
+
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+
+    #[account(mut, address = derive_mempool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: mempool_account
+    pub mempool_account: UncheckedAccount<'info>,
+
+    #[account(mut, address = derive_execpool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: executing_pool
+    pub executing_pool: UncheckedAccount<'info>,
+
+    #[account(mut, address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_INIT_FLOW_AUCTION))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+
+    #[account(mut, address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+
+    #[account(mut, address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+}
+
+#[callback_accounts("init_flow_auction")]
+#[derive(Accounts)]
+pub struct InitFlowAuctionCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_INIT_FLOW_AUCTION))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+
+    #[account(address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: instructions_sysvar
+    pub instructions_sysvar: AccountInfo<'info>,
+
+    #[account(mut)]
+    pub flow_auction: Account<'info, FlowAuction>,
+}
+
+#[queue_computation_accounts("submit_flow_bid", maker)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64)]
+pub struct SubmitFlowBid<'info> {
+    #[account(mut)]
+    pub maker: Signer<'info>,
+
+    #[account(mut, has_one = batch)]
+    pub flow_auction: Account<'info, FlowAuction>,
+
+    pub batch: Account<'info, TradingBatch>,
+
+    #[account(
+        mut,
+        seeds = [&SIGN_PDA_SEED],
+        bump = sign_pda_account.bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, ArciumSignerAccount>,
+
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+
+    #[account(mut, address = derive_mempool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: mempool_account
+    pub mempool_account: UncheckedAccount<'info>,
+
+    #[account(mut, address = derive_execpool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: executing_pool
+    pub executing_pool: UncheckedAccount<'info>,
+
+    #[account(mut, address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_SUBMIT_FLOW_BID))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+
+    #[account(mut, address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+
+    #[account(mut, address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+}
+
+#[callback_accounts("submit_flow_bid")]
+#[derive(Accounts)]
+pub struct SubmitFlowBidCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_SUBMIT_FLOW_BID))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+
+    #[account(address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: instructions_sysvar
+    pub instructions_sysvar: AccountInfo<'info>,
+
+    #[account(mut)]
+    pub flow_auction: Account<'info, FlowAuction>,
+}
+
+#[queue_computation_accounts("settle_flow_auction", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64)]
+pub struct SettleFlowAuction<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(mut)]
+    pub flow_auction: Account<'info, FlowAuction>,
+
+    #[account(
+        mut,
+        seeds = [&SIGN_PDA_SEED],
+        bump = sign_pda_account.bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, ArciumSignerAccount>,
+
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+
+    #[account(mut, address = derive_mempool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: mempool_account
+    pub mempool_account: UncheckedAccount<'info>,
+
+    #[account(mut, address = derive_execpool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: executing_pool
+    pub executing_pool: UncheckedAccount<'info>,
+
+    #[account(mut, address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_SETTLE_FLOW_AUCTION))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+
+    #[account(mut, address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+
+    #[account(mut, address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+}
+
+#[callback_accounts("settle_flow_auction")]
+#[derive(Accounts)]
+pub struct SettleFlowAuctionCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_SETTLE_FLOW_AUCTION))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+
+    #[account(address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: instructions_sysvar
+    pub instructions_sysvar: AccountInfo<'info>,
+
+    #[account(mut)]
+    pub flow_auction: Account<'info, FlowAuction>,
+}
+
+#[derive(Accounts)]
+pub struct AbandonComputation<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(mut, has_one = authority @ ErrorCode::Unauthorized)]
+    pub batch: Account<'info, TradingBatch>,
+
+    /// Order a stuck `add_order` was folding, required for that kind
+    #[account(
+        mut,
+        seeds = [b"order", batch.key().as_ref(), &[batch.order_count]],
+        bump = order.bump,
+    )]
+    pub order: Option<Account<'info, OrderCommitment>>,
+
+    /// CHECK: receives the order's rent; checked against `order.user`
+    #[account(mut)]
+    pub order_user: Option<UncheckedAccount<'info>>,
+
+    /// Session a stuck session order left pending
+    #[account(mut)]
+    pub session: Option<Account<'info, Session>>,
+
+    /// Risk profile a stuck limited order left pending
+    #[account(mut)]
+    pub risk_profile: Option<Account<'info, RiskProfile>>,
+
+    /// House account a stuck house order left pending
+    #[account(mut)]
+    pub house_account: Option<Account<'info, HouseAccount>>,
+}
+
+#[derive(Accounts)]
+pub struct AbandonLaneOrder<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(has_one = authority @ ErrorCode::Unauthorized)]
+    pub batch: Account<'info, TradingBatch>,
+
+    #[account(mut, has_one = batch)]
+    pub lane: Account<'info, OrderLane>,
+
+    /// Order the stuck `add_lane_order` was folding
+    #[account(
+        mut,
+        seeds = [
+            b"order",
+            batch.key().as_ref(),
+            &[lane.lane_index * LANE_CAPACITY + lane.order_count],
+        ],
+        bump = order.bump,
+    )]
+    pub order: Account<'info, OrderCommitment>,
+
+    /// CHECK: receives the order's rent; checked against `order.user`
+    #[account(mut)]
+    pub order_user: UncheckedAccount<'info>,
+}
+
+#[queue_computation_accounts("init_batch", authority)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64)]
+pub struct RequeueInitBatch<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(mut, has_one = authority @ ErrorCode::Unauthorized)]
+    pub batch: Account<'info, TradingBatch>,
+
+    #[account(
+        mut,
+        seeds = [&SIGN_PDA_SEED],
+        bump = sign_pda_account.bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, ArciumSignerAccount>,
+
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+
+    #[account(mut, address = derive_mempool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: mempool_account
+    pub mempool_account: UncheckedAccount<'info>,
+
+    #[account(mut, address = derive_execpool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: executing_pool
+    pub executing_pool: UncheckedAccount<'info>,
+
+    #[account(mut, address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_INIT_BATCH))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+
+    #[account(mut, address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+
+    #[account(mut, address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+}
+
+#[queue_computation_accounts("init_parlay", user)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64, parlay_id: u64)]
+pub struct CreateParlay<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        init,
+        payer = user,
+        space = 8 + Parlay::INIT_SPACE,
+        seeds = [b"parlay", user.key().as_ref(), &parlay_id.to_le_bytes()],
+        bump,
+    )]
+    pub parlay: Account<'info, Parlay>,
+
+    /// CHECK: `user`'s `UserEncryptionKey` PDA, empty if they never registered one
+    #[account(seeds = [b"encryption_key", user.key().as_ref()], bump)]
+    pub encryption_key: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [&SIGN_PDA_SEED],
+        bump = sign_pda_account.bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, ArciumSignerAccount>,
+
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+
+    #[account(mut, address = derive_mempool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: mempool_account
+    pub mempool_account: UncheckedAccount<'info>,
+
+    #[account(mut, address = derive_execpool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: executing_pool
+    pub executing_pool: UncheckedAccount<'info>,
+
+    #[account(mut, address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_INIT_PARLAY))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+
+    #[account(mut, address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+
+    #[account(mut, address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+}
+
+#[callback_accounts("init_parlay")]
+#[derive(Accounts)]
+pub struct InitParlayCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_INIT_PARLAY))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+
+    #[account(address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: instructions_sysvar
+    pub instructions_sysvar: AccountInfo<'info>,
+
+    #[account(mut)]
+    pub parlay: Account<'info, Parlay>,
+}
+
+#[queue_computation_accounts("add_parlay_leg", user)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64, leg_index: u8)]
+pub struct AddParlayLeg<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        has_one = user @ ErrorCode::Unauthorized,
+        constraint = parlay.batches.get(leg_index as usize) == Some(&batch.key())
+            @ ErrorCode::ParlayLegMismatch,
+    )]
+    pub parlay: Account<'info, Parlay>,
+
+    #[account(mut)]
+    pub batch: Account<'info, TradingBatch>,
+
+    #[account(
+        init,
+        payer = user,
+        space = 8 + OrderCommitment::INIT_SPACE,
+        seeds = [b"order", batch.key().as_ref(), &[batch.order_count]],
+        bump,
+    )]
+    pub order: Account<'info, OrderCommitment>,
+
+    /// CHECK: `user`'s `RiskProfile` PDA, empty if they never created one
+    #[account(seeds = [b"risk_profile", user.key().as_ref()], bump)]
+    pub risk_profile: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"rent_sponsor", batch.authority.as_ref()],
+        bump = rent_sponsor.bump,
+    )]
+    pub rent_sponsor: Option<Account<'info, RentSponsor>>,
+
+    #[account(
+        mut,
+        seeds = [b"compute_pool", batch.market_id.as_bytes(), batch.authority.as_ref()],
+        bump = compute_pool.bump,
+    )]
+    pub compute_pool: Option<Account<'info, ComputePool>>,
+
+    /// CHECK: SAS attestation, required when `batch.required_credential` is set
+    pub attestation: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: zk-verifier eligibility proof, required when `batch.eligibility_predicate` is set
+    pub eligibility_proof: Option<UncheckedAccount<'info>>,
+
+    #[account(
+        mut,
+        seeds = [&SIGN_PDA_SEED],
+        bump = sign_pda_account.bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, ArciumSignerAccount>,
+
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+
+    #[account(mut, address = derive_mempool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: mempool_account
+    pub mempool_account: UncheckedAccount<'info>,
+
+    #[account(mut, address = derive_execpool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: executing_pool
+    pub executing_pool: UncheckedAccount<'info>,
+
+    #[account(mut, address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_ADD_PARLAY_LEG))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+
+    #[account(mut, address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+
+    #[account(mut, address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+}
+
+#[callback_accounts("add_parlay_leg")]
+#[derive(Accounts)]
+pub struct AddParlayLegCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_ADD_PARLAY_LEG))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+
+    #[account(address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: instructions_sysvar
+    pub instructions_sysvar: AccountInfo<'info>,
+
+    #[account(mut)]
+    pub batch: Account<'info, TradingBatch>,
+
+    /// The order this callback folds: the batch's next slot
+    #[account(
+        mut,
+        has_one = batch @ ErrorCode::OrderBatchMismatch,
+        seeds = [b"order", batch.key().as_ref(), &[batch.order_count]],
+        bump = order.bump,
+    )]
+    pub order: Account<'info, OrderCommitment>,
+
+    #[account(
+        mut,
+        constraint = parlay.user == order.user @ ErrorCode::ParlayLegMismatch,
+    )]
+    pub parlay: Account<'info, Parlay>,
+}
+
+#[derive(Accounts)]
+pub struct SettleParlay<'info> {
+    #[account(mut)]
+    pub parlay: Account<'info, Parlay>,
+}
+
+#[derive(Accounts)]
+pub struct PrepareCondition<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub oracle: Account<'info, ConfidentialOracle>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + Condition::INIT_SPACE,
+        seeds = [b"condition", oracle.key().as_ref()],
+        bump,
+    )]
+    pub condition: Account<'info, Condition>,
+
+    #[account(mint::token_program = token_program)]
+    pub collateral_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        init,
+        payer = payer,
+        seeds = [b"condition_vault", condition.key().as_ref()],
+        bump,
+        token::mint = collateral_mint,
+        token::authority = condition,
+        token::token_program = token_program,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        init,
+        payer = payer,
+        seeds = [b"yes_mint", condition.key().as_ref()],
+        bump,
+        mint::decimals = collateral_mint.decimals,
+        mint::authority = condition,
+        mint::token_program = token_program,
+    )]
+    pub yes_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        init,
+        payer = payer,
+        seeds = [b"no_mint", condition.key().as_ref()],
+        bump,
+        mint::decimals = collateral_mint.decimals,
+        mint::authority = condition,
+        mint::token_program = token_program,
+    )]
+    pub no_mint: InterfaceAccount<'info, Mint>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct SplitPosition<'info> {
+    pub user: Signer<'info>,
+
+    #[account(has_one = collateral_mint, has_one = vault, has_one = yes_mint, has_one = no_mint)]
+    pub condition: Account<'info, Condition>,
+
+    pub collateral_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(mut)]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub yes_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(mut)]
+    pub no_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(mut, token::mint = collateral_mint, token::authority = user)]
+    pub user_collateral: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut, token::mint = yes_mint)]
+    pub user_yes: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut, token::mint = no_mint)]
+    pub user_no: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct MergePositions<'info> {
+    pub user: Signer<'info>,
+
+    #[account(has_one = collateral_mint, has_one = vault, has_one = yes_mint, has_one = no_mint)]
+    pub condition: Account<'info, Condition>,
+
+    pub collateral_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(mut)]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub yes_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(mut)]
+    pub no_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(mut, token::mint = collateral_mint)]
+    pub user_collateral: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut, token::mint = yes_mint, token::authority = user)]
+    pub user_yes: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut, token::mint = no_mint, token::authority = user)]
+    pub user_no: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct RedeemPositions<'info> {
+    pub user: Signer<'info>,
+
+    #[account(has_one = oracle, has_one = collateral_mint, has_one = vault)]
+    pub condition: Account<'info, Condition>,
+
+    pub oracle: Account<'info, ConfidentialOracle>,
+
+    pub collateral_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(mut)]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    /// Must be the condition's winning mint
+    #[account(mut)]
+    pub outcome_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(mut, token::mint = collateral_mint)]
+    pub user_collateral: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut, token::mint = outcome_mint, token::authority = user)]
+    pub user_outcome: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct CreateAmmPool<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(has_one = yes_mint, has_one = no_mint)]
+    pub condition: Account<'info, Condition>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + AmmPool::INIT_SPACE,
+        seeds = [b"amm_pool", condition.key().as_ref()],
+        bump,
+    )]
+    pub pool: Account<'info, AmmPool>,
+
+    pub yes_mint: InterfaceAccount<'info, Mint>,
+    pub no_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        init,
+        payer = payer,
+        seeds = [b"amm_yes", pool.key().as_ref()],
+        bump,
+        token::mint = yes_mint,
+        token::authority = pool,
+        token::token_program = token_program,
+    )]
+    pub yes_reserve: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        init,
+        payer = payer,
+        seeds = [b"amm_no", pool.key().as_ref()],
+        bump,
+        token::mint = no_mint,
+        token::authority = pool,
+        token::token_program = token_program,
+    )]
+    pub no_reserve: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        init,
+        payer = payer,
+        seeds = [b"amm_lp", pool.key().as_ref()],
+        bump,
+        mint::decimals = yes_mint.decimals,
+        mint::authority = pool,
+        mint::token_program = token_program,
+    )]
+    pub lp_mint: InterfaceAccount<'info, Mint>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct AddLiquidity<'info> {
+    pub user: Signer<'info>,
+
+    #[account(has_one = condition, has_one = yes_reserve, has_one = no_reserve, has_one = lp_mint)]
+    pub pool: Account<'info, AmmPool>,
+
+    #[account(has_one = yes_mint, has_one = no_mint)]
+    pub condition: Account<'info, Condition>,
+
+    pub yes_mint: InterfaceAccount<'info, Mint>,
+    pub no_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(mut)]
+    pub yes_reserve: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub no_reserve: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub lp_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(mut, token::mint = yes_mint, token::authority = user)]
+    pub user_yes: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut, token::mint = no_mint, token::authority = user)]
+    pub user_no: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut, token::mint = lp_mint)]
+    pub user_lp: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct RemoveLiquidity<'info> {
+    pub user: Signer<'info>,
+
+    #[account(has_one = condition, has_one = yes_reserve, has_one = no_reserve, has_one = lp_mint)]
+    pub pool: Account<'info, AmmPool>,
+
+    #[account(has_one = yes_mint, has_one = no_mint)]
+    pub condition: Account<'info, Condition>,
+
+    pub yes_mint: InterfaceAccount<'info, Mint>,
+    pub no_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(mut)]
+    pub yes_reserve: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub no_reserve: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub lp_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(mut, token::mint = yes_mint)]
+    pub user_yes: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut, token::mint = no_mint)]
+    pub user_no: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut, token::mint = lp_mint, token::authority = user)]
+    pub user_lp: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct AmmSwap<'info> {
+    pub user: Signer<'info>,
+
+    #[account(has_one = condition, has_one = yes_reserve, has_one = no_reserve)]
+    pub pool: Account<'info, AmmPool>,
+
+    #[account(has_one = yes_mint, has_one = no_mint)]
+    pub condition: Account<'info, Condition>,
+
+    pub yes_mint: InterfaceAccount<'info, Mint>,
+    pub no_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(mut)]
+    pub yes_reserve: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub no_reserve: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut, token::mint = yes_mint, token::authority = user)]
+    pub user_yes: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut, token::mint = no_mint, token::authority = user)]
+    pub user_no: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct RecordPriceImprovement<'info> {
+    #[account(mut)]
+    pub batch: Account<'info, TradingBatch>,
+
+    #[account(mut)]
+    pub condition: Account<'info, Condition>,
+}
+
+#[derive(Accounts)]
+#[instruction(market_id: String)]
+pub struct CreateFillBulletin<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + FillBulletin::INIT_SPACE,
+        seeds = [b"fill_bulletin", market_id.as_bytes()],
+        bump,
+    )]
+    pub bulletin: Account<'info, FillBulletin>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct PublishFill<'info> {
+    #[account(mut)]
+    pub batch: Account<'info, TradingBatch>,
+
+    #[account(
+        mut,
+        seeds = [b"fill_bulletin", batch.market_id.as_bytes()],
+        bump = bulletin.bump,
+    )]
+    pub bulletin: Account<'info, FillBulletin>,
+}
+
+#[derive(Accounts)]
+pub struct CreateRiskProfile<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + RiskProfile::INIT_SPACE,
+        seeds = [b"risk_profile", owner.key().as_ref()],
+        bump,
+    )]
+    pub risk_profile: Account<'info, RiskProfile>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateRiskLimits<'info> {
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"risk_profile", owner.key().as_ref()],
+        bump = risk_profile.bump,
+        has_one = owner @ ErrorCode::Unauthorized,
+    )]
+    pub risk_profile: Account<'info, RiskProfile>,
+}
+
+#[queue_computation_accounts("add_limited_order", user)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64)]
+pub struct AddLimitedOrder<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"risk_profile", user.key().as_ref()],
+        bump = risk_profile.bump,
+    )]
+    pub risk_profile: Account<'info, RiskProfile>,
+
+    #[account(mut)]
+    pub batch: Account<'info, TradingBatch>,
+
+    #[account(
+        init,
+        payer = user,
+        space = 8 + OrderCommitment::INIT_SPACE,
+        seeds = [b"order", batch.key().as_ref(), &[batch.order_count]],
+        bump,
+    )]
+    pub order: Account<'info, OrderCommitment>,
+
+    /// CHECK: `user`'s `UserEncryptionKey` PDA, empty if they never registered one
+    #[account(seeds = [b"encryption_key", user.key().as_ref()], bump)]
+    pub encryption_key: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"rent_sponsor", batch.authority.as_ref()],
+        bump = rent_sponsor.bump,
+    )]
+    pub rent_sponsor: Option<Account<'info, RentSponsor>>,
+
+    #[account(
+        mut,
+        seeds = [b"compute_pool", batch.market_id.as_bytes(), batch.authority.as_ref()],
+        bump = compute_pool.bump,
+    )]
+    pub compute_pool: Option<Account<'info, ComputePool>>,
+
+    /// CHECK: SAS attestation, required when `batch.required_credential` is set
+    pub attestation: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: zk-verifier eligibility proof, required when `batch.eligibility_predicate` is set
+    pub eligibility_proof: Option<UncheckedAccount<'info>>,
+
+    #[account(
+        mut,
+        seeds = [&SIGN_PDA_SEED],
+        bump = sign_pda_account.bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, ArciumSignerAccount>,
+
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+
+    #[account(mut, address = derive_mempool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: mempool_account
+    pub mempool_account: UncheckedAccount<'info>,
+
+    #[account(mut, address = derive_execpool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: executing_pool
+    pub executing_pool: UncheckedAccount<'info>,
+
+    #[account(mut, address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_ADD_LIMITED_ORDER))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+
+    #[account(mut, address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+
+    #[account(mut, address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+}
+
+#[callback_accounts("add_limited_order")]
+#[derive(Accounts)]
+pub struct AddLimitedOrderCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_ADD_LIMITED_ORDER))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+
+    #[account(address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: instructions_sysvar
+    pub instructions_sysvar: AccountInfo<'info>,
+
+    #[account(mut)]
+    pub batch: Account<'info, TradingBatch>,
+
+    /// The order this callback folds: the batch's next slot
+    #[account(
+        mut,
+        has_one = batch @ ErrorCode::OrderBatchMismatch,
+        seeds = [b"order", batch.key().as_ref(), &[batch.order_count]],
+        bump = order.bump,
+    )]
+    pub order: Account<'info, OrderCommitment>,
+
+    #[account(mut, constraint = risk_profile.owner == order.user @ ErrorCode::RiskProfileMismatch)]
+    pub risk_profile: Account<'info, RiskProfile>,
+}
+
+#[queue_computation_accounts("add_limited_session_order", session_key)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64)]
+pub struct AddLimitedOrderWithSession<'info> {
+    #[account(mut)]
+    pub session_key: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"session", session.owner.as_ref(), session_key.key().as_ref()],
+        bump = session.bump,
+    )]
+    pub session: Account<'info, Session>,
+
+    #[account(
+        mut,
+        seeds = [b"risk_profile", session.owner.as_ref()],
+        bump = risk_profile.bump,
+    )]
+    pub risk_profile: Account<'info, RiskProfile>,
+
+    #[account(mut)]
+    pub batch: Account<'info, TradingBatch>,
+
+    #[account(
+        init,
+        payer = session_key,
+        space = 8 + OrderCommitment::INIT_SPACE,
+        seeds = [b"order", batch.key().as_ref(), &[batch.order_count]],
+        bump,
+    )]
+    pub order: Account<'info, OrderCommitment>,
+
+    #[account(
+        mut,
+        seeds = [b"rent_sponsor", batch.authority.as_ref()],
+        bump = rent_sponsor.bump,
+    )]
+    pub rent_sponsor: Option<Account<'info, RentSponsor>>,
+
+    #[account(
+        mut,
+        seeds = [b"compute_pool", batch.market_id.as_bytes(), batch.authority.as_ref()],
+        bump = compute_pool.bump,
+    )]
+    pub compute_pool: Option<Account<'info, ComputePool>>,
+
+    /// CHECK: SAS attestation, required when `batch.required_credential` is set
+    pub attestation: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: zk-verifier eligibility proof, required when `batch.eligibility_predicate` is set
+    pub eligibility_proof: Option<UncheckedAccount<'info>>,
+
+    #[account(
+        mut,
+        seeds = [&SIGN_PDA_SEED],
+        bump = sign_pda_account.bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, ArciumSignerAccount>,
+
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+
+    #[account(mut, address = derive_mempool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: mempool_account
+    pub mempool_account: UncheckedAccount<'info>,
+
+    #[account(mut, address = derive_execpool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: executing_pool
+    pub executing_pool: UncheckedAccount<'info>,
+
+    #[account(mut, address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_ADD_LIMITED_SESSION_ORDER))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+
+    #[account(mut, address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+
+    #[account(mut, address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+}
+
+#[callback_accounts("add_limited_session_order")]
+#[derive(Accounts)]
+pub struct AddLimitedSessionOrderCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_ADD_LIMITED_SESSION_ORDER))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+
+    #[account(address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: instructions_sysvar
+    pub instructions_sysvar: AccountInfo<'info>,
+
+    #[account(mut)]
+    pub batch: Account<'info, TradingBatch>,
+
+    /// The order this callback folds: the batch's next slot
+    #[account(
+        mut,
+        has_one = batch @ ErrorCode::OrderBatchMismatch,
+        seeds = [b"order", batch.key().as_ref(), &[batch.order_count]],
+        bump = order.bump,
+    )]
+    pub order: Account<'info, OrderCommitment>,
+
+    #[account(mut, constraint = risk_profile.owner == order.user @ ErrorCode::RiskProfileMismatch)]
+    pub risk_profile: Account<'info, RiskProfile>,
+
+    #[account(mut, constraint = session.owner == order.user @ ErrorCode::SessionScopeMismatch)]
+    pub session: Account<'info, Session>,
+}
+
+#[derive(Accounts)]
+pub struct CreateAgentPolicy<'info> {
+    #[account(mut)]
+    pub agent: Signer<'info>,
+
+    #[account(
+        init,
+        payer = agent,
+        space = 8 + AgentPolicy::INIT_SPACE,
+        seeds = [b"agent_policy", agent.key().as_ref()],
+        bump,
+    )]
+    pub agent_policy: Account<'info, AgentPolicy>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct AgentHeartbeat<'info> {
+    pub agent: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"agent_policy", agent.key().as_ref()],
+        bump = agent_policy.bump,
+    )]
+    pub agent_policy: Account<'info, AgentPolicy>,
+}
+
+#[derive(Accounts)]
+pub struct TripAgentPolicy<'info> {
+    #[account(mut)]
+    pub agent_policy: Account<'info, AgentPolicy>,
+}
+
+#[derive(Accounts)]
+pub struct CancelAgentRfq<'info> {
+    pub agent_policy: Account<'info, AgentPolicy>,
+
+    /// CHECK: receives the RFQ's rent; must be the policy's agent
+    #[account(mut, address = agent_policy.agent @ ErrorCode::Unauthorized)]
+    pub agent: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        close = agent,
+        constraint = rfq.taker == agent.key() @ ErrorCode::Unauthorized,
+    )]
+    pub rfq: Account<'info, Rfq>,
+}
+
+#[derive(Accounts)]
+pub struct RevokeAgentSession<'info> {
+    pub agent_policy: Account<'info, AgentPolicy>,
+
+    /// CHECK: receives the session's rent; must be the policy's agent
+    #[account(mut, address = agent_policy.agent @ ErrorCode::Unauthorized)]
+    pub agent: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        close = agent,
+        constraint = session.owner == agent.key() @ ErrorCode::Unauthorized,
+    )]
+    pub session: Account<'info, Session>,
+}
+
+#[derive(Accounts)]
+pub struct RecoverPosition<'info> {
+    pub recovery: Signer<'info>,
+
+    #[account(has_one = recovery @ ErrorCode::Unauthorized)]
+    pub agent_policy: Account<'info, AgentPolicy>,
+
+    pub condition: Account<'info, Condition>,
+
+    /// Either of the condition's outcome mints
+    #[account(
+        constraint = outcome_mint.key() == condition.yes_mint
+            || outcome_mint.key() == condition.no_mint @ ErrorCode::NotOutcomeMint,
+    )]
+    pub outcome_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        token::mint = outcome_mint,
+        token::authority = agent_policy.agent,
+    )]
+    pub agent_outcome: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut, token::mint = outcome_mint)]
+    pub recovery_outcome: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct CloseAgentPolicy<'info> {
+    pub authority: Signer<'info>,
+
+    /// CHECK: receives the policy's rent; must be the policy's agent
+    #[account(mut, address = agent_policy.agent @ ErrorCode::Unauthorized)]
+    pub agent: UncheckedAccount<'info>,
+
+    #[account(mut, close = agent)]
+    pub agent_policy: Account<'info, AgentPolicy>,
+}
+
+#[derive(Accounts)]
+pub struct RegisterEncryptionKey<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + UserEncryptionKey::INIT_SPACE,
+        seeds = [b"encryption_key", owner.key().as_ref()],
+        bump,
+    )]
+    pub encryption_key: Account<'info, UserEncryptionKey>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RotateEncryptionKey<'info> {
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"encryption_key", owner.key().as_ref()],
+        bump = encryption_key.bump,
+        has_one = owner @ ErrorCode::Unauthorized,
+    )]
+    pub encryption_key: Account<'info, UserEncryptionKey>,
+}
+
+#[derive(Accounts)]
+#[instruction(market_id: String)]
+pub struct CreateHouseAccount<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + HouseAccount::INIT_SPACE,
+        seeds = [b"house_account", market_id.as_bytes(), authority.key().as_ref()],
+        bump,
+    )]
+    pub house_account: Account<'info, HouseAccount>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[queue_computation_accounts("add_house_order", desk)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64)]
+pub struct AddHouseOrder<'info> {
+    #[account(mut)]
+    pub desk: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = desk @ ErrorCode::Unauthorized,
+        seeds = [b"house_account", batch.market_id.as_bytes(), batch.authority.as_ref()],
+        bump = house_account.bump,
+    )]
+    pub house_account: Account<'info, HouseAccount>,
+
+    #[account(mut)]
+    pub batch: Account<'info, TradingBatch>,
+
+    #[account(
+        init,
+        payer = desk,
+        space = 8 + OrderCommitment::INIT_SPACE,
+        seeds = [b"order", batch.key().as_ref(), &[batch.order_count]],
+        bump,
+    )]
+    pub order: Account<'info, OrderCommitment>,
+
+    #[account(
+        mut,
+        seeds = [&SIGN_PDA_SEED],
+        bump = sign_pda_account.bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, ArciumSignerAccount>,
+
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+
+    #[account(mut, address = derive_mempool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: mempool_account
+    pub mempool_account: UncheckedAccount<'info>,
+
+    #[account(mut, address = derive_execpool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: executing_pool
+    pub executing_pool: UncheckedAccount<'info>,
+
+    #[account(mut, address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_ADD_HOUSE_ORDER))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+
+    #[account(mut, address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+
+    #[account(mut, address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+}
+
+#[callback_accounts("add_house_order")]
+#[derive(Accounts)]
+pub struct AddHouseOrderCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_ADD_HOUSE_ORDER))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+
+    #[account(address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: instructions_sysvar
+    pub instructions_sysvar: AccountInfo<'info>,
+
+    #[account(mut)]
+    pub batch: Account<'info, TradingBatch>,
+
+    /// The order this callback folds: the batch's next slot
+    #[account(
+        mut,
+        has_one = batch @ ErrorCode::OrderBatchMismatch,
+        seeds = [b"order", batch.key().as_ref(), &[batch.order_count]],
+        bump = order.bump,
+    )]
+    pub order: Account<'info, OrderCommitment>,
+
+    #[account(mut, constraint = house_account.desk == order.user @ ErrorCode::HouseAccountMismatch)]
+    pub house_account: Account<'info, HouseAccount>,
+}
+
+#[queue_computation_accounts("settle_house_batch", authority)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64)]
+pub struct SettleHouseBatch<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(mut, has_one = authority @ ErrorCode::Unauthorized)]
+    pub house_account: Account<'info, HouseAccount>,
+
+    pub batch: Account<'info, TradingBatch>,
+
+    #[account(
+        mut,
+        seeds = [&SIGN_PDA_SEED],
+        bump = sign_pda_account.bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, ArciumSignerAccount>,
+
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+
+    #[account(mut, address = derive_mempool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: mempool_account
+    pub mempool_account: UncheckedAccount<'info>,
+
+    #[account(mut, address = derive_execpool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: executing_pool
+    pub executing_pool: UncheckedAccount<'info>,
+
+    #[account(mut, address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_SETTLE_HOUSE_BATCH))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+
+    #[account(mut, address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+
+    #[account(mut, address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+}
+
+#[callback_accounts("settle_house_batch")]
+#[derive(Accounts)]
+pub struct SettleHouseBatchCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_SETTLE_HOUSE_BATCH))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+
+    #[account(address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: instructions_sysvar
+    pub instructions_sysvar: AccountInfo<'info>,
+
+    #[account(mut)]
+    pub house_account: Account<'info, HouseAccount>,
+}
+
+#[queue_computation_accounts("reveal_house_ledger", authority)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64)]
+pub struct RevealHouseLedger<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(mut, has_one = authority @ ErrorCode::Unauthorized)]
+    pub house_account: Account<'info, HouseAccount>,
+
+    #[account(
+        mut,
+        seeds = [&SIGN_PDA_SEED],
+        bump = sign_pda_account.bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, ArciumSignerAccount>,
+
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+
+    #[account(mut, address = derive_mempool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: mempool_account
+    pub mempool_account: UncheckedAccount<'info>,
+
+    #[account(mut, address = derive_execpool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: executing_pool
+    pub executing_pool: UncheckedAccount<'info>,
+
+    #[account(mut, address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::ClusterNotSet))]
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_REVEAL_HOUSE_LEDGER))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+
+    #[account(mut, address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+
+    #[account(mut, address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+}
+
+#[callback_accounts("reveal_house_ledger")]
+#[derive(Accounts)]
+pub struct RevealHouseLedgerCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_REVEAL_HOUSE_LEDGER))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+
+    /// CHECK: computation_account
+    pub computation_account: UncheckedAccount<'info>,
+
+    #[account(address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: instructions_sysvar
+    pub instructions_sysvar: AccountInfo<'info>,
+
+    #[account(mut)]
+    pub house_account: Account<'info, HouseAccount>,
+}
+
+#[derive(Accounts)]
+#[instruction(market_id: String)]
+pub struct CreateComputePool<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + ComputePool::INIT_SPACE,
+        seeds = [b"compute_pool", market_id.as_bytes(), authority.key().as_ref()],
+        bump,
+    )]
+    pub compute_pool: Account<'info, ComputePool>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct FundComputePool<'info> {
+    #[account(mut)]
+    pub funder: Signer<'info>,
+
+    #[account(mut)]
+    pub compute_pool: Account<'info, ComputePool>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetComputePoolLowBalance<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(mut, has_one = authority @ ErrorCode::Unauthorized)]
+    pub compute_pool: Account<'info, ComputePool>,
+}
+
+#[derive(Accounts)]
+pub struct CloseComputePool<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        close = authority,
+        has_one = authority @ ErrorCode::Unauthorized,
+    )]
+    pub compute_pool: Account<'info, ComputePool>,
+}
+
+#[derive(Accounts)]
+pub struct CreateRentSponsor<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + RentSponsor::INIT_SPACE,
+        seeds = [b"rent_sponsor", authority.key().as_ref()],
+        bump,
+    )]
+    pub rent_sponsor: Account<'info, RentSponsor>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CloseRentSponsor<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        close = authority,
+        has_one = authority @ ErrorCode::Unauthorized,
+    )]
+    pub rent_sponsor: Account<'info, RentSponsor>,
+}
+
+#[derive(Accounts)]
+#[instruction(market_id: String)]
+pub struct RegisterExecutionAdapter<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + ExecutionAdapter::INIT_SPACE,
+        seeds = [b"execution_adapter", market_id.as_bytes(), authority.key().as_ref()],
+        bump,
+    )]
+    pub adapter: Account<'info, ExecutionAdapter>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RemoveExecutionAdapter<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        close = authority,
+        has_one = authority @ ErrorCode::Unauthorized,
+    )]
+    pub adapter: Account<'info, ExecutionAdapter>,
+}
+
+#[derive(Accounts)]
+pub struct RouteBatch<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(has_one = authority @ ErrorCode::Unauthorized)]
+    pub batch: Account<'info, TradingBatch>,
+
+    #[account(
+        seeds = [b"execution_adapter", batch.market_id.as_bytes(), authority.key().as_ref()],
+        bump = adapter.bump,
+    )]
+    pub adapter: Account<'info, ExecutionAdapter>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + ExecutionFill::INIT_SPACE,
+        seeds = [b"execution_fill", batch.key().as_ref()],
+        bump,
+    )]
+    pub fill: Account<'info, ExecutionFill>,
+
+    /// CHECK: must be `adapter.program_id`, checked in `route_batch`
+    #[account(executable)]
+    pub adapter_program: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+// ============================================
+// Computation Definition Initialization Contexts
+// ============================================
+
+#[derive(Accounts)]
+pub struct InitSignPda<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        init,
+        space = 9,
+        payer = payer,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, ArciumSignerAccount>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[init_computation_definition_accounts("init_batch", payer)]
+#[derive(Accounts)]
+pub struct InitBatchCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[init_computation_definition_accounts("add_order", payer)]
+#[derive(Accounts)]
+pub struct InitAddOrderCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[init_computation_definition_accounts("execute_batch", payer)]
 #[derive(Accounts)]
-pub struct CloseBatch<'info> {
+pub struct InitExecuteBatchCompDef<'info> {
     #[account(mut)]
-    pub authority: Signer<'info>,
-
-    #[account(
-        mut,
-        has_one = authority @ ErrorCode::Unauthorized,
-    )]
-    pub batch: Account<'info, TradingBatch>,
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
 }
 
-#[queue_computation_accounts("execute_batch", authority)]
+#[init_computation_definition_accounts("verify_allocation", payer)]
 #[derive(Accounts)]
-#[instruction(computation_offset: u64)]
-pub struct ExecuteBatch<'info> {
+pub struct InitVerifyAllocationCompDef<'info> {
     #[account(mut)]
-    pub authority: Signer<'info>,
-
-    #[account(mut, has_one = authority @ ErrorCode::Unauthorized)]
-    pub batch: Account<'info, TradingBatch>,
-
-    #[account(
-        init_if_needed,
-        space = 9,
-        payer = authority,
-        seeds = [&SIGN_PDA_SEED],
-        bump,
-        address = derive_sign_pda!(),
-    )]
-    pub sign_pda_account: Account<'info, ArciumSignerAccount>,
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
 
-    #[account(address = derive_mxe_pda!())]
-    pub mxe_account: Account<'info, MXEAccount>,
+#[init_computation_definition_accounts("simulate_execute_batch", payer)]
+#[derive(Accounts)]
+pub struct InitSimulateExecuteBatchCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
 
-    #[account(mut, address = derive_mempool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
-    /// CHECK: mempool_account
-    pub mempool_account: UncheckedAccount<'info>,
+#[init_computation_definition_accounts("init_rfq", payer)]
+#[derive(Accounts)]
+pub struct InitRfqCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
 
-    #[account(mut, address = derive_execpool_pda!(mxe_account, ErrorCode::ClusterNotSet))]
-    /// CHECK: executing_pool
-    pub executing_pool: UncheckedAccount<'info>,
+#[init_computation_definition_accounts("submit_quote", payer)]
+#[derive(Accounts)]
+pub struct InitSubmitQuoteCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
 
-    #[account(mut, address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::ClusterNotSet))]
-    /// CHECK: computation_account
-    pub computation_account: UncheckedAccount<'info>,
+#[init_computation_definition_accounts("settle_rfq", payer)]
+#[derive(Accounts)]
+pub struct InitSettleRfqCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
 
-    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_EXECUTE_BATCH))]
-    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+#[init_computation_definition_accounts("init_session", payer)]
+#[derive(Accounts)]
+pub struct InitSessionCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
 
-    #[account(mut, address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
-    pub cluster_account: Account<'info, Cluster>,
+#[init_computation_definition_accounts("add_session_order", payer)]
+#[derive(Accounts)]
+pub struct InitAddSessionOrderCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
 
-    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
-    pub pool_account: Account<'info, FeePool>,
+#[init_computation_definition_accounts("add_orders_bulk", payer)]
+#[derive(Accounts)]
+pub struct InitAddOrdersBulkCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
 
-    #[account(mut, address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
-    pub clock_account: Account<'info, ClockAccount>,
+#[init_computation_definition_accounts("init_lane", payer)]
+#[derive(Accounts)]
+pub struct InitLaneCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
 
+#[init_computation_definition_accounts("add_lane_order", payer)]
+#[derive(Accounts)]
+pub struct InitAddLaneOrderCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
     pub system_program: Program<'info, System>,
+}
+
+#[init_computation_definition_accounts("merge_lanes", payer)]
+#[derive(Accounts)]
+pub struct InitMergeLanesCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account
+    pub comp_def_account: UncheckedAccount<'info>,
     pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
 }
 
-#[callback_accounts("execute_batch")]
+#[init_computation_definition_accounts("init_oracle", payer)]
 #[derive(Accounts)]
-pub struct ExecuteBatchCallback<'info> {
+pub struct InitOracleCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account
+    pub comp_def_account: UncheckedAccount<'info>,
     pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
 
-    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_EXECUTE_BATCH))]
-    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+#[init_computation_definition_accounts("submit_observation", payer)]
+#[derive(Accounts)]
+pub struct InitSubmitObservationCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
 
-    #[account(address = derive_mxe_pda!())]
-    pub mxe_account: Account<'info, MXEAccount>,
+#[init_computation_definition_accounts("resolve_oracle", payer)]
+#[derive(Accounts)]
+pub struct InitResolveOracleCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
 
-    /// CHECK: computation_account
-    pub computation_account: UncheckedAccount<'info>,
+#[init_computation_definition_accounts("init_flow_auction", payer)]
+#[derive(Accounts)]
+pub struct InitFlowAuctionCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
 
-    #[account(address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
-    pub cluster_account: Account<'info, Cluster>,
+#[init_computation_definition_accounts("submit_flow_bid", payer)]
+#[derive(Accounts)]
+pub struct InitSubmitFlowBidCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
 
-    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
-    /// CHECK: instructions_sysvar
-    pub instructions_sysvar: AccountInfo<'info>,
+#[init_computation_definition_accounts("settle_flow_auction", payer)]
+#[derive(Accounts)]
+pub struct InitSettleFlowAuctionCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
 
+#[init_computation_definition_accounts("init_parlay", payer)]
+#[derive(Accounts)]
+pub struct InitParlayCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
     #[account(mut)]
-    pub batch: Account<'info, TradingBatch>,
+    /// CHECK: comp_def_account
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
 }
 
+#[init_computation_definition_accounts("add_parlay_leg", payer)]
 #[derive(Accounts)]
-pub struct VerifyAllocation<'info> {
+pub struct InitAddParlayLegCompDef<'info> {
     #[account(mut)]
-    pub authority: Signer<'info>,
-
-    #[account(
-        mut,
-        has_one = authority @ ErrorCode::Unauthorized,
-    )]
-    pub batch: Account<'info, TradingBatch>,
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
 }
 
-// ============================================
-// Computation Definition Initialization Contexts
-// ============================================
+#[init_computation_definition_accounts("add_limited_order", payer)]
+#[derive(Accounts)]
+pub struct InitAddLimitedOrderCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
 
-#[init_computation_definition_accounts("init_batch", payer)]
+#[init_computation_definition_accounts("add_limited_session_order", payer)]
 #[derive(Accounts)]
-pub struct InitBatchCompDef<'info> {
+pub struct InitAddLimitedSessionOrderCompDef<'info> {
     #[account(mut)]
     pub payer: Signer<'info>,
     #[account(mut, address = derive_mxe_pda!())]
@@ -678,9 +10936,9 @@ pub struct InitBatchCompDef<'info> {
     pub system_program: Program<'info, System>,
 }
 
-#[init_computation_definition_accounts("add_order", payer)]
+#[init_computation_definition_accounts("add_house_order", payer)]
 #[derive(Accounts)]
-pub struct InitAddOrderCompDef<'info> {
+pub struct InitAddHouseOrderCompDef<'info> {
     #[account(mut)]
     pub payer: Signer<'info>,
     #[account(mut, address = derive_mxe_pda!())]
@@ -692,9 +10950,9 @@ pub struct InitAddOrderCompDef<'info> {
     pub system_program: Program<'info, System>,
 }
 
-#[init_computation_definition_accounts("execute_batch", payer)]
+#[init_computation_definition_accounts("settle_house_batch", payer)]
 #[derive(Accounts)]
-pub struct InitExecuteBatchCompDef<'info> {
+pub struct InitSettleHouseBatchCompDef<'info> {
     #[account(mut)]
     pub payer: Signer<'info>,
     #[account(mut, address = derive_mxe_pda!())]
@@ -706,9 +10964,9 @@ pub struct InitExecuteBatchCompDef<'info> {
     pub system_program: Program<'info, System>,
 }
 
-#[init_computation_definition_accounts("verify_allocation", payer)]
+#[init_computation_definition_accounts("reveal_house_ledger", payer)]
 #[derive(Accounts)]
-pub struct InitVerifyAllocationCompDef<'info> {
+pub struct InitRevealHouseLedgerCompDef<'info> {
     #[account(mut)]
     pub payer: Signer<'info>,
     #[account(mut, address = derive_mxe_pda!())]
@@ -730,6 +10988,8 @@ pub struct BatchCreated {
     pub market_id: String,
     pub side: Side,
     pub authority: Pubkey,
+    pub required_credential: Option<Pubkey>,
+    pub eligibility_predicate: Option<[u8; 32]>,
 }
 
 #[event]
@@ -740,6 +11000,29 @@ pub struct OrderAdded {
     pub commitment_hash: [u8; 32],
 }
 
+#[event]
+pub struct OrderStaged {
+    pub batch: Pubkey,
+    pub order: Pubkey,
+    pub order_index: u8,
+}
+
+#[event]
+pub struct OrderRejected {
+    pub batch: Pubkey,
+    pub order: Pubkey,
+    pub order_index: u8,
+    pub reason: OrderRejection,
+}
+
+#[event]
+pub struct BatchStatusHistory {
+    pub batch: Pubkey,
+    pub from: BatchStatus,
+    pub to: BatchStatus,
+    pub slot: u64,
+}
+
 #[event]
 pub struct BatchClosed {
     pub batch: Pubkey,
@@ -754,16 +11037,443 @@ pub struct BatchExecuted {
     pub order_count: u8,
 }
 
+#[event]
+pub struct ExecutionPreviewed {
+    pub batch: Pubkey,
+    /// `ExecutionPreview` ciphertexts, decryptable by the authority only
+    pub encrypted_preview: [[u8; 32]; 3],
+    pub nonce: u128,
+}
+
+#[event]
+pub struct ComputationLatency {
+    pub batch: Pubkey,
+    pub computation: ComputationKind,
+    pub queued_at: u64,
+    pub completed_at: u64,
+    pub latency_slots: u64,
+}
+
 #[event]
 pub struct AllocationVerified {
     pub batch: Pubkey,
     pub merkle_root: [u8; 32],
+    /// Commitment to every (wallet, amount, shares) allocation leaf
+    pub allocation_root: [u8; 32],
+}
+
+#[event]
+pub struct RfqCreated {
+    pub rfq: Pubkey,
+    pub taker: Pubkey,
+    pub market_id: String,
+    pub makers: Vec<Pubkey>,
+    pub window_end_slot: u64,
+}
+
+#[event]
+pub struct QuoteReceived {
+    pub rfq: Pubkey,
+    pub quote_count: u8,
+}
+
+#[event]
+pub struct RfqSettled {
+    pub rfq: Pubkey,
+    pub winning_maker: Option<Pubkey>,
+    pub fill_notional: u64,
+}
+
+#[event]
+pub struct RfqFillConfirmed {
+    pub rfq: Pubkey,
+    pub maker: Pubkey,
+    pub fill_notional: u64,
+}
+
+#[event]
+pub struct QuoteBondReleased {
+    pub rfq: Pubkey,
+    pub maker: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct QuoteBondSlashed {
+    pub rfq: Pubkey,
+    pub maker: Pubkey,
+    pub taker: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct RfqCancelled {
+    pub rfq: Pubkey,
+    pub status: RfqStatus,
+}
+
+#[event]
+pub struct LaneOpened {
+    pub batch: Pubkey,
+    pub lane: Pubkey,
+    pub lane_index: u8,
+}
+
+#[event]
+pub struct LanesMerged {
+    pub batch: Pubkey,
+    pub order_count: u8,
+    /// A lane total that would have overflowed the batch total was zeroed
+    pub total_overflow: bool,
+}
+
+#[event]
+pub struct SessionCreated {
+    pub session: Pubkey,
+    pub owner: Pubkey,
+    pub session_key: Pubkey,
+    pub market_id: String,
+    pub expiry_slot: u64,
+}
+
+#[event]
+pub struct SessionRevoked {
+    pub session: Pubkey,
+    pub owner: Pubkey,
+    pub session_key: Pubkey,
+}
+
+#[event]
+pub struct DistributionCreated {
+    pub distributor: Pubkey,
+    pub authority: Pubkey,
+    pub mint: Pubkey,
+    pub merkle_root: [u8; 32],
+    pub total_amount: u64,
+    pub max_claims: u32,
+}
+
+#[event]
+pub struct DistributionClaimed {
+    pub distributor: Pubkey,
+    pub claimant: Pubkey,
+    pub index: u32,
+    pub amount: u64,
+}
+
+#[event]
+pub struct OracleCreated {
+    pub oracle: Pubkey,
+    pub creator: Pubkey,
+    pub market_id: String,
+    pub providers: Vec<Pubkey>,
+    pub min_observations: u8,
+    pub window_end_slot: u64,
+}
+
+#[event]
+pub struct ObservationReceived {
+    pub oracle: Pubkey,
+    pub observation_count: u8,
+}
+
+#[event]
+pub struct OracleResolved {
+    pub oracle: Pubkey,
+    pub market_id: String,
+    pub outcome: bool,
+    pub observation_count: u8,
+}
+
+#[event]
+pub struct FlowAuctionCreated {
+    pub flow_auction: Pubkey,
+    pub batch: Pubkey,
+    pub makers: Vec<Pubkey>,
+    pub reference_price: u64,
+    pub window_end_slot: u64,
+}
+
+#[event]
+pub struct FlowBidReceived {
+    pub flow_auction: Pubkey,
+    pub bid_count: u8,
+}
+
+#[event]
+pub struct FlowAuctionSettled {
+    pub flow_auction: Pubkey,
+    pub batch: Pubkey,
+    pub winning_maker: Option<Pubkey>,
+    pub improvement_bps: u64,
+    pub execution_price: u64,
+}
+
+#[event]
+pub struct ComputationAbandoned {
+    pub batch: Pubkey,
+    pub computation: ComputationKind,
+    pub queued_at: u64,
+    pub abandoned_at: u64,
+}
+
+#[event]
+pub struct RiskProfileReleased {
+    pub risk_profile: Pubkey,
+    pub owner: Pubkey,
+    pub queued_at: u64,
+    pub released_at: u64,
+}
+
+#[event]
+pub struct ParlayCreated {
+    pub parlay: Pubkey,
+    pub user: Pubkey,
+    pub batches: Vec<Pubkey>,
+    pub rejected: bool,
+}
+
+#[event]
+pub struct ParlayLegAdded {
+    pub parlay: Pubkey,
+    pub batch: Pubkey,
+    pub order: Pubkey,
+    pub leg_index: u8,
+}
+
+#[event]
+pub struct ParlaySettled {
+    pub parlay: Pubkey,
+    pub user: Pubkey,
+    pub won: bool,
+}
+
+#[event]
+pub struct ConditionPrepared {
+    pub condition: Pubkey,
+    pub oracle: Pubkey,
+    pub market_id: String,
+    pub collateral_mint: Pubkey,
+    pub yes_mint: Pubkey,
+    pub no_mint: Pubkey,
+}
+
+#[event]
+pub struct PositionSplit {
+    pub condition: Pubkey,
+    pub user: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct PositionsMerged {
+    pub condition: Pubkey,
+    pub user: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct PositionsRedeemed {
+    pub condition: Pubkey,
+    pub user: Pubkey,
+    pub outcome: bool,
+    pub amount: u64,
+}
+
+#[event]
+pub struct AmmPoolCreated {
+    pub pool: Pubkey,
+    pub condition: Pubkey,
+    pub fee_bps: u16,
+}
+
+#[event]
+pub struct LiquidityAdded {
+    pub pool: Pubkey,
+    pub provider: Pubkey,
+    pub yes_amount: u64,
+    pub no_amount: u64,
+    pub shares: u64,
+}
+
+#[event]
+pub struct LiquidityRemoved {
+    pub pool: Pubkey,
+    pub provider: Pubkey,
+    pub yes_amount: u64,
+    pub no_amount: u64,
+    pub shares: u64,
+}
+
+#[event]
+pub struct AmmSwapped {
+    pub pool: Pubkey,
+    pub user: Pubkey,
+    pub yes_in: bool,
+    pub amount_in: u64,
+    pub amount_out: u64,
+}
+
+#[event]
+pub struct PriceImprovement {
+    pub batch: Pubkey,
+    pub condition: Pubkey,
+    pub reference_mid: u64,
+    pub execution_price: u64,
+    pub improvement_bps: i64,
+    pub improvement_usdc: i64,
+}
+
+#[event]
+pub struct FillBulletinCreated {
+    pub bulletin: Pubkey,
+    pub market_id: String,
+}
+
+#[event]
+pub struct FillPublished {
+    pub bulletin: Pubkey,
+    pub sequence: u64,
+    pub fill: PublicFill,
+}
+
+#[event]
+pub struct RiskLimitsUpdated {
+    pub risk_profile: Pubkey,
+    pub owner: Pubkey,
+    pub limits: RiskLimits,
+    /// Slot the limits apply from; later than now for loosened limits
+    pub effective_slot: u64,
+}
+
+#[event]
+pub struct AgentPolicyCreated {
+    pub agent_policy: Pubkey,
+    pub agent: Pubkey,
+    pub recovery: Pubkey,
+    pub timeout_slots: u64,
+}
+
+#[event]
+pub struct AgentPolicyTripped {
+    pub agent_policy: Pubkey,
+    pub agent: Pubkey,
+    pub last_heartbeat: u64,
+    pub tripped_at: u64,
+}
+
+#[event]
+pub struct AgentPositionRecovered {
+    pub agent_policy: Pubkey,
+    pub condition: Pubkey,
+    pub mint: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct EncryptionKeyRotated {
+    pub encryption_key: Pubkey,
+    pub owner: Pubkey,
+    /// None on first registration
+    pub previous: Option<[u8; 32]>,
+    pub current: [u8; 32],
+    pub slot: u64,
+}
+
+#[event]
+pub struct ComputePoolFunded {
+    pub compute_pool: Pubkey,
+    pub funder: Pubkey,
+    pub lamports: u64,
+    /// Spendable balance after the top-up
+    pub available: u64,
+}
+
+#[event]
+pub struct ComputeFeeSponsored {
+    pub compute_pool: Pubkey,
+    pub payer: Pubkey,
+    pub lamports: u64,
+}
+
+/// A pool fell under its low-balance mark, or could not cover `unpaid_fee`
+#[event]
+pub struct ComputePoolLow {
+    pub compute_pool: Pubkey,
+    pub market_id: String,
+    pub available: u64,
+    /// Fee left with the payer, 0 when the draw went through
+    pub unpaid_fee: u64,
+}
+
+#[event]
+pub struct HouseOrderAdded {
+    pub house_account: Pubkey,
+    pub batch: Pubkey,
+    pub order: Pubkey,
+    pub order_index: u8,
+}
+
+#[event]
+pub struct HouseBatchSettled {
+    pub house_account: Pubkey,
+    pub batch: Pubkey,
+}
+
+#[event]
+pub struct HouseLedgerRevealed {
+    pub house_account: Pubkey,
+    /// `HouseStatement` ciphertexts, decryptable by the authority only
+    pub encrypted_statement: [[u8; 32]; 4],
+    pub nonce: u128,
+}
+
+#[event]
+pub struct VoucherRedeemed {
+    pub distributor: Pubkey,
+    pub claimant: Pubkey,
+    pub relayer: Pubkey,
+    pub index: u32,
+    pub fee: u64,
+}
+
+#[event]
+pub struct RentSponsored {
+    pub sponsor: Pubkey,
+    pub payer: Pubkey,
+    pub order: Pubkey,
+    pub lamports: u64,
+}
+
+#[event]
+pub struct ExecutionAdapterRegistered {
+    pub adapter: Pubkey,
+    pub authority: Pubkey,
+    pub market_id: String,
+    pub program_id: Pubkey,
+}
+
+#[event]
+pub struct ExecutionAdapterRemoved {
+    pub adapter: Pubkey,
+    pub market_id: String,
+}
+
+#[event]
+pub struct BatchRouted {
+    pub batch: Pubkey,
+    pub adapter_program: Pubkey,
+    pub net_size: u64,
+    pub filled_size: u64,
+    pub average_price: u64,
 }
 
 // ============================================
 // Errors
 // ============================================
 
+/// Codes are assigned by position (6000 + index), and clients match on
+/// them: append new variants at the end, never reorder or remove.
 #[error_code]
 pub enum ErrorCode {
     #[msg("Computation was aborted")]
@@ -788,4 +11498,250 @@ pub enum ErrorCode {
     InvalidProofData,
     #[msg("Merkle root mismatch")]
     MerkleRootMismatch,
+    #[msg("Batch is already executed")]
+    BatchAlreadyExecuted,
+    #[msg("Invalid maker set (1-8 unique makers)")]
+    InvalidMakerSet,
+    #[msg("Invalid RFQ window")]
+    InvalidRfqWindow,
+    #[msg("RFQ is not open")]
+    RfqNotOpen,
+    #[msg("RFQ quote window has closed")]
+    RfqWindowClosed,
+    #[msg("RFQ quote window is still open")]
+    RfqWindowOpen,
+    #[msg("Maker is not whitelisted for this RFQ")]
+    MakerNotWhitelisted,
+    #[msg("Maker already submitted a quote")]
+    QuoteAlreadySubmitted,
+    #[msg("A computation is already pending for this account")]
+    ComputationPending,
+    #[msg("Bond amount must be greater than zero")]
+    InvalidBond,
+    #[msg("RFQ is not settled")]
+    RfqNotSettled,
+    #[msg("Signer is not the winning maker")]
+    NotWinningMaker,
+    #[msg("RFQ fill is already confirmed")]
+    RfqAlreadyFilled,
+    #[msg("Settlement deadline has passed")]
+    SettlementDeadlinePassed,
+    #[msg("Settlement window is still open")]
+    SettlementWindowOpen,
+    #[msg("Quote bond cannot be released yet")]
+    BondNotReleasable,
+    #[msg("Quote bonds are still outstanding")]
+    BondsOutstanding,
+    #[msg("Invalid distribution parameters")]
+    InvalidDistribution,
+    #[msg("Invalid distribution merkle proof")]
+    InvalidDistributionProof,
+    #[msg("Distribution leaf already claimed")]
+    AlreadyClaimed,
+    #[msg("Claim exceeds distribution total")]
+    DistributionExhausted,
+    #[msg("Distribution requires a uniqueness attestation")]
+    UniquenessRequired,
+    #[msg("Invalid uniqueness attestation")]
+    InvalidUniquenessAttestation,
+    #[msg("Batch requires a credential attestation")]
+    AttestationRequired,
+    #[msg("Invalid credential attestation")]
+    InvalidAttestation,
+    #[msg("Credential attestation has expired")]
+    AttestationExpired,
+    #[msg("Invalid zk-verifier account")]
+    InvalidZkVerifierAccount,
+    #[msg("Batch requires an eligibility proof")]
+    EligibilityRequired,
+    #[msg("Invalid eligibility proof")]
+    InvalidEligibilityProof,
+    #[msg("Session expiry must be in the future")]
+    InvalidSessionExpiry,
+    #[msg("Session has expired")]
+    SessionExpired,
+    #[msg("Session is not scoped to this market")]
+    SessionScopeMismatch,
+    #[msg("Batch has staged orders to fold first")]
+    StagedOrdersPending,
+    #[msg("Staged order is not the batch's next")]
+    StagedOrderMismatch,
+    #[msg("Invalid number of staged orders to fold")]
+    InvalidBulkSize,
+    #[msg("Batch takes orders through lanes")]
+    BatchUsesLanes,
+    #[msg("Lanes must be opened before the first order")]
+    BatchHasOrders,
+    #[msg("Invalid lane index")]
+    InvalidLaneIndex,
+    #[msg("Batch lanes are not all open")]
+    LanesNotOpen,
+    #[msg("Batch lanes have not been merged")]
+    LanesNotMerged,
+    #[msg("Batch lanes are already merged")]
+    LanesAlreadyMerged,
+    #[msg("Computation offset is already in use")]
+    ComputationOffsetInUse,
+    #[msg("Proof is missing public inputs")]
+    MissingPublicInputs,
+    #[msg("Attestation was issued under a different credential")]
+    AttestationCredentialMismatch,
+    #[msg("Attestation was issued to a different wallet")]
+    AttestationSubjectMismatch,
+    #[msg("Eligibility proof is for a different predicate")]
+    EligibilityPredicateMismatch,
+    #[msg("Eligibility proof was submitted by a different wallet")]
+    EligibilityWalletMismatch,
+    #[msg("Order count would overflow")]
+    OrderCountOverflow,
+    #[msg("Merged lane totals overflow the batch total")]
+    BatchTotalOverflow,
+    #[msg("Allocation proof is for a different batch total")]
+    AllocationTotalMismatch,
+    #[msg("Adapter config is too long")]
+    InvalidAdapterConfig,
+    #[msg("Invalid execution adapter program")]
+    InvalidAdapterProgram,
+    #[msg("Adapter returned an invalid execution report")]
+    InvalidExecutionReport,
+    #[msg("Fill price exceeds the limit price")]
+    LimitPriceExceeded,
+    #[msg("Transfer memo must be 1-128 bytes")]
+    InvalidTransferMemo,
+    #[msg("Memo program account is required to attach a memo")]
+    MemoProgramRequired,
+    #[msg("Rent sponsor balance too low")]
+    RentSponsorExhausted,
+    #[msg("Invalid provider set (1-5 unique providers, quorum within it)")]
+    InvalidProviderSet,
+    #[msg("Invalid oracle window")]
+    InvalidOracleWindow,
+    #[msg("Oracle is not open")]
+    OracleNotOpen,
+    #[msg("Oracle observation window has closed")]
+    OracleWindowClosed,
+    #[msg("Oracle observation window is still open")]
+    OracleWindowOpen,
+    #[msg("Provider is not whitelisted for this oracle")]
+    ProviderNotWhitelisted,
+    #[msg("Provider already submitted an observation")]
+    ObservationAlreadySubmitted,
+    #[msg("Oracle has fewer observations than its quorum")]
+    NotEnoughObservations,
+    #[msg("Reference price must be positive")]
+    InvalidReferencePrice,
+    #[msg("Invalid flow auction window")]
+    InvalidFlowAuctionWindow,
+    #[msg("Flow auction is not open")]
+    FlowAuctionNotOpen,
+    #[msg("Flow auction bidding window has closed")]
+    FlowAuctionWindowClosed,
+    #[msg("Flow auction bidding window is still open")]
+    FlowAuctionWindowOpen,
+    #[msg("Maker already bid in this flow auction")]
+    FlowBidAlreadySubmitted,
+    #[msg("Batch flow was auctioned; pass its flow auction")]
+    FlowAuctionRequired,
+    #[msg("Flow auction has not settled")]
+    FlowAuctionNotSettled,
+    #[msg("Execution price does not match the flow auction price")]
+    ExecutionPriceMismatch,
+    #[msg("Batch has no pending computation")]
+    NoPendingComputation,
+    #[msg("Pending computation has not timed out yet")]
+    ComputationNotStale,
+    #[msg("Abandoning an order needs the pending order and its user")]
+    PendingOrderRequired,
+    #[msg("Batch state is already initialized")]
+    BatchAlreadyInitialized,
+    #[msg("Batch status transition not allowed")]
+    InvalidStatusTransition,
+    #[msg("Callback is not from the computation this account queued")]
+    UnexpectedComputation,
+    #[msg("Order or lane does not belong to this batch")]
+    OrderBatchMismatch,
+    #[msg("Parlay needs 2 to 4 distinct batches")]
+    InvalidParlayLegs,
+    #[msg("Parlay order is not encrypted yet")]
+    ParlayNotReady,
+    #[msg("Parlay order was rejected by its circuit")]
+    ParlayRejected,
+    #[msg("Batch is not this parlay leg")]
+    ParlayLegMismatch,
+    #[msg("Parlay leg already added")]
+    ParlayLegAlreadyAdded,
+    #[msg("Not every parlay leg has been added")]
+    ParlayIncomplete,
+    #[msg("Parlay already settled")]
+    ParlayAlreadySettled,
+    #[msg("Oracle does not resolve this leg's market")]
+    ParlayOracleMismatch,
+    #[msg("Oracle has not resolved")]
+    OracleNotResolved,
+    #[msg("Position amount must be positive")]
+    InvalidPositionAmount,
+    #[msg("Outcome mint is not the winning outcome")]
+    NotWinningOutcome,
+    #[msg("AMM fee exceeds MAX_AMM_FEE_BPS")]
+    InvalidAmmFee,
+    #[msg("Invalid liquidity or swap amount")]
+    InvalidLiquidityAmount,
+    #[msg("Pool has no liquidity")]
+    EmptyPool,
+    #[msg("Output below the caller's minimum")]
+    SlippageExceeded,
+    #[msg("AMM pool accounts do not match each other or the batch market")]
+    AmmPoolMismatch,
+    #[msg("Batch has no price reference for this condition")]
+    NoPriceReference,
+    #[msg("Voucher signature missing or not by the claimant")]
+    InvalidVoucher,
+    #[msg("Voucher fee must be below the claim amount")]
+    InvalidVoucherFee,
+    #[msg("Batch fill is already on the bulletin")]
+    FillAlreadyPublished,
+    #[msg("Batch fill is not due for publication yet")]
+    FillNotDue,
+    #[msg("Bulletin published a fill too recently")]
+    BulletinRateLimited,
+    #[msg("Risk limits list too many or too long markets")]
+    InvalidRiskLimits,
+    #[msg("Risk profile does not belong to the order's user")]
+    RiskProfileMismatch,
+    #[msg("Market is not in the user's risk profile")]
+    MarketNotAllowed,
+    #[msg("User's risk profile caps amounts; use add_limited_order")]
+    RiskLimitedOrderRequired,
+    #[msg("No loosened risk limits are due yet")]
+    RiskLimitsNotDue,
+    #[msg("Heartbeat timeout is below the minimum")]
+    InvalidHeartbeatTimeout,
+    #[msg("Agent policy has already tripped")]
+    AgentPolicyTripped,
+    #[msg("Agent policy has not tripped")]
+    AgentPolicyNotTripped,
+    #[msg("Agent heartbeat has not timed out")]
+    AgentStillAlive,
+    #[msg("Mint is not one of the condition's outcome mints")]
+    NotOutcomeMint,
+    #[msg("Position is not delegated to the agent policy")]
+    PositionNotDelegated,
+    #[msg("Inputs are not encrypted under the registered x25519 key")]
+    EncryptionKeyMismatch,
+    #[msg("New x25519 key is the current one")]
+    EncryptionKeyUnchanged,
+    #[msg("Funding amount must be positive")]
+    InvalidFundingAmount,
+    #[msg("House account has another batch to settle first")]
+    HouseBatchOpen,
+    #[msg("Batch is not the house account's open batch")]
+    HouseBatchMismatch,
+    #[msg("House account does not belong to the order's desk")]
+    HouseAccountMismatch,
+    #[msg("House ledger has no orders yet")]
+    HouseLedgerEmpty,
+    #[msg("RFQ was not settled in time; makers may reclaim their bonds")]
+    RfqExpired,
+    #[msg("RFQ has no outstanding quote bonds")]
+    NoOutstandingBonds,
 }
@@ -0,0 +1,100 @@
+//! HTTP API
+//!
+//! - `POST /jobs` with a `BatchSnapshot` body queues a job, proved under
+//!   the `x-correlation-id` header's batch and computation offset if set
+//! - `GET /jobs/<id>` returns its status, with the proof once done
+
+use crate::{JobStatus, ProverService, SubmitError};
+use allocation_prover::BatchSnapshot;
+use privacy_sdk::trace::{CorrelationId, CORRELATION_HEADER};
+use hyper::{
+    service::{make_service_fn, service_fn},
+    Body, Method, Request, Response, Server, StatusCode,
+};
+use serde::Serialize;
+use std::{convert::Infallible, net::SocketAddr, sync::Arc};
+
+#[derive(Serialize)]
+struct JobView<'a> {
+    id: &'a str,
+    #[serde(flatten)]
+    status: &'a JobStatus,
+}
+
+#[derive(Serialize)]
+struct ErrorView {
+    error: String,
+}
+
+/// Serve the job API until the server stops
+pub async fn serve(address: SocketAddr, service: Arc<ProverService>) {
+    let make_service = make_service_fn(move |_| {
+        let service = service.clone();
+        async move {
+            Ok::<_, Infallible>(service_fn(move |request| {
+                let service = service.clone();
+                async move { Ok::<_, Infallible>(route(&service, request).await) }
+            }))
+        }
+    });
+
+    match Server::try_bind(&address) {
+        Ok(server) => {
+            if let Err(e) = server.serve(make_service).await {
+                tracing::error!("Prover server stopped: {e}");
+            }
+        }
+        Err(e) => tracing::error!("Failed to bind prover server on {address}: {e}"),
+    }
+}
+
+async fn route(service: &ProverService, request: Request<Body>) -> Response<Body> {
+    let path = request.uri().path().to_string();
+
+    match (request.method(), path.strip_prefix("/jobs")) {
+        (&Method::POST, Some("")) => {
+            let correlation = match request.headers().get(CORRELATION_HEADER) {
+                None => None,
+                Some(value) => match value.to_str().map_err(|e| e.to_string()).and_then(str::parse::<CorrelationId>) {
+                    Ok(correlation) => Some(correlation),
+                    Err(e) => return error(StatusCode::BAD_REQUEST, e),
+                },
+            };
+            let body = match hyper::body::to_bytes(request.into_body()).await {
+                Ok(body) => body,
+                Err(e) => return error(StatusCode::BAD_REQUEST, e.to_string()),
+            };
+            let snapshot: BatchSnapshot = match serde_json::from_slice(&body) {
+                Ok(snapshot) => snapshot,
+                Err(e) => return error(StatusCode::BAD_REQUEST, e.to_string()),
+            };
+
+            match service.submit(&snapshot, correlation) {
+                Ok((id, status)) => json(StatusCode::ACCEPTED, &JobView { id: &id, status: &status }),
+                Err(e @ SubmitError::InvalidSnapshot(_)) => error(StatusCode::BAD_REQUEST, e.to_string()),
+                Err(e @ SubmitError::QueueFull) => error(StatusCode::SERVICE_UNAVAILABLE, e.to_string()),
+            }
+        }
+        (&Method::GET, Some(id)) if id.starts_with('/') => {
+            let id = &id[1..];
+            match service.status(id) {
+                Some(status) => json(StatusCode::OK, &JobView { id, status: &status }),
+                None => error(StatusCode::NOT_FOUND, format!("unknown job {id}")),
+            }
+        }
+        _ => error(StatusCode::NOT_FOUND, format!("no route for {path}")),
+    }
+}
+
+fn json<T: Serialize>(status: StatusCode, body: &T) -> Response<Body> {
+    let body = serde_json::to_vec(body).expect("views serialize");
+    Response::builder()
+        .status(status)
+        .header("content-type", "application/json")
+        .body(Body::from(body))
+        .expect("static response parts")
+}
+
+fn error(status: StatusCode, error: String) -> Response<Body> {
+    json(status, &ErrorView { error })
+}
@@ -0,0 +1,252 @@
+//! Prover Service
+//!
+//! HTTP front for `allocation-prover`, for agents that cannot prove on
+//! their own machines. Clients post indexed batch snapshots as jobs; a pool
+//! of workers proves them off a bounded queue. Each worker proves in its
+//! own copy of the circuit package, so the compiled circuit and
+//! verification key are built once per worker and reused. Jobs are keyed
+//! by a hash of their witness, so resubmitting a batch returns the cached
+//! job instead of proving it again.
+//!
+//! A job submitted with a correlation id is proved inside that batch's
+//! settle span, so its logs line up with the other services'.
+
+use allocation_prover::{prove, AllocationProof, BatchSnapshot, HarnessError, Witness};
+use privacy_sdk::trace::{batch_span, CorrelationId, LogExport, Stage};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::{
+    collections::HashMap,
+    fmt, fs, io,
+    net::SocketAddr,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
+use tokio::sync::mpsc;
+use tracing::Instrument;
+
+pub mod http;
+
+const DEFAULT_WORKERS: usize = 2;
+const DEFAULT_QUEUE_CAPACITY: usize = 64;
+
+// ============================================
+// Configuration
+// ============================================
+
+/// Service config file, e.g.
+/// `{ "bind_address": "0.0.0.0:8910", "circuit_dir": "circuits/allocation", "work_dir": "/tmp/prover" }`
+#[derive(Deserialize, Debug, Clone)]
+pub struct ServiceConfig {
+    pub bind_address: SocketAddr,
+    /// The `circuits/allocation` Noir package
+    pub circuit_dir: PathBuf,
+    /// Scratch directory for the per-worker circuit copies
+    pub work_dir: PathBuf,
+    #[serde(default = "default_workers")]
+    pub workers: usize,
+    #[serde(default = "default_queue_capacity")]
+    pub queue_capacity: usize,
+    /// JSON-lines log file; text on stderr when unset
+    #[serde(default)]
+    pub log_file: Option<PathBuf>,
+}
+
+impl ServiceConfig {
+    pub fn log_export(&self) -> LogExport {
+        self.log_file.clone().map_or(LogExport::Stderr, LogExport::JsonFile)
+    }
+}
+
+fn default_workers() -> usize {
+    DEFAULT_WORKERS
+}
+
+fn default_queue_capacity() -> usize {
+    DEFAULT_QUEUE_CAPACITY
+}
+
+// ============================================
+// Jobs
+// ============================================
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(tag = "status", rename_all = "camelCase")]
+pub enum JobStatus {
+    Queued,
+    Proving,
+    Done { proof: AllocationProof },
+    Failed { error: String },
+}
+
+#[derive(Debug)]
+pub struct Job {
+    pub id: String,
+    pub witness: Witness,
+    pub correlation: Option<CorrelationId>,
+}
+
+#[derive(Debug)]
+pub enum SubmitError {
+    InvalidSnapshot(HarnessError),
+    QueueFull,
+}
+
+impl fmt::Display for SubmitError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::InvalidSnapshot(err) => write!(f, "invalid snapshot: {err}"),
+            Self::QueueFull => write!(f, "job queue is full"),
+        }
+    }
+}
+
+impl std::error::Error for SubmitError {}
+
+/// Content address of a job: the hash of its rendered witness
+pub fn job_id(witness: &Witness) -> String {
+    hex::encode(Sha256::digest(witness.to_prover_toml()))
+}
+
+// ============================================
+// Service
+// ============================================
+
+#[derive(Debug)]
+pub struct ProverService {
+    jobs: Mutex<HashMap<String, JobStatus>>,
+    queue: mpsc::Sender<Job>,
+}
+
+impl ProverService {
+    /// Create the service and the receiving end of its job queue
+    pub fn new(queue_capacity: usize) -> (Arc<Self>, mpsc::Receiver<Job>) {
+        let (queue, receiver) = mpsc::channel(queue_capacity);
+        let service = Self {
+            jobs: Mutex::new(HashMap::new()),
+            queue,
+        };
+        (Arc::new(service), receiver)
+    }
+
+    /// Queue a snapshot for proving
+    ///
+    /// A snapshot already queued, proving or proven returns its existing
+    /// job. Failed jobs are queued again.
+    pub fn submit(
+        &self,
+        snapshot: &BatchSnapshot,
+        correlation: Option<CorrelationId>,
+    ) -> Result<(String, JobStatus), SubmitError> {
+        let witness = Witness::from_snapshot(snapshot).map_err(SubmitError::InvalidSnapshot)?;
+        let id = job_id(&witness);
+
+        let mut jobs = self.jobs.lock().unwrap();
+        if let Some(status) = jobs.get(&id) {
+            if !matches!(status, JobStatus::Failed { .. }) {
+                return Ok((id, status.clone()));
+            }
+        }
+
+        self.queue
+            .try_send(Job {
+                id: id.clone(),
+                witness,
+                correlation,
+            })
+            .map_err(|_| SubmitError::QueueFull)?;
+        jobs.insert(id.clone(), JobStatus::Queued);
+        tracing::info!(job = %id, "queued proof");
+
+        Ok((id, JobStatus::Queued))
+    }
+
+    pub fn status(&self, id: &str) -> Option<JobStatus> {
+        self.jobs.lock().unwrap().get(id).cloned()
+    }
+
+    fn set_status(&self, id: &str, status: JobStatus) {
+        self.jobs.lock().unwrap().insert(id.to_string(), status);
+    }
+}
+
+// ============================================
+// Workers
+// ============================================
+
+/// Prove jobs off the queue with `config.workers` workers until it closes
+pub async fn run_workers(
+    service: Arc<ProverService>,
+    queue: mpsc::Receiver<Job>,
+    config: &ServiceConfig,
+) -> io::Result<()> {
+    let queue = Arc::new(tokio::sync::Mutex::new(queue));
+    let mut workers = Vec::with_capacity(config.workers);
+
+    for worker in 0..config.workers {
+        // Proofs write Prover.toml and target/ into the package, so every
+        // worker gets its own copy
+        let dir = config.work_dir.join(format!("worker-{worker}"));
+        copy_package(&config.circuit_dir, &dir)?;
+
+        let service = service.clone();
+        let queue = queue.clone();
+        workers.push(tokio::spawn(async move {
+            loop {
+                let Some(job) = queue.lock().await.recv().await else {
+                    break;
+                };
+                let span = match &job.correlation {
+                    Some(correlation) => batch_span(correlation, Stage::Settle),
+                    None => tracing::info_span!("job"),
+                };
+                prove_job(&service, &dir, job).instrument(span).await;
+            }
+        }));
+    }
+
+    for worker in workers {
+        if let Err(e) = worker.await {
+            tracing::error!("Prover worker stopped: {e}");
+        }
+    }
+    Ok(())
+}
+
+async fn prove_job(service: &ProverService, dir: &Path, job: Job) {
+    service.set_status(&job.id, JobStatus::Proving);
+    tracing::info!(job = %job.id, "proving");
+
+    let dir = dir.to_path_buf();
+    let witness = job.witness;
+    let status = match tokio::task::spawn_blocking(move || prove(&dir, &witness)).await {
+        Ok(Ok(proof)) => JobStatus::Done { proof },
+        Ok(Err(e)) => JobStatus::Failed { error: e.to_string() },
+        Err(e) => JobStatus::Failed { error: e.to_string() },
+    };
+    match &status {
+        JobStatus::Failed { error } => tracing::warn!(job = %job.id, "Job failed: {error}"),
+        _ => tracing::info!(job = %job.id, "proved"),
+    }
+    service.set_status(&job.id, status);
+}
+
+/// Copy a Noir package's sources (not its build output) into `to`
+fn copy_package(from: &Path, to: &Path) -> io::Result<()> {
+    fs::create_dir_all(to)?;
+    for entry in fs::read_dir(from)? {
+        let entry = entry?;
+        let name = entry.file_name();
+        if name == "target" || name == "Prover.toml" {
+            continue;
+        }
+
+        let path = entry.path();
+        if path.is_dir() {
+            copy_package(&path, &to.join(&name))?;
+        } else {
+            fs::copy(&path, to.join(&name))?;
+        }
+    }
+    Ok(())
+}
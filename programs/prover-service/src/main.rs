@@ -0,0 +1,36 @@
+//! `prover-service <config.json>`
+
+use prover_service::{http, run_workers, ProverService, ServiceConfig};
+use std::{fs, process};
+
+#[tokio::main]
+async fn main() {
+    let Some(config_path) = std::env::args().nth(1) else {
+        eprintln!("usage: prover-service <config.json>");
+        process::exit(2);
+    };
+
+    let config: ServiceConfig = match fs::read_to_string(&config_path)
+        .map_err(|e| e.to_string())
+        .and_then(|json| serde_json::from_str(&json).map_err(|e| e.to_string()))
+    {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("prover-service: invalid config {config_path}: {e}");
+            process::exit(1);
+        }
+    };
+
+    if let Err(e) = privacy_sdk::trace::init(&config.log_export()) {
+        eprintln!("prover-service: {e}");
+        process::exit(1);
+    }
+
+    let (service, queue) = ProverService::new(config.queue_capacity);
+    tokio::spawn(http::serve(config.bind_address, service.clone()));
+
+    if let Err(e) = run_workers(service, queue, &config).await {
+        eprintln!("prover-service: {e}");
+        process::exit(1);
+    }
+}
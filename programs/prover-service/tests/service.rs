@@ -0,0 +1,70 @@
+use allocation_prover::{BatchSnapshot, OrderSnapshot};
+use circuit_sim::{run_batch, Order};
+use privacy_sdk::trace::CorrelationId;
+use prover_service::*;
+
+fn snapshot(amounts: &[u64]) -> BatchSnapshot {
+    let wallets: Vec<[u8; 32]> = (0..amounts.len()).map(|i| [i as u8 + 1; 32]).collect();
+    let orders: Vec<Order> = amounts
+        .iter()
+        .zip(&wallets)
+        .map(|(&amount, wallet)| Order::new(amount, wallet))
+        .collect();
+    let (state, output) = run_batch(&orders, 1_000, 5_000);
+
+    BatchSnapshot {
+        merkle_root: hex::encode(output.merkle_root),
+        total_usdc: state.total_amount,
+        total_shares: 1_000,
+        execution_price: 5_000,
+        orders: amounts
+            .iter()
+            .zip(&wallets)
+            .map(|(&amount, wallet)| OrderSnapshot {
+                user: bs58::encode(wallet).into_string(),
+                amount,
+            })
+            .collect(),
+    }
+}
+
+#[test]
+fn resubmitted_snapshot_returns_cached_job() {
+    let (service, mut queue) = ProverService::new(4);
+
+    let (first, status) = service.submit(&snapshot(&[10, 30]), None).unwrap();
+    let (second, _) = service.submit(&snapshot(&[10, 30]), None).unwrap();
+
+    assert_eq!(first, second);
+    assert_eq!(status, JobStatus::Queued);
+    assert_eq!(service.status(&first), Some(JobStatus::Queued));
+    assert_eq!(queue.try_recv().unwrap().id, first);
+    assert!(queue.try_recv().is_err());
+}
+
+#[test]
+fn invalid_snapshot_is_rejected() {
+    let (service, _queue) = ProverService::new(4);
+    let mut tampered = snapshot(&[10, 30]);
+    tampered.orders[0].amount = 11;
+
+    assert!(matches!(service.submit(&tampered, None), Err(SubmitError::InvalidSnapshot(_))));
+}
+
+#[test]
+fn full_queue_rejects_new_jobs() {
+    let (service, _queue) = ProverService::new(1);
+
+    service.submit(&snapshot(&[10]), None).unwrap();
+    assert!(matches!(service.submit(&snapshot(&[20]), None), Err(SubmitError::QueueFull)));
+    assert_eq!(service.status("unknown"), None);
+}
+
+#[test]
+fn correlation_id_travels_with_the_job() {
+    let (service, mut queue) = ProverService::new(4);
+    let correlation: CorrelationId = "Batch111:42".parse().unwrap();
+
+    service.submit(&snapshot(&[10, 30]), Some(correlation.clone())).unwrap();
+    assert_eq!(queue.try_recv().unwrap().correlation, Some(correlation));
+}
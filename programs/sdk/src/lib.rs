@@ -0,0 +1,16 @@
+//! Privacy-Trading SDK
+//!
+//! Client-side helpers for agents and frontends trading through
+//! privacy-trading batches.
+//!
+//! - [`simulate`] estimates, from public data only, how a batch is likely
+//!   to clear before an order is committed to it.
+//! - [`send`] lands transactions without ever landing one twice, over
+//!   the JSON-RPC client in [`rpc`].
+//! - [`trace`] ties the logs of every service handling one batch
+//!   computation together under a correlation id.
+
+pub mod rpc;
+pub mod send;
+pub mod simulate;
+pub mod trace;
@@ -0,0 +1,147 @@
+//! Solana JSON-RPC client for the sender
+
+use crate::send::{Blockhash, Commitment, Rpc, RpcError, SignatureStatus};
+use base64::Engine;
+use hyper::{client::HttpConnector, Body, Client, Method, Request, Uri};
+use serde_json::{json, Value};
+
+/// Offset of the stored value in a nonce account: version, state and
+/// authority come first
+const NONCE_VALUE_OFFSET: usize = 4 + 4 + 32;
+
+/// `State::Initialized` of a nonce account
+const NONCE_INITIALIZED: u32 = 1;
+
+pub struct RpcClient {
+    url: Uri,
+    client: Client<HttpConnector>,
+    /// Commitment reads are made at
+    commitment: Commitment,
+}
+
+impl RpcClient {
+    pub fn new(url: Uri) -> Self {
+        Self {
+            url,
+            client: Client::new(),
+            commitment: Commitment::Confirmed,
+        }
+    }
+
+    async fn call(&self, method: &str, params: Value) -> Result<Value, RpcError> {
+        let body = json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params });
+        let request = Request::builder()
+            .method(Method::POST)
+            .uri(self.url.clone())
+            .header("content-type", "application/json")
+            .body(Body::from(body.to_string()))
+            .expect("static request parts");
+
+        let response = self.client.request(request).await?;
+        let body = hyper::body::to_bytes(response.into_body()).await?;
+        let mut reply: Value =
+            serde_json::from_slice(&body).map_err(|e| RpcError::Malformed(e.to_string()))?;
+
+        if let Some(error) = reply.get_mut("error") {
+            return Err(RpcError::Node(error.take()));
+        }
+        Ok(reply["result"].take())
+    }
+
+    fn options(&self) -> Value {
+        json!({ "commitment": self.commitment.as_str() })
+    }
+}
+
+impl Rpc for RpcClient {
+    async fn latest_blockhash(&self) -> Result<(Blockhash, u64), RpcError> {
+        let result = self
+            .call("getLatestBlockhash", json!([self.options()]))
+            .await?;
+        let blockhash = result["value"]["blockhash"].as_str().and_then(decode_hash);
+        let last_valid = result["value"]["lastValidBlockHeight"].as_u64();
+        blockhash
+            .zip(last_valid)
+            .ok_or_else(|| RpcError::Malformed(format!("bad blockhash in {result}")))
+    }
+
+    async fn block_height(&self) -> Result<u64, RpcError> {
+        let result = self.call("getBlockHeight", json!([self.options()])).await?;
+        result
+            .as_u64()
+            .ok_or_else(|| RpcError::Malformed(format!("bad block height {result}")))
+    }
+
+    async fn nonce(&self, account: &str) -> Result<Blockhash, RpcError> {
+        let mut options = self.options();
+        options["encoding"] = json!("base64");
+        let result = self
+            .call("getAccountInfo", json!([account, options]))
+            .await?;
+        let data = result["value"]["data"][0]
+            .as_str()
+            .and_then(|data| base64::engine::general_purpose::STANDARD.decode(data).ok())
+            .ok_or_else(|| RpcError::Malformed(format!("no nonce account {account}")))?;
+        read_nonce_account(&data)
+            .ok_or_else(|| RpcError::Malformed(format!("{account} is not an initialized nonce")))
+    }
+
+    async fn send_transaction(&self, transaction: &[u8]) -> Result<String, RpcError> {
+        let encoded = base64::engine::general_purpose::STANDARD.encode(transaction);
+        // The sender polls and rebroadcasts itself
+        let options = json!({
+            "encoding": "base64",
+            "preflightCommitment": self.commitment.as_str(),
+            "maxRetries": 0,
+        });
+        let result = self
+            .call("sendTransaction", json!([encoded, options]))
+            .await?;
+        result
+            .as_str()
+            .map(str::to_string)
+            .ok_or_else(|| RpcError::Malformed(format!("bad signature in {result}")))
+    }
+
+    async fn signature_status(&self, signature: &str) -> Result<Option<SignatureStatus>, RpcError> {
+        let result = self
+            .call(
+                "getSignatureStatuses",
+                json!([[signature], { "searchTransactionHistory": true }]),
+            )
+            .await?;
+        let status = &result["value"][0];
+        if status.is_null() {
+            return Ok(None);
+        }
+        let slot = status["slot"].as_u64();
+        let commitment = status["confirmationStatus"]
+            .as_str()
+            .and_then(Commitment::parse);
+        let err = Some(status["err"].clone()).filter(|err| !err.is_null());
+        slot.zip(commitment)
+            .map(|(slot, commitment)| {
+                Some(SignatureStatus {
+                    slot,
+                    commitment,
+                    err,
+                })
+            })
+            .ok_or_else(|| RpcError::Malformed(format!("bad signature status {status}")))
+    }
+}
+
+/// The stored value of an initialized nonce account
+pub fn read_nonce_account(data: &[u8]) -> Option<Blockhash> {
+    let state = u32::from_le_bytes(data.get(4..8)?.try_into().ok()?);
+    if state != NONCE_INITIALIZED {
+        return None;
+    }
+    data.get(NONCE_VALUE_OFFSET..NONCE_VALUE_OFFSET + 32)?
+        .try_into()
+        .ok()
+}
+
+fn decode_hash(encoded: &str) -> Option<Blockhash> {
+    bs58::decode(encoded).into_vec().ok()?.try_into().ok()
+}
@@ -0,0 +1,372 @@
+//! Retry-safe transaction sending
+//!
+//! An Arcium flow takes two landed transactions per action (the queueing
+//! transaction, then the callback that lands its result), so a client
+//! has to know exactly what happened to the first before it acts again.
+//! [`send`] signs, sends and polls one transaction until it reaches the
+//! requested commitment, and re-signs it only once the previous signature
+//! can no longer land: its blockhash is past its last valid block height,
+//! or its durable nonce has advanced. A resend can therefore never land
+//! the same action twice.
+//!
+//! Failures are classified from the transaction error the node reports:
+//! `AlreadyProcessed` means an earlier send landed, `BlockhashNotFound`
+//! means the lifetime expired, and an instruction error means the program
+//! rejected it, which no retry fixes.
+
+use serde_json::Value;
+use std::{fmt, future::Future, time::Duration};
+
+/// 32-byte blockhash or durable nonce value
+pub type Blockhash = [u8; 32];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Commitment {
+    Processed,
+    Confirmed,
+    Finalized,
+}
+
+impl Commitment {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Processed => "processed",
+            Self::Confirmed => "confirmed",
+            Self::Finalized => "finalized",
+        }
+    }
+
+    pub fn parse(status: &str) -> Option<Self> {
+        match status {
+            "processed" => Some(Self::Processed),
+            "confirmed" => Some(Self::Confirmed),
+            "finalized" => Some(Self::Finalized),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum RpcError {
+    Http(hyper::Error),
+    /// Error object from the node
+    Node(Value),
+    /// Result not shaped as expected
+    Malformed(String),
+}
+
+impl fmt::Display for RpcError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Http(err) => write!(f, "rpc request failed: {err}"),
+            Self::Node(err) => write!(f, "rpc error: {err}"),
+            Self::Malformed(err) => write!(f, "unexpected rpc result: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for RpcError {}
+
+impl From<hyper::Error> for RpcError {
+    fn from(err: hyper::Error) -> Self {
+        Self::Http(err)
+    }
+}
+
+/// A transaction's entry in `getSignatureStatuses`
+#[derive(Debug, Clone, PartialEq)]
+pub struct SignatureStatus {
+    pub slot: u64,
+    pub commitment: Commitment,
+    /// Transaction error, if it landed failed
+    pub err: Option<Value>,
+}
+
+/// The node calls the sender makes
+pub trait Rpc {
+    /// Latest blockhash and the last block height it is valid at
+    fn latest_blockhash(&self) -> impl Future<Output = Result<(Blockhash, u64), RpcError>> + Send;
+
+    fn block_height(&self) -> impl Future<Output = Result<u64, RpcError>> + Send;
+
+    /// Current value of a durable nonce account
+    fn nonce(&self, account: &str) -> impl Future<Output = Result<Blockhash, RpcError>> + Send;
+
+    /// Send a signed wire transaction, returning its signature
+    fn send_transaction(
+        &self,
+        transaction: &[u8],
+    ) -> impl Future<Output = Result<String, RpcError>> + Send;
+
+    fn signature_status(
+        &self,
+        signature: &str,
+    ) -> impl Future<Output = Result<Option<SignatureStatus>, RpcError>> + Send;
+}
+
+/// What a transaction signs as its recent blockhash
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Lifetime {
+    /// A fresh blockhash per attempt, expiring after ~150 blocks
+    Blockhash,
+    /// The value of this durable nonce account, valid until it advances;
+    /// the signed transaction must start with its `AdvanceNonceAccount`
+    DurableNonce(String),
+}
+
+#[derive(Debug, Clone)]
+pub struct SendConfig {
+    pub lifetime: Lifetime,
+    /// Commitment the transaction must reach to count as landed
+    pub commitment: Commitment,
+    /// Times to sign against a new blockhash or nonce
+    pub max_attempts: u32,
+    /// Wait between status polls; the transaction is rebroadcast on each
+    pub poll_interval: Duration,
+}
+
+impl Default for SendConfig {
+    fn default() -> Self {
+        Self {
+            lifetime: Lifetime::Blockhash,
+            commitment: Commitment::Confirmed,
+            max_attempts: 3,
+            poll_interval: Duration::from_millis(500),
+        }
+    }
+}
+
+/// Why a transaction did not land
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Failure {
+    /// An identical transaction already landed
+    AlreadyProcessed,
+    /// Its blockhash or nonce is no longer valid
+    Expired,
+    /// The transaction executed and failed, or failed simulation
+    ProgramError,
+    /// Anything else: the node was unreachable, overloaded or lagging
+    Transient,
+}
+
+/// Classify a `TransactionError` as the node serializes it
+pub fn classify(err: &Value) -> Failure {
+    match err {
+        Value::String(name) => match name.as_str() {
+            "AlreadyProcessed" => Failure::AlreadyProcessed,
+            "BlockhashNotFound" => Failure::Expired,
+            // Nothing ran: the cluster did not take the transaction
+            "AccountInUse"
+            | "AccountLoadedTwice"
+            | "WouldExceedMaxBlockCostLimit"
+            | "WouldExceedMaxAccountCostLimit"
+            | "WouldExceedAccountDataBlockLimit"
+            | "TooManyAccountLocks" => Failure::Transient,
+            _ => Failure::ProgramError,
+        },
+        Value::Object(_) => Failure::ProgramError,
+        _ => Failure::Transient,
+    }
+}
+
+/// Classify a failed `sendTransaction`: preflight failures carry the
+/// transaction error under `data.err`
+pub fn classify_send_error(err: &RpcError) -> Failure {
+    match err {
+        RpcError::Node(error) => match error.pointer("/data/err") {
+            Some(err) if !err.is_null() => classify(err),
+            _ => Failure::Transient,
+        },
+        _ => Failure::Transient,
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Landed {
+    pub signature: String,
+    pub slot: u64,
+    /// Broadcasts made across every attempt
+    pub sends: u32,
+}
+
+#[derive(Debug)]
+pub enum SendError {
+    /// The transaction landed and failed, or failed preflight
+    Program {
+        signature: Option<String>,
+        err: Value,
+    },
+    /// Every attempt expired without landing
+    Expired {
+        attempts: u32,
+    },
+    Rpc(RpcError),
+}
+
+impl fmt::Display for SendError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Program {
+                signature: Some(signature),
+                err,
+            } => write!(f, "transaction {signature} failed: {err}"),
+            Self::Program {
+                signature: None,
+                err,
+            } => write!(f, "transaction failed preflight: {err}"),
+            Self::Expired { attempts } => {
+                write!(f, "transaction expired {attempts} times without landing")
+            }
+            Self::Rpc(err) => err.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for SendError {}
+
+impl From<RpcError> for SendError {
+    fn from(err: RpcError) -> Self {
+        Self::Rpc(err)
+    }
+}
+
+/// Base58 first signature of a wire transaction, which names it
+pub fn transaction_signature(transaction: &[u8]) -> Option<String> {
+    // One compact-u16 byte of signature count for fewer than 128 signers
+    let signature = transaction
+        .get(1..65)
+        .filter(|_| transaction.first().is_some_and(|n| (1..0x80).contains(n)))?;
+    Some(bs58::encode(signature).into_string())
+}
+
+/// Sign with `sign` and send until the transaction lands at
+/// `config.commitment`
+///
+/// `sign` returns the signed wire transaction for a recent blockhash (or
+/// nonce value). It is called again only after the previous signature
+/// expired unlanded.
+pub async fn send<R: Rpc>(
+    rpc: &R,
+    config: &SendConfig,
+    mut sign: impl FnMut(&Blockhash) -> Vec<u8>,
+) -> Result<Landed, SendError> {
+    let mut sends = 0;
+    for _ in 0..config.max_attempts {
+        let (blockhash, expiry) = match &config.lifetime {
+            Lifetime::Blockhash => {
+                let (blockhash, last_valid_height) = rpc.latest_blockhash().await?;
+                (blockhash, Expiry::BlockHeight(last_valid_height))
+            }
+            Lifetime::DurableNonce(account) => {
+                let nonce = rpc.nonce(account).await?;
+                (nonce, Expiry::NonceAdvanced(account, nonce))
+            }
+        };
+        let transaction = sign(&blockhash);
+        let signature = transaction_signature(&transaction).ok_or_else(|| {
+            RpcError::Malformed("signed transaction has no signature".to_string())
+        })?;
+        tracing::info!(%signature, "sending transaction");
+
+        loop {
+            sends += 1;
+            let mut expired = false;
+            if let Err(err) = rpc.send_transaction(&transaction).await {
+                match (classify_send_error(&err), err) {
+                    (Failure::ProgramError, RpcError::Node(mut error)) => {
+                        let err = error["data"]["err"].take();
+                        return Err(SendError::Program {
+                            signature: None,
+                            err,
+                        });
+                    }
+                    // An earlier send of this signature may still have
+                    // landed, so it gets the final poll below
+                    (Failure::Expired, _) => expired = true,
+                    // Poll for the earlier send; expiry is checked there
+                    _ => {}
+                }
+            }
+
+            if !expired {
+                tokio::time::sleep(config.poll_interval).await;
+                match poll(rpc, &signature, config.commitment).await? {
+                    Poll::Landed(slot) => {
+                        return Ok(Landed {
+                            signature,
+                            slot,
+                            sends,
+                        })
+                    }
+                    Poll::Failed(err) => {
+                        return Err(SendError::Program {
+                            signature: Some(signature),
+                            err,
+                        })
+                    }
+                    Poll::Pending => continue,
+                    Poll::Unseen => {}
+                }
+                expired = expiry.passed(rpc).await?;
+            }
+            if expired {
+                // It may have landed between the poll and the expiry
+                // check; after expiry it cannot land any more
+                match poll(rpc, &signature, config.commitment).await? {
+                    Poll::Landed(slot) => {
+                        return Ok(Landed {
+                            signature,
+                            slot,
+                            sends,
+                        })
+                    }
+                    Poll::Failed(err) => {
+                        return Err(SendError::Program {
+                            signature: Some(signature),
+                            err,
+                        })
+                    }
+                    Poll::Pending => continue,
+                    Poll::Unseen => {
+                        tracing::warn!(%signature, "transaction expired unlanded");
+                        break;
+                    }
+                }
+            }
+        }
+    }
+    Err(SendError::Expired {
+        attempts: config.max_attempts,
+    })
+}
+
+enum Expiry<'a> {
+    BlockHeight(u64),
+    NonceAdvanced(&'a str, Blockhash),
+}
+
+impl Expiry<'_> {
+    async fn passed<R: Rpc>(&self, rpc: &R) -> Result<bool, RpcError> {
+        Ok(match self {
+            Self::BlockHeight(last_valid) => rpc.block_height().await? > *last_valid,
+            Self::NonceAdvanced(account, signed) => rpc.nonce(account).await? != *signed,
+        })
+    }
+}
+
+enum Poll {
+    Landed(u64),
+    Failed(Value),
+    /// Landed, not yet at the wanted commitment
+    Pending,
+    Unseen,
+}
+
+async fn poll<R: Rpc>(rpc: &R, signature: &str, commitment: Commitment) -> Result<Poll, RpcError> {
+    Ok(match rpc.signature_status(signature).await? {
+        None => Poll::Unseen,
+        Some(SignatureStatus { err: Some(err), .. }) => Poll::Failed(err),
+        Some(status) if status.commitment >= commitment => Poll::Landed(status.slot),
+        Some(_) => Poll::Pending,
+    })
+}
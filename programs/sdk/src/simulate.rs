@@ -0,0 +1,374 @@
+//! Batch simulation from public data
+//!
+//! An agent deciding whether to join an open batch can see only what the
+//! chain shows everyone: the batch's side, status and order count (its
+//! total stays encrypted until execution), the indexer's history of the
+//! market's earlier batches, and the market's delayed fill bulletin. This
+//! module turns those into an [`Estimate`] for one intended order:
+//!
+//! - fill probability: how often earlier same-side batches that reached
+//!   the order count joining would give went on to execute;
+//! - clearing price: a recency-weighted mean of same-side bulletin fills;
+//! - share of the batch: the order against the notional the batch is
+//!   expected to end with, from earlier batches' per-order notional.
+//!
+//! Every order in a batch fills pro rata at one price, so the order's own
+//! size moves only its share, never its price or fill probability.
+
+use serde::Deserialize;
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+
+/// Most orders a batch takes (`BatchFull` past this)
+pub const BATCH_CAPACITY: u8 = 32;
+
+/// Fills a bulletin keeps (`FILL_TAPE_CAPACITY` in the program)
+const FILL_TAPE_CAPACITY: usize = 64;
+
+/// Serialized `PublicFill`: side, execution_price, size_bucket, epoch_slot
+const PUBLIC_FILL_LEN: usize = 1 + 8 + 1 + 8;
+
+/// Slots a closed batch may go unexecuted before it counts as failed,
+/// matching the program's `COMPUTATION_TIMEOUT_SLOTS`
+pub const SETTLE_GRACE_SLOTS: u64 = 3_000;
+
+/// Bulletin fills after which a fill weighs half as much in the price
+const PRICE_HALF_LIFE_FILLS: f64 = 8.0;
+
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Side {
+    Yes,
+    No,
+    /// A side the indexer could not decode
+    #[serde(other)]
+    Unknown,
+}
+
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum BatchStatus {
+    Open,
+    Closed,
+    Executed,
+    Verified,
+    #[serde(other)]
+    Unknown,
+}
+
+/// The public fields of a `TradingBatch`, as the indexer streams them
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchView {
+    pub market_id: String,
+    pub side: Side,
+    pub status: BatchStatus,
+    pub order_count: u8,
+    /// Zero until the batch executes
+    pub total_usdc: u64,
+}
+
+/// One account update line from the indexer
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AccountUpdate {
+    slot: u64,
+    pubkey: String,
+    account: String,
+    data: Value,
+}
+
+/// A `TradingBatch` update from the indexer
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BatchUpdate {
+    pub pubkey: String,
+    pub slot: u64,
+    pub view: BatchView,
+}
+
+/// Parse one JSON line of the indexer's account stream
+///
+/// Lines for other account types give `None`.
+pub fn read_batch_update(line: &str) -> Result<Option<BatchUpdate>, String> {
+    let update: AccountUpdate = serde_json::from_str(line).map_err(|e| e.to_string())?;
+    if update.account != "TradingBatch" {
+        return Ok(None);
+    }
+    let view = serde_json::from_value(update.data).map_err(|e| e.to_string())?;
+    Ok(Some(BatchUpdate {
+        pubkey: update.pubkey,
+        slot: update.slot,
+        view,
+    }))
+}
+
+/// A fill from a market's `FillBulletin`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PublicFill {
+    pub side: Side,
+    pub execution_price: u64,
+    /// Decade bucket of the batch notional in whole USDC; 0 below 1 USDC
+    pub size_bucket: u8,
+    pub epoch_slot: u64,
+}
+
+/// A decoded `FillBulletin` account
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FillTape {
+    pub market_id: String,
+    pub last_published_at: u64,
+    /// Oldest first; at most the last `FILL_TAPE_CAPACITY` fills
+    pub fills: Vec<PublicFill>,
+}
+
+/// Decode a `FillBulletin` account's data
+pub fn read_fill_tape(data: &[u8]) -> Result<FillTape, String> {
+    let discriminator = &Sha256::digest(b"account:FillBulletin")[..8];
+    let mut reader = Reader { data, at: 0 };
+    if reader.take(8)? != discriminator {
+        return Err("not a FillBulletin account".to_string());
+    }
+    reader.take(1)?; // bump
+    let market_len = u32::from_le_bytes(reader.array()?) as usize;
+    let market_id =
+        String::from_utf8(reader.take(market_len)?.to_vec()).map_err(|e| e.to_string())?;
+    let last_published_at = u64::from_le_bytes(reader.array()?);
+    let published = u64::from_le_bytes(reader.array()?);
+
+    let mut ring = Vec::with_capacity(FILL_TAPE_CAPACITY);
+    for _ in 0..FILL_TAPE_CAPACITY {
+        let fill = reader.take(PUBLIC_FILL_LEN)?;
+        ring.push(PublicFill {
+            side: match fill[0] {
+                0 => Side::Yes,
+                1 => Side::No,
+                _ => Side::Unknown,
+            },
+            execution_price: u64::from_le_bytes(fill[1..9].try_into().expect("8 bytes")),
+            size_bucket: fill[9],
+            epoch_slot: u64::from_le_bytes(fill[10..18].try_into().expect("8 bytes")),
+        });
+    }
+
+    // Until the ring wraps the fills sit in order from index 0; after,
+    // the oldest is the one the next fill will overwrite
+    let fills = if published <= FILL_TAPE_CAPACITY as u64 {
+        ring.truncate(published as usize);
+        ring
+    } else {
+        let next = (published % FILL_TAPE_CAPACITY as u64) as usize;
+        ring[next..].iter().chain(&ring[..next]).copied().collect()
+    };
+    Ok(FillTape {
+        market_id,
+        last_published_at,
+        fills,
+    })
+}
+
+struct Reader<'a> {
+    data: &'a [u8],
+    at: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], String> {
+        let bytes = self
+            .data
+            .get(self.at..self.at + len)
+            .ok_or_else(|| format!("account data ends at {} bytes", self.data.len()))?;
+        self.at += len;
+        Ok(bytes)
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N], String> {
+        Ok(self.take(N)?.try_into().expect("N bytes"))
+    }
+}
+
+/// What the indexer and the bulletins have shown about past batches
+#[derive(Debug, Clone, Default)]
+pub struct MarketHistory {
+    /// Latest view per batch pubkey, with the slot it was seen at
+    batches: BTreeMap<String, (u64, BatchView)>,
+    /// Bulletin fills per market, oldest first
+    fills: BTreeMap<String, Vec<PublicFill>>,
+}
+
+impl MarketHistory {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Take one JSON line of the indexer's account stream
+    ///
+    /// Updates for other account types are skipped; an update older than
+    /// the one already held for a batch is ignored.
+    pub fn ingest(&mut self, line: &str) -> Result<(), String> {
+        if let Some(update) = read_batch_update(line)? {
+            self.record_batch(update.pubkey, update.slot, update.view);
+        }
+        Ok(())
+    }
+
+    pub fn record_batch(&mut self, pubkey: String, slot: u64, view: BatchView) {
+        match self.batches.get(&pubkey) {
+            Some((seen, _)) if *seen > slot => {}
+            _ => {
+                self.batches.insert(pubkey, (slot, view));
+            }
+        }
+    }
+
+    /// Replace a market's fills with a freshly read bulletin
+    pub fn record_fill_tape(&mut self, tape: FillTape) {
+        self.fills.insert(tape.market_id, tape.fills);
+    }
+
+    /// Append one fill as the market's bulletin would, keeping the last
+    /// `FILL_TAPE_CAPACITY`
+    pub fn record_fill(&mut self, market_id: &str, fill: PublicFill) {
+        let fills = self.fills.entry(market_id.to_string()).or_default();
+        if fills.len() == FILL_TAPE_CAPACITY {
+            fills.remove(0);
+        }
+        fills.push(fill);
+    }
+
+    /// Same-side batches of `market_id` whose outcome is known at
+    /// `current_slot`, with whether they executed
+    fn settled<'a>(
+        &'a self,
+        market_id: &'a str,
+        side: Side,
+        current_slot: u64,
+    ) -> impl Iterator<Item = (&'a BatchView, bool)> + 'a {
+        self.batches.values().filter_map(move |(slot, view)| {
+            if view.market_id != market_id || view.side != side {
+                return None;
+            }
+            match view.status {
+                BatchStatus::Executed | BatchStatus::Verified => Some((view, true)),
+                BatchStatus::Closed if current_slot.saturating_sub(*slot) > SETTLE_GRACE_SLOTS => {
+                    Some((view, false))
+                }
+                _ => None,
+            }
+        })
+    }
+}
+
+/// Expected outcome of joining one batch with one order
+#[derive(Debug, Clone, PartialEq)]
+pub struct Estimate {
+    /// Chance the batch executes with the order in it
+    pub fill_probability: f64,
+    /// Settled batches behind `fill_probability`
+    pub sample_size: usize,
+    /// `None` without a same-side fill on the bulletin
+    pub expected_price: Option<u64>,
+    /// Lowest and highest same-side bulletin price
+    pub price_range: Option<(u64, u64)>,
+    /// Orders the batch is expected to execute with, this one included
+    pub expected_orders: u8,
+    pub expected_notional: u64,
+    /// The order's expected pro-rata share of the batch
+    pub expected_share_bps: u64,
+}
+
+impl Estimate {
+    /// Whether the batch is worth joining for an agent that pays at most
+    /// `max_price` and wants at least `min_fill_probability`
+    ///
+    /// A market with no fills yet has no price to hold against the limit,
+    /// so it is never worth joining on this estimate alone.
+    pub fn worth_joining(&self, max_price: u64, min_fill_probability: f64) -> bool {
+        self.fill_probability >= min_fill_probability
+            && self.expected_price.is_some_and(|price| price <= max_price)
+    }
+}
+
+/// Estimate how `batch` clears if an order of `amount` joins it now
+pub fn estimate(
+    history: &MarketHistory,
+    batch: &BatchView,
+    amount: u64,
+    current_slot: u64,
+) -> Estimate {
+    let joining_count = batch.order_count.saturating_add(1);
+    let joinable = batch.status == BatchStatus::Open && batch.order_count < BATCH_CAPACITY;
+
+    // Batches that got at least as far as this one would with the order
+    let (mut executed, mut sample_size) = (0usize, 0usize);
+    let (mut executed_orders, mut notional) = (0u64, 0u64);
+    for (view, did_execute) in history.settled(&batch.market_id, batch.side, current_slot) {
+        if view.order_count < joining_count {
+            continue;
+        }
+        sample_size += 1;
+        if did_execute {
+            executed += 1;
+            executed_orders += view.order_count as u64;
+            notional = notional.saturating_add(view.total_usdc);
+        }
+    }
+    // Laplace smoothing keeps a thin history away from 0 and 1
+    let fill_probability = if joinable {
+        (executed as f64 + 1.0) / (sample_size as f64 + 2.0)
+    } else {
+        0.0
+    };
+
+    let expected_orders = if executed == 0 {
+        joining_count
+    } else {
+        ((executed_orders + executed as u64 / 2) / executed as u64)
+            .clamp(joining_count as u64, BATCH_CAPACITY as u64) as u8
+    };
+    let per_order = notional.checked_div(executed_orders).unwrap_or(amount);
+    let expected_notional = per_order
+        .saturating_mul(expected_orders.saturating_sub(1) as u64)
+        .saturating_add(amount);
+    let expected_share_bps = (amount as u128 * 10_000)
+        .checked_div(expected_notional as u128)
+        .unwrap_or(0) as u64;
+
+    let fills: Vec<&PublicFill> = history
+        .fills
+        .get(&batch.market_id)
+        .into_iter()
+        .flatten()
+        .filter(|fill| fill.side == batch.side)
+        .collect();
+    let (mut weighted, mut weights) = (0.0, 0.0);
+    for (age, fill) in fills.iter().rev().enumerate() {
+        let weight = 0.5f64.powf(age as f64 / PRICE_HALF_LIFE_FILLS);
+        weighted += fill.execution_price as f64 * weight;
+        weights += weight;
+    }
+    let expected_price = (!fills.is_empty()).then(|| (weighted / weights).round() as u64);
+    let price_range = fills
+        .iter()
+        .map(|fill| fill.execution_price)
+        .min()
+        .zip(fills.iter().map(|fill| fill.execution_price).max());
+
+    tracing::debug!(
+        market_id = %batch.market_id,
+        order_count = batch.order_count,
+        fill_probability,
+        sample_size,
+        ?expected_price,
+        "estimated batch"
+    );
+    Estimate {
+        fill_probability,
+        sample_size,
+        expected_price,
+        price_range,
+        expected_orders,
+        expected_notional,
+        expected_share_bps,
+    }
+}
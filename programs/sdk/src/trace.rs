@@ -0,0 +1,108 @@
+//! Tracing across services
+//!
+//! One batch computation passes through several processes: a client
+//! queues it, the Arcium callback lands it, and the prover service
+//! settles the batch with an allocation proof. Each records its spans
+//! under the same [`CorrelationId`] (the batch pubkey and the
+//! computation offset), so filtering the exported logs of every service
+//! on one id follows a stuck batch end to end.
+
+use std::{fmt, fs::OpenOptions, path::PathBuf, str::FromStr, sync::Mutex};
+use tracing::Span;
+
+/// Header services pass a correlation id in
+pub const CORRELATION_HEADER: &str = "x-correlation-id";
+
+/// `<batch pubkey>:<computation offset>`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CorrelationId {
+    pub batch: String,
+    pub computation_offset: u64,
+}
+
+impl fmt::Display for CorrelationId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}:{}", self.batch, self.computation_offset)
+    }
+}
+
+impl FromStr for CorrelationId {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, String> {
+        let (batch, offset) = s
+            .rsplit_once(':')
+            .ok_or_else(|| format!("correlation id {s:?} is not <batch>:<computation offset>"))?;
+        let computation_offset = offset
+            .parse()
+            .map_err(|e| format!("correlation id {s:?}: invalid computation offset: {e}"))?;
+        if batch.is_empty() {
+            return Err(format!("correlation id {s:?} has no batch"));
+        }
+        Ok(Self {
+            batch: batch.to_string(),
+            computation_offset,
+        })
+    }
+}
+
+/// Where in a computation's life a span runs
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stage {
+    /// Queueing the computation
+    Submit,
+    /// Handling the Arcium callback
+    Callback,
+    /// Proving and verifying the executed batch
+    Settle,
+}
+
+impl Stage {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Submit => "submit",
+            Self::Callback => "callback",
+            Self::Settle => "settle",
+        }
+    }
+}
+
+/// Span for one stage of a batch computation
+pub fn batch_span(id: &CorrelationId, stage: Stage) -> Span {
+    tracing::info_span!("batch", correlation_id = %id, stage = stage.as_str())
+}
+
+/// Where a service writes its logs
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum LogExport {
+    /// Text lines on stderr
+    #[default]
+    Stderr,
+    /// JSON lines appended to a file, each event with its spans' fields,
+    /// for shipping to a log store
+    JsonFile(PathBuf),
+}
+
+/// Install the global subscriber, which also takes `log` records
+///
+/// Fails if the export file cannot be opened or a subscriber is already
+/// installed.
+pub fn init(export: &LogExport) -> Result<(), String> {
+    let builder = tracing_subscriber::fmt().with_max_level(tracing::Level::INFO);
+    match export {
+        LogExport::Stderr => builder.with_writer(std::io::stderr).try_init(),
+        LogExport::JsonFile(path) => {
+            let file = OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(path)
+                .map_err(|e| format!("opening {}: {e}", path.display()))?;
+            builder
+                .json()
+                .with_ansi(false)
+                .with_writer(Mutex::new(file))
+                .try_init()
+        }
+    }
+    .map_err(|e| e.to_string())
+}
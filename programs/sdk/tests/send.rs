@@ -0,0 +1,358 @@
+use privacy_sdk::{rpc::read_nonce_account, send::*};
+use serde_json::{json, Value};
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    sync::Mutex,
+    time::Duration,
+};
+
+/// Blocks a blockhash stays valid for in the scripted node
+const VALID_BLOCKS: u64 = 3;
+
+/// A node that advances one block per `getBlockHeight` and lands the
+/// transactions signed against the blockhashes (or nonces) in `lands`
+#[derive(Default)]
+struct Node {
+    state: Mutex<State>,
+}
+
+#[derive(Default)]
+struct State {
+    height: u64,
+    issued: u8,
+    nonce: u8,
+    /// Nonce value after each `nonce` call, front first
+    nonces: VecDeque<u8>,
+    lands: HashSet<u8>,
+    /// Replies to `sendTransaction`, front first; `Ok` for `null` and
+    /// once empty
+    send_replies: VecDeque<Value>,
+    /// Statuses each landed signature goes through, one per poll
+    landing: Vec<SignatureStatus>,
+    statuses: HashMap<String, VecDeque<SignatureStatus>>,
+    sends: Vec<u8>,
+}
+
+impl Node {
+    fn landing(lands: &[u8], landing: Vec<SignatureStatus>) -> Self {
+        let node = Self::default();
+        {
+            let mut state = node.state.lock().unwrap();
+            state.lands = lands.iter().copied().collect();
+            state.landing = landing;
+        }
+        node
+    }
+
+    fn sends(&self) -> Vec<u8> {
+        self.state.lock().unwrap().sends.clone()
+    }
+}
+
+impl Rpc for Node {
+    async fn latest_blockhash(&self) -> Result<(Blockhash, u64), RpcError> {
+        let mut state = self.state.lock().unwrap();
+        state.issued += 1;
+        Ok(([state.issued; 32], state.height + VALID_BLOCKS))
+    }
+
+    async fn block_height(&self) -> Result<u64, RpcError> {
+        let mut state = self.state.lock().unwrap();
+        state.height += 1;
+        Ok(state.height)
+    }
+
+    async fn nonce(&self, _account: &str) -> Result<Blockhash, RpcError> {
+        let mut state = self.state.lock().unwrap();
+        if let Some(nonce) = state.nonces.pop_front() {
+            state.nonce = nonce;
+        }
+        Ok([state.nonce; 32])
+    }
+
+    async fn send_transaction(&self, transaction: &[u8]) -> Result<String, RpcError> {
+        let mut state = self.state.lock().unwrap();
+        let blockhash = transaction[65];
+        state.sends.push(blockhash);
+        if let Some(reply) = state
+            .send_replies
+            .pop_front()
+            .filter(|reply| !reply.is_null())
+        {
+            return Err(RpcError::Node(reply));
+        }
+        let signature = transaction_signature(transaction).unwrap();
+        if state.lands.contains(&blockhash) && !state.statuses.contains_key(&signature) {
+            let landing = state.landing.iter().cloned().collect();
+            state.statuses.insert(signature.clone(), landing);
+        }
+        Ok(signature)
+    }
+
+    async fn signature_status(&self, signature: &str) -> Result<Option<SignatureStatus>, RpcError> {
+        let mut state = self.state.lock().unwrap();
+        Ok(state.statuses.get_mut(signature).and_then(|statuses| {
+            if statuses.len() > 1 {
+                statuses.pop_front()
+            } else {
+                statuses.front().cloned()
+            }
+        }))
+    }
+}
+
+fn status(commitment: Commitment, err: Option<Value>) -> SignatureStatus {
+    SignatureStatus {
+        slot: 77,
+        commitment,
+        err,
+    }
+}
+
+/// Wire transaction whose signature and message both name the blockhash
+fn signer(signed: &mut Vec<u8>) -> impl FnMut(&Blockhash) -> Vec<u8> + '_ {
+    move |blockhash| {
+        signed.push(blockhash[0]);
+        let mut transaction = vec![1];
+        transaction.extend_from_slice(blockhash);
+        transaction.extend_from_slice(blockhash);
+        transaction.extend_from_slice(blockhash);
+        transaction
+    }
+}
+
+fn config() -> SendConfig {
+    SendConfig {
+        poll_interval: Duration::ZERO,
+        ..SendConfig::default()
+    }
+}
+
+fn preflight_error(err: Value) -> Value {
+    json!({ "code": -32002, "message": "Transaction simulation failed", "data": { "err": err, "logs": [] } })
+}
+
+#[tokio::test]
+async fn expired_blockhash_is_resigned_and_lands() {
+    let node = Node::landing(
+        &[2],
+        vec![
+            status(Commitment::Processed, None),
+            status(Commitment::Confirmed, None),
+        ],
+    );
+    let mut signed = Vec::new();
+
+    let landed = send(&node, &config(), signer(&mut signed)).await.unwrap();
+
+    assert_eq!(signed, vec![1, 2]);
+    assert_eq!(
+        landed.signature,
+        transaction_signature(&signer(&mut Vec::new())(&[2; 32])).unwrap()
+    );
+    assert_eq!(landed.slot, 77);
+    // Blockhash 1 is rebroadcast until its last valid height passes
+    assert!(node.sends().iter().filter(|hash| **hash == 1).count() >= VALID_BLOCKS as usize);
+    assert_eq!(landed.sends as usize, node.sends().len());
+}
+
+#[tokio::test]
+async fn failed_sends_keep_the_same_signature() {
+    let node = Node::landing(&[1], vec![status(Commitment::Confirmed, None)]);
+    {
+        let mut state = node.state.lock().unwrap();
+        state
+            .send_replies
+            .push_back(json!({ "code": -32603, "message": "overloaded" }));
+        state
+            .send_replies
+            .push_back(preflight_error(json!("AlreadyProcessed")));
+    }
+    let mut signed = Vec::new();
+
+    // Neither failure re-signs: the same transaction is rebroadcast and
+    // lands on the third send
+    let landed = send(&node, &config(), signer(&mut signed)).await.unwrap();
+    assert_eq!(signed, vec![1]);
+    assert_eq!(landed.sends, 3);
+}
+
+#[tokio::test]
+async fn program_errors_are_not_retried() {
+    let custom = json!({ "InstructionError": [0, { "Custom": 6001 }] });
+
+    let node = Node::default();
+    node.state
+        .lock()
+        .unwrap()
+        .send_replies
+        .push_back(preflight_error(custom.clone()));
+    let mut signed = Vec::new();
+    match send(&node, &config(), signer(&mut signed)).await {
+        Err(SendError::Program {
+            signature: None,
+            err,
+        }) => assert_eq!(err, custom),
+        other => panic!("{other:?}"),
+    }
+    assert_eq!(signed, vec![1]);
+
+    let node = Node::landing(
+        &[1],
+        vec![status(Commitment::Processed, Some(custom.clone()))],
+    );
+    let mut signed = Vec::new();
+    match send(&node, &config(), signer(&mut signed)).await {
+        Err(SendError::Program {
+            signature: Some(_),
+            err,
+        }) => assert_eq!(err, custom),
+        other => panic!("{other:?}"),
+    }
+    assert_eq!(signed, vec![1]);
+}
+
+#[tokio::test]
+async fn finalized_waits_past_confirmed() {
+    let node = Node::landing(
+        &[1],
+        vec![
+            status(Commitment::Processed, None),
+            status(Commitment::Confirmed, None),
+            status(Commitment::Confirmed, None),
+            status(Commitment::Confirmed, None),
+            status(Commitment::Confirmed, None),
+            status(Commitment::Finalized, None),
+        ],
+    );
+    let config = SendConfig {
+        commitment: Commitment::Finalized,
+        ..config()
+    };
+    let mut signed = Vec::new();
+
+    // Landed but unfinalized is never treated as expired
+    let landed = send(&node, &config, signer(&mut signed)).await.unwrap();
+    assert_eq!(signed, vec![1]);
+    assert_eq!(landed.sends, 6);
+}
+
+#[tokio::test]
+async fn every_attempt_expiring_gives_up() {
+    let node = Node::default();
+    let mut signed = Vec::new();
+
+    match send(&node, &config(), signer(&mut signed)).await {
+        Err(SendError::Expired { attempts: 3 }) => {}
+        other => panic!("{other:?}"),
+    }
+    assert_eq!(signed, vec![1, 2, 3]);
+
+    let node = Node::default();
+    node.state
+        .lock()
+        .unwrap()
+        .send_replies
+        .push_back(preflight_error(json!("BlockhashNotFound")));
+    let config = SendConfig {
+        max_attempts: 1,
+        ..config()
+    };
+    assert!(matches!(
+        send(&node, &config, signer(&mut Vec::new())).await,
+        Err(SendError::Expired { attempts: 1 })
+    ));
+    assert_eq!(node.sends(), vec![1]);
+}
+
+#[tokio::test]
+async fn expired_rebroadcast_polls_the_earlier_send() {
+    // The first send lands but is only processed when the rebroadcast
+    // finds its blockhash gone
+    let node = Node::landing(
+        &[1],
+        vec![
+            status(Commitment::Processed, None),
+            status(Commitment::Confirmed, None),
+        ],
+    );
+    node.state.lock().unwrap().send_replies =
+        VecDeque::from([Value::Null, preflight_error(json!("BlockhashNotFound"))]);
+    let mut signed = Vec::new();
+
+    let landed = send(&node, &config(), signer(&mut signed)).await.unwrap();
+    assert_eq!(signed, vec![1]);
+    assert_eq!(landed.sends, 2);
+    assert_eq!(node.sends(), vec![1, 1]);
+}
+
+#[tokio::test]
+async fn durable_nonce_is_resigned_only_once_it_advances() {
+    let node = Node::landing(&[9], vec![status(Commitment::Confirmed, None)]);
+    // Signed at 5, unchanged for three checks, then advanced by someone
+    // else to 9
+    node.state.lock().unwrap().nonces = VecDeque::from([5, 5, 5, 5, 9, 9, 9]);
+    let config = SendConfig {
+        lifetime: Lifetime::DurableNonce("Nonce1111".into()),
+        ..config()
+    };
+    let mut signed = Vec::new();
+
+    send(&node, &config, signer(&mut signed)).await.unwrap();
+    assert_eq!(signed, vec![5, 9]);
+    assert_eq!(node.sends(), vec![5, 5, 5, 5, 9]);
+}
+
+#[test]
+fn transaction_errors_classify() {
+    assert_eq!(
+        classify(&json!("AlreadyProcessed")),
+        Failure::AlreadyProcessed
+    );
+    assert_eq!(classify(&json!("BlockhashNotFound")), Failure::Expired);
+    assert_eq!(classify(&json!("AccountInUse")), Failure::Transient);
+    assert_eq!(
+        classify(&json!("InsufficientFundsForFee")),
+        Failure::ProgramError
+    );
+    assert_eq!(
+        classify(&json!({ "InstructionError": [1, "InvalidAccountData"] })),
+        Failure::ProgramError
+    );
+
+    assert_eq!(
+        classify_send_error(&RpcError::Node(
+            json!({ "code": -32005, "message": "behind" })
+        )),
+        Failure::Transient
+    );
+    assert_eq!(
+        classify_send_error(&RpcError::Malformed("bad".into())),
+        Failure::Transient
+    );
+    assert_eq!(
+        classify_send_error(&RpcError::Node(preflight_error(json!("BlockhashNotFound")))),
+        Failure::Expired
+    );
+}
+
+#[test]
+fn nonce_accounts_and_signatures_decode() {
+    let mut data = vec![0u8; 80];
+    data[0] = 1;
+    data[40..72].copy_from_slice(&[7; 32]);
+    assert_eq!(read_nonce_account(&data), None);
+    data[4] = 1;
+    assert_eq!(read_nonce_account(&data), Some([7; 32]));
+    assert_eq!(read_nonce_account(&data[..50]), None);
+
+    let mut transaction = vec![1];
+    transaction.extend_from_slice(&[3; 64]);
+    assert_eq!(
+        transaction_signature(&transaction),
+        Some(bs58::encode([3; 64]).into_string())
+    );
+    assert_eq!(transaction_signature(&transaction[..40]), None);
+    transaction[0] = 0;
+    assert_eq!(transaction_signature(&transaction), None);
+}
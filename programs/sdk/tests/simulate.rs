@@ -0,0 +1,187 @@
+use privacy_sdk::simulate::*;
+use sha2::{Digest, Sha256};
+
+const MARKET: &str = "btc-100k";
+
+fn view(side: Side, status: BatchStatus, order_count: u8, total_usdc: u64) -> BatchView {
+    BatchView {
+        market_id: MARKET.to_string(),
+        side,
+        status,
+        order_count,
+        total_usdc,
+    }
+}
+
+fn open_batch(order_count: u8) -> BatchView {
+    view(Side::Yes, BatchStatus::Open, order_count, 0)
+}
+
+fn fill(side: Side, execution_price: u64) -> PublicFill {
+    PublicFill {
+        side,
+        execution_price,
+        size_bucket: 3,
+        epoch_slot: 0,
+    }
+}
+
+/// A `FillBulletin` account holding `fills` oldest first
+fn bulletin(fills: &[PublicFill]) -> Vec<u8> {
+    let mut data = Sha256::digest(b"account:FillBulletin")[..8].to_vec();
+    data.push(254);
+    data.extend((MARKET.len() as u32).to_le_bytes());
+    data.extend(MARKET.as_bytes());
+    data.extend(900u64.to_le_bytes());
+    data.extend((fills.len() as u64).to_le_bytes());
+    let mut ring = vec![[0u8; 18]; 64];
+    for (i, fill) in fills.iter().enumerate() {
+        let slot = &mut ring[i % 64];
+        slot[0] = fill.side as u8;
+        slot[1..9].copy_from_slice(&fill.execution_price.to_le_bytes());
+        slot[9] = fill.size_bucket;
+        slot[10..18].copy_from_slice(&fill.epoch_slot.to_le_bytes());
+    }
+    data.extend(ring.concat());
+    data
+}
+
+fn small_order(history: &MarketHistory, batch: &BatchView) -> Estimate {
+    estimate(history, batch, 100, 10_000)
+}
+
+#[test]
+fn indexer_updates_keep_the_latest_view() {
+    let mut history = MarketHistory::new();
+    let update = |slot: u64, status: &str, count: u8, total: u64| {
+        format!(
+            r#"{{"slot":{slot},"pubkey":"Batch1","isStartup":false,"account":"TradingBatch","data":{{"bump":255,"authority":"Auth","marketId":"{MARKET}","side":"yes","status":"{status}","orderCount":{count},"totalUsdc":{total},"stateNonce":"0","merkleRoot":"00"}}}}"#
+        )
+    };
+    history.ingest(&update(10, "open", 3, 0)).unwrap();
+    history
+        .ingest(&update(30, "executed", 5, 500_000_000))
+        .unwrap();
+    // A late-delivered older update does not roll the batch back
+    history.ingest(&update(20, "closed", 5, 0)).unwrap();
+    history
+        .ingest(r#"{"slot":40,"pubkey":"Order1","isStartup":false,"account":"OrderCommitment","data":{}}"#)
+        .unwrap();
+
+    let estimate = estimate(&history, &open_batch(2), 100_000_000, 40);
+    assert_eq!(estimate.sample_size, 1);
+    assert_eq!(estimate.expected_orders, 5);
+    assert!(history.ingest("not json").is_err());
+}
+
+#[test]
+fn fill_probability_counts_batches_that_got_as_far() {
+    let mut history = MarketHistory::new();
+    history.record_batch(
+        "a".into(),
+        100,
+        view(Side::Yes, BatchStatus::Executed, 10, 1_000),
+    );
+    history.record_batch(
+        "b".into(),
+        100,
+        view(Side::Yes, BatchStatus::Verified, 8, 800),
+    );
+    history.record_batch("c".into(), 100, view(Side::Yes, BatchStatus::Closed, 9, 0));
+    // Too few orders, the other side, or not settled yet: left out
+    history.record_batch("d".into(), 100, view(Side::Yes, BatchStatus::Closed, 2, 0));
+    history.record_batch("e".into(), 100, view(Side::No, BatchStatus::Closed, 9, 0));
+    history.record_batch(
+        "f".into(),
+        9_000,
+        view(Side::Yes, BatchStatus::Closed, 9, 0),
+    );
+    history.record_batch("g".into(), 100, view(Side::Yes, BatchStatus::Open, 9, 0));
+
+    let estimate = estimate(&history, &open_batch(4), 100, 10_000);
+    assert_eq!(estimate.sample_size, 3);
+    assert!((estimate.fill_probability - 3.0 / 5.0).abs() < 1e-9);
+
+    // With no history the estimate is a coin flip, not a certainty
+    let empty = MarketHistory::new();
+    assert_eq!(small_order(&empty, &open_batch(4)).fill_probability, 0.5);
+}
+
+#[test]
+fn closed_or_full_batches_cannot_fill() {
+    let history = MarketHistory::new();
+    assert_eq!(
+        small_order(&history, &open_batch(BATCH_CAPACITY)).fill_probability,
+        0.0
+    );
+    let closed = view(Side::Yes, BatchStatus::Closed, 4, 0);
+    assert_eq!(small_order(&history, &closed).fill_probability, 0.0);
+}
+
+#[test]
+fn expected_share_uses_past_per_order_notional() {
+    let mut history = MarketHistory::new();
+    history.record_batch(
+        "a".into(),
+        100,
+        view(Side::Yes, BatchStatus::Executed, 10, 10_000_000),
+    );
+    history.record_batch(
+        "b".into(),
+        100,
+        view(Side::Yes, BatchStatus::Executed, 6, 6_000_000),
+    );
+
+    // Batches ended with 8 orders of 1 USDC on average; ours is 7 more of
+    // those plus 3 USDC
+    let estimate = estimate(&history, &open_batch(1), 3_000_000, 10_000);
+    assert_eq!(estimate.expected_orders, 8);
+    assert_eq!(estimate.expected_notional, 10_000_000);
+    assert_eq!(estimate.expected_share_bps, 3_000);
+
+    // A batch already past the usual size is expected to end where it is
+    let estimate = small_order(&history, &open_batch(9));
+    assert_eq!(estimate.expected_orders, 10);
+}
+
+#[test]
+fn price_weights_recent_same_side_fills() {
+    let mut history = MarketHistory::new();
+    let mut fills: Vec<PublicFill> = (0..20).map(|_| fill(Side::Yes, 400_000)).collect();
+    fills.extend((0..20).map(|_| fill(Side::Yes, 600_000)));
+    fills.push(fill(Side::No, 100_000));
+    history.record_fill_tape(read_fill_tape(&bulletin(&fills)).unwrap());
+
+    let estimate = small_order(&history, &open_batch(0));
+    let price = estimate.expected_price.unwrap();
+    assert!(price > 500_000 && price < 600_000, "{price}");
+    assert_eq!(estimate.price_range, Some((400_000, 600_000)));
+
+    let no_fills = small_order(&MarketHistory::new(), &open_batch(0));
+    assert_eq!(no_fills.expected_price, None);
+    assert!(!no_fills.worth_joining(u64::MAX, 0.0));
+    assert!(estimate.worth_joining(600_000, 0.5));
+    assert!(!estimate.worth_joining(500_000, 0.5));
+    assert!(!estimate.worth_joining(600_000, 0.6));
+}
+
+#[test]
+fn fill_tape_reads_oldest_first_after_wrapping() {
+    let fills: Vec<PublicFill> = (0..70).map(|i| fill(Side::No, i)).collect();
+    let tape = read_fill_tape(&bulletin(&fills)).unwrap();
+
+    assert_eq!(tape.market_id, MARKET);
+    assert_eq!(tape.last_published_at, 900);
+    assert_eq!(tape.fills.len(), 64);
+    assert_eq!(tape.fills.first().unwrap().execution_price, 6);
+    assert_eq!(tape.fills.last().unwrap().execution_price, 69);
+
+    let tape = read_fill_tape(&bulletin(&fills[..3])).unwrap();
+    assert_eq!(tape.fills, fills[..3].to_vec());
+
+    let data = bulletin(&fills);
+    assert!(read_fill_tape(&data[..data.len() - 1]).is_err());
+    let mut wrong = data.clone();
+    wrong[0] ^= 1;
+    assert!(read_fill_tape(&wrong).is_err());
+}
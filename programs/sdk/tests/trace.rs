@@ -0,0 +1,34 @@
+use privacy_sdk::trace::*;
+
+#[test]
+fn correlation_id_round_trips() {
+    let id = CorrelationId {
+        batch: "7xKXtg2CW87d97TXJSDpbD5jBkheTqA83TZRuJosgAsU".into(),
+        computation_offset: 9_007,
+    };
+    assert_eq!(id.to_string().parse::<CorrelationId>(), Ok(id));
+
+    assert!("no-offset".parse::<CorrelationId>().is_err());
+    assert!("batch:-1".parse::<CorrelationId>().is_err());
+    assert!(":5".parse::<CorrelationId>().is_err());
+}
+
+#[test]
+fn json_export_carries_the_correlation_id() {
+    let path = std::env::temp_dir().join(format!("privacy-sdk-trace-{}.jsonl", std::process::id()));
+    init(&LogExport::JsonFile(path.clone())).unwrap();
+    assert!(init(&LogExport::Stderr).is_err());
+
+    let id = CorrelationId {
+        batch: "Batch111".into(),
+        computation_offset: 42,
+    };
+    batch_span(&id, Stage::Callback).in_scope(|| tracing::info!("callback landed"));
+
+    let logs = std::fs::read_to_string(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    let line: serde_json::Value = serde_json::from_str(logs.lines().last().unwrap()).unwrap();
+    assert_eq!(line["fields"]["message"], "callback landed");
+    assert_eq!(line["span"]["correlation_id"], "Batch111:42");
+    assert_eq!(line["span"]["stage"], "callback");
+}
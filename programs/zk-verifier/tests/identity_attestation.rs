@@ -0,0 +1,43 @@
+use anchor_lang::prelude::*;
+use zk_verifier::{eligibility_message, eligibility_scope, uniqueness_message, uniqueness_scope};
+
+fn uniqueness(attestation_key: &Pubkey, wallet: &Pubkey) -> [u8; 32] {
+    uniqueness_message(attestation_key, &[1; 32], &[2; 32], &[3; 32], wallet)
+}
+
+#[test]
+fn uniqueness_message_covers_wallet() {
+    let attestation_key = Pubkey::new_unique();
+    let wallet = Pubkey::new_unique();
+    assert_eq!(uniqueness(&attestation_key, &wallet), uniqueness(&attestation_key, &wallet));
+    assert_ne!(
+        uniqueness(&attestation_key, &wallet),
+        uniqueness(&attestation_key, &Pubkey::new_unique())
+    );
+}
+
+#[test]
+fn uniqueness_message_covers_attestation_key() {
+    let wallet = Pubkey::new_unique();
+    assert_ne!(
+        uniqueness(&Pubkey::new_unique(), &wallet),
+        uniqueness(&Pubkey::new_unique(), &wallet)
+    );
+}
+
+#[test]
+fn eligibility_message_covers_wallet() {
+    let attestation_key = Pubkey::new_unique();
+    assert_ne!(
+        eligibility_message(&attestation_key, &[1; 32], &[2; 32], &Pubkey::new_unique()),
+        eligibility_message(&attestation_key, &[1; 32], &[2; 32], &Pubkey::new_unique())
+    );
+}
+
+#[test]
+fn scopes_do_not_collide() {
+    assert_ne!(uniqueness_scope(1), uniqueness_scope(2));
+    let mut predicate = [0u8; 32];
+    predicate[..8].copy_from_slice(&1u64.to_le_bytes());
+    assert_ne!(uniqueness_scope(1), eligibility_scope(&predicate));
+}
@@ -0,0 +1,59 @@
+use anchor_lang::prelude::*;
+use zk_verifier::{PostedVaa, RelayedProof, RELAY_PAYLOAD_PROOF_VERIFIED};
+
+fn payload(timestamp: u64) -> Vec<u8> {
+    let mut payload = vec![RELAY_PAYLOAD_PROOF_VERIFIED];
+    for word in [1u8, 2, 3, 4] {
+        payload.extend_from_slice(&[word; 32]);
+    }
+    payload.extend_from_slice(&timestamp.to_be_bytes());
+    payload
+}
+
+fn posted_vaa(payload: &[u8]) -> Vec<u8> {
+    let mut data = b"vaa".to_vec();
+    data.push(1); // vaa_version
+    data.push(32); // consistency_level
+    data.extend_from_slice(&1_760_000_000u32.to_le_bytes());
+    data.extend_from_slice(Pubkey::new_unique().as_ref());
+    data.extend_from_slice(&1_760_000_010u32.to_le_bytes());
+    data.extend_from_slice(&7u32.to_le_bytes()); // nonce
+    data.extend_from_slice(&99u64.to_le_bytes()); // sequence
+    data.extend_from_slice(&30u16.to_le_bytes()); // Base
+    data.extend_from_slice(&[0xee; 32]);
+    data.extend_from_slice(&(payload.len() as u32).to_le_bytes());
+    data.extend_from_slice(payload);
+    data
+}
+
+#[test]
+fn parses_posted_vaa_and_payload() {
+    let vaa = PostedVaa::parse(&posted_vaa(&payload(1_759_999_999))).unwrap();
+    assert_eq!(vaa.sequence, 99);
+    assert_eq!(vaa.emitter_chain, 30);
+    assert_eq!(vaa.emitter_address, [0xee; 32]);
+
+    let proof = RelayedProof::parse(&vaa.payload).unwrap();
+    assert_eq!(
+        proof,
+        RelayedProof {
+            verification_key: [1; 32],
+            query_commitment: [2; 32],
+            response_commitment: [3; 32],
+            merkle_root: [4; 32],
+            timestamp: 1_759_999_999,
+        }
+    );
+}
+
+#[test]
+fn rejects_other_accounts_and_payloads() {
+    let mut message = posted_vaa(&payload(0));
+    message[..3].copy_from_slice(b"msg");
+    assert!(PostedVaa::parse(&message).is_err());
+
+    let mut wrong_type = payload(0);
+    wrong_type[0] = 2;
+    assert!(RelayedProof::parse(&wrong_type).is_err());
+    assert!(RelayedProof::parse(&payload(0)[..RelayedProof::LEN - 1]).is_err());
+}
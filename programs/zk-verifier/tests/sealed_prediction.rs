@@ -0,0 +1,24 @@
+use anchor_lang::prelude::*;
+use zk_verifier::{brier_score_bps, prediction_commitment, BPS};
+
+#[test]
+fn brier_score_rewards_calibrated_calls() {
+    assert_eq!(brier_score_bps(10_000, true), 0);
+    assert_eq!(brier_score_bps(0, true), BPS);
+    assert_eq!(brier_score_bps(5_000, false), 2_500);
+    assert_eq!(brier_score_bps(7_000, true), 900);
+    assert_eq!(brier_score_bps(7_000, false), 4_900);
+}
+
+#[test]
+fn commitment_binds_market_agent_and_probability() {
+    let registry = Pubkey::new_unique();
+    let agent = Pubkey::new_unique();
+    let salt = [9u8; 32];
+    let commitment = prediction_commitment(&registry, &agent, 6_500, &salt);
+
+    assert_ne!(commitment, prediction_commitment(&registry, &agent, 6_501, &salt));
+    assert_ne!(commitment, prediction_commitment(&registry, &Pubkey::new_unique(), 6_500, &salt));
+    assert_ne!(commitment, prediction_commitment(&Pubkey::new_unique(), &agent, 6_500, &salt));
+    assert_ne!(commitment, prediction_commitment(&registry, &agent, 6_500, &[8u8; 32]));
+}
@@ -0,0 +1,21 @@
+use anchor_lang::prelude::*;
+use zk_verifier::tee_message;
+
+fn message(registry: &Pubkey, proof_id: &str) -> [u8; 32] {
+    tee_message(registry, proof_id, &[1; 32], &[2; 32], &[3; 32], 1_760_000_000)
+}
+
+#[test]
+fn message_covers_proof_id() {
+    let registry = Pubkey::new_unique();
+    assert_eq!(message(&registry, "proof-1"), message(&registry, "proof-1"));
+    assert_ne!(message(&registry, "proof-1"), message(&registry, "proof-2"));
+}
+
+#[test]
+fn message_covers_registry() {
+    assert_ne!(
+        message(&Pubkey::new_unique(), "proof-1"),
+        message(&Pubkey::new_unique(), "proof-1")
+    );
+}
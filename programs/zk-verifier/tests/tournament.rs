@@ -0,0 +1,64 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::keccak;
+use zk_verifier::{
+    rank_tournament, tournament_payout_leaf, tournament_payout_root, TournamentEntry,
+    TournamentPayout,
+};
+
+fn entry(predictions: u32, brier_sum: u64) -> TournamentEntry {
+    TournamentEntry {
+        agent: Pubkey::new_unique(),
+        predictions,
+        brier_sum,
+    }
+}
+
+/// privacy-trading's `verify_merkle_proof`
+fn verify(proof: &[[u8; 32]], root: [u8; 32], leaf: [u8; 32]) -> bool {
+    let computed = proof.iter().fold(leaf, |node, sibling| {
+        let (left, right) = if node <= *sibling { (node, *sibling) } else { (*sibling, node) };
+        keccak::hashv(&[&[1u8], &left, &right]).to_bytes()
+    });
+    computed == root
+}
+
+fn node(a: [u8; 32], b: [u8; 32]) -> [u8; 32] {
+    let (left, right) = if a <= b { (a, b) } else { (b, a) };
+    keccak::hashv(&[&[1u8], &left, &right]).to_bytes()
+}
+
+#[test]
+fn ranks_by_mean_brier_then_volume() {
+    let entries = vec![
+        entry(4, 4_000), // mean 1000
+        entry(2, 400),   // mean 200
+        entry(1, 0),     // below minimum
+        entry(4, 800),   // mean 200, more predictions
+        entry(3, 9_000), // mean 3000
+    ];
+
+    assert_eq!(rank_tournament(&entries, 2), vec![3, 1, 0, 4]);
+}
+
+#[test]
+fn payout_root_verifies_with_distribution_proofs() {
+    let payouts: Vec<TournamentPayout> = [500u64, 300, 200]
+        .iter()
+        .map(|amount| TournamentPayout {
+            agent: Pubkey::new_unique(),
+            amount: *amount,
+        })
+        .collect();
+    let leaves: Vec<[u8; 32]> = payouts
+        .iter()
+        .enumerate()
+        .map(|(place, payout)| tournament_payout_leaf(place as u32, payout))
+        .collect();
+    let root = tournament_payout_root(&payouts);
+
+    // Third leaf is carried up unpaired, so its proof is the first pair's node
+    assert!(verify(&[leaves[1]], node(leaves[0], leaves[1]), leaves[0]));
+    assert!(verify(&[leaves[1], leaves[2]], root, leaves[0]));
+    assert!(verify(&[node(leaves[0], leaves[1])], root, leaves[2]));
+    assert!(!verify(&[leaves[0], leaves[2]], root, leaves[0]));
+}
@@ -0,0 +1,65 @@
+use anchor_lang::solana_program::keccak;
+use zk_verifier::{recover_eth_address, WebClaim};
+
+fn claim() -> WebClaim {
+    WebClaim {
+        provider: "http".into(),
+        parameters: r#"{"url":"https://api.example.com/result","responseMatches":[{"type":"contains","value":"\"winner\":\"YES\""}]}"#.into(),
+        context: r#"{"contextMessage":"market-42"}"#.into(),
+        owner: [0x11; 20],
+        timestamp_s: 1_760_000_000,
+        epoch: 1,
+    }
+}
+
+fn sign(digest: &[u8; 32], secret: &libsecp256k1::SecretKey) -> [u8; 65] {
+    let (signature, recovery_id) = libsecp256k1::sign(&libsecp256k1::Message::parse(digest), secret);
+    let mut bytes = [0u8; 65];
+    bytes[..64].copy_from_slice(&signature.serialize());
+    bytes[64] = recovery_id.serialize() + 27;
+    bytes
+}
+
+fn address(secret: &libsecp256k1::SecretKey) -> [u8; 20] {
+    let pubkey = libsecp256k1::PublicKey::from_secret_key(secret).serialize();
+    keccak::hash(&pubkey[1..]).to_bytes()[12..].try_into().unwrap()
+}
+
+#[test]
+fn recovers_witness_address() {
+    let secret = libsecp256k1::SecretKey::parse(&[0x42; 32]).unwrap();
+    let digest = claim().signed_digest();
+
+    assert_eq!(recover_eth_address(&digest, &sign(&digest, &secret)).unwrap(), address(&secret));
+}
+
+#[test]
+fn signature_over_other_claim_recovers_other_address() {
+    let secret = libsecp256k1::SecretKey::parse(&[0x42; 32]).unwrap();
+    let mut other = claim();
+    other.parameters = other.parameters.replace("YES", "NO");
+    let signature = sign(&other.signed_digest(), &secret);
+
+    assert_ne!(
+        recover_eth_address(&claim().signed_digest(), &signature).unwrap(),
+        address(&secret)
+    );
+}
+
+#[test]
+fn identifier_covers_provider_parameters_and_context() {
+    let base = claim().identifier();
+    let mut changed = claim();
+    changed.context = r#"{"contextMessage":"market-43"}"#.into();
+
+    assert_ne!(changed.identifier(), base);
+    assert_eq!(claim().identifier(), base);
+}
+
+#[test]
+fn rejects_bad_recovery_byte() {
+    let mut signature = [1u8; 65];
+    signature[64] = 29;
+
+    assert!(recover_eth_address(&[0u8; 32], &signature).is_err());
+}